@@ -1,5 +1,8 @@
 extern crate build;
 fn main() {
+    build::link("comdlg32", false);
+    build::link("uxtheme", false);
+
     if cfg!(feature = "canvas") {
         build::link("d2d1", true);
     }
@@ -65,7 +65,10 @@ nwg_template!(
                 text: text.get_text(),
                 position: (width_offset, height_offset), size: (195, 30), 
                 visible: true, disabled: false, 
-                parent:  MainWindow, font: Some(TextFont)
+                parent:  MainWindow, font: Some(TextFont),
+                tooltip: None,
+                style: nwg::constants::ButtonStyle::Push, image: None,
+                text_color: None, background_color: None
             };
             ui.pack_control(&new_id, t);
 
@@ -1,70 +1,62 @@
 /**
-    A simple example demonstrating multithreading. Two simple ui and allow the user to pause the thread.
+    A simple example demonstrating multithreading. A worker thread updates a progress bar and a label
+    through a `UiProxy` while the ui stays responsive.
 */
 
 #[macro_use] extern crate native_windows_gui as nwg;
 
-use nwg::{Ui, fatal_message, dispatch_events};
+use nwg::{Ui, UiProxy, fatal_message, dispatch_events};
 use nwg::events as nwge;
 use std::thread;
 use std::time::Duration;
 
 nwg_template!(
-    head: setup_sleep_window<&'static str>,
+    head: setup_ui<&'static str>,
     controls: [
-        ("Sleep", nwg_window!( title="Sleep"; size=(200, 200); position=(200, 200) )),
-        ("SleepButton", nwg_button!( parent="Sleep"; text="SLEEP"; size=(200, 200); position=(0, 0) ))
+        ("MainWindow", nwg_window!( title="Worker"; size=(280, 125); position=(200, 200) )),
+        ("StartButton", nwg_button!( parent="MainWindow"; text="Start"; size=(260, 35); position=(10, 10) )),
+        ("Progress", nwg_progressbar!( parent="MainWindow"; size=(260, 25); position=(10, 55) )),
+        ("Status", nwg_label!( parent="MainWindow"; text="Idle"; size=(260, 25); position=(10, 90) ))
     ];
     events: [
-        ("SleepButton", "Sleep", nwge::button::Click, |ui,_,_,_| {
-            let btn = nwg_get!(ui; ("SleepButton", nwg::Button));
-            btn.set_text("SLEEPING!");
-            thread::sleep(Duration::from_millis(5000));
-            btn.set_text("SLEEP");
+        ("StartButton", "Start", nwge::button::Click, |ui,_,_,_| {
+            let btn = nwg_get!(ui; ("StartButton", nwg::Button));
+            btn.set_enabled(false);
+
+            // The ui can't leave its thread, but a proxy can
+            let proxy: UiProxy<&'static str> = ui.proxy();
+            thread::spawn(move || {
+                for step in 1..11 {
+                    thread::sleep(Duration::from_millis(300));
+                    proxy.post(move |ui| {
+                        let (progress, status) = nwg_get!(ui; [("Progress", nwg::ProgressBar), ("Status", nwg::Label)]);
+                        progress.set_value(step * 10);
+                        status.set_text(&format!("Working... {}/10", step));
+                    }).ok();
+                }
+
+                proxy.post(|ui| {
+                    let (btn, status) = nwg_get!(ui; [("StartButton", nwg::Button), ("Status", nwg::Label)]);
+                    status.set_text("Done!");
+                    btn.set_enabled(true);
+                }).ok();
+            });
         })
     ];
     resources: [];
     values: []
 );
 
-nwg_template!(
-    head: setup_test_window<&'static str>,
-    controls: [
-        ("Test", nwg_window!( title="Test"; size=(200, 200); position=(420, 200) ))
-    ];
-    events: [];
-    resources: [];
-    values: []
-);
-
 fn main() {
-    // Create the main window on the current thread
     let app: Ui<&'static str>;
     match Ui::new() {
         Ok(_app) => { app = _app; },
         Err(e) => { fatal_message("Fatal Error", &format!("{:?}", e) ); }
     }
 
-    if let Err(e) = setup_test_window(&app) {
+    if let Err(e) = setup_ui(&app) {
         fatal_message("Fatal Error", &format!("{:?}", e));
     }
 
-    // Create another ui on a new thread
-    let t = thread::spawn(||{
-        let app2: Ui<&'static str>;
-        match Ui::new() {
-            Ok(_app2) => { app2 = _app2; },
-            Err(e) => { fatal_message("Fatal Error", &format!("{:?}", e) ); }
-        }
-
-        if let Err(e) = setup_sleep_window(&app2) {
-            fatal_message("Fatal Error", &format!("{:?}", e));
-        }
-
-        dispatch_events();
-    });
-    
-    // Wait for both event loop to finish
     dispatch_events();
-    t.join().unwrap();
 }
@@ -29,7 +29,9 @@ pub fn setup_ui(ui: &Ui<&'static str>) -> Result<(), Error> {
         title: "No template",
         position: (100, 100), size: (280, 105),
         resizable: false, visible: true, disabled: false,
-        exit_on_close: true, icon: None
+        exit_on_close: true, icon: None,
+        owner: None, tool_window: false,
+        text_color: None, background_color: None
     };
 
     // nwg_label!( parent="MainWindow"; [...] font=Some("TextFont") )
@@ -38,7 +40,9 @@ pub fn setup_ui(ui: &Ui<&'static str>) -> Result<(), Error> {
         position: (5,15), size: (80, 25),
         visible: true, disabled: false,
         align: nwg::constants::HTextAlign::Left,
-        parent: "MainWindow", font: Some("TextFont")
+        parent: "MainWindow", font: Some("TextFont"),
+        tooltip: None, text_color: None, background_color: None,
+        owner_draw: false
     };
 
     // nwg_textinput!( parent="MainWindow"; [..] font=Some("TextFont") )
@@ -46,8 +50,9 @@ pub fn setup_ui(ui: &Ui<&'static str>) -> Result<(), Error> {
         text: "",
         position: (85,13), size: (185,22),
         visible: true, disabled: false, readonly: false, password: false,
-        limit: 32_767, placeholder: None,
-        parent: "MainWindow", font: Some("TextFont")
+        limit: 32_767, placeholder: None, validators: vec![],
+        parent: "MainWindow", font: Some("TextFont"),
+        tooltip: None, text_color: None, background_color: None
     };
 
     // nwg_button!( parent="MainWindow"; [..] font=Some("MainFont") )
@@ -55,7 +60,10 @@ pub fn setup_ui(ui: &Ui<&'static str>) -> Result<(), Error> {
         text: "Hello World!",
         position: (5, 45), size: (270, 50),
         visible: true, disabled: false,
-        parent: "MainWindow", font: Some("MainFont")
+        parent: "MainWindow", font: Some("MainFont"),
+        tooltip: Some("Say hello to the name above".to_string()),
+        style: nwg::constants::ButtonStyle::Push, image: None,
+        text_color: None, background_color: None
     };
 
     // resources: 
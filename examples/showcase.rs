@@ -26,6 +26,12 @@ nwg_template!(
         ("S1", nwg_separator!(parent="FileMenu")),
         ("QuitItem", nwg_menuitem!(parent="FileMenu"; text="&Quit")),
         ("WindowAction", nwg_menuitem!(parent="MainWindow"; text="&Action")),
+        ("ViewMenu", nwg_menu!(parent="MainWindow"; text="&View"; index=Some(1))),
+        ("WordWrapItem", nwg_menuitem!(parent="ViewMenu"; text="&Word wrap"; checked=true)),
+        ("S3", nwg_separator!(parent="ViewMenu")),
+        ("SmallTextItem", nwg_menuitem!(parent="ViewMenu"; text="&Small text"; radio=true)),
+        ("NormalTextItem", nwg_menuitem!(parent="ViewMenu"; text="&Normal text"; radio=true; checked=true; default=true)),
+        ("LargeTextItem", nwg_menuitem!(parent="ViewMenu"; text="&Large text"; radio=true)),
 
         // Context Menu
         ("Context", nwg_contextmenu!()),
@@ -35,6 +41,9 @@ nwg_template!(
         ("TestSubmenu3", nwg_menu!(parent="Context"; text="&Submenu")),
         ("Action3", nwg_menuitem!(parent="TestSubmenu3"; text="SayHello")),
         ("Action4", nwg_menuitem!(parent="TestSubmenu3"; text="Disabled :("; disabled=true)),
+
+        // Keyboard shortcuts
+        ("Shortcuts", nwg_accelerator!(window="MainWindow"; items=vec![("Ctrl+H", "NestedAction"), ("Ctrl+Q", "QuitItem")])),
         
         // Tabs
         ("TabView", nwg_tabsview!(parent="MainWindow"; position=(5, 5); size=(490, 370))),
@@ -187,7 +196,14 @@ nwg_template!(
 
         ("NestedAction", "SayHello", nwge::menu::Triggered, |_,_,_,_| { nwg::simple_message("Hello", "Hello World!");  }),
         ("Action3", "SayHello", nwge::menu::Triggered, |_,_,_,_| { nwg::simple_message("Hello", "Hello World!"); }),
-        ("QuitItem", "Quit", nwge::menu::Triggered, |_,_,_,_| { nwg_exit() })
+        ("QuitItem", "Quit", nwge::menu::Triggered, |_,_,_,_| { nwg_exit() }),
+        ("WordWrapItem", "ToggleCheck", nwge::menu::Triggered, |ui,_,_,_| {
+            let item = nwg_get!(ui; ("WordWrapItem", nwg::MenuItem));
+            item.set_checked(!item.get_checked());
+        }),
+        ("SmallTextItem", "CheckRadio", nwge::menu::Triggered, |ui,_,_,_| { nwg_get!(ui; ("SmallTextItem", nwg::MenuItem)).set_checked(true); }),
+        ("NormalTextItem", "CheckRadio", nwge::menu::Triggered, |ui,_,_,_| { nwg_get!(ui; ("NormalTextItem", nwg::MenuItem)).set_checked(true); }),
+        ("LargeTextItem", "CheckRadio", nwge::menu::Triggered, |ui,_,_,_| { nwg_get!(ui; ("LargeTextItem", nwg::MenuItem)).set_checked(true); })
 
     ];
     resources: [
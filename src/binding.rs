@@ -0,0 +1,415 @@
+/*!
+    Data binding between the values packed in a Ui and the properties of its controls.
+
+    An `Observable` is a shared value that notifies its listeners when it changes. A `Binding` describes
+    how an observable value is copied into a control property (and back): the property, the converters
+    and the validation rules. Bindings are installed with `Ui::bind_value`.
+
+    The types in this module are pure rust. Only the builtin properties (ex: `Property::text`) require the system controls.
+*/
+
+use std::hash::Hash;
+use std::rc::Rc;
+use std::cell::{Cell, RefCell};
+use std::str::FromStr;
+
+use ui::Ui;
+use events::Event;
+use error::Error;
+
+struct ObservableInner<T> {
+    value: RefCell<T>,
+    listeners: RefCell<Vec<(u64, Rc<Fn(&T)>)>>,
+    next_key: Cell<u64>
+}
+
+/**
+    A value that notifies its listeners each time it is modified.
+    Cloning an observable returns a new handle to the same value.
+
+    Usage: `ui.pack_value(&"Name", Observable::new(String::new()))`
+*/
+pub struct Observable<T: Clone> {
+    inner: Rc<ObservableInner<T>>
+}
+
+impl<T: Clone> Observable<T> {
+
+    /// Create a new observable value without listeners
+    pub fn new(value: T) -> Observable<T> {
+        let inner = ObservableInner{ value: RefCell::new(value), listeners: RefCell::new(Vec::new()), next_key: Cell::new(0) };
+        Observable{ inner: Rc::new(inner) }
+    }
+
+    /// Return a copy of the value
+    pub fn get(&self) -> T {
+        self.inner.value.borrow().clone()
+    }
+
+    /// Replace the value and notify the listeners
+    pub fn set(&self, value: T) {
+        *self.inner.value.borrow_mut() = value;
+        self.notify();
+    }
+
+    /// Modify the value in place and notify the listeners
+    pub fn update<F: FnOnce(&mut T)>(&self, f: F) {
+        f(&mut *self.inner.value.borrow_mut());
+        self.notify();
+    }
+
+    /**
+        Add a listener called with the new value each time the value is modified.
+        Returns a key that can be used with `unsubscribe`.
+    */
+    pub fn subscribe<F: Fn(&T)+'static>(&self, listener: F) -> u64 {
+        let key = self.inner.next_key.get();
+        self.inner.next_key.set(key + 1);
+        self.inner.listeners.borrow_mut().push((key, Rc::new(listener)));
+        key
+    }
+
+    /// Remove a listener. Returns false if the key do not identify a listener of this value.
+    pub fn unsubscribe(&self, key: u64) -> bool {
+        let mut listeners = self.inner.listeners.borrow_mut();
+        let len = listeners.len();
+        listeners.retain(|&(k, _)| k != key);
+        listeners.len() != len
+    }
+
+    /// Return the number of listeners of the value
+    pub fn listeners_count(&self) -> usize {
+        self.inner.listeners.borrow().len()
+    }
+
+    fn notify(&self) {
+        // The listeners are cloned because they can modify the value or the listeners list
+        let listeners: Vec<Rc<Fn(&T)>> = self.inner.listeners.borrow().iter().map(|&(_, ref l)| l.clone()).collect();
+        let value = self.get();
+        for listener in listeners.iter() {
+            listener(&value);
+        }
+    }
+
+}
+
+impl<T: Clone> Clone for Observable<T> {
+    fn clone(&self) -> Observable<T> {
+        Observable{ inner: self.inner.clone() }
+    }
+}
+
+/**
+    The direction in which a binding copies the values
+
+    Members:
+    • `OneWay`: The control is updated when the value changes
+    • `TwoWay`: Same as `OneWay`, and the value is updated when the user modifies the control
+    • `OneWayToSource`: The value is updated when the user modifies the control. The control is never written
+*/
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BindingMode {
+    OneWay,
+    TwoWay,
+    OneWayToSource
+}
+
+/**
+    A property of a control that can be bound to a value.
+
+    Members:
+    • `get`: Read the property of the control identified by the id
+    • `set`: Write the property of the control identified by the id
+    • `changed`: The event raised by the control when the user modifies the property. None if the user can't modify it
+    • `read_only`: Tell if the user can't modify the property of a control. See `Property::read_only`
+*/
+pub struct Property<ID: Hash+Clone+'static, V> {
+    get: Box<Fn(&Ui<ID>, &ID) -> Result<V, Error>>,
+    set: Box<Fn(&Ui<ID>, &ID, &V) -> Result<(), Error>>,
+    changed: Option<Event>,
+    read_only: Box<Fn(&Ui<ID>, &ID) -> bool>
+}
+
+impl<ID: Hash+Clone+'static, V> Property<ID, V> {
+
+    /// Create a custom property
+    pub fn new<G, S>(get: G, set: S, changed: Option<Event>) -> Property<ID, V> where
+      G: Fn(&Ui<ID>, &ID) -> Result<V, Error>+'static,
+      S: Fn(&Ui<ID>, &ID, &V) -> Result<(), Error>+'static
+    {
+        Property{ get: Box::new(get), set: Box::new(set), changed: changed, read_only: Box::new(|_, _| false) }
+    }
+
+    /**
+        Set the function that tells if the user can't modify the property of a control, for the properties shared by
+        editable and read only controls (ex: the text of a label). By default, the property is read only if `changed` is None.
+    */
+    pub fn read_only<F: Fn(&Ui<ID>, &ID) -> bool+'static>(mut self, read_only: F) -> Property<ID, V> {
+        self.read_only = Box::new(read_only);
+        self
+    }
+
+    /// Read the property of a control
+    pub fn get(&self, ui: &Ui<ID>, id: &ID) -> Result<V, Error> {
+        (self.get)(ui, id)
+    }
+
+    /// Write the property of a control
+    pub fn set(&self, ui: &Ui<ID>, id: &ID, value: &V) -> Result<(), Error> {
+        (self.set)(ui, id, value)
+    }
+
+    /// Return the event raised when the user modifies the property
+    pub fn changed(&self) -> Option<Event> {
+        self.changed
+    }
+
+    /// Return the event raised when the user modifies the property of the control identified by the id. None if the property is read only.
+    pub fn changed_by(&self, ui: &Ui<ID>, id: &ID) -> Option<Event> {
+        if (self.read_only)(ui, id) { None } else { self.changed }
+    }
+
+}
+
+#[cfg(windows)]
+impl<ID: Hash+Clone+'static> Property<ID, String> {
+
+    /// The text of a `TextInput`, a `TextBox` or a `Label`. The text of a label is read only: it is bound with `BindingMode::OneWay`.
+    pub fn text() -> Property<ID, String> {
+        use controls::{ControlType, TextInput, TextBox, Label};
+        use events::textinput::ValueChanged;
+
+        let get = |ui: &Ui<ID>, id: &ID| -> Result<String, Error> {
+            match ui.type_of_control(id) {
+                Ok(ControlType::TextInput) => ui.get::<TextInput>(id).map(|c| c.get_text()),
+                Ok(ControlType::TextBox) => ui.get::<TextBox>(id).map(|c| c.get_text()),
+                Ok(ControlType::Label) => ui.get::<Label>(id).map(|c| c.get_text()),
+                Ok(_) => Err(Error::BadType),
+                Err(e) => Err(e)
+            }
+        };
+
+        let set = |ui: &Ui<ID>, id: &ID, text: &String| -> Result<(), Error> {
+            match ui.type_of_control(id) {
+                Ok(ControlType::TextInput) => ui.get::<TextInput>(id).map(|c| c.set_text(text)),
+                Ok(ControlType::TextBox) => ui.get::<TextBox>(id).map(|c| c.set_text(text)),
+                Ok(ControlType::Label) => ui.get::<Label>(id).map(|c| c.set_text(text)),
+                Ok(_) => Err(Error::BadType),
+                Err(e) => Err(e)
+            }
+        };
+
+        let read_only = |ui: &Ui<ID>, id: &ID| match ui.type_of_control(id) {
+            Ok(ControlType::Label) => true,
+            _ => false
+        };
+
+        Property::new(get, set, Some(ValueChanged)).read_only(read_only)
+    }
+
+}
+
+#[cfg(windows)]
+impl<ID: Hash+Clone+'static> Property<ID, bool> {
+
+    /// The check state of a `CheckBox`. An indeterminate checkbox is read as unchecked.
+    pub fn checked() -> Property<ID, bool> {
+        use controls::CheckBox;
+        use defs::CheckState;
+        use events::checkbox::Click;
+
+        let get = |ui: &Ui<ID>, id: &ID| ui.get::<CheckBox>(id).map(|c| c.get_checkstate() == CheckState::Checked);
+        let set = |ui: &Ui<ID>, id: &ID, checked: &bool| {
+            let state = if *checked { CheckState::Checked } else { CheckState::Unchecked };
+            ui.get::<CheckBox>(id).map(|c| c.set_checkstate(state))
+        };
+
+        Property::new(get, set, Some(Click))
+    }
+
+}
+
+#[cfg(windows)]
+impl<ID: Hash+Clone+'static> Property<ID, Option<usize>> {
+
+    /// The selected index of a `ComboBox<D>`. `None` if no item is selected.
+    pub fn selected_index<D: Clone+::std::fmt::Display+'static>() -> Property<ID, Option<usize>> {
+        use controls::ComboBox;
+        use events::combobox::SelectionChanged;
+
+        let get = |ui: &Ui<ID>, id: &ID| ui.get::<ComboBox<D>>(id).map(|c| c.get_selected_index());
+        let set = |ui: &Ui<ID>, id: &ID, index: &Option<usize>| {
+            ui.get::<ComboBox<D>>(id).map(|c| c.set_selected_index(index.unwrap_or(usize::max_value())))
+        };
+
+        Property::new(get, set, Some(SelectionChanged))
+    }
+
+}
+
+#[cfg(windows)]
+impl<ID: Hash+Clone+'static> Property<ID, u32> {
+
+    /// The value of a `ProgressBar`. Can only be bound with `BindingMode::OneWay`.
+    pub fn progress() -> Property<ID, u32> {
+        use controls::ProgressBar;
+
+        let get = |ui: &Ui<ID>, id: &ID| ui.get::<ProgressBar>(id).map(|c| c.get_value());
+        let set = |ui: &Ui<ID>, id: &ID, value: &u32| ui.get::<ProgressBar>(id).map(|c| c.set_value(*value));
+
+        Property::new(get, set, None)
+    }
+
+}
+
+/**
+    Describe how a value of type `M` is bound to a control property of type `V`.
+    Bindings are installed with `Ui::bind_value`.
+
+    The value is converted with `to_control` before being written in the control. When the user modifies the control,
+    the property is converted back with `to_model` and checked by the validators. If the conversion or a validator fails,
+    the value is not modified and the error handler (see `on_error`) receives the error message.
+
+    Usage:
+    `Binding::new(Property::text())`: bind a `String` to the text of a text input
+    `Binding::parse(Property::text())`: bind a number to the text of a text input
+    `Binding::new(Property::checked()).mode(BindingMode::OneWay)`
+*/
+pub struct Binding<ID: Hash+Clone+'static, M, V> {
+    property: Property<ID, V>,
+    mode: Option<BindingMode>,
+    to_control: Box<Fn(&M) -> V>,
+    to_model: Box<Fn(&V) -> Result<M, String>>,
+    validators: Vec<Box<Fn(&M) -> Result<(), String>>>,
+    on_error: Option<Box<Fn(&Ui<ID>, &ID, Option<&str>)>>
+}
+
+impl<ID: Hash+Clone+'static, V: Clone+'static> Binding<ID, V, V> {
+
+    /// Bind a value that has the same type as the property
+    pub fn new(property: Property<ID, V>) -> Binding<ID, V, V> {
+        Binding::with_converter(property, |v: &V| v.clone(), |v: &V| Ok(v.clone()))
+    }
+
+}
+
+impl<ID: Hash+Clone+'static, M: FromStr+ToString+'static> Binding<ID, M, String> {
+
+    /// Bind a value to a text property. The text is parsed with `FromStr` and the value is displayed with `ToString`.
+    pub fn parse(property: Property<ID, String>) -> Binding<ID, M, String> {
+        let to_model = |text: &String| match text.trim().parse::<M>() {
+            Ok(v) => Ok(v),
+            Err(_) => Err(format!("\"{}\" is not a valid value", text))
+        };
+
+        Binding::with_converter(property, |v: &M| v.to_string(), to_model)
+    }
+
+}
+
+impl<ID: Hash+Clone+'static, M, V> Binding<ID, M, V> {
+
+    /**
+        Bind a value to a property using converters.
+
+        Params:
+          • property: The control property
+          • to_control: Convert the value into a property value
+          • to_model: Convert a property value into a value. Return an error message if the property value cannot be converted
+    */
+    pub fn with_converter<C, B>(property: Property<ID, V>, to_control: C, to_model: B) -> Binding<ID, M, V> where
+      C: Fn(&M) -> V+'static,
+      B: Fn(&V) -> Result<M, String>+'static
+    {
+        Binding{
+            property: property,
+            mode: None,
+            to_control: Box::new(to_control),
+            to_model: Box::new(to_model),
+            validators: Vec::new(),
+            on_error: None
+        }
+    }
+
+    /// Set the binding direction. The default is `TwoWay` if the user can modify the property of the bound control, `OneWay` otherwise.
+    pub fn mode(mut self, mode: BindingMode) -> Binding<ID, M, V> {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Add a validation rule. The rules are checked in the order they were added. Return an error message if the value is rejected.
+    pub fn validate<F: Fn(&M) -> Result<(), String>+'static>(mut self, validator: F) -> Binding<ID, M, V> {
+        self.validators.push(Box::new(validator));
+        self
+    }
+
+    /// Set the function called each time the control is read. It receives the error message if the
+    /// value was rejected or `None` if it was accepted.
+    pub fn on_error<F: Fn(&Ui<ID>, &ID, Option<&str>)+'static>(mut self, handler: F) -> Binding<ID, M, V> {
+        self.on_error = Some(Box::new(handler));
+        self
+    }
+
+    /// Return the binding direction set with `mode` or None if the binding uses the default direction
+    pub fn get_mode(&self) -> Option<BindingMode> {
+        self.mode
+    }
+
+    /// Return the binding direction used with the control identified by the id
+    pub fn mode_for(&self, ui: &Ui<ID>, id: &ID) -> BindingMode {
+        match (self.mode, self.property.changed_by(ui, id)) {
+            (Some(mode), _) => mode,
+            (None, Some(_)) => BindingMode::TwoWay,
+            (None, None) => BindingMode::OneWay
+        }
+    }
+
+    /// Return the bound property
+    pub fn property(&self) -> &Property<ID, V> {
+        &self.property
+    }
+
+    /// Convert a value with `to_control`
+    pub fn convert_to_control(&self, value: &M) -> V {
+        (self.to_control)(value)
+    }
+
+    /// Convert a property value with `to_model` and check the result with the validators
+    pub fn convert_to_model(&self, value: &V) -> Result<M, String> {
+        let value = match (self.to_model)(value) {
+            Ok(v) => v,
+            Err(e) => { return Err(e); }
+        };
+
+        for validator in self.validators.iter() {
+            if let Err(e) = validator(&value) {
+                return Err(e);
+            }
+        }
+
+        Ok(value)
+    }
+
+    /// Write a value in the control property
+    pub fn write_control(&self, ui: &Ui<ID>, id: &ID, value: &M) -> Result<(), Error> {
+        self.property.set(ui, id, &self.convert_to_control(value))
+    }
+
+    /**
+        Read the control property and convert it into a value. The error handler is called with the result.
+        Returns `None` if the property could not be read or if the value was rejected.
+    */
+    pub fn read_control(&self, ui: &Ui<ID>, id: &ID) -> Option<M> {
+        let value = match self.property.get(ui, id) {
+            Ok(v) => self.convert_to_model(&v),
+            Err(_) => { return None; }
+        };
+
+        if let Some(ref handler) = self.on_error {
+            handler(ui, id, value.as_ref().err().map(|e| e.as_ref()));
+        }
+
+        value.ok()
+    }
+
+}
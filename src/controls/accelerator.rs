@@ -0,0 +1,197 @@
+/*!
+    A keyboard accelerator table that maps shortcuts to menu items or to callbacks
+*/
+use std::hash::Hash;
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::ptr;
+
+use winapi::{HWND, HMENU, HACCEL, UINT, MSG, ACCEL, WORD, c_int};
+
+use ui::Ui;
+use controls::{Control, ControlT, ControlType, AnyHandle};
+use shortcut::{Shortcut, shortcut_command, SHORTCUT_COMMAND_FLAG};
+use error::{Error, SystemError};
+
+// The accelerator tables used by `dispatch_events`. A window can only have one table.
+thread_local!(static ACCELERATORS: RefCell<Vec<(HWND, HACCEL)>> = RefCell::new(Vec::new()));
+
+/**
+    A template that creates an accelerator table for a window. The shortcuts are active while the window
+    or one of its children has the keyboard focus. Because the accelerator needs the window and the menu items,
+    it must be packed after them.
+
+    When a shortcut in `items` is pressed, the `menu::Triggered` event of the menu item is triggered and the
+    shortcut is displayed next to the menu item text.
+    When a shortcut in `shortcuts` is pressed, the `accelerator::Triggered` event of the accelerator is triggered.
+    The shortcut is passed to the callbacks in a `EventArgs::Shortcut`.
+
+    A shortcut is a string like "Ctrl+Shift+N" or "F5". See `Shortcut::parse`.
+
+    Control specific events:
+    `accelerator::Triggered`
+
+    Members:
+    • `window`: The window that receives the shortcuts
+    • `items`: A list of `(shortcut, menu item id)`. The menu items must be in the menu bar of the window
+    • `shortcuts`: A list of shortcuts that trigger the `accelerator::Triggered` event
+*/
+#[derive(Clone)]
+pub struct AcceleratorT<S: Clone+Into<String>, ID: Hash+Clone> {
+    pub window: ID,
+    pub items: Vec<(S, ID)>,
+    pub shortcuts: Vec<S>
+}
+
+impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for AcceleratorT<S, ID> {
+    fn type_id(&self) -> TypeId { TypeId::of::<Accelerator>() }
+
+    fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        use user32::{CreateAcceleratorTableW, DrawMenuBar};
+
+        let window = match ui.handle_of(&self.window) {
+            Ok(AnyHandle::HWND(h)) => h,
+            Ok(h) => { return Err(Error::BadParent(format!("An accelerator window must be a window, got {}", h.human_name()))); },
+            Err(e) => { return Err(e); }
+        };
+
+        if ACCELERATORS.with(|a| a.borrow().iter().any(|&(w, _)| w == window)) {
+            return Err(Error::BadParent("The window already has an accelerator table".to_string()));
+        }
+
+        let mut table: Vec<ACCEL> = Vec::with_capacity(self.items.len() + self.shortcuts.len());
+        let mut menu_items: Vec<(HMENU, UINT, Shortcut)> = Vec::with_capacity(self.items.len());
+
+        for &(ref text, ref id) in self.items.iter() {
+            let shortcut = match Shortcut::parse(&text.clone().into()) {
+                Ok(s) => s,
+                Err(e) => { return Err(e); }
+            };
+            let (menu, uid) = match ui.handle_of(id) {
+                Ok(AnyHandle::HMENU_ITEM(menu, uid)) => (menu, uid),
+                Ok(_) => { return Err(Error::BadType); },
+                Err(e) => { return Err(e); }
+            };
+
+            if uid >= SHORTCUT_COMMAND_FLAG as UINT {
+                return Err(Error::BadShortcut(format!("The id of the menu item bound to {} is too big", shortcut)));
+            }
+
+            table.push(accel(&shortcut, uid as WORD));
+            menu_items.push((menu, uid, shortcut));
+        }
+
+        for text in self.shortcuts.iter() {
+            let shortcut = match Shortcut::parse(&text.clone().into()) {
+                Ok(s) => s,
+                Err(e) => { return Err(e); }
+            };
+            table.push(accel(&shortcut, shortcut_command(&shortcut)));
+        }
+
+        let handle = unsafe{ CreateAcceleratorTableW(table.as_mut_ptr(), table.len() as c_int) };
+        if handle.is_null() {
+            return Err(Error::System(SystemError::AcceleratorCreation));
+        }
+
+        ACCELERATORS.with(|a| a.borrow_mut().push((window, handle)));
+
+        for &(menu, uid, ref shortcut) in menu_items.iter() {
+            unsafe{ show_menuitem_shortcut(menu, uid, Some(shortcut)); }
+        }
+        unsafe{ DrawMenuBar(window); }
+
+        let menu_items = menu_items.into_iter().map(|(menu, uid, _)| (menu, uid)).collect();
+        Ok( Box::new(Accelerator{handle: handle, window: window, menu_items: menu_items}) )
+    }
+}
+
+/**
+    An accelerator table
+*/
+pub struct Accelerator {
+    handle: HACCEL,
+    window: HWND,
+    menu_items: Vec<(HMENU, UINT)>
+}
+
+impl Control for Accelerator {
+
+    fn handle(&self) -> AnyHandle {
+        // The events of an accelerator are received by its window
+        AnyHandle::Custom(TypeId::of::<Accelerator>(), self.window as usize)
+    }
+
+    fn control_type(&self) -> ControlType {
+        ControlType::Accelerator
+    }
+
+    fn free(&mut self) {
+        use user32::{DestroyAcceleratorTable, DrawMenuBar};
+
+        let handle = self.handle;
+        ACCELERATORS.with(|a| a.borrow_mut().retain(|&(_, h)| h != handle));
+
+        unsafe{
+            for &(menu, uid) in self.menu_items.iter() {
+                show_menuitem_shortcut(menu, uid, None);
+            }
+            DrawMenuBar(self.window);
+
+            DestroyAcceleratorTable(self.handle);
+        }
+
+        self.handle = ptr::null_mut();
+    }
+
+}
+
+/**
+    Translate the keyboard message if it matches a shortcut of the accelerator table of the focused window.
+    Returns `true` if the message was translated. In this case, the message must not be dispatched.
+*/
+pub unsafe fn translate_accelerator(msg: &MSG) -> bool {
+    use user32::IsChild;
+    use low::defs::TranslateAcceleratorW;
+
+    // Copy the tables because the callbacks executed by `TranslateAcceleratorW` can create or free accelerators
+    let tables = ACCELERATORS.with(|a| a.borrow().clone());
+    for (window, handle) in tables {
+        if window == msg.hwnd || IsChild(window, msg.hwnd) != 0 {
+            return TranslateAcceleratorW(window, handle, msg) != 0;
+        }
+    }
+
+    false
+}
+
+fn accel(shortcut: &Shortcut, cmd: WORD) -> ACCEL {
+    use low::defs::{FVIRTKEY, FSHIFT, FCONTROL, FALT};
+
+    let mut flags = FVIRTKEY;
+    if shortcut.modifiers.shift { flags |= FSHIFT; }
+    if shortcut.modifiers.ctrl { flags |= FCONTROL; }
+    if shortcut.modifiers.alt { flags |= FALT; }
+
+    ACCEL{ fVirt: flags, key: shortcut.key as WORD, cmd: cmd }
+}
+
+/**
+    Display a shortcut after the text of a menu item. If `shortcut` is None, the displayed shortcut is removed.
+    Does nothing if the menu item was freed.
+*/
+unsafe fn show_menuitem_shortcut(menu: HMENU, uid: UINT, shortcut: Option<&Shortcut>) {
+    use low::menu_helper::{get_menuitem_text, set_menuitem_text, find_menuitem_parent};
+
+    if find_menuitem_parent(menu, uid) != Some(menu) {
+        return;
+    }
+
+    // The text after the tab is aligned to the right of the menu
+    let text = get_menuitem_text(menu, uid);
+    let label = text.split('\t').next().unwrap_or("");
+    match shortcut {
+        Some(s) => set_menuitem_text(menu, uid, &format!("{}\t{}", label, s)),
+        None => set_menuitem_text(menu, uid, label)
+    }
+}
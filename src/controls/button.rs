@@ -4,18 +4,28 @@
 
 use std::hash::Hash;
 use std::any::TypeId;
+use std::ptr;
 
-use winapi::{HWND, HFONT};
+use winapi::{HWND, HFONT, HANDLE, UINT, WPARAM, LPARAM, LRESULT, UINT_PTR, DWORD_PTR};
+use user32::SendMessageW;
 
 use ui::Ui;
 use error::Error;
-use controls::{Control, ControlT, ControlType, AnyHandle};
+use defs::ButtonStyle;
+use controls::{Control, ControlT, ControlType, AnyHandle, HandleSpec};
+use low::other_helper::{to_utf16, from_utf16};
+
+/// A magic number to identify the subclass that tracks the mouse over the owner drawn buttons
+const BUTTON_HOT_ID: UINT_PTR = 2466;
+
+/// The window property set while the mouse is over an owner drawn button
+const BUTTON_HOT_PROP: &'static str = "NWG_BUTTON_HOT";
 
 /**
     A template that creates a standard button
 
     Control specific events:  
-    `button::Click, button::DoubleClick, button::Focus`
+    `button::Click, button::DoubleClick, button::Focus, button::DropDown, button::Draw`
 
     Members:  
     • `text`: The text of the button  
@@ -25,6 +35,16 @@ use controls::{Control, ControlT, ControlType, AnyHandle};
     • `disabled`: If the user can or can't click on the button  
     • `parent`: The button parent  
     • `font`: The button font. If None, use the system default  
+    • `tooltip`: A tip displayed when the mouse hovers the button. If None, the button has no tip  
+    • `style`: The style of the button. See `ButtonStyle`  
+    • `image`: An image resource (bitmap or icon) displayed next to the text. If None, the button has no image  
+    • `text_color`: The text color of the button as `[red, green, blue]`. If None, use the system default  
+    • `background_color`: The background color of the button as `[red, green, blue]`. If None, use the system default  
+
+    The colors only apply to the `Push` and `Flat` styles. A button with a color is painted by NWG with its text only, the image is not displayed.  
+
+    Failures:  
+    • `Error::BadResource`: if `image` is not a bitmap or an icon  
 */
 #[derive(Clone)]
 pub struct ButtonT<S: Clone+Into<String>, ID: Hash+Clone> {
@@ -35,16 +55,23 @@ pub struct ButtonT<S: Clone+Into<String>, ID: Hash+Clone> {
     pub disabled: bool,
     pub parent: ID,
     pub font: Option<ID>,
+    pub tooltip: Option<String>,
+    pub style: ButtonStyle,
+    pub image: Option<ID>,
+    pub text_color: Option<[u8; 3]>,
+    pub background_color: Option<[u8; 3]>,
 }
 
 impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for ButtonT<S, ID> {
     fn type_id(&self) -> TypeId { TypeId::of::<Button>() }
 
     fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        use controls::tooltip::set_template_tip;
+        use low::color_helper::set_template_colors;
         use low::window_helper::{WindowParams, build_window, set_window_font_raw, handle_of_window, handle_of_font};
         use winapi::{DWORD, WS_VISIBLE, WS_DISABLED, WS_CHILD, BS_NOTIFY, BS_TEXT, WS_CLIPSIBLINGS, WS_CLIPCHILDREN};
 
-        let flags: DWORD = WS_CHILD | BS_NOTIFY | BS_TEXT | WS_CLIPSIBLINGS | WS_CLIPCHILDREN |
+        let flags: DWORD = WS_CHILD | BS_NOTIFY | BS_TEXT | WS_CLIPSIBLINGS | WS_CLIPCHILDREN | style_flags(self.style) |
         if self.visible    { WS_VISIBLE }   else { 0 } |
         if self.disabled   { WS_DISABLED }  else { 0 };
 
@@ -64,6 +91,16 @@ impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for ButtonT<S, ID> {
             None => None
         };
 
+        // Get the image handle (if any)
+        let image = match self.image.as_ref() {
+            Some(image_id) =>
+                match image_handle(ui, image_id) {
+                    Ok(h) => Some(h),
+                    Err(e) => { return Err(e); }
+                },
+            None => None
+        };
+
         let params = WindowParams {
             title: self.text.clone().into(),
             class_name: "BUTTON",
@@ -77,6 +114,16 @@ impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for ButtonT<S, ID> {
         match unsafe{ build_window(params) } {
             Ok(h) => {
                 unsafe{ set_window_font_raw(h, font_handle, true); }
+                if let Err(e) = unsafe{ set_template_tip(ui, h, &self.tooltip) } {
+                    return Err(e);
+                }
+
+                unsafe {
+                    if let Some((image, image_type)) = image { set_image(h, image, image_type); }
+                    if self.style == ButtonStyle::OwnerDraw { hook_owner_draw(h); }
+                    set_template_colors(h, self.text_color, self.background_color);
+                }
+
                 Ok( Box::new(Button{handle: h}) )
             },
             Err(e) => Err(Error::System(e))
@@ -106,6 +153,101 @@ impl Button {
     pub fn set_font<ID: Hash+Clone>(&self, ui: &Ui<ID>, f: Option<&ID>) -> Result<(), Error> { unsafe{ ::low::window_helper::set_window_font(self.handle, ui, f) } }
     pub fn update(&self) { unsafe{ ::low::window_helper::update(self.handle); } }
     pub fn focus(&self) { unsafe{ ::user32::SetFocus(self.handle); } }
+    pub fn get_text_color(&self) -> Option<[u8; 3]> { ::low::color_helper::get_text_color(self.handle) }
+    pub fn set_text_color(&self, color: Option<[u8; 3]>) { unsafe{ ::low::color_helper::set_text_color(self.handle, color); } }
+    pub fn get_background_color(&self) -> Option<[u8; 3]> { ::low::color_helper::get_background_color(self.handle) }
+    pub fn set_background_color(&self, color: Option<[u8; 3]>) { unsafe{ ::low::color_helper::set_background_color(self.handle, color); } }
+
+    /// Return the style of the button
+    pub fn get_style(&self) -> ButtonStyle {
+        use low::window_helper::get_window_long;
+        use winapi::{GWL_STYLE, BS_TYPEMASK, BS_OWNERDRAW, BS_FLAT, BS_SPLITBUTTON, BS_DEFSPLITBUTTON, BS_COMMANDLINK, BS_DEFCOMMANDLINK};
+
+        let style = get_window_long(self.handle, GWL_STYLE) as UINT;
+        match style & BS_TYPEMASK {
+            BS_OWNERDRAW => ButtonStyle::OwnerDraw,
+            BS_SPLITBUTTON | BS_DEFSPLITBUTTON => ButtonStyle::Split,
+            BS_COMMANDLINK | BS_DEFCOMMANDLINK => ButtonStyle::CommandLink,
+            _ if style & BS_FLAT == BS_FLAT => ButtonStyle::Flat,
+            _ => ButtonStyle::Push
+        }
+    }
+
+    /**
+        Set the image displayed next to the text of the button. Pass `None` to remove the image.
+
+        Failures:  
+        • `Error::BadUi`: if the image and the button are not in the same Ui  
+        • `Error::BadResource`: if the image is not a bitmap or an icon  
+    */
+    pub fn set_image<ID: Hash+Clone>(&self, ui: &Ui<ID>, image: Option<&ID>) -> Result<(), Error> {
+        use winapi::{IMAGE_BITMAP, IMAGE_ICON};
+
+        if !ui.has_handle(&self.handle()) {
+            return Err(Error::BadUi("Image resource and control must be in the same Ui.".to_string()));
+        }
+
+        let image = match image {
+            Some(id) => match image_handle(ui, id) {
+                Ok(h) => h,
+                Err(e) => { return Err(e); }
+            },
+            None => {
+                // Remove both image types, the button may hold a bitmap or an icon
+                unsafe {
+                    set_image(self.handle, ptr::null_mut(), IMAGE_BITMAP);
+                    set_image(self.handle, ptr::null_mut(), IMAGE_ICON);
+                }
+                return Ok(());
+            }
+        };
+
+        unsafe{ set_image(self.handle, image.0, image.1); }
+        Ok(())
+    }
+
+    /// Return the resource identifier of the button image. Return `None` if the button do not have an image.
+    pub fn get_image<ID: Hash+Clone>(&self, ui: &Ui<ID>) -> Option<ID> {
+        use low::defs::BM_GETIMAGE;
+        use winapi::{IMAGE_BITMAP, IMAGE_ICON, HICON};
+
+        let bitmap = unsafe{ SendMessageW(self.handle, BM_GETIMAGE, IMAGE_BITMAP as WPARAM, 0) as HANDLE };
+        let handle = if !bitmap.is_null() {
+            AnyHandle::HANDLE(bitmap, HandleSpec::Bitmap)
+        } else {
+            let icon = unsafe{ SendMessageW(self.handle, BM_GETIMAGE, IMAGE_ICON as WPARAM, 0) as HICON };
+            if icon.is_null() { return None; }
+            AnyHandle::HICON(icon)
+        };
+
+        match ui.id_from_handle(&handle) {
+            Ok(id) => Some(id),
+            Err(_) => None
+        }
+    }
+
+    /// Set the note displayed under the title of a command link. Do nothing if the button is not a command link.
+    pub fn set_note<'a>(&self, note: &'a str) {
+        use winapi::BCM_SETNOTE;
+
+        let note = to_utf16(note);
+        unsafe{ SendMessageW(self.handle, BCM_SETNOTE, 0, note.as_ptr() as LPARAM); }
+    }
+
+    /// Return the note of a command link. Return an empty string if the button is not a command link.
+    pub fn get_note(&self) -> String {
+        use winapi::{DWORD, BCM_GETNOTE, BCM_GETNOTELENGTH};
+
+        let length = unsafe{ SendMessageW(self.handle, BCM_GETNOTELENGTH, 0, 0) } as DWORD;
+        if length == 0 {
+            return String::new();
+        }
+
+        let mut length: DWORD = length + 1;
+        let mut buffer: Vec<u16> = vec![0; length as usize];
+        unsafe{ SendMessageW(self.handle, BCM_GETNOTE, &mut length as *mut DWORD as WPARAM, buffer.as_mut_ptr() as LPARAM); }
+        from_utf16(&buffer)
+    }
 
 }
 
@@ -121,7 +263,99 @@ impl Control for Button {
 
     fn free(&mut self) {
         use user32::DestroyWindow;
-        unsafe{ DestroyWindow(self.handle) };
+        use controls::tooltip::clear_template_tip;
+        use low::color_helper::clear_colors;
+        use controls::window::clear_dialog_button;
+        unsafe{
+            clear_dialog_button(self.handle);
+            clear_template_tip(self.handle);
+            clear_colors(self.handle);
+            unhook_owner_draw(self.handle);
+            DestroyWindow(self.handle);
+        }
+    }
+
+}
+
+fn style_flags(style: ButtonStyle) -> ::winapi::DWORD {
+    use winapi::{BS_PUSHBUTTON, BS_SPLITBUTTON, BS_COMMANDLINK, BS_FLAT, BS_OWNERDRAW};
+
+    match style {
+        ButtonStyle::Push => BS_PUSHBUTTON,
+        ButtonStyle::Split => BS_SPLITBUTTON,
+        ButtonStyle::CommandLink => BS_COMMANDLINK,
+        ButtonStyle::Flat => BS_PUSHBUTTON | BS_FLAT,
+        ButtonStyle::OwnerDraw => BS_OWNERDRAW
+    }
+}
+
+/// Return the handle of an image resource and its image type (`IMAGE_BITMAP` or `IMAGE_ICON`)
+fn image_handle<ID: Hash+Clone>(ui: &Ui<ID>, id: &ID) -> Result<(HANDLE, UINT), Error> {
+    use winapi::{IMAGE_BITMAP, IMAGE_ICON};
+
+    match ui.handle_of(id) {
+        Ok(AnyHandle::HANDLE(h, HandleSpec::Bitmap)) => Ok((h, IMAGE_BITMAP)),
+        Ok(AnyHandle::HICON(h)) => Ok((h as HANDLE, IMAGE_ICON)),
+        Ok(h) => Err(Error::BadResource(format!("The image of a button must be a bitmap or an icon, got {:?}", h))),
+        Err(e) => Err(e)
+    }
+}
+
+unsafe fn set_image(handle: HWND, image: HANDLE, image_type: UINT) {
+    use low::defs::BM_SETIMAGE;
+    SendMessageW(handle, BM_SETIMAGE, image_type as WPARAM, image as LPARAM);
+}
+
+/// Return `true` if the mouse is over an owner drawn button
+pub unsafe fn is_hot(handle: HWND) -> bool {
+    use user32::GetPropW;
+
+    let prop = to_utf16(BUTTON_HOT_PROP);
+    !GetPropW(handle, prop.as_ptr()).is_null()
+}
+
+unsafe fn hook_owner_draw(handle: HWND) {
+    use comctl32::SetWindowSubclass;
+    SetWindowSubclass(handle, Some(owner_draw_proc), BUTTON_HOT_ID, 0);
+}
+
+unsafe fn unhook_owner_draw(handle: HWND) {
+    use comctl32::RemoveWindowSubclass;
+    use user32::RemovePropW;
+
+    let prop = to_utf16(BUTTON_HOT_PROP);
+    RemovePropW(handle, prop.as_ptr());
+    RemoveWindowSubclass(handle, Some(owner_draw_proc), BUTTON_HOT_ID);
+}
+
+/// Track the mouse over an owner drawn button and repaint the button when the mouse enters or leaves it
+unsafe extern "system" fn owner_draw_proc(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM, _id: UINT_PTR, _data: DWORD_PTR) -> LRESULT {
+    use comctl32::DefSubclassProc;
+    use user32::{TrackMouseEvent, SetPropW, RemovePropW, InvalidateRect};
+    use winapi::{WM_MOUSEMOVE, WM_MOUSELEAVE, TRACKMOUSEEVENT, TME_LEAVE, DWORD};
+    use std::mem;
+
+    match msg {
+        WM_MOUSEMOVE => if !is_hot(hwnd) {
+            let mut track = TRACKMOUSEEVENT {
+                cbSize: mem::size_of::<TRACKMOUSEEVENT>() as DWORD,
+                dwFlags: TME_LEAVE,
+                hwndTrack: hwnd,
+                dwHoverTime: 0
+            };
+
+            let prop = to_utf16(BUTTON_HOT_PROP);
+            TrackMouseEvent(&mut track);
+            SetPropW(hwnd, prop.as_ptr(), 1 as HANDLE);
+            InvalidateRect(hwnd, ptr::null(), 0);
+        },
+        WM_MOUSELEAVE => {
+            let prop = to_utf16(BUTTON_HOT_PROP);
+            RemovePropW(hwnd, prop.as_ptr());
+            InvalidateRect(hwnd, ptr::null(), 0);
+        },
+        _ => {}
     }
 
+    DefSubclassProc(hwnd, msg, w, l)
 }
\ No newline at end of file
@@ -30,6 +30,9 @@ use defs::CheckState;
     • `checkstate`: The starting checkstate  
     • `tristate`: If the checkbox should have three states  
     • `font`: The checkbox font. If None, use the system default  
+    • `tooltip`: A tip displayed when the mouse hovers the checkbox. If None, the checkbox has no tip  
+    • `text_color`: The text color of the checkbox as `[red, green, blue]`. A text color removes the visual style of the checkbox, so the text color of the parent is not displayed. If None, use the system default  
+    • `background_color`: The background color of the checkbox as `[red, green, blue]`. If None, use the color of the parent or the system default  
 */
 #[derive(Clone)]
 pub struct CheckBoxT<S: Clone+Into<String>, ID: Hash+Clone> {
@@ -42,12 +45,17 @@ pub struct CheckBoxT<S: Clone+Into<String>, ID: Hash+Clone> {
     pub checkstate: CheckState,
     pub tristate: bool,
     pub font: Option<ID>,
+    pub tooltip: Option<String>,
+    pub text_color: Option<[u8; 3]>,
+    pub background_color: Option<[u8; 3]>,
 }
 
 impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for CheckBoxT<S, ID> {
     fn type_id(&self) -> TypeId { TypeId::of::<CheckBox>() }
 
     fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        use controls::tooltip::set_template_tip;
+        use low::color_helper::set_template_colors;
         use low::window_helper::{WindowParams, build_window, set_window_font_raw, handle_of_window, handle_of_font};
         use winapi::{DWORD, WS_VISIBLE, WS_DISABLED, WS_CHILD, BS_NOTIFY, BS_AUTO3STATE, BS_AUTOCHECKBOX, BS_TEXT};
 
@@ -88,6 +96,12 @@ impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for CheckBoxT<S, ID> {
                     set_window_font_raw(h, font_handle, true); 
                     set_checkstate(h, &self.checkstate);
                 }
+                if let Err(e) = unsafe{ set_template_tip(ui, h, &self.tooltip) } {
+                    return Err(e);
+                }
+
+                unsafe{ set_template_colors(h, self.text_color, self.background_color); }
+
                 Ok( Box::new(CheckBox{handle: h}) )
             },
             Err(e) => Err(Error::System(e))
@@ -137,6 +151,10 @@ impl CheckBox {
     pub fn set_font<ID: Hash+Clone>(&self, ui: &Ui<ID>, f: Option<&ID>) -> Result<(), Error> { unsafe{ ::low::window_helper::set_window_font(self.handle, ui, f) } }
     pub fn update(&self) { unsafe{ ::low::window_helper::update(self.handle); } }
     pub fn focus(&self) { unsafe{ ::user32::SetFocus(self.handle); } }
+    pub fn get_text_color(&self) -> Option<[u8; 3]> { ::low::color_helper::get_text_color(self.handle) }
+    pub fn set_text_color(&self, color: Option<[u8; 3]>) { unsafe{ ::low::color_helper::set_text_color(self.handle, color); } }
+    pub fn get_background_color(&self) -> Option<[u8; 3]> { ::low::color_helper::get_background_color(self.handle) }
+    pub fn set_background_color(&self, color: Option<[u8; 3]>) { unsafe{ ::low::color_helper::set_background_color(self.handle, color); } }
 }
 
 impl Control for CheckBox {
@@ -151,7 +169,13 @@ impl Control for CheckBox {
 
     fn free(&mut self) {
         use user32::DestroyWindow;
-        unsafe{ DestroyWindow(self.handle) };
+        use controls::tooltip::clear_template_tip;
+        use low::color_helper::clear_colors;
+        unsafe{
+            clear_template_tip(self.handle);
+            clear_colors(self.handle);
+            DestroyWindow(self.handle);
+        }
     }
 
 }
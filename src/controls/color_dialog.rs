@@ -0,0 +1,184 @@
+/*!
+    Color dialog control definition
+*/
+
+use std::hash::Hash;
+use std::any::TypeId;
+use std::cell::{Cell, RefCell};
+use std::ptr;
+use std::mem;
+
+use winapi::{HWND, UINT, COLORREF};
+
+use ui::Ui;
+use controls::{Control, ControlT, ControlType, AnyHandle};
+use low::other_helper::{to_colorref, from_colorref};
+use error::Error;
+
+static mut COLOR_DIALOG_ID: UINT = 0;
+
+/// The number of custom color slots in a color dialog
+const CUSTOM_COLORS_COUNT: usize = 16;
+
+/**
+    A template that creates a standard color dialog
+
+    Control specific events: None  
+
+    Members:  
+    • `parent`: The dialog parent window. While the dialog is running, the parent will be disabled.  
+    • `color`: The color selected when the dialog is opened, as `[red, green, blue]`  
+    • `custom_colors`: The colors of the custom color slots. At most 16. The remaining slots are white  
+    • `full_open`: If the custom colors editor should be displayed when the dialog is opened  
+
+    Failures:  
+    • `Error::UserError`: if there are more than 16 custom colors  
+*/
+#[derive(Clone)]
+pub struct ColorDialogT<ID: Hash+Clone> {
+    pub parent: Option<ID>,
+    pub color: [u8; 3],
+    pub custom_colors: Vec<[u8; 3]>,
+    pub full_open: bool
+}
+
+impl<ID: Hash+Clone> ControlT<ID> for ColorDialogT<ID> {
+    fn type_id(&self) -> TypeId { TypeId::of::<ColorDialog>() }
+
+    fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        use low::window_helper::handle_of_window;
+
+        let parent = match self.parent.as_ref() {
+            Some(id) =>
+            match handle_of_window(ui, id, "The parent of a ColorDialog must be a window-like control.") {
+                Ok(h) => h,
+                Err(e) => { return Err(e); }
+            },
+            None => ptr::null_mut()
+        };
+
+        let custom_colors = match custom_colors(&self.custom_colors) {
+            Ok(c) => c,
+            Err(e) => { return Err(e); }
+        };
+
+        Ok(Box::new(
+            ColorDialog{
+                parent: parent,
+                id: unsafe{ COLOR_DIALOG_ID+=1; COLOR_DIALOG_ID },
+                color: Cell::new(self.color),
+                custom_colors: RefCell::new(custom_colors),
+                full_open: Cell::new(self.full_open)
+            }
+        ) as Box<Control>)
+    }
+}
+
+/**
+    A color dialog control
+*/
+pub struct ColorDialog {
+    parent: HWND,
+    id: UINT,
+    color: Cell<[u8; 3]>,
+    custom_colors: RefCell<[COLORREF; CUSTOM_COLORS_COUNT]>,
+    full_open: Cell<bool>
+}
+
+impl ColorDialog {
+
+    /**
+        Display the dialog. Return true if the dialog was accepted or false if it was cancelled.
+        If the dialog was accepted, the selected color can be read with `get_color`. The custom colors edited by the user are kept.
+    */
+    pub fn run(&self) -> bool {
+        use low::defs::ChooseColorW;
+        use winapi::{CHOOSECOLORW, CC_RGBINIT, CC_ANYCOLOR, CC_FULLOPEN};
+
+        let mut custom_colors = self.custom_colors.borrow_mut();
+        let full_open = if self.full_open.get() { CC_FULLOPEN } else { 0 };
+
+        let mut cc = CHOOSECOLORW {
+            lStructSize: mem::size_of::<CHOOSECOLORW>() as u32,
+            hwndOwner: self.parent,
+            hInstance: ptr::null_mut(),
+            rgbResult: to_colorref(self.color.get()),
+            lpCustColors: custom_colors.as_mut_ptr(),
+            Flags: CC_RGBINIT | CC_ANYCOLOR | full_open,
+            lCustData: 0,
+            lpfnHook: None,
+            lpTemplateName: ptr::null()
+        };
+
+        if unsafe{ ChooseColorW(&mut cc) } != 0 {
+            self.color.set(from_colorref(cc.rgbResult));
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Return the color selected by the user as `[red, green, blue]`. Before the dialog ran, return the initial color.
+    pub fn get_color(&self) -> [u8; 3] {
+        self.color.get()
+    }
+
+    /// Set the color selected when the dialog is opened
+    pub fn set_color(&self, color: [u8; 3]) {
+        self.color.set(color);
+    }
+
+    /// Return the colors of the 16 custom color slots
+    pub fn get_custom_colors(&self) -> Vec<[u8; 3]> {
+        self.custom_colors.borrow().iter().map(|&c| from_colorref(c)).collect()
+    }
+
+    /**
+        Set the colors of the custom color slots. The slots that are not in `colors` are white.
+
+        Failures:  
+        • `Error::UserError`: if there are more than 16 colors  
+    */
+    pub fn set_custom_colors(&self, colors: &[[u8; 3]]) -> Result<(), Error> {
+        match custom_colors(colors) {
+            Ok(c) => { *self.custom_colors.borrow_mut() = c; Ok(()) },
+            Err(e) => Err(e)
+        }
+    }
+
+    /// Return `true` if the custom colors editor is displayed when the dialog is opened
+    pub fn get_full_open(&self) -> bool {
+        self.full_open.get()
+    }
+
+    /// Display or hide the custom colors editor when the dialog is opened
+    pub fn set_full_open(&self, full_open: bool) {
+        self.full_open.set(full_open);
+    }
+}
+
+impl Control for ColorDialog {
+
+    fn handle(&self) -> AnyHandle {
+        AnyHandle::Custom(TypeId::of::<ColorDialog>(), self.id as usize)
+    }
+
+    fn control_type(&self) -> ControlType {
+        ControlType::ColorDialog
+    }
+
+}
+
+fn custom_colors(colors: &[[u8; 3]]) -> Result<[COLORREF; CUSTOM_COLORS_COUNT], Error> {
+    if colors.len() > CUSTOM_COLORS_COUNT {
+        let msg = format!("A color dialog has {} custom colors, got {}", CUSTOM_COLORS_COUNT, colors.len());
+        return Err(Error::UserError(msg));
+    }
+
+    let mut custom_colors = [to_colorref([255, 255, 255]); CUSTOM_COLORS_COUNT];
+    for (slot, &color) in custom_colors.iter_mut().zip(colors.iter()) {
+        *slot = to_colorref(color);
+    }
+
+    Ok(custom_colors)
+}
@@ -28,6 +28,7 @@ use low::other_helper::{to_utf16, from_utf16};
     • `disabled`: If the user can or can't use the combobox   
     • `parent`: The combobox parent  
     • `font`: The combobox font. If None, use the system default  
+    • `tooltip`: A tip displayed when the mouse hovers the combobox. If None, the combobox has no tip  
 */
 #[derive(Clone)]
 pub struct ComboBoxT<D: Clone+Display+'static, ID: Hash+Clone, S: Clone+Into<String>> {
@@ -39,12 +40,14 @@ pub struct ComboBoxT<D: Clone+Display+'static, ID: Hash+Clone, S: Clone+Into<Str
     pub placeholder: Option<S>,
     pub parent: ID,
     pub font: Option<ID>,
+    pub tooltip: Option<String>,
 }
 
 impl<D: Clone+Display+'static, ID: Hash+Clone, S: Clone+Into<String>> ControlT<ID> for ComboBoxT<D, ID, S> {
     fn type_id(&self) -> TypeId { TypeId::of::<ComboBox<D>>() }
 
     fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        use controls::tooltip::set_template_tip;
         use low::window_helper::{WindowParams, build_window, set_window_font_raw, handle_of_window, handle_of_font};
         use low::defs::{CBS_DROPDOWNLIST, CBS_HASSTRINGS, CB_ADDSTRING};
         use winapi::{DWORD, WS_VISIBLE, WS_DISABLED, WS_CHILD};
@@ -102,6 +105,10 @@ impl<D: Clone+Display+'static, ID: Hash+Clone, S: Clone+Into<String>> ControlT<I
                     ).collect();
 
 
+                    if let Err(e) = set_template_tip(ui, h, &self.tooltip) {
+                        return Err(e);
+                    }
+
                     Ok( Box::new(ComboBox{handle: h, collection: collection}) )
                 }
             },
@@ -299,7 +306,11 @@ impl<D: Clone+Display> Control for ComboBox<D> {
 
     fn free(&mut self) {
         use user32::DestroyWindow;
-        unsafe{ DestroyWindow(self.handle) };
+        use controls::tooltip::clear_template_tip;
+        unsafe{
+            clear_template_tip(self.handle);
+            DestroyWindow(self.handle);
+        }
     }
 }
 
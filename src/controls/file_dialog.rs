@@ -4,40 +4,47 @@
 
 use std::hash::Hash;
 use std::any::TypeId;
+use std::cell::Cell;
+use std::path::PathBuf;
 use std::ptr;
 use std::mem;
 
-use winapi::{S_OK, HWND, IShellItem, FILEOPENDIALOGOPTIONS};
-use winapi::shobjidl::IFileDialog;
+use winapi::{S_OK, HWND, IShellItem, FILEOPENDIALOGOPTIONS, DWORD, UINT, ULONG, HRESULT, REFIID, c_void, IUnknown, IUnknownVtbl};
+use winapi::shobjidl::{IFileDialog, IFileDialogEvents, IFileDialogEventsVtbl, FDE_SHAREVIOLATION_RESPONSE, FDE_OVERWRITE_RESPONSE};
 
 use ui::Ui;
 use controls::{Control, ControlT, ControlType, AnyHandle};
 use error::{Error, SystemError};
 use defs::FileDialogAction;
+use file_filter::FileFilters;
 use low::other_helper::to_utf16;
 
 /**
     A template that creates a standard file dialog
 
-    Control specific events: None    
+    Control specific events:  
+    `file_dialog::FolderChanged, file_dialog::SelectionChanged` (sent while the dialog is running)  
 
     Members:  
     • `title`: The title of the dialog  
     • `parent`: The dialog parent window. While the dialog is running, the parent will be disabled.  
     • `action`: The action that the dialog will execute. It can be `Open`, `OpenDirectory` or `Save`  
-    • `multiselect`: The user can select more than one choice.  
+    • `multiselect`: The user can select more than one choice. With `OpenDirectory`, the user can select more than one folder.  
     • `default_folder`: If defined, this defines the default folder that is openened when `run` is called  
-    • `filters`: If defined, filter the files that the user can select (In a `Open` dialog) or which extension to add to the saved file (in a `Save` dialog)
+    • `filters`: If defined, filter the files that the user can select (In a `Open` dialog) or which extension to add to the saved file (in a `Save` dialog)  
+    • `default_name`: If defined, the file name displayed when the dialog is opened  
+    • `default_extension`: If defined, the extension added to the file name if the user do not type one (without the dot). Ex: "txt"  
+    • `overwrite_prompt`: Ask the user to confirm before overwriting an existing file. Only used by the `Save` dialogs  
 
     Failures:  
     • `Error::UserError`: if the default folder do not identify a folder  
-    • `Error::UserError`: if the extension filter format is not valid  
+    • `Error::BadFilter`: if the extension filter format is not valid  
     • `Error::System`: if the folder do not exists  
-    • `Error::System`: if the extensions filters could not be set  
+    • `Error::System`: if the extensions filters, the default name or the default extension could not be set  
 
 
     Example of filters:
-    "txt files (*.txt)", "Test(.txt;.rs)|Any(.)"
+    "txt files (*.txt)", "Test(*.txt;*.rs)|Any(*.*)". See `FileFilters` to build the filters without a string.
 
 */
 #[derive(Clone)]
@@ -47,7 +54,10 @@ pub struct FileDialogT<S: Clone+Into<String>, ID: Hash+Clone> {
     pub action: FileDialogAction,
     pub multiselect: bool,
     pub default_folder: Option<S>,
-    pub filters: Option<S>
+    pub filters: Option<S>,
+    pub default_name: Option<S>,
+    pub default_extension: Option<S>,
+    pub overwrite_prompt: bool
 }
 
 impl<S1: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for FileDialogT<S1, ID> {
@@ -55,7 +65,7 @@ impl<S1: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for FileDialogT<S1, ID
 
     fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
         use ole32::{CoCreateInstance};
-        use winapi::{CLSCTX_INPROC_SERVER, FOS_FORCEFILESYSTEM, FOS_PICKFOLDERS, FOS_ALLOWMULTISELECT};
+        use winapi::{CLSCTX_INPROC_SERVER, FOS_FORCEFILESYSTEM, FOS_PICKFOLDERS, FOS_ALLOWMULTISELECT, FOS_OVERWRITEPROMPT};
         use low::clsid::{CLSID_FileOpenDialog, CLSID_FileSaveDialog, UUIDOF_IFileOpenDialog, UUIDOF_IFileDialog};
         use low::window_helper::handle_of_window;
 
@@ -91,7 +101,9 @@ impl<S1: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for FileDialogT<S1, ID
 
             let use_dir = if self.action == FileDialogAction::OpenDirectory { FOS_PICKFOLDERS } else { FILEOPENDIALOGOPTIONS(0) };
             let multiselect = if self.multiselect { FOS_ALLOWMULTISELECT } else { FILEOPENDIALOGOPTIONS(0) };
-            if pfd.SetOptions(flags | FOS_FORCEFILESYSTEM | use_dir | multiselect) != S_OK {
+            let overwrite_prompt = if self.action == FileDialogAction::Save && self.overwrite_prompt { FOS_OVERWRITEPROMPT } else { FILEOPENDIALOGOPTIONS(0) };
+            flags = flags & (!FOS_OVERWRITEPROMPT);
+            if pfd.SetOptions(flags | FOS_FORCEFILESYSTEM | use_dir | multiselect | overwrite_prompt) != S_OK {
                 pfd.Release();
                 return Err(Error::System(SystemError::ComError("Failed to set the file dialog options".to_string())));
             }
@@ -113,13 +125,50 @@ impl<S1: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for FileDialogT<S1, ID
                 },
                 &None => ()
             }
+
+            // Set the default name and extension
+            match &self.default_name {
+                &Some(ref n) => match set_default_name(pfd, &n.clone().into()) {
+                    Ok(_) => (),
+                    Err(e) => { pfd.Release(); return Err(e); }
+                },
+                &None => ()
+            }
+
+            match &self.default_extension {
+                &Some(ref e) => match set_default_extension(pfd, &e.clone().into()) {
+                    Ok(_) => (),
+                    Err(e) => { pfd.Release(); return Err(e); }
+                },
+                &None => ()
+            }
+        }
+
+        // Forward the folder and selection changes to the ui
+        let events = Box::into_raw(Box::new(FileDialogEvents {
+            vtbl: &FILE_DIALOG_EVENTS_VTBL,
+            refs: Cell::new(1),
+            watcher: unsafe{ ui.message_handle() },
+            dialog: handle as usize
+        }));
+
+        let mut cookie: DWORD = 0;
+        unsafe {
+            let pfd = &mut *handle;
+            if pfd.Advise(events as *mut IFileDialogEvents, &mut cookie) != S_OK {
+                events_release(events as *mut IUnknown);
+                pfd.Release();
+                return Err(Error::System(SystemError::ComError("Failed to register the file dialog events".to_string())));
+            }
         }
         
         Ok(Box::new(
             FileDialog{
                 handle: handle, parent: parent,
                 action: self.action.clone(),
-                multiselect: self.multiselect
+                multiselect: self.multiselect,
+                events: events,
+                cookie: cookie
             }
         )as Box<Control>)
     }
@@ -132,7 +181,9 @@ pub struct FileDialog {
     parent: HWND,
     handle: *mut IFileDialog,
     action: FileDialogAction,
-    multiselect: bool
+    multiselect: bool,
+    events: *mut FileDialogEvents,
+    cookie: DWORD
 }
 
 
@@ -205,6 +256,60 @@ impl FileDialog {
 
         Ok(item_names)
     }}
+
+    /**
+        Return the path selected in the dialog by the user. See `get_selected_item`.
+    */
+    pub fn get_selected_path(&self) -> Result<PathBuf, Error> {
+        self.get_selected_item().map(PathBuf::from)
+    }
+
+    /**
+        Return the paths selected in the dialog by the user. See `get_selected_items`.
+    */
+    pub fn get_selected_paths(&self) -> Result<Vec<PathBuf>, Error> {
+        self.get_selected_items().map(|items| items.into_iter().map(PathBuf::from).collect())
+    }
+
+    /**
+        Return the folder displayed by the dialog. Can be called from a `file_dialog::FolderChanged` callback.
+
+        Failures:  
+        • `Error::System` if there was a system error while reading the folder  
+    */
+    pub fn get_current_folder(&self) -> Result<PathBuf, Error> { unsafe {
+        let handle = &mut *self.handle;
+        let mut _item: *mut IShellItem = ptr::null_mut();
+
+        if handle.GetFolder(&mut _item) != S_OK {
+            return Err(Error::System(SystemError::ComError("Failed to get the current folder".to_string())));
+        }
+
+        let path = get_ishellitem_path(&mut *_item);
+        (&mut *_item).Release();
+
+        path.map(PathBuf::from)
+    }}
+
+    /**
+        Return the item selected in the dialog while it is running. Can be called from a `file_dialog::SelectionChanged` callback.
+
+        Failures:  
+        • `Error::System` if there is no selection or if there was a system error while reading the selection  
+    */
+    pub fn get_current_selection(&self) -> Result<PathBuf, Error> { unsafe {
+        let handle = &mut *self.handle;
+        let mut _item: *mut IShellItem = ptr::null_mut();
+
+        if handle.GetCurrentSelection(&mut _item) != S_OK {
+            return Err(Error::System(SystemError::ComError("Failed to get the current selection".to_string())));
+        }
+
+        let path = get_ishellitem_path(&mut *_item);
+        (&mut *_item).Release();
+
+        path.map(PathBuf::from)
+    }}
     
     /// Return the action type executed by this dialog
     pub fn action(&self) -> FileDialogAction {
@@ -256,7 +361,8 @@ impl FileDialog {
         This can only be set ONCE (the initialization counts) and won't work if the dialog is `OpenDirectory`.  
        
         The `filters` value must be a '|' separated string having this format: "Test(*.txt;*.rs)|Any(*.*)"  
-        Where the fist part is the "human name" and the second part is a filter for the system.
+        Where the fist part is the "human name" and the second part is a filter for the system.  
+        Returns `Error::BadFilter` if the string is not valid (see `FileFilters::parse`).
     */
     pub fn set_filters<'a>(&self, filters: &'a str) -> Result<(), Error> {
         unsafe{ 
@@ -265,6 +371,62 @@ impl FileDialog {
         }
     }
 
+    /**
+        Set the file filters of the dialog. See `set_filters`.
+
+        Failures:  
+        • `Error::BadFilter` if the filter list is not valid  
+        • `Error::System` if the filters could not be set  
+    */
+    pub fn set_file_filters(&self, filters: &FileFilters) -> Result<(), Error> {
+        unsafe{
+            let handle = &mut *self.handle;
+            set_file_types(handle, filters)
+        }
+    }
+
+    /// Set the file name displayed in the dialog
+    pub fn set_default_name<'a>(&self, name: &'a str) -> Result<(), Error> {
+        unsafe{
+            let handle = &mut *self.handle;
+            set_default_name(handle, name)
+        }
+    }
+
+    /// Set the extension added to the file name if the user do not type one (without the dot). Ex: "txt"
+    pub fn set_default_extension<'a>(&self, extension: &'a str) -> Result<(), Error> {
+        unsafe{
+            let handle = &mut *self.handle;
+            set_default_extension(handle, extension)
+        }
+    }
+
+    /// Return `true` if the dialog asks the user to confirm before overwriting an existing file
+    pub fn get_overwrite_prompt(&self) -> bool {
+        use winapi::FOS_OVERWRITEPROMPT;
+
+        let mut flags: FILEOPENDIALOGOPTIONS = FILEOPENDIALOGOPTIONS(0);
+        unsafe{ (&mut *self.handle).GetOptions(&mut flags); }
+        (flags & FOS_OVERWRITEPROMPT) == FOS_OVERWRITEPROMPT
+    }
+
+    /**
+        Ask the user to confirm before overwriting an existing file.
+
+        Failures:  
+        • `Error::System` if there was a system error while setting the new flag value  
+        • `Error::UserError` if the dialog do not have `Save` for action  
+    */
+    pub fn set_overwrite_prompt(&self, overwrite_prompt: bool) -> Result<(), Error> {
+        use winapi::FOS_OVERWRITEPROMPT;
+
+        if self.action != FileDialogAction::Save {
+            return Err(Error::UserError("Only a save file dialog can prompt before overwriting a file".to_string()));
+        }
+
+        unsafe{ toggle_dialog_flags(&mut *self.handle, FOS_OVERWRITEPROMPT, overwrite_prompt) }
+    }
+
     /// Change the dialog title
     pub fn set_title<'a>(&self, title: &'a str) { unsafe{
         let handle = &mut *self.handle;
@@ -299,6 +461,8 @@ impl Control for FileDialog {
     fn free(&mut self) {
         unsafe{
             let handle = &mut*self.handle;
+            handle.Unadvise(self.cookie);
+            events_release(self.events as *mut IUnknown);
             handle.Release(); 
         }
     }
@@ -368,31 +532,54 @@ unsafe fn set_default_folder<S: Clone+Into<String>>(dialog: &mut IFileDialog, fo
 
 #[inline(always)]
 unsafe fn set_filters<S: Clone+Into<String>>(dialog: &mut IFileDialog, filters: &S) -> Result<(), Error> {
+    match FileFilters::parse(&filters.clone().into()) {
+        Ok(f) => set_file_types(dialog, &f),
+        Err(e) => Err(e)
+    }
+}
+
+unsafe fn set_file_types(dialog: &mut IFileDialog, filters: &FileFilters) -> Result<(), Error> {
     use winapi::{COMDLG_FILTERSPEC, UINT};
 
-    let filters = filters.clone().into();
-    let mut raw_filters: Vec<COMDLG_FILTERSPEC> = Vec::with_capacity(3);
-    let mut keep_alive: Vec<(Vec<u16>, Vec<u16>)> = Vec::with_capacity(3);
+    if let Err(e) = filters.validate() {
+        return Err(e);
+    }
 
-    for f in filters.split('|') {
-        let end = f.rfind('(');
-        if end.is_none() {
-            let msg = format!("Bad extension filter format: {:?}", filters);
-            return Err(Error::UserError(msg));
-        }
+    let mut raw_filters: Vec<COMDLG_FILTERSPEC> = Vec::with_capacity(filters.len());
+    let mut keep_alive: Vec<(Vec<u16>, Vec<u16>)> = Vec::with_capacity(filters.len());
 
-        let (_name, _filter) = f.split_at(end.unwrap());
-        let (name, filter) = (to_utf16(_name), to_utf16(&_filter[1.._filter.len()-1]));
+    for f in filters.filters().iter() {
+        let (name, spec) = (to_utf16(&f.name), to_utf16(&f.spec()));
         
-        raw_filters.push(COMDLG_FILTERSPEC{ pszName: name.as_ptr(), pszSpec: filter.as_ptr() });
-        keep_alive.push( (name, filter) );
+        raw_filters.push(COMDLG_FILTERSPEC{ pszName: name.as_ptr(), pszSpec: spec.as_ptr() });
+        keep_alive.push( (name, spec) );
     }
 
     let filters_count = raw_filters.len() as UINT;
     if dialog.SetFileTypes(filters_count, raw_filters.as_ptr()) == S_OK {
         Ok(())
     } else {
-        let msg = format!("Failed to set the filters using {:?}", filters);
+        let msg = format!("Failed to set the filters using {:?}", filters.to_string());
+        Err(Error::System(SystemError::ComError(msg)))
+    }
+}
+
+unsafe fn set_default_name(dialog: &mut IFileDialog, name: &str) -> Result<(), Error> {
+    let name_raw = to_utf16(name);
+    if dialog.SetFileName(name_raw.as_ptr()) == S_OK {
+        Ok(())
+    } else {
+        let msg = format!("Failed to set the dialog default name {:?}", name);
+        Err(Error::System(SystemError::ComError(msg)))
+    }
+}
+
+unsafe fn set_default_extension(dialog: &mut IFileDialog, extension: &str) -> Result<(), Error> {
+    let extension_raw = to_utf16(extension.trim_start_matches('.'));
+    if dialog.SetDefaultExtension(extension_raw.as_ptr()) == S_OK {
+        Ok(())
+    } else {
+        let msg = format!("Failed to set the dialog default extension {:?}", extension);
         Err(Error::System(SystemError::ComError(msg)))
     }
 }
@@ -414,4 +601,109 @@ unsafe fn toggle_dialog_flags(dialog: &mut IFileDialog, flag: FILEOPENDIALOGOPTI
     } else {
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/**
+    The `IFileDialogEvents` implementation registered on every file dialog. It sends `NWG_FILE_DIALOG` to the
+    Ui hidden window when the folder or the selection changes. The object is freed when its last reference is released.
+*/
+#[repr(C)]
+struct FileDialogEvents {
+    vtbl: *const IFileDialogEventsVtbl,
+    refs: Cell<ULONG>,
+    watcher: HWND,
+    dialog: usize
+}
+
+static FILE_DIALOG_EVENTS_VTBL: IFileDialogEventsVtbl = IFileDialogEventsVtbl {
+    parent: IUnknownVtbl {
+        QueryInterface: events_query_interface,
+        AddRef: events_add_ref,
+        Release: events_release
+    },
+    OnFileOk: events_ignored,
+    OnFolderChanging: events_folder_changing,
+    OnFolderChange: events_folder_change,
+    OnSelectionChange: events_selection_change,
+    OnShareViolation: events_share_violation,
+    OnTypeChange: events_ignored,
+    OnOverwrite: events_overwrite
+};
+
+unsafe extern "system" fn events_query_interface(this: *mut IUnknown, riid: REFIID, ppv: *mut *mut c_void) -> HRESULT {
+    use winapi::{GUID, E_NOINTERFACE};
+    use low::clsid::{IID_IUnknown, IID_IFileDialogEvents};
+
+    fn same_guid(a: &GUID, b: &GUID) -> bool {
+        a.Data1 == b.Data1 && a.Data2 == b.Data2 && a.Data3 == b.Data3 && a.Data4 == b.Data4
+    }
+
+    let riid = &*riid;
+    if same_guid(riid, &IID_IUnknown()) || same_guid(riid, &IID_IFileDialogEvents()) {
+        *ppv = this as *mut c_void;
+        events_add_ref(this);
+        S_OK
+    } else {
+        *ppv = ptr::null_mut();
+        E_NOINTERFACE
+    }
+}
+
+unsafe extern "system" fn events_add_ref(this: *mut IUnknown) -> ULONG {
+    let events = &*(this as *mut FileDialogEvents);
+    events.refs.set(events.refs.get() + 1);
+    events.refs.get()
+}
+
+unsafe extern "system" fn events_release(this: *mut IUnknown) -> ULONG {
+    let events = this as *mut FileDialogEvents;
+    let refs = (*events).refs.get() - 1;
+    (*events).refs.set(refs);
+
+    if refs == 0 {
+        drop(Box::from_raw(events));
+    }
+
+    refs
+}
+
+unsafe extern "system" fn events_ignored(_this: *mut IFileDialogEvents, _pfd: *mut IFileDialog) -> HRESULT {
+    S_OK
+}
+
+unsafe extern "system" fn events_folder_changing(_this: *mut IFileDialogEvents, _pfd: *mut IFileDialog, _folder: *mut IShellItem) -> HRESULT {
+    S_OK
+}
+
+unsafe extern "system" fn events_folder_change(this: *mut IFileDialogEvents, _pfd: *mut IFileDialog) -> HRESULT {
+    use low::defs::FILE_DIALOG_FOLDER_CHANGED;
+    send_event(this, FILE_DIALOG_FOLDER_CHANGED);
+    S_OK
+}
+
+unsafe extern "system" fn events_selection_change(this: *mut IFileDialogEvents, _pfd: *mut IFileDialog) -> HRESULT {
+    use low::defs::FILE_DIALOG_SELECTION_CHANGED;
+    send_event(this, FILE_DIALOG_SELECTION_CHANGED);
+    S_OK
+}
+
+unsafe extern "system" fn events_share_violation(_this: *mut IFileDialogEvents, _pfd: *mut IFileDialog, _item: *mut IShellItem, response: *mut FDE_SHAREVIOLATION_RESPONSE) -> HRESULT {
+    use winapi::FDESVR_DEFAULT;
+    *response = FDESVR_DEFAULT;
+    S_OK
+}
+
+unsafe extern "system" fn events_overwrite(_this: *mut IFileDialogEvents, _pfd: *mut IFileDialog, _item: *mut IShellItem, response: *mut FDE_OVERWRITE_RESPONSE) -> HRESULT {
+    use winapi::FDEOR_DEFAULT;
+    *response = FDEOR_DEFAULT;
+    S_OK
+}
+
+unsafe fn send_event(this: *mut IFileDialogEvents, kind: UINT) {
+    use user32::SendMessageW;
+    use winapi::{WPARAM, LPARAM};
+    use low::defs::NWG_FILE_DIALOG;
+
+    let events = &*(this as *mut FileDialogEvents);
+    SendMessageW(events.watcher, NWG_FILE_DIALOG, kind as WPARAM, events.dialog as LPARAM);
+}
@@ -0,0 +1,201 @@
+/*!
+    Font dialog control definition
+*/
+
+use std::hash::Hash;
+use std::any::TypeId;
+use std::cell::{Cell, RefCell};
+use std::ptr;
+use std::mem;
+
+use winapi::{HWND, UINT, LOGFONTW, c_int};
+
+use ui::Ui;
+use controls::{Control, ControlT, ControlType, AnyHandle};
+use resources::FontT;
+use defs::{FONT_DECO_ITALIC, FONT_DECO_UNDERLINE, FONT_DECO_STRIKEOUT};
+use low::other_helper::{to_utf16, from_utf16, to_colorref, from_colorref};
+use error::Error;
+
+static mut FONT_DIALOG_ID: UINT = 0;
+
+/**
+    A template that creates a standard font dialog. The font selected by the user is returned as a `FontT` that can be packed in a Ui.
+
+    Control specific events: None  
+
+    Members:  
+    • `parent`: The dialog parent window. While the dialog is running, the parent will be disabled.  
+    • `font`: The font selected when the dialog is opened. If `None`, the system picks the font  
+    • `color`: The text color selected when the dialog is opened, as `[red, green, blue]`. Only used if `effects` is true  
+    • `effects`: If the user can select the underline and strikeout decorations and the text color  
+    • `fixed_pitch_only`: Only list the fixed pitch fonts  
+*/
+#[derive(Clone)]
+pub struct FontDialogT<S: Clone+Into<String>, ID: Hash+Clone> {
+    pub parent: Option<ID>,
+    pub font: Option<FontT<S>>,
+    pub color: [u8; 3],
+    pub effects: bool,
+    pub fixed_pitch_only: bool
+}
+
+impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for FontDialogT<S, ID> {
+    fn type_id(&self) -> TypeId { TypeId::of::<FontDialog>() }
+
+    fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        use low::window_helper::handle_of_window;
+
+        let parent = match self.parent.as_ref() {
+            Some(id) =>
+            match handle_of_window(ui, id, "The parent of a FontDialog must be a window-like control.") {
+                Ok(h) => h,
+                Err(e) => { return Err(e); }
+            },
+            None => ptr::null_mut()
+        };
+
+        Ok(Box::new(
+            FontDialog{
+                parent: parent,
+                id: unsafe{ FONT_DIALOG_ID+=1; FONT_DIALOG_ID },
+                logfont: RefCell::new(logfont(self.font.as_ref())),
+                color: Cell::new(self.color),
+                effects: self.effects,
+                fixed_pitch_only: self.fixed_pitch_only
+            }
+        ) as Box<Control>)
+    }
+}
+
+/**
+    A font dialog control
+*/
+pub struct FontDialog {
+    parent: HWND,
+    id: UINT,
+    logfont: RefCell<LOGFONTW>,
+    color: Cell<[u8; 3]>,
+    effects: bool,
+    fixed_pitch_only: bool
+}
+
+impl FontDialog {
+
+    /**
+        Display the dialog. Return true if the dialog was accepted or false if it was cancelled.
+        If the dialog was accepted, the selected font can be read with `get_font`.
+    */
+    pub fn run(&self) -> bool {
+        use low::defs::ChooseFontW;
+        use winapi::{CHOOSEFONTW, CF_SCREENFONTS, CF_INITTOLOGFONTSTRUCT, CF_EFFECTS, CF_FIXEDPITCHONLY, CF_NOVERTFONTS};
+
+        let mut logfont = self.logfont.borrow_mut();
+
+        let mut flags = CF_SCREENFONTS | CF_NOVERTFONTS;
+        if logfont.lfFaceName[0] != 0 { flags |= CF_INITTOLOGFONTSTRUCT; }
+        if self.effects { flags |= CF_EFFECTS; }
+        if self.fixed_pitch_only { flags |= CF_FIXEDPITCHONLY; }
+
+        let mut cf = CHOOSEFONTW {
+            lStructSize: mem::size_of::<CHOOSEFONTW>() as u32,
+            hwndOwner: self.parent,
+            hDC: ptr::null_mut(),
+            lpLogFont: &mut *logfont,
+            iPointSize: 0,
+            Flags: flags,
+            rgbColors: to_colorref(self.color.get()),
+            lCustData: 0,
+            lpfnHook: None,
+            lpTemplateName: ptr::null(),
+            hInstance: ptr::null_mut(),
+            lpszStyle: ptr::null_mut(),
+            nFontType: 0,
+            ___MISSING_ALIGNMENT__: 0,
+            nSizeMin: 0,
+            nSizeMax: 0
+        };
+
+        if unsafe{ ChooseFontW(&mut cf) } != 0 {
+            self.color.set(from_colorref(cf.rgbColors));
+            true
+        } else {
+            false
+        }
+    }
+
+    /**
+        Return the font selected by the user. The template can be packed in a Ui to create the font resource.
+        Before the dialog ran, return the initial font. Return `None` if there is no font selected.
+    */
+    pub fn get_font(&self) -> Option<FontT<String>> {
+        let logfont = self.logfont.borrow();
+        if logfont.lfFaceName[0] == 0 {
+            return None;
+        }
+
+        let mut decoration = 0;
+        if logfont.lfItalic != 0 { decoration |= FONT_DECO_ITALIC; }
+        if logfont.lfUnderline != 0 { decoration |= FONT_DECO_UNDERLINE; }
+        if logfont.lfStrikeOut != 0 { decoration |= FONT_DECO_STRIKEOUT; }
+
+        Some(FontT {
+            family: from_utf16(&logfont.lfFaceName),
+            size: logfont.lfHeight as c_int,
+            weight: logfont.lfWeight as c_int,
+            decoration: decoration
+        })
+    }
+
+    /// Set the font selected when the dialog is opened. If `None`, the system picks the font.
+    pub fn set_font<S: Clone+Into<String>>(&self, font: Option<&FontT<S>>) {
+        *self.logfont.borrow_mut() = logfont(font);
+    }
+
+    /// Return the text color selected by the user as `[red, green, blue]`. Before the dialog ran, return the initial color.
+    pub fn get_color(&self) -> [u8; 3] {
+        self.color.get()
+    }
+
+    /// Set the text color selected when the dialog is opened
+    pub fn set_color(&self, color: [u8; 3]) {
+        self.color.set(color);
+    }
+}
+
+impl Control for FontDialog {
+
+    fn handle(&self) -> AnyHandle {
+        AnyHandle::Custom(TypeId::of::<FontDialog>(), self.id as usize)
+    }
+
+    fn control_type(&self) -> ControlType {
+        ControlType::FontDialog
+    }
+
+}
+
+/// Convert a font template into a LOGFONTW. The face name is truncated to 31 characters.
+fn logfont<S: Clone+Into<String>>(font: Option<&FontT<S>>) -> LOGFONTW {
+    use winapi::{DEFAULT_CHARSET, OUT_DEFAULT_PRECIS, CLIP_DEFAULT_PRECIS, CLEARTYPE_QUALITY, VARIABLE_PITCH, BYTE, LONG, LF_FACESIZE};
+
+    let mut logfont: LOGFONTW = unsafe{ mem::zeroed() };
+    if let Some(font) = font {
+        let family = to_utf16(&font.family.clone().into());
+        let len = ::std::cmp::min(family.len(), LF_FACESIZE - 1);
+
+        logfont.lfHeight = font.size as LONG;
+        logfont.lfWeight = font.weight as LONG;
+        logfont.lfItalic = ((font.decoration & FONT_DECO_ITALIC) != 0) as BYTE;
+        logfont.lfUnderline = ((font.decoration & FONT_DECO_UNDERLINE) != 0) as BYTE;
+        logfont.lfStrikeOut = ((font.decoration & FONT_DECO_STRIKEOUT) != 0) as BYTE;
+        logfont.lfCharSet = DEFAULT_CHARSET as BYTE;
+        logfont.lfOutPrecision = OUT_DEFAULT_PRECIS as BYTE;
+        logfont.lfClipPrecision = CLIP_DEFAULT_PRECIS as BYTE;
+        logfont.lfQuality = CLEARTYPE_QUALITY as BYTE;
+        logfont.lfPitchAndFamily = VARIABLE_PITCH as BYTE;
+        logfont.lfFaceName[0..len].copy_from_slice(&family[0..len]);
+    }
+
+    logfont
+}
@@ -25,6 +25,8 @@ use defs::HTextAlign;
     • `disabled`: If the user can or can't click on the groupbox  
     • `parent`: The groupbox parent  
     • `font`: The groupbox font. If None, use the system default  
+    • `text_color`: The text color of the groupbox and of its children as `[red, green, blue]`. A text color removes the visual style of the groupbox, so the text color of the parent is not displayed. If None, use the system default  
+    • `background_color`: The background color of the groupbox and of its children as `[red, green, blue]`. If None, use the color of the parent or the system default  
 */
 #[derive(Clone)]
 pub struct GroupBoxT<S: Clone+Into<String>, ID: Hash+Clone> {
@@ -36,6 +38,8 @@ pub struct GroupBoxT<S: Clone+Into<String>, ID: Hash+Clone> {
     pub align: HTextAlign,
     pub parent: ID,
     pub font: Option<ID>,
+    pub text_color: Option<[u8; 3]>,
+    pub background_color: Option<[u8; 3]>,
 }
 
 impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for GroupBoxT<S, ID> {
@@ -43,6 +47,7 @@ impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for GroupBoxT<S, ID> {
 
     fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
         use low::window_helper::{WindowParams, build_window, set_window_font_raw, handle_of_window, handle_of_font};
+        use low::color_helper::set_template_colors;
         use winapi::{DWORD, WS_VISIBLE, WS_DISABLED, WS_CHILD, BS_NOTIFY, BS_GROUPBOX, BS_TOP, BS_CENTER, BS_LEFT, BS_RIGHT};
 
         let flags: DWORD = WS_CHILD | BS_NOTIFY | BS_GROUPBOX | BS_TOP |
@@ -78,7 +83,10 @@ impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for GroupBoxT<S, ID> {
 
         match unsafe{ build_window(params) } {
             Ok(h) => {
-                unsafe{ set_window_font_raw(h, font_handle, true); }
+                unsafe{ 
+                    set_window_font_raw(h, font_handle, true);
+                    set_template_colors(h, self.text_color, self.background_color);
+                }
                 Ok( Box::new(GroupBox{handle: h}) )
             },
             Err(e) => Err(Error::System(e))
@@ -108,6 +116,10 @@ impl GroupBox {
     pub fn set_font<ID: Hash+Clone>(&self, ui: &Ui<ID>, f: Option<&ID>) -> Result<(), Error> { unsafe{ ::low::window_helper::set_window_font(self.handle, ui, f) } }
     pub fn update(&self) { unsafe{ ::low::window_helper::update(self.handle); } }
     pub fn focus(&self) { unsafe{ ::user32::SetFocus(self.handle); } }
+    pub fn get_text_color(&self) -> Option<[u8; 3]> { ::low::color_helper::get_text_color(self.handle) }
+    pub fn set_text_color(&self, color: Option<[u8; 3]>) { unsafe{ ::low::color_helper::set_text_color(self.handle, color); } }
+    pub fn get_background_color(&self) -> Option<[u8; 3]> { ::low::color_helper::get_background_color(self.handle) }
+    pub fn set_background_color(&self, color: Option<[u8; 3]>) { unsafe{ ::low::color_helper::set_background_color(self.handle, color); } }
 }
 
 impl Control for GroupBox {
@@ -127,7 +139,11 @@ impl Control for GroupBox {
 
     fn free(&mut self) {
         use user32::DestroyWindow;
-        unsafe{ DestroyWindow(self.handle) };
+        use low::color_helper::clear_colors;
+        unsafe{
+            clear_colors(self.handle);
+            DestroyWindow(self.handle);
+        }
     }
 
 }
\ No newline at end of file
@@ -16,7 +16,7 @@ use defs::HTextAlign;
     A template that creates a standard label
 
     Control specific events:  
-    `label::Click, label::DoubleClick`
+    `label::Click, label::DoubleClick, label::Draw`
 
     Members:  
     • `text`: The text of the label  
@@ -27,6 +27,10 @@ use defs::HTextAlign;
     • `align`: The text align of the label
     • `parent`: The label parent  
     • `font`: The label font. If None, use the system default  
+    • `tooltip`: A tip displayed when the mouse hovers the label. If None, the label has no tip  
+    • `text_color`: The text color of the label as `[red, green, blue]`. If None, use the color of the parent or the system default  
+    • `background_color`: The background color of the label as `[red, green, blue]`. If None, use the color of the parent or the system default  
+    • `owner_draw`: If the label is painted by the application in the `label::Draw` event. The text align is then ignored  
 */
 #[derive(Clone)]
 pub struct LabelT<S: Clone+Into<String>, ID: Hash+Clone> {
@@ -38,20 +42,28 @@ pub struct LabelT<S: Clone+Into<String>, ID: Hash+Clone> {
     pub align: HTextAlign,
     pub parent: ID,
     pub font: Option<ID>,
+    pub tooltip: Option<String>,
+    pub text_color: Option<[u8; 3]>,
+    pub background_color: Option<[u8; 3]>,
+    pub owner_draw: bool,
 }
 
 impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for LabelT<S, ID> {
     fn type_id(&self) -> TypeId { TypeId::of::<Label>() }
 
     fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        use controls::tooltip::set_template_tip;
+        use low::color_helper::set_template_colors;
         use low::window_helper::{WindowParams, build_window, set_window_font_raw, handle_of_window, handle_of_font};
-        use low::defs::{SS_NOTIFY, SS_NOPREFIX, SS_LEFT, SS_RIGHT, SS_CENTER};
+        use low::defs::{SS_NOTIFY, SS_NOPREFIX, SS_LEFT, SS_RIGHT, SS_CENTER, SS_OWNERDRAW};
         use winapi::{DWORD, WS_VISIBLE, WS_DISABLED, WS_CHILD};
 
         let flags: DWORD = WS_CHILD | SS_NOTIFY | SS_NOPREFIX | 
         if self.visible    { WS_VISIBLE }   else { 0 } |
         if self.disabled   { WS_DISABLED }  else { 0 } |
-        match self.align   { HTextAlign::Center=>SS_CENTER, HTextAlign::Left=>SS_LEFT, HTextAlign::Right=>SS_RIGHT };
+        if self.owner_draw { SS_OWNERDRAW } else {
+            match self.align { HTextAlign::Center=>SS_CENTER, HTextAlign::Left=>SS_LEFT, HTextAlign::Right=>SS_RIGHT }
+        };
 
         // Get the parent handle
         let parent = match handle_of_window(ui, &self.parent, "The parent of a label must be a window-like control.") {
@@ -82,6 +94,12 @@ impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for LabelT<S, ID> {
         match unsafe{ build_window(params) } {
             Ok(h) => {
                 unsafe{ set_window_font_raw(h, font_handle, true); }
+                if let Err(e) = unsafe{ set_template_tip(ui, h, &self.tooltip) } {
+                    return Err(e);
+                }
+
+                unsafe{ set_template_colors(h, self.text_color, self.background_color); }
+
                 Ok( Box::new(Label{handle: h}) )
             },
             Err(e) => Err(Error::System(e))
@@ -111,6 +129,10 @@ impl Label {
     pub fn set_font<ID: Hash+Clone>(&self, ui: &Ui<ID>, f: Option<&ID>) -> Result<(), Error> { unsafe{ ::low::window_helper::set_window_font(self.handle, ui, f) } }
     pub fn update(&self) { unsafe{ ::low::window_helper::update(self.handle); } }
     pub fn focus(&self) { unsafe{ ::user32::SetFocus(self.handle); } }
+    pub fn get_text_color(&self) -> Option<[u8; 3]> { ::low::color_helper::get_text_color(self.handle) }
+    pub fn set_text_color(&self, color: Option<[u8; 3]>) { unsafe{ ::low::color_helper::set_text_color(self.handle, color); } }
+    pub fn get_background_color(&self) -> Option<[u8; 3]> { ::low::color_helper::get_background_color(self.handle) }
+    pub fn set_background_color(&self, color: Option<[u8; 3]>) { unsafe{ ::low::color_helper::set_background_color(self.handle, color); } }
 }
 
 impl Control for Label {
@@ -125,7 +147,13 @@ impl Control for Label {
 
     fn free(&mut self) {
         use user32::DestroyWindow;
-        unsafe{ DestroyWindow(self.handle) };
+        use controls::tooltip::clear_template_tip;
+        use low::color_helper::clear_colors;
+        unsafe{
+            clear_template_tip(self.handle);
+            clear_colors(self.handle);
+            DestroyWindow(self.handle);
+        }
     }
 
 }
\ No newline at end of file
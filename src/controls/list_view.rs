@@ -18,44 +18,38 @@
 
 use std::hash::Hash;
 use std::any::TypeId;
-use std::mem::{transmute, zeroed, size_of};
+use std::cmp::Ordering;
+use std::mem::zeroed;
+use std::ptr;
+use std::rc::Rc;
+use std::cell::RefCell;
 
-use winapi::{LPARAM, HWND, HFONT, BOOL};
+use winapi::{HWND, HFONT, UINT, WPARAM, LPARAM, LRESULT, UINT_PTR, DWORD_PTR, c_int};
 use winapi::commctrl::*;
-use low::other_helper::{from_utf16, to_utf16};
+use low::other_helper::{from_utf16, to_utf16, from_wide_ptr};
 
 use ui::Ui;
 use controls::{Control, ControlT, ControlType, AnyHandle};
 use error::Error;
-use events::Event;
 use defs::HTextAlign;
 use user32::SendMessageW;
 use low::window_helper::{WindowParams, build_window, set_window_font_raw, handle_of_window,
                          handle_of_font};
-use winapi::{DWORD, WS_VISIBLE, WS_DISABLED, WS_CHILD, BS_NOTIFY, BS_GROUPBOX, BS_TOP, BS_CENTER,
-             BS_LEFT, BS_RIGHT, WS_TABSTOP, WS_EX_CLIENTEDGE, WS_CHILDWINDOW, WS_VSCROLL,
+use winapi::{DWORD, WS_VISIBLE, WS_DISABLED, WS_TABSTOP, WS_EX_CLIENTEDGE, WS_CHILDWINDOW, WS_VSCROLL,
              WS_EX_LTRREADING, WS_EX_RIGHTSCROLLBAR};
-use winapi::winuser::{WS_EX_LEFT, WS_EX_RIGHT};
-
-use std::thread::sleep;
-use std::time::Duration;
+use winapi::winuser::WS_EX_LEFT;
 
 /**
-    A template that creates a standard list view
+    The way the items of a list view are displayed
 
-    Available events:  
-    Event::Destroyed, Event::Moved, Event::Resized, Event::Raw  
-
-    Members:  
-    • `text`: The text of the groupbox  
-    • `position`: The start position of groupbox  
-    • `size`: The start size of the groupbox  
-    • `visible`: If the groupbox should be visible to the user   
-    • `disabled`: If the user can or can't click on the groupbox  
-    • `parent`: The groupbox parent  
-    • `font`: The groupbox font. If None, use the system default  
+    Members:
+    • `Report`: Each item is a row and the subitems are displayed in columns
+    • `Tile`: Each item is a large icon with its text and subitems next to it
+    • `IconSmall`: Each item is a small icon with its text next to it
+    • `List`: Same as `IconSmall`, but the items are arranged in columns
+    • `Icon`: Each item is a large icon with its text under it
 */
-#[derive(Clone, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub enum ViewMode {
     Report,
     Tile,
@@ -64,30 +58,69 @@ pub enum ViewMode {
     Icon,
 }
 
-#[derive(Clone, Debug)]
-pub struct ListViewT<ID: Hash + Clone>{
-    pub column: Vec<String>,
-    pub view_mode: ViewMode,
-    pub position: (i32, i32),
-    pub size: (u32, u32),
-    pub visible: bool,
-    pub disabled: bool,
+/**
+    The sort indicator displayed in a list view column header
+*/
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SortDirection {
+    Ascending,
+    Descending
+}
+
+/**
+    A list view column definition
+
+    Members:
+    • `text`: The text of the column header
+    • `width`: The width of the column in pixels
+    • `align`: The alignment of the column text. The first column of a list view is always left aligned
+    • `sort`: The sort indicator displayed in the column header. `None` to hide it
+*/
+#[derive(Clone, PartialEq, Debug)]
+pub struct ListViewColumn {
+    pub text: String,
+    pub width: i32,
     pub align: HTextAlign,
-    pub parent: ID,
-    pub font: Option<ID>,
+    pub sort: Option<SortDirection>
 }
 
-// MSDN:Dynamically switching to and from the LVS_OWNERDATA style is not supported
-// So we need a new struct
+impl ListViewColumn {
+
+    /// Create a left aligned column without a sort indicator
+    pub fn new<S: Into<String>>(text: S, width: i32) -> ListViewColumn {
+        ListViewColumn{ text: text.into(), width: width, align: HTextAlign::Left, sort: None }
+    }
+
+}
+
+/**
+    A template that creates a standard list view
+
+    Control specific events:
+    `list_view::ItemActivated, list_view::ColumnClick, list_view::SelectionChanged, list_view::ListViewCustomDraw`
+
+    Members:
+    • `columns`: The columns of the list view
+    • `view_mode`: The way the items are displayed
+    • `position`: The start position of the list view
+    • `size`: The start size of the list view
+    • `visible`: If the list view should be visible to the user
+    • `disabled`: If the user can or can't use the list view
+    • `checkboxes`: If a checkbox is displayed next to each item
+    • `multi_select`: If true, allow the user to select more than one item
+    • `parent`: The list view parent
+    • `font`: The list view font. If None, use the system default
+*/
 #[derive(Clone, Debug)]
-pub struct VitualListViewT<ID: Hash + Clone>{
-    pub column: Vec<String>,
+pub struct ListViewT<ID: Hash + Clone>{
+    pub columns: Vec<ListViewColumn>,
     pub view_mode: ViewMode,
     pub position: (i32, i32),
     pub size: (u32, u32),
     pub visible: bool,
     pub disabled: bool,
-    pub align: HTextAlign,
+    pub checkboxes: bool,
+    pub multi_select: bool,
     pub parent: ID,
     pub font: Option<ID>,
 }
@@ -98,54 +131,34 @@ impl<ID: Hash + Clone> ControlT<ID> for ListViewT<ID>{
     }
 
     fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
-        let style: DWORD = WS_CHILDWINDOW | WS_VSCROLL | WS_TABSTOP | LVS_SHOWSELALWAYS |
-                           LVS_REPORT |
-                           if self.visible {
-            WS_VISIBLE
-        } else {
-            0
-        } |
-                           if self.disabled {
-            WS_DISABLED
-        } else {
-            0
-        };
+        let style: DWORD = WS_CHILDWINDOW | WS_VSCROLL | WS_TABSTOP | LVS_SHOWSELALWAYS | LVS_REPORT |
+          if self.visible       { WS_VISIBLE }    else { 0 } |
+          if self.disabled      { WS_DISABLED }   else { 0 } |
+          if !self.multi_select { LVS_SINGLESEL } else { 0 };
 
-        let ex_style: DWORD = WS_EX_LTRREADING | WS_EX_RIGHTSCROLLBAR | WS_EX_LEFT |
-                              WS_EX_CLIENTEDGE;
+        let ex_style: DWORD = WS_EX_LTRREADING | WS_EX_RIGHTSCROLLBAR | WS_EX_LEFT | WS_EX_CLIENTEDGE;
 
-        let list_view_ex_style = LVS_EX_GRIDLINES | LVS_EX_HEADERDRAGDROP | LVS_EX_FULLROWSELECT |
-                                 LVS_EX_DOUBLEBUFFER;
+        let list_view_ex_style = LVS_EX_GRIDLINES | LVS_EX_HEADERDRAGDROP | LVS_EX_FULLROWSELECT | LVS_EX_DOUBLEBUFFER |
+          if self.checkboxes { LVS_EX_CHECKBOXES } else { 0 };
 
-        // match self.align   { HTextAlign::Center=>WS_EX_CENTER, HTextAlign::Left=>WS_EX_LEFT, HTextAlign::Right=>WS_EX_RIGHT};
         // Get the parent handle
-        let parent = match handle_of_window(ui,
-                                            &self.parent,
-                                            "The parent of a ListBox must be a window-like \
-                                             control.") {
+        let parent = match handle_of_window(ui, &self.parent, "The parent of a ListView must be a window-like control.") {
             Ok(h) => h,
-            Err(e) => {
-                return Err(e);
-            }
+            Err(e) => { return Err(e); }
         };
 
         // Get the font handle (if any)
         let font_handle: Option<HFONT> = match self.font.as_ref() {
-            Some(font_id) => {
-                match handle_of_font(ui,
-                                     &font_id,
-                                     "The font of a ListBox must be a font resource.") {
+            Some(font_id) =>
+                match handle_of_font(ui, &font_id, "The font of a ListView must be a font resource.") {
                     Ok(h) => Some(h),
-                    Err(e) => {
-                        return Err(e);
-                    }
-                }
-            }
+                    Err(e) => { return Err(e); }
+                },
             None => None,
         };
 
         let params = WindowParams {
-            title: "", 
+            title: "",
             class_name: "SysListView32",
             position: self.position.clone(),
             size: self.size.clone(),
@@ -157,11 +170,17 @@ impl<ID: Hash + Clone> ControlT<ID> for ListViewT<ID>{
         match unsafe { build_window(params) } {
             Ok(h) => {
                 unsafe {
+                    set_window_font_raw(h, font_handle, true);
+                    set_lv_ex_style(h, list_view_ex_style, list_view_ex_style);
+                }
 
-                    set_window_font_raw(h, font_handle, true); 
-                    set_lv_ex_style(h, list_view_ex_style);
+                let list = ListView { handle: h };
+                for (i, c) in self.columns.iter().enumerate() {
+                    list.insert_col(i, c);
                 }
-                Ok(Box::new(ListView { handle: h }))
+                list.set_view_mode(&self.view_mode);
+
+                Ok(Box::new(list))
             }
             Err(e) => Err(Error::System(e)),
         }
@@ -173,151 +192,437 @@ fn get_lv_ex_style(h: HWND) -> DWORD {
     unsafe { SendMessageW(h, LVM_GETEXTENDEDLISTVIEWSTYLE, 0, 0) as DWORD }
 }
 
-// remove all exiest style and set given style
-fn set_lv_ex_style(h: HWND, exsty: DWORD) -> DWORD {
-    let old_sty = get_lv_ex_style(h);
-    let new_sty = old_sty | exsty;
-    unsafe { SendMessageW(h, LVM_SETEXTENDEDLISTVIEWSTYLE, new_sty, exsty as i32) as DWORD }
+// set the ex_style flags selected by `mask`
+fn set_lv_ex_style(h: HWND, mask: DWORD, exsty: DWORD) -> DWORD {
+    unsafe { SendMessageW(h, LVM_SETEXTENDEDLISTVIEWSTYLE, mask as WPARAM, exsty as LPARAM) as DWORD }
 }
 
 /**
-    A ListView
+    A list view control. Items and columns are identified by their index.
 */
 #[derive(Clone, Debug)]
 pub struct ListView {
     handle: HWND,
 }
 
-
-#[derive(Clone, Debug)]
-pub struct ImageList {}
-
 /// https://msdn.microsoft.com/en-us/library/windows/desktop/ff485961(v=vs.85).aspx
 impl ListView {
-    // method for rows
-    pub fn add(&self, data: Vec<String>) -> i32{
-        self.insert(10000, data)
+
+    /// Add a row at the end of the list view. The first value is the item text and the next values are the subitems text.
+    /// Return the index of the new item or None if the item could not be added.
+    pub fn add<S: AsRef<str>>(&self, data: &[S]) -> Option<usize> {
+        let count = self.get_count();
+        self.insert(count, data)
     }
 
-    pub fn insert(&self, aindex: isize,mut data: Vec<String>) -> i32{
-        // LVM_INSERTITEM
-        unsafe {
-            let mut item: LVITEMW = zeroed();
-            item.iItem = aindex as i32;
-            item.mask = LVIF_TEXT;
-            let mut re = -1;
-            match data.pop(){
-                Some(x) => {
-                    let mut item_text = to_utf16(&x);
-                    // the first item in current row
-                    item.pszText = item_text.as_mut_ptr();
-                    re = SendMessageW(self.handle, LVM_INSERTITEMW, 0, transmute(&mut item));
-                    for i in 0..data.len() {
-                        // add subitem
-                        let mut sub_item: LVITEMW = zeroed();
-                        sub_item.iSubItem = (i + 1) as i32;
-                        let mut item_text = to_utf16(&(data[i].clone()));
-                        sub_item.pszText = item_text.as_mut_ptr();
-                        re = SendMessageW(self.handle,
-                                                LVM_SETITEMTEXTW,
-                                                aindex as DWORD,
-                                                transmute(&sub_item))
-                    }
-                },
-                None => {}
+    /// Insert a row at the selected index. If index is greater than the number of items, the row is added at the end.
+    /// Return the index of the new item or None if the item could not be added.
+    pub fn insert<S: AsRef<str>>(&self, index: usize, data: &[S]) -> Option<usize> {
+        let (first, subitems) = match data.split_first() {
+            Some(d) => d,
+            None => { return None; }
+        };
+
+        let mut text = to_utf16(first.as_ref());
+        let mut item: LVITEMW = unsafe{ zeroed() };
+        item.mask = LVIF_TEXT;
+        item.iItem = index.min(self.get_count()) as c_int;
+        item.pszText = text.as_mut_ptr();
+
+        let index = unsafe{ SendMessageW(self.handle, LVM_INSERTITEMW, 0, &mut item as *mut LVITEMW as LPARAM) };
+        if index == -1 {
+            return None;
+        }
+
+        let index = index as usize;
+        for (i, s) in subitems.iter().enumerate() {
+            self.set_text(index, i+1, s.as_ref());
+        }
+
+        Some(index)
+    }
+
+    /// Replace the text of the item at `index` and of its subitems. Cells that are not in `data` are not modified.
+    pub fn modify<S: AsRef<str>>(&self, index: usize, data: &[S]) {
+        for (i, s) in data.iter().enumerate() {
+            self.set_text(index, i, s.as_ref());
+        }
+    }
+
+    /// Remove the item at `index`. Return false if the item could not be removed.
+    pub fn delete(&self, index: usize) -> bool {
+        unsafe{ SendMessageW(self.handle, LVM_DELETEITEM, index as WPARAM, 0) != 0 }
+    }
+
+    /// Remove every item in the list view. The columns are kept.
+    pub fn clear(&self) {
+        unsafe{ SendMessageW(self.handle, LVM_DELETEALLITEMS, 0, 0); }
+    }
+
+    /// Return the text of an item (`column` = 0) or of a subitem. Return an empty string if the item does not exist.
+    pub fn get_text(&self, index: usize, column: usize) -> String {
+        // LVM_GETITEMTEXT do not return the text length. The buffer is grown until the text fits.
+        let mut size = 256;
+        loop {
+            let mut buffer: Vec<u16> = vec![0; size];
+            let mut item: LVITEMW = unsafe{ zeroed() };
+            item.iSubItem = column as c_int;
+            item.pszText = buffer.as_mut_ptr();
+            item.cchTextMax = size as c_int;
+
+            let length = unsafe{ SendMessageW(self.handle, LVM_GETITEMTEXTW, index as WPARAM, &mut item as *mut LVITEMW as LPARAM) } as usize;
+            if length + 1 < size {
+                return from_utf16(&buffer[..length]);
             }
-            re
+
+            size *= 2;
         }
     }
 
-    pub fn modify(&self) {
-        // LVM_SETITEM
+    /// Set the text of an item (`column` = 0) or of a subitem
+    pub fn set_text<'a>(&self, index: usize, column: usize, text: &'a str) {
+        let mut text = to_utf16(text);
+        let mut item: LVITEMW = unsafe{ zeroed() };
+        item.iSubItem = column as c_int;
+        item.pszText = text.as_mut_ptr();
+
+        unsafe{ SendMessageW(self.handle, LVM_SETITEMTEXTW, index as WPARAM, &mut item as *mut LVITEMW as LPARAM); }
     }
-    pub fn delete(&self) {
-        // LVM_DELETEITEM
+
+    /// Return the number of items in the list view
+    pub fn get_count(&self) -> usize {
+        unsafe{ SendMessageW(self.handle, LVM_GETITEMCOUNT, 0, 0) as usize }
     }
 
-    // method for col
-    pub fn delete_col(&self) {}
+    /// Return the index of the next item after `start`. If `start` is None, the search begins at the first item.
+    /// If `selected` is true, only the selected items are returned. Returns None if there are no more items.
+    pub fn get_next(&self, start: Option<usize>, selected: bool) -> Option<usize> {
+        let start = match start {
+            Some(i) => i as WPARAM,
+            None => -1isize as WPARAM
+        };
+        let flags = if selected { LVNI_SELECTED } else { LVNI_ALL };
 
-    pub fn insert_col(&self, col_index: isize, col_name: &str, col_width: usize) -> isize {
-        // LVM_INSERTCOLUMN
-        unsafe {
-            let mut col_item: LVCOLUMNW = zeroed();
-            col_item.mask = LVCF_TEXT | LVCF_FMT | LVCF_WIDTH;
-            col_item.fmt = LVCFMT_LEFT;
-            col_item.cx = col_width as i32;
-            let mut col_name = to_utf16(col_name);
-            col_item.pszText = col_name.as_mut_ptr();
-            let lre = SendMessageW(self.handle,
-                                   LVM_INSERTCOLUMNW,
-                                   col_index as DWORD,
-                                   transmute(&col_item));
-            println!("insert_col: return {:?}", lre);
-            // sleep(Duration::from_secs(50));
-            lre as isize
+        let index = unsafe{ SendMessageW(self.handle, LVM_GETNEXTITEM, start, flags) };
+        if index < 0 { None }
+        else { Some(index as usize) }
+    }
+
+    /// Return the index of the first selected item. Return None if there is no selected item.
+    pub fn get_selected_index(&self) -> Option<usize> {
+        self.get_next(None, true)
+    }
+
+    /// Return a vector filled with the selected indexes of the list view
+    pub fn get_selected_indexes(&self) -> Vec<usize> {
+        let mut indexes = Vec::with_capacity(self.len_selected());
+        let mut next = self.get_next(None, true);
+        while let Some(i) = next {
+            indexes.push(i);
+            next = self.get_next(Some(i), true);
         }
+
+        indexes
+    }
+
+    /// Return the number of selected items
+    pub fn len_selected(&self) -> usize {
+        unsafe{ SendMessageW(self.handle, LVM_GETSELECTEDCOUNT, 0, 0) as usize }
     }
 
-    pub fn custom_sort(&self) {
-        // LVM_SORTITEMS
+    /// Return true if the item at `index` is selected
+    pub fn index_selected(&self, index: usize) -> bool {
+        let state = unsafe{ SendMessageW(self.handle, LVM_GETITEMSTATE, index as WPARAM, LVIS_SELECTED as LPARAM) } as u32;
+        state & LVIS_SELECTED == LVIS_SELECTED
     }
-    pub fn sort(&self) {
-        // LVS_SORTASCENDING or LVS_SORTDESCENDING
+
+    /// Select or unselect the item at `index`.
+    /// If index is `usize::max_value`, the change is applied to every item.
+    pub fn set_index_selected(&self, index: usize, selected: bool) {
+        let state = if selected { LVIS_SELECTED } else { 0 };
+        self.set_item_state(index, LVIS_SELECTED, state);
     }
 
-    pub fn modify_col(&self) {}
+    /// Return true if the checkbox of the item at `index` is checked
+    pub fn get_checked(&self, index: usize) -> bool {
+        let state = unsafe{ SendMessageW(self.handle, LVM_GETITEMSTATE, index as WPARAM, LVIS_STATEIMAGEMASK as LPARAM) } as u32;
+        state & LVIS_STATEIMAGEMASK == INDEXTOSTATEIMAGEMASK(2)
+    }
 
-    // other func
-    pub fn get_count(&self) {}
-    pub fn get_next(&self) {}
-    pub fn get_text(&self) {
-        // LVM_GETITEMTEXT
+    /// Check or uncheck the checkbox of the item at `index`. The list view must have the `checkboxes` style.
+    /// If index is `usize::max_value`, the change is applied to every item.
+    pub fn set_checked(&self, index: usize, checked: bool) {
+        // The state image 1 is the unchecked box and the state image 2 is the checked box
+        let state = INDEXTOSTATEIMAGEMASK(if checked { 2 } else { 1 });
+        self.set_item_state(index, LVIS_STATEIMAGEMASK, state);
     }
-    pub fn set_img_list(&self) {
-        // LVM_SETIMAGELIST
 
+    /// Return the indexes of the checked items
+    pub fn get_checked_indexes(&self) -> Vec<usize> {
+        (0..self.get_count()).filter(|&i| self.get_checked(i)).collect()
     }
 
-    pub fn set_text<'a>(&self, text: &'a str) {
-        unsafe {
-            ::low::window_helper::set_window_text(self.handle, text);
+    /// Return true if the list view displays a checkbox next to each item
+    pub fn get_checkboxes(&self) -> bool {
+        get_lv_ex_style(self.handle) & LVS_EX_CHECKBOXES == LVS_EX_CHECKBOXES
+    }
+
+    /// Show or hide the checkboxes of the items
+    pub fn set_checkboxes(&self, checkboxes: bool) {
+        set_lv_ex_style(self.handle, LVS_EX_CHECKBOXES, if checkboxes { LVS_EX_CHECKBOXES } else { 0 });
+    }
+
+    /// Return true if the list view accepts multiple selected items, false otherwise.
+    pub fn get_multi_select(&self) -> bool {
+        use low::window_helper::get_window_long;
+        use winapi::GWL_STYLE;
+
+        let style = get_window_long(self.handle, GWL_STYLE) as u32;
+        (style & LVS_SINGLESEL) != LVS_SINGLESEL
+    }
+
+    /// Set or unset the list view multiple selected flag
+    pub fn set_multi_select(&self, multi: bool) {
+        use low::window_helper::{set_window_long, get_window_long};
+        use winapi::GWL_STYLE;
+
+        let old_style = get_window_long(self.handle, GWL_STYLE) as usize;
+        if multi {
+            set_window_long(self.handle, GWL_STYLE, old_style&(!LVS_SINGLESEL as usize));
+        } else {
+            set_window_long(self.handle, GWL_STYLE, old_style|(LVS_SINGLESEL as usize));
         }
     }
-    pub fn get_visibility(&self) -> bool {
-        unsafe { ::low::window_helper::get_window_visibility(self.handle) }
+
+    /// Scroll the list view until the item at `index` is fully visible
+    pub fn ensure_visible(&self, index: usize) {
+        unsafe{ SendMessageW(self.handle, LVM_ENSUREVISIBLE, index as WPARAM, 0); }
     }
-    pub fn set_visibility(&self, visible: bool) {
-        unsafe {
-            ::low::window_helper::set_window_visibility(self.handle, visible);
+
+    /// Return the `(item, column)` indexes of the cell under a point in the list view client area.
+    /// Return None if there is no item under the point.
+    pub fn hit_test(&self, x: i32, y: i32) -> Option<(usize, usize)> {
+        use winapi::POINT;
+
+        let mut info: LVHITTESTINFO = unsafe{ zeroed() };
+        info.pt = POINT{x: x, y: y};
+
+        let index = unsafe{ SendMessageW(self.handle, LVM_SUBITEMHITTEST, 0, &mut info as *mut LVHITTESTINFO as LPARAM) };
+        if index < 0 || info.flags & LVHT_ONITEM == 0 { None }
+        else { Some((info.iItem as usize, info.iSubItem as usize)) }
+    }
+
+    /// Add a column at the end of the list view. Return the index of the new column or None if the column could not be added.
+    pub fn add_col(&self, column: &ListViewColumn) -> Option<usize> {
+        let count = self.col_count();
+        self.insert_col(count, column)
+    }
+
+    /// Insert a column at the selected index. Return the index of the new column or None if the column could not be added.
+    pub fn insert_col(&self, index: usize, column: &ListViewColumn) -> Option<usize> {
+        let mut text = to_utf16(&column.text);
+        let mut col: LVCOLUMNW = unsafe{ zeroed() };
+        col.mask = LVCF_TEXT | LVCF_FMT | LVCF_WIDTH;
+        col.fmt = column_format(&column.align);
+        col.cx = column.width;
+        col.pszText = text.as_mut_ptr();
+
+        let index = unsafe{ SendMessageW(self.handle, LVM_INSERTCOLUMNW, index as WPARAM, &mut col as *mut LVCOLUMNW as LPARAM) };
+        if index == -1 {
+            return None;
+        }
+
+        let index = index as usize;
+        if column.sort.is_some() {
+            self.set_sort_indicator(index, column.sort);
         }
+
+        Some(index)
     }
-    pub fn get_position(&self) -> (i32, i32) {
-        unsafe { ::low::window_helper::get_window_position(self.handle) }
+
+    /// Replace the text, the width, the alignment and the sort indicator of the column at `index`
+    pub fn modify_col(&self, index: usize, column: &ListViewColumn) {
+        let mut text = to_utf16(&column.text);
+        let mut col: LVCOLUMNW = unsafe{ zeroed() };
+        col.mask = LVCF_TEXT | LVCF_FMT | LVCF_WIDTH;
+        col.fmt = column_format(&column.align);
+        col.cx = column.width;
+        col.pszText = text.as_mut_ptr();
+
+        unsafe{ SendMessageW(self.handle, LVM_SETCOLUMNW, index as WPARAM, &mut col as *mut LVCOLUMNW as LPARAM); }
+        self.set_sort_indicator(index, column.sort);
     }
-    pub fn set_position(&self, x: i32, y: i32) {
-        unsafe {
-            ::low::window_helper::set_window_position(self.handle, x, y);
+
+    /// Return the definition of the column at `index`. Return None if the column does not exist.
+    pub fn get_col(&self, index: usize) -> Option<ListViewColumn> {
+        // LVM_GETCOLUMNW do not return the text length. The buffer is grown until the text fits.
+        let mut size = 256;
+        loop {
+            let mut buffer: Vec<u16> = vec![0; size];
+            let mut col: LVCOLUMNW = unsafe{ zeroed() };
+            col.mask = LVCF_TEXT | LVCF_FMT | LVCF_WIDTH;
+            col.pszText = buffer.as_mut_ptr();
+            col.cchTextMax = size as c_int;
+
+            if unsafe{ SendMessageW(self.handle, LVM_GETCOLUMNW, index as WPARAM, &mut col as *mut LVCOLUMNW as LPARAM) } == 0 {
+                return None;
+            }
+
+            let length = buffer.iter().position(|&c| c == 0).unwrap_or(size);
+            if length + 1 < size {
+                let align = match col.fmt & LVCFMT_JUSTIFYMASK {
+                    LVCFMT_RIGHT => HTextAlign::Right,
+                    LVCFMT_CENTER => HTextAlign::Center,
+                    _ => HTextAlign::Left
+                };
+
+                return Some(ListViewColumn{ text: from_utf16(&buffer[..length]), width: col.cx, align: align, sort: self.get_sort_indicator(index) });
+            }
+
+            size *= 2;
         }
     }
-    pub fn get_size(&self) -> (u32, u32) {
-        unsafe { ::low::window_helper::get_window_size(self.handle) }
+
+    /// Remove the column at `index`. Return false if the column could not be removed.
+    pub fn delete_col(&self, index: usize) -> bool {
+        unsafe{ SendMessageW(self.handle, LVM_DELETECOLUMN, index as WPARAM, 0) != 0 }
     }
-    pub fn set_size(&self, w: u32, h: u32) {
-        unsafe {
-            ::low::window_helper::set_window_size(self.handle, w, h, false);
+
+    /// Return the number of columns in the list view
+    pub fn col_count(&self) -> usize {
+        let count = unsafe{ SendMessageW(self.header(), HDM_GETITEMCOUNT, 0, 0) };
+        if count < 0 { 0 } else { count as usize }
+    }
+
+    /// Return the width of the column at `index` in pixels
+    pub fn get_col_width(&self, index: usize) -> i32 {
+        unsafe{ SendMessageW(self.handle, LVM_GETCOLUMNWIDTH, index as WPARAM, 0) as i32 }
+    }
+
+    /// Set the width of the column at `index` in pixels
+    pub fn set_col_width(&self, index: usize, width: i32) {
+        unsafe{ SendMessageW(self.handle, LVM_SETCOLUMNWIDTH, index as WPARAM, width as LPARAM); }
+    }
+
+    /// Return the sort indicator displayed in the header of the column at `index`
+    pub fn get_sort_indicator(&self, index: usize) -> Option<SortDirection> {
+        let fmt = self.header_format(index);
+        if fmt & HDF_SORTUP == HDF_SORTUP { Some(SortDirection::Ascending) }
+        else if fmt & HDF_SORTDOWN == HDF_SORTDOWN { Some(SortDirection::Descending) }
+        else { None }
+    }
+
+    /// Display a sort indicator in the header of the column at `index`. Pass `None` to hide the indicator.
+    pub fn set_sort_indicator(&self, index: usize, sort: Option<SortDirection>) {
+        let mut item: HDITEMW = unsafe{ zeroed() };
+        item.mask = HDI_FORMAT;
+        item.fmt = self.header_format(index) & !(HDF_SORTUP | HDF_SORTDOWN);
+        item.fmt |= match sort {
+            Some(SortDirection::Ascending) => HDF_SORTUP,
+            Some(SortDirection::Descending) => HDF_SORTDOWN,
+            None => 0
+        };
+
+        unsafe{ SendMessageW(self.header(), HDM_SETITEMW, index as WPARAM, &mut item as *mut HDITEMW as LPARAM); }
+    }
+
+    /// Return the index and the direction of the column that displays a sort indicator. Return None if the list view is not sorted.
+    pub fn get_sort(&self) -> Option<(usize, SortDirection)> {
+        (0..self.col_count())
+          .filter_map(|i| self.get_sort_indicator(i).map(|s| (i, s)) )
+          .next()
+    }
+
+    /// Sort the items using the text of a column and display the sort indicator in the column header.
+    /// The text is compared without case sensitivity. Usually called in a `list_view::ColumnClick` callback.
+    pub fn sort(&self, column: usize, direction: SortDirection) {
+        let texts: Vec<String> = (0..self.get_count()).map(|i| self.get_text(i, column).to_lowercase()).collect();
+        self.custom_sort(|a, b| {
+            match direction {
+                SortDirection::Ascending => texts[a].cmp(&texts[b]),
+                SortDirection::Descending => texts[b].cmp(&texts[a])
+            }
+        });
+
+        for i in 0..self.col_count() {
+            self.set_sort_indicator(i, if i == column { Some(direction) } else { None });
         }
     }
-    pub fn get_enabled(&self) -> bool {
-        unsafe { ::low::window_helper::get_window_enabled(self.handle) }
+
+    /// Sort the items using a comparator. The comparator receives the indexes that the items had before the sort.
+    /// The list view must not be modified by the comparator.
+    pub fn custom_sort<F>(&self, mut cmp: F) where F: FnMut(usize, usize) -> Ordering {
+        // Store the current index of the items in their lParam, because the list view sends the lParam to the comparator
+        for i in 0..self.get_count() {
+            let mut item: LVITEMW = unsafe{ zeroed() };
+            item.mask = LVIF_PARAM;
+            item.iItem = i as c_int;
+            item.lParam = i as LPARAM;
+            unsafe{ SendMessageW(self.handle, LVM_SETITEMW, 0, &mut item as *mut LVITEMW as LPARAM); }
+        }
+
+        let mut cmp: &mut FnMut(usize, usize) -> Ordering = &mut cmp;
+        let data = &mut cmp as *mut &mut FnMut(usize, usize) -> Ordering;
+        let compare: unsafe extern "system" fn(LPARAM, LPARAM, LPARAM) -> c_int = compare_items;
+        unsafe{ SendMessageW(self.handle, LVM_SORTITEMS, data as WPARAM, compare as LPARAM); }
     }
-    pub fn set_enabled(&self, e: bool) {
-        unsafe {
-            ::low::window_helper::set_window_enabled(self.handle, e);
+
+    /// Return the way the items are displayed
+    pub fn get_view_mode(&self) -> ViewMode {
+        match unsafe{ SendMessageW(self.handle, LVM_GETVIEW, 0, 0) } as DWORD {
+            LV_VIEW_DETAILS => ViewMode::Report,
+            LV_VIEW_TILE => ViewMode::Tile,
+            LV_VIEW_SMALLICON => ViewMode::IconSmall,
+            LV_VIEW_LIST => ViewMode::List,
+            _ => ViewMode::Icon
         }
+    }
+
+    /// Change the way the items are displayed
+    pub fn set_view_mode(&self, mode: &ViewMode) {
+        let view = match mode {
+            &ViewMode::Report => LV_VIEW_DETAILS,
+            &ViewMode::Tile => LV_VIEW_TILE,
+            &ViewMode::IconSmall => LV_VIEW_SMALLICON,
+            &ViewMode::List => LV_VIEW_LIST,
+            &ViewMode::Icon => LV_VIEW_ICON
+        };
 
+        unsafe{ SendMessageW(self.handle, LVM_SETVIEW, view as WPARAM, 0); }
+    }
+
+    pub fn get_visibility(&self) -> bool { unsafe{ ::low::window_helper::get_window_visibility(self.handle) } }
+    pub fn set_visibility(&self, visible: bool) { unsafe{ ::low::window_helper::set_window_visibility(self.handle, visible); }}
+    pub fn get_position(&self) -> (i32, i32) { unsafe{ ::low::window_helper::get_window_position(self.handle) } }
+    pub fn set_position(&self, x: i32, y: i32) { unsafe{ ::low::window_helper::set_window_position(self.handle, x, y); }}
+    pub fn get_size(&self) -> (u32, u32) { unsafe{ ::low::window_helper::get_window_size(self.handle) } }
+    pub fn set_size(&self, w: u32, h: u32) { unsafe{ ::low::window_helper::set_window_size(self.handle, w, h, false); } }
+    pub fn get_enabled(&self) -> bool { unsafe{ ::low::window_helper::get_window_enabled(self.handle) } }
+    pub fn set_enabled(&self, e:bool) { unsafe{ ::low::window_helper::set_window_enabled(self.handle, e); } }
+    pub fn get_font<ID: Hash+Clone>(&self, ui: &Ui<ID>) -> Option<ID> { unsafe{ ::low::window_helper::get_window_font(self.handle, ui) } }
+    pub fn set_font<ID: Hash+Clone>(&self, ui: &Ui<ID>, f: Option<&ID>) -> Result<(), Error> { unsafe{ ::low::window_helper::set_window_font(self.handle, ui, f) } }
+    pub fn update(&self) { unsafe{ ::low::window_helper::update(self.handle); } }
+    pub fn focus(&self) { unsafe{ ::user32::SetFocus(self.handle); } }
+
+    fn set_item_state(&self, index: usize, mask: u32, state: u32) {
+        let mut item: LVITEMW = unsafe{ zeroed() };
+        item.stateMask = mask;
+        item.state = state;
+
+        let index = if index == usize::max_value() { -1isize as WPARAM } else { index as WPARAM };
+        unsafe{ SendMessageW(self.handle, LVM_SETITEMSTATE, index, &mut item as *mut LVITEMW as LPARAM); }
+    }
+
+    fn header(&self) -> HWND {
+        unsafe{ SendMessageW(self.handle, LVM_GETHEADER, 0, 0) as HWND }
+    }
+
+    fn header_format(&self, index: usize) -> c_int {
+        let mut item: HDITEMW = unsafe{ zeroed() };
+        item.mask = HDI_FORMAT;
+        unsafe{ SendMessageW(self.header(), HDM_GETITEMW, index as WPARAM, &mut item as *mut HDITEMW as LPARAM); }
+        item.fmt
     }
-    pub fn set_costum_draw() {}
 }
 
 impl Control for ListView {
@@ -326,7 +631,7 @@ impl Control for ListView {
     }
 
     fn control_type(&self) -> ControlType {
-        ControlType::GroupBox
+        ControlType::ListView
     }
 
     fn free(&mut self) {
@@ -334,3 +639,407 @@ impl Control for ListView {
         unsafe { DestroyWindow(self.handle) };
     }
 }
+
+/**
+    The rows displayed by a `VirtualListView`. The list view does not store any item, instead it asks
+    the data source for the content of the rows that are visible on screen.
+
+    Only `row_count` and `cell` must be implemented.
+*/
+#[allow(unused_variables)]
+pub trait ListViewDataSource {
+
+    /// Return the number of rows. Read when the list view is created and when `VirtualListView.notify_changed` is called.
+    fn row_count(&self) -> usize;
+
+    /// Return the text of a cell. `col` is 0 for the item text.
+    fn cell(&self, row: usize, col: usize) -> String;
+
+    /// Return the index of the image of a cell in the list view image list. Return None to display no image.
+    fn image(&self, row: usize, col: usize) -> Option<i32> { None }
+
+    /// Return the checkbox state of a row. Only used if the list view has the `checkboxes` style. None is unchecked.
+    fn checked(&self, row: usize) -> Option<bool> { None }
+
+    /// Called when the user clicks on the checkbox of a row or press space on the focused row
+    fn set_checked(&mut self, row: usize, checked: bool) {}
+
+    /// Called before the list view asks for the rows `from` to `to` (inclusive).
+    /// Can be used to load the rows in advance.
+    fn cache_hint(&mut self, from: usize, to: usize) {}
+
+    /// Return the index of the first row which text starts with `text` (`partial`) or is equal to `text`.
+    /// The search begins at `start` and wraps around the end. Used by the list view keyboard search.  
+    /// The default implementation compares the text of the first column without case sensitivity.
+    fn find(&self, text: &str, start: usize, partial: bool) -> Option<usize> {
+        let count = self.row_count();
+        let text = text.to_lowercase();
+
+        (0..count).map(|i| (start + i) % count).find(|&row| {
+            let cell = self.cell(row, 0).to_lowercase();
+            if partial { cell.starts_with(&text) } else { cell == text }
+        })
+    }
+
+}
+
+/**
+    A template that creates a virtual list view. The rows are read from a `ListViewDataSource`, which allows
+    the list view to display millions of rows. A virtual list view can only be sorted by sorting the data source.
+
+    Control specific events:
+    `list_view::ItemActivated, list_view::ColumnClick, list_view::SelectionChanged, list_view::ListViewCustomDraw`
+
+    Members:
+    • `columns`: The columns of the list view
+    • `data`: The data source of the list view. Keep a reference to it in order to modify the rows
+    • `view_mode`: The way the items are displayed
+    • `position`: The start position of the list view
+    • `size`: The start size of the list view
+    • `visible`: If the list view should be visible to the user
+    • `disabled`: If the user can or can't use the list view
+    • `checkboxes`: If a checkbox is displayed next to each item. The state is read from `ListViewDataSource.checked`
+    • `multi_select`: If true, allow the user to select more than one item
+    • `parent`: The list view parent
+    • `font`: The list view font. If None, use the system default
+*/
+// MSDN:Dynamically switching to and from the LVS_OWNERDATA style is not supported
+// So we need a new struct
+#[derive(Clone)]
+pub struct VirtualListViewT<ID: Hash + Clone>{
+    pub columns: Vec<ListViewColumn>,
+    pub data: Rc<RefCell<ListViewDataSource>>,
+    pub view_mode: ViewMode,
+    pub position: (i32, i32),
+    pub size: (u32, u32),
+    pub visible: bool,
+    pub disabled: bool,
+    pub checkboxes: bool,
+    pub multi_select: bool,
+    pub parent: ID,
+    pub font: Option<ID>,
+}
+
+impl<ID: Hash + Clone> ControlT<ID> for VirtualListViewT<ID>{
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<VirtualListView>()
+    }
+
+    fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        let style: DWORD = WS_CHILDWINDOW | WS_VSCROLL | WS_TABSTOP | LVS_SHOWSELALWAYS | LVS_REPORT | LVS_OWNERDATA |
+          if self.visible       { WS_VISIBLE }    else { 0 } |
+          if self.disabled      { WS_DISABLED }   else { 0 } |
+          if !self.multi_select { LVS_SINGLESEL } else { 0 };
+
+        let ex_style: DWORD = WS_EX_LTRREADING | WS_EX_RIGHTSCROLLBAR | WS_EX_LEFT | WS_EX_CLIENTEDGE;
+
+        let list_view_ex_style = LVS_EX_GRIDLINES | LVS_EX_HEADERDRAGDROP | LVS_EX_FULLROWSELECT | LVS_EX_DOUBLEBUFFER |
+          if self.checkboxes { LVS_EX_CHECKBOXES } else { 0 };
+
+        // Get the parent handle
+        let parent = match handle_of_window(ui, &self.parent, "The parent of a VirtualListView must be a window-like control.") {
+            Ok(h) => h,
+            Err(e) => { return Err(e); }
+        };
+
+        // Get the font handle (if any)
+        let font_handle: Option<HFONT> = match self.font.as_ref() {
+            Some(font_id) =>
+                match handle_of_font(ui, &font_id, "The font of a VirtualListView must be a font resource.") {
+                    Ok(h) => Some(h),
+                    Err(e) => { return Err(e); }
+                },
+            None => None,
+        };
+
+        let params = WindowParams {
+            title: "",
+            class_name: "SysListView32",
+            position: self.position.clone(),
+            size: self.size.clone(),
+            flags: style,
+            ex_flags: Some(ex_style),
+            parent: parent,
+        };
+
+        match unsafe { build_window(params) } {
+            Ok(h) => {
+                unsafe {
+                    set_window_font_raw(h, font_handle, true);
+                    set_lv_ex_style(h, list_view_ex_style, list_view_ex_style);
+
+                    // The checkbox state is stored in the data source
+                    if self.checkboxes {
+                        SendMessageW(h, LVM_SETCALLBACKMASK, LVIS_STATEIMAGEMASK as WPARAM, 0);
+                    }
+                }
+
+                let list = ListView { handle: h };
+                for (i, c) in self.columns.iter().enumerate() {
+                    list.insert_col(i, c);
+                }
+                list.set_view_mode(&self.view_mode);
+
+                let row_count = match self.data.try_borrow() {
+                    Ok(data) => data.row_count(),
+                    Err(_) => 0
+                };
+                unsafe{ SendMessageW(h, LVM_SETITEMCOUNT, row_count as WPARAM, 0); }
+
+                // The list view notifications are sent to the parent window
+                let data = Box::into_raw(Box::new(self.data.clone()));
+                unsafe{ hook_parent(parent, h, data); }
+
+                Ok(Box::new(VirtualListView{ list: list, parent: parent, data: data }))
+            }
+            Err(e) => Err(Error::System(e)),
+        }
+    }
+}
+
+/**
+    A list view that reads its rows from a `ListViewDataSource`. Items and columns are identified by their index.  
+    When the data source is modified, call `notify_changed` (or `notify_rows_changed`) to refresh the list view.
+*/
+pub struct VirtualListView {
+    list: ListView,
+    parent: HWND,
+    data: *mut Rc<RefCell<ListViewDataSource>>
+}
+
+impl VirtualListView {
+
+    /// Return the data source of the list view
+    pub fn data(&self) -> Rc<RefCell<ListViewDataSource>> {
+        unsafe{ (*self.data).clone() }
+    }
+
+    /// Read the number of rows in the data source and redraw every row. The selection is kept if the rows still exist.  
+    /// The data source must not be mutably borrowed when this method is called.
+    pub fn notify_changed(&self) {
+        let row_count = unsafe{ (*self.data).borrow().row_count() };
+        unsafe{ SendMessageW(self.list.handle, LVM_SETITEMCOUNT, row_count as WPARAM, LVSICF_NOSCROLL); }
+    }
+
+    /// Redraw the rows `from` to `to` (inclusive). Use this if the number of rows did not change.
+    pub fn notify_rows_changed(&self, from: usize, to: usize) {
+        unsafe{ SendMessageW(self.list.handle, LVM_REDRAWITEMS, from as WPARAM, to as LPARAM); }
+        self.list.update();
+    }
+
+    /// Return the number of rows in the list view
+    pub fn get_count(&self) -> usize { self.list.get_count() }
+
+    /// Return the text of a cell. Return an empty string if the row does not exist.
+    pub fn get_text(&self, index: usize, column: usize) -> String {
+        let data = unsafe{ (*self.data).borrow() };
+        if index < data.row_count() { data.cell(index, column) }
+        else { String::new() }
+    }
+
+    /// Return the index of the first row which text starts with `text`, using `ListViewDataSource.find`
+    pub fn find(&self, text: &str, start: usize) -> Option<usize> {
+        let data = unsafe{ (*self.data).borrow() };
+        if data.row_count() == 0 { None }
+        else { data.find(text, start, true) }
+    }
+
+    pub fn get_next(&self, start: Option<usize>, selected: bool) -> Option<usize> { self.list.get_next(start, selected) }
+    pub fn get_selected_index(&self) -> Option<usize> { self.list.get_selected_index() }
+    pub fn get_selected_indexes(&self) -> Vec<usize> { self.list.get_selected_indexes() }
+    pub fn len_selected(&self) -> usize { self.list.len_selected() }
+    pub fn index_selected(&self, index: usize) -> bool { self.list.index_selected(index) }
+    pub fn set_index_selected(&self, index: usize, selected: bool) { self.list.set_index_selected(index, selected) }
+    pub fn get_multi_select(&self) -> bool { self.list.get_multi_select() }
+    pub fn set_multi_select(&self, multi: bool) { self.list.set_multi_select(multi) }
+    pub fn ensure_visible(&self, index: usize) { self.list.ensure_visible(index) }
+    pub fn hit_test(&self, x: i32, y: i32) -> Option<(usize, usize)> { self.list.hit_test(x, y) }
+
+    pub fn add_col(&self, column: &ListViewColumn) -> Option<usize> { self.list.add_col(column) }
+    pub fn insert_col(&self, index: usize, column: &ListViewColumn) -> Option<usize> { self.list.insert_col(index, column) }
+    pub fn modify_col(&self, index: usize, column: &ListViewColumn) { self.list.modify_col(index, column) }
+    pub fn get_col(&self, index: usize) -> Option<ListViewColumn> { self.list.get_col(index) }
+    pub fn delete_col(&self, index: usize) -> bool { self.list.delete_col(index) }
+    pub fn col_count(&self) -> usize { self.list.col_count() }
+    pub fn get_col_width(&self, index: usize) -> i32 { self.list.get_col_width(index) }
+    pub fn set_col_width(&self, index: usize, width: i32) { self.list.set_col_width(index, width) }
+    pub fn get_sort_indicator(&self, index: usize) -> Option<SortDirection> { self.list.get_sort_indicator(index) }
+    pub fn set_sort_indicator(&self, index: usize, sort: Option<SortDirection>) { self.list.set_sort_indicator(index, sort) }
+    pub fn get_sort(&self) -> Option<(usize, SortDirection)> { self.list.get_sort() }
+    pub fn get_view_mode(&self) -> ViewMode { self.list.get_view_mode() }
+    pub fn set_view_mode(&self, mode: &ViewMode) { self.list.set_view_mode(mode) }
+
+    pub fn get_visibility(&self) -> bool { self.list.get_visibility() }
+    pub fn set_visibility(&self, visible: bool) { self.list.set_visibility(visible) }
+    pub fn get_position(&self) -> (i32, i32) { self.list.get_position() }
+    pub fn set_position(&self, x: i32, y: i32) { self.list.set_position(x, y) }
+    pub fn get_size(&self) -> (u32, u32) { self.list.get_size() }
+    pub fn set_size(&self, w: u32, h: u32) { self.list.set_size(w, h) }
+    pub fn get_enabled(&self) -> bool { self.list.get_enabled() }
+    pub fn set_enabled(&self, e:bool) { self.list.set_enabled(e) }
+    pub fn get_font<ID: Hash+Clone>(&self, ui: &Ui<ID>) -> Option<ID> { self.list.get_font(ui) }
+    pub fn set_font<ID: Hash+Clone>(&self, ui: &Ui<ID>, f: Option<&ID>) -> Result<(), Error> { self.list.set_font(ui, f) }
+    pub fn update(&self) { self.list.update() }
+    pub fn focus(&self) { self.list.focus() }
+}
+
+impl Control for VirtualListView {
+    fn handle(&self) -> AnyHandle {
+        AnyHandle::HWND(self.list.handle)
+    }
+
+    fn control_type(&self) -> ControlType {
+        ControlType::ListView
+    }
+
+    fn free(&mut self) {
+        use user32::DestroyWindow;
+        unsafe {
+            unhook_parent(self.parent, self.list.handle);
+            DestroyWindow(self.list.handle);
+            drop(Box::from_raw(self.data));
+        }
+    }
+}
+
+// The subclass id is the list view handle, so a window can be the parent of many virtual list views
+unsafe fn hook_parent(parent: HWND, list: HWND, data: *mut Rc<RefCell<ListViewDataSource>>) {
+    use comctl32::SetWindowSubclass;
+    SetWindowSubclass(parent, Some(virtual_list_proc), list as UINT_PTR, data as DWORD_PTR);
+}
+
+unsafe fn unhook_parent(parent: HWND, list: HWND) {
+    use comctl32::RemoveWindowSubclass;
+    RemoveWindowSubclass(parent, Some(virtual_list_proc), list as UINT_PTR);
+}
+
+/// Answer the notifications that an owner data list view sends to its parent.
+/// The data source is only borrowed if it is not already borrowed by the application.
+unsafe extern "system" fn virtual_list_proc(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM, id: UINT_PTR, data: DWORD_PTR) -> LRESULT {
+    use comctl32::DefSubclassProc;
+    use winapi::{WM_NOTIFY, NMHDR, VK_SPACE};
+
+    if msg != WM_NOTIFY {
+        return DefSubclassProc(hwnd, msg, w, l);
+    }
+
+    let nmhdr: &NMHDR = &*(l as *const NMHDR);
+    if nmhdr.hwndFrom as UINT_PTR != id {
+        return DefSubclassProc(hwnd, msg, w, l);
+    }
+
+    let list = nmhdr.hwndFrom;
+    let data = &*(data as *const Rc<RefCell<ListViewDataSource>>);
+    match nmhdr.code {
+        LVN_GETDISPINFOW => {
+            let info: &mut NMLVDISPINFOW = &mut *(l as *mut NMLVDISPINFOW);
+            if let Ok(data) = data.try_borrow() {
+                fill_item(&*data, &mut info.item);
+            }
+            0
+        },
+        LVN_ODCACHEHINT => {
+            let hint: &NMLVCACHEHINT = &*(l as *const NMLVCACHEHINT);
+            if let Ok(mut data) = data.try_borrow_mut() {
+                data.cache_hint(hint.iFrom as usize, hint.iTo as usize);
+            }
+            0
+        },
+        LVN_ODFINDITEMW => {
+            let find: &NMLVFINDITEMW = &*(l as *const NMLVFINDITEMW);
+            if find.lvfi.flags & (LVFI_STRING | LVFI_PARTIAL) == 0 || find.lvfi.psz.is_null() {
+                return -1;
+            }
+
+            let text = from_wide_ptr(find.lvfi.psz as *mut u16);
+            let partial = find.lvfi.flags & LVFI_PARTIAL == LVFI_PARTIAL;
+            match data.try_borrow() {
+                Ok(ref data) if data.row_count() > 0 => {
+                    let start = (find.iStart.max(0) as usize) % data.row_count();
+                    match data.find(&text, start, partial) {
+                        Some(row) => row as LRESULT,
+                        None => -1
+                    }
+                },
+                _ => -1
+            }
+        },
+        NM_CLICK => {
+            let click: &NMITEMACTIVATE = &*(l as *const NMITEMACTIVATE);
+            let mut info: LVHITTESTINFO = zeroed();
+            info.pt = click.ptAction;
+            SendMessageW(list, LVM_HITTEST, 0, &mut info as *mut LVHITTESTINFO as LPARAM);
+            if info.iItem >= 0 && info.flags & LVHT_ONITEMSTATEICON == LVHT_ONITEMSTATEICON {
+                toggle_checked(list, data, info.iItem as usize);
+            }
+            DefSubclassProc(hwnd, msg, w, l)
+        },
+        LVN_KEYDOWN => {
+            let key: &NMLVKEYDOWN = &*(l as *const NMLVKEYDOWN);
+            if key.wVKey as c_int == VK_SPACE {
+                let focused = SendMessageW(list, LVM_GETNEXTITEM, -1isize as WPARAM, LVNI_FOCUSED as LPARAM);
+                if focused >= 0 {
+                    toggle_checked(list, data, focused as usize);
+                }
+            }
+            DefSubclassProc(hwnd, msg, w, l)
+        },
+        _ => DefSubclassProc(hwnd, msg, w, l)
+    }
+}
+
+/// Fill the fields of `item` requested by the list view in a LVN_GETDISPINFO notification
+fn fill_item(data: &ListViewDataSource, item: &mut LVITEMW) {
+    let (row, col) = (item.iItem as usize, item.iSubItem as usize);
+    if item.iItem < 0 || row >= data.row_count() {
+        return;
+    }
+
+    if item.mask & LVIF_TEXT == LVIF_TEXT && !item.pszText.is_null() && item.cchTextMax > 0 {
+        // The text is truncated to fit the list view buffer. `to_utf16` adds the null terminator.
+        let text = to_utf16(&data.cell(row, col));
+        let length = (text.len() - 1).min(item.cchTextMax as usize - 1);
+        unsafe {
+            ptr::copy_nonoverlapping(text.as_ptr(), item.pszText, length);
+            *item.pszText.offset(length as isize) = 0;
+        }
+    }
+
+    if item.mask & LVIF_IMAGE == LVIF_IMAGE {
+        item.iImage = data.image(row, col).unwrap_or(I_IMAGENONE);
+    }
+
+    if item.mask & LVIF_STATE == LVIF_STATE && item.stateMask & LVIS_STATEIMAGEMASK != 0 {
+        let checked = data.checked(row).unwrap_or(false);
+        item.state = (item.state & !LVIS_STATEIMAGEMASK) | INDEXTOSTATEIMAGEMASK(if checked { 2 } else { 1 });
+    }
+}
+
+/// Flip the checkbox state of a row in the data source and redraw the row
+unsafe fn toggle_checked(list: HWND, data: &Rc<RefCell<ListViewDataSource>>, row: usize) {
+    if let Ok(mut data) = data.try_borrow_mut() {
+        if row >= data.row_count() { return; }
+        let checked = data.checked(row).unwrap_or(false);
+        data.set_checked(row, !checked);
+    }
+
+    SendMessageW(list, LVM_REDRAWITEMS, row as WPARAM, row as LPARAM);
+}
+
+fn column_format(align: &HTextAlign) -> c_int {
+    match align {
+        &HTextAlign::Left => LVCFMT_LEFT,
+        &HTextAlign::Center => LVCFMT_CENTER,
+        &HTextAlign::Right => LVCFMT_RIGHT
+    }
+}
+
+/// The comparison function sent to LVM_SORTITEMS. `data` is a pointer to the comparator passed to `custom_sort`
+unsafe extern "system" fn compare_items(a: LPARAM, b: LPARAM, data: LPARAM) -> c_int {
+    let cmp = &mut *(data as *mut &mut FnMut(usize, usize) -> Ordering);
+    match cmp(a as usize, b as usize) {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1
+    }
+}
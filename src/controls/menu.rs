@@ -7,7 +7,7 @@ use std::any::TypeId;
 use std::mem;
 use std::ptr;
 
-use winapi::{HWND, HMENU, UINT, BOOL};
+use winapi::{HWND, HMENU, HBITMAP, UINT, UINT_PTR, BOOL};
 
 use ui::Ui;
 use controls::{Control, ControlT, ControlType, AnyHandle};
@@ -23,12 +23,15 @@ static mut MENU_ITEMS_ID: UINT = 0;
     Members:  
       • `text` : The menu text  
       • `parent` : ID of the parent control to add the menu to  
+      • `disabled` : If the menu can be opened by the user  
+      • `index` : The position of the menu in its parent. If `None` or out of bounds, the menu is added at the end  
 */
 #[derive(Clone)]
 pub struct MenuT<S: Clone+Into<String>, ID: Hash+Clone> {
     pub text: S,
     pub parent: ID,
-    pub disabled: bool
+    pub disabled: bool,
+    pub index: Option<u32>
 }
 
 impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for MenuT<S, ID> {
@@ -171,12 +174,23 @@ impl Control for ContextMenu {
     Members:  
       • `text` : The menu text  
       • `parent` : ID of the parent control to add the menu to  
+      • `disabled` : If the menuitem can be triggered by the user  
+      • `checked` : If a check mark is displayed next to the menuitem  
+      • `radio` : Display a bullet instead of a check mark. Consecutive radio menuitems form a group where only one item is checked  
+      • `default` : If the menuitem is the default item of its menu. The default item is displayed in bold  
+      • `image` : The ID of a bitmap `Image` resource displayed next to the menuitem text  
+      • `index` : The position of the menuitem in its parent. If `None` or out of bounds, the menuitem is added at the end  
 */
 #[derive(Clone)]
 pub struct MenuItemT<S: Clone+Into<String>, ID: Hash+Clone> {
     pub text: S,
     pub parent: ID,
-    pub disabled: bool
+    pub disabled: bool,
+    pub checked: bool,
+    pub radio: bool,
+    pub default: bool,
+    pub image: Option<ID>,
+    pub index: Option<u32>
 }
 
 impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for MenuItemT<S, ID> {
@@ -212,6 +226,82 @@ impl MenuItem {
         unsafe{ ::low::menu_helper::enable_menuitem(self.parent, None, Some(self.unique_id), enabled); }
     }
 
+    /// Return true if a check mark (or a bullet) is displayed next to the menuitem
+    pub fn get_checked(&self) -> bool {
+        use low::defs::MFS_CHECKED;
+        unsafe{ ::low::menu_helper::get_menuitem_state_flag(self.parent, self.unique_id, MFS_CHECKED) }
+    }
+
+    /// Check or uncheck the menuitem. Checking a radio menuitem unchecks the other menuitems of its group.
+    pub fn set_checked(&self, checked: bool) {
+        unsafe{ set_checked(self.parent, self.unique_id, checked); }
+    }
+
+    /// Return true if the menuitem displays a bullet instead of a check mark
+    pub fn get_radio(&self) -> bool {
+        use low::defs::{GetMenuItemInfoW, MIIM_FTYPE, MFT_RADIOCHECK};
+        use low::menu_helper::menuitem_info;
+
+        let mut info = menuitem_info(MIIM_FTYPE);
+        unsafe{ GetMenuItemInfoW(self.parent, self.unique_id, false as BOOL, &mut info); }
+        info.fType & MFT_RADIOCHECK == MFT_RADIOCHECK
+    }
+
+    /// Display a bullet (`true`) or a check mark (`false`) when the menuitem is checked
+    pub fn set_radio(&self, radio: bool) {
+        unsafe{ ::low::menu_helper::set_menuitem_radio(self.parent, self.unique_id, radio); }
+    }
+
+    /// Return true if the menuitem is the default item of its menu
+    pub fn get_default(&self) -> bool {
+        use low::defs::MFS_DEFAULT;
+        unsafe{ ::low::menu_helper::get_menuitem_state_flag(self.parent, self.unique_id, MFS_DEFAULT) }
+    }
+
+    /// Make the menuitem the default item of its menu. A menu can only have one default item.
+    /// Passing `false` removes the default item of the menu if it is this menuitem.
+    pub fn set_default(&self, default: bool) {
+        if default || self.get_default() {
+            unsafe{ set_default(self.parent, self.unique_id, default); }
+        }
+    }
+
+    /// Return the menuitem text. The shortcut displayed by an accelerator is not included.
+    pub fn get_text(&self) -> String {
+        let text = unsafe{ ::low::menu_helper::get_menuitem_text(self.parent, self.unique_id) };
+        text.split('\t').next().unwrap_or("").to_string()
+    }
+
+    /// Set the menuitem text. The shortcut displayed by an accelerator is kept.
+    pub fn set_text<'a>(&self, text: &'a str) {
+        use low::menu_helper::{get_menuitem_text, set_menuitem_text};
+
+        unsafe{
+            let old_text = get_menuitem_text(self.parent, self.unique_id);
+            match old_text.find('\t') {
+                Some(i) => set_menuitem_text(self.parent, self.unique_id, &format!("{}{}", text, &old_text[i..])),
+                None => set_menuitem_text(self.parent, self.unique_id, text)
+            }
+        }
+    }
+
+    /// Set the bitmap displayed next to the menuitem text. The image must be a bitmap `Image` resource.
+    /// Pass `None` as an argument to remove the image.
+    pub fn set_image<ID: Hash+Clone>(&self, ui: &Ui<ID>, img: Option<&ID>) -> Result<(), Error> {
+        if !ui.has_handle(&self.handle()) {
+            return Err(Error::BadUi("Image resource and control must be in the same Ui.".to_string()));
+        }
+
+        let bitmap = match menuitem_bitmap(ui, img) {
+            Ok(b) => b,
+            Err(e) => { return Err(e); }
+        };
+
+        unsafe{ ::low::menu_helper::set_menuitem_bitmap(self.parent, self.unique_id, bitmap); }
+
+        Ok(())
+    }
+
 }
 
 
@@ -293,7 +383,7 @@ impl Control for Separator {
 
 #[inline(always)]
 unsafe fn build_menu<S: Clone+Into<String>, ID: Clone+Hash>(ui: &Ui<ID>, t: &MenuT<S, ID>) -> Result<(HMENU, AnyHandle), Error> {
-    use user32::{CreateMenu, CreatePopupMenu, AppendMenuW, GetMenu, SetMenu, DrawMenuBar};
+    use user32::{CreateMenu, CreatePopupMenu, GetMenu, SetMenu, DrawMenuBar};
     use winapi::{MF_STRING, MF_POPUP};
     use low::menu_helper::{use_menu_command, enable_menuitem};
     use low::other_helper::to_utf16;
@@ -319,8 +409,8 @@ unsafe fn build_menu<S: Clone+Into<String>, ID: Clone+Hash>(ui: &Ui<ID>, t: &Men
             use_menu_command(h);
 
             let text = to_utf16(t.text.clone().into().as_ref());
-            AppendMenuW(menubar, MF_STRING|MF_POPUP, mem::transmute(h), text.as_ptr());
-            enable_menuitem(menubar, None, None, !t.disabled);
+            let pos = insert_menu_item(menubar, t.index, MF_STRING|MF_POPUP, mem::transmute(h), &text);
+            enable_menuitem(menubar, Some(pos), None, !t.disabled);
             DrawMenuBar(parent_h); // Draw the menu bar to make sure the changes are visible
 
             Ok( ( h, AnyHandle::HWND(parent_h)) )
@@ -330,8 +420,8 @@ unsafe fn build_menu<S: Clone+Into<String>, ID: Clone+Hash>(ui: &Ui<ID>, t: &Men
             use_menu_command(h);
 
             let text = to_utf16(t.text.clone().into().as_ref());
-            AppendMenuW(parent_h, MF_STRING|MF_POPUP, mem::transmute(h), text.as_ptr());
-            enable_menuitem(parent_h, None, None, !t.disabled);
+            let pos = insert_menu_item(parent_h, t.index, MF_STRING|MF_POPUP, mem::transmute(h), &text);
+            enable_menuitem(parent_h, Some(pos), None, !t.disabled);
 
             Ok( ( h, AnyHandle::HMENU(parent_h) ) )
 
@@ -353,14 +443,19 @@ unsafe fn build_context_menu<ID: Clone+Hash>(ui: &Ui<ID>) -> Result<(HMENU, HWND
 
 #[inline(always)]
 unsafe fn build_menu_item<S: Clone+Into<String>, ID: Clone+Hash>(ui: &Ui<ID>, t: &MenuItemT<S, ID>) -> Result<(HMENU, UINT), Error> {
-    use user32::{AppendMenuW, CreateMenu, GetMenu, SetMenu, DrawMenuBar};
-    use winapi::{MF_STRING, UINT_PTR};
+    use user32::{CreateMenu, GetMenu, SetMenu, DrawMenuBar};
+    use winapi::MF_STRING;
     use low::other_helper::to_utf16;
-    use low::menu_helper::enable_menuitem;
     
     let ph_result = ui.handle_of(&t.parent);
     if ph_result.is_err() { return Err(ph_result.err().unwrap()); }
 
+    // Get the image before creating the menuitem
+    let bitmap = match menuitem_bitmap(ui, t.image.as_ref()) {
+        Ok(b) => b,
+        Err(e) => { return Err(e); }
+    };
+
     match ph_result.unwrap() {
         AnyHandle::HWND(parent_h) => {
             let mut menubar = GetMenu(parent_h);
@@ -374,8 +469,8 @@ unsafe fn build_menu_item<S: Clone+Into<String>, ID: Clone+Hash>(ui: &Ui<ID>, t:
             let text = to_utf16(t.text.clone().into().as_ref());
             let ensure_id_stays_the_same = MENU_ITEMS_ID;
 
-            AppendMenuW(menubar, MF_STRING, ensure_id_stays_the_same as UINT_PTR, text.as_ptr());
-            enable_menuitem(menubar, None, None, !t.disabled);
+            insert_menu_item(menubar, t.index, MF_STRING, ensure_id_stays_the_same as UINT_PTR, &text);
+            set_menu_item_options(menubar, ensure_id_stays_the_same, t, bitmap);
 
             DrawMenuBar(parent_h); // Draw the menu bar to make sure the changes are visible
 
@@ -387,14 +482,97 @@ unsafe fn build_menu_item<S: Clone+Into<String>, ID: Clone+Hash>(ui: &Ui<ID>, t:
         AnyHandle::HMENU(parent_h) => {
             let text = to_utf16(t.text.clone().into().as_ref());
             MENU_ITEMS_ID += 1;
-            AppendMenuW(parent_h, MF_STRING, MENU_ITEMS_ID as UINT_PTR, text.as_ptr());
-            enable_menuitem(parent_h, None, None, !t.disabled);
-            Ok( (parent_h, MENU_ITEMS_ID) )
+            let ensure_id_stays_the_same = MENU_ITEMS_ID;
+
+            insert_menu_item(parent_h, t.index, MF_STRING, ensure_id_stays_the_same as UINT_PTR, &text);
+            set_menu_item_options(parent_h, ensure_id_stays_the_same, t, bitmap);
+
+            Ok( (parent_h, ensure_id_stays_the_same) )
         },
         h => Err( Error::BadParent(format!("A menu item parent must be a Menu or a Window. Got {:?}", h)) )
     }
 }
 
+/**
+    Insert an item in a menu at `index`. If `index` is None or out of bounds, the item is appended.
+    Returns the position of the new item.
+*/
+#[inline(always)]
+unsafe fn insert_menu_item(menu: HMENU, index: Option<u32>, flags: UINT, id: UINT_PTR, text: &Vec<u16>) -> UINT {
+    use user32::{AppendMenuW, InsertMenuW};
+    use low::defs::{GetMenuItemCount, MF_BYPOSITION};
+
+    let count = GetMenuItemCount(menu) as UINT;
+    match index {
+        Some(i) if i < count => {
+            InsertMenuW(menu, i, flags|MF_BYPOSITION, id, text.as_ptr());
+            i
+        },
+        _ => {
+            AppendMenuW(menu, flags, id, text.as_ptr());
+            count
+        }
+    }
+}
+
+/**
+    Apply the state of a menuitem template to a new menuitem
+*/
+#[inline(always)]
+unsafe fn set_menu_item_options<S: Clone+Into<String>, ID: Clone+Hash>(menu: HMENU, id: UINT, t: &MenuItemT<S, ID>, bitmap: HBITMAP) {
+    use low::menu_helper::{enable_menuitem, set_menuitem_radio, set_menuitem_bitmap};
+
+    enable_menuitem(menu, None, Some(id), !t.disabled);
+    if t.radio { set_menuitem_radio(menu, id, true); }
+    if t.checked { set_checked(menu, id, true); }
+    if t.default { set_default(menu, id, true); }
+    if !bitmap.is_null() { set_menuitem_bitmap(menu, id, bitmap); }
+}
+
+/**
+    Check or uncheck a menuitem. If the menuitem is a radio item, the other items of its group are unchecked.
+*/
+unsafe fn set_checked(menu: HMENU, id: UINT, checked: bool) {
+    use low::defs::{GetMenuItemInfoW, MIIM_FTYPE, MFT_RADIOCHECK, MFS_CHECKED};
+    use low::menu_helper::{menuitem_info, set_menuitem_state_flag, uncheck_radio_group};
+
+    let mut info = menuitem_info(MIIM_FTYPE);
+    GetMenuItemInfoW(menu, id, false as BOOL, &mut info);
+    if checked && info.fType & MFT_RADIOCHECK == MFT_RADIOCHECK {
+        uncheck_radio_group(menu, id);
+    }
+
+    set_menuitem_state_flag(menu, id, MFS_CHECKED, checked);
+}
+
+/**
+    Set or remove the default item of a menu
+*/
+unsafe fn set_default(menu: HMENU, id: UINT, default: bool) {
+    use low::defs::SetMenuDefaultItem;
+
+    match default {
+        true => SetMenuDefaultItem(menu, id, 0),
+        false => SetMenuDefaultItem(menu, !0, 0)  // -1 removes the default item
+    };
+}
+
+/**
+    Return the bitmap handle of an image resource. Returns a null handle if `img` is None.
+*/
+fn menuitem_bitmap<ID: Clone+Hash>(ui: &Ui<ID>, img: Option<&ID>) -> Result<HBITMAP, Error> {
+    use controls::HandleSpec;
+
+    match img {
+        Some(id) => match ui.handle_of(id) {
+            Ok(AnyHandle::HANDLE(h, HandleSpec::Bitmap)) => Ok(h as HBITMAP),
+            Ok(h) => Err(Error::BadResource(format!("A menuitem image must be a bitmap, got {:?}", h))),
+            Err(e) => Err(e)
+        },
+        None => Ok(ptr::null_mut())
+    }
+}
+
 #[inline(always)]
 unsafe fn build_separator<ID: Clone+Hash>(ui: &Ui<ID>, t: &SeparatorT<ID>) -> Result<(HMENU, UINT), Error> {
     use user32::AppendMenuW;
@@ -2,54 +2,77 @@
     Control trait definition. The base control definitions are located in the submodules.
 */
 
-pub mod window;
-pub mod menu;
-pub mod button;
-pub mod textinput;
-pub mod textbox;
-pub mod checkbox;
-pub mod radiobutton;
-pub mod label;
-pub mod listbox;
-pub mod combobox;
-pub mod groupbox;
-pub mod progress_bar;
-pub mod datepicker;
-pub mod image_frame;
-pub mod file_dialog;
-pub mod timer;
-pub mod treeview;
-pub mod frame;
-pub mod tabs;
-pub mod list_view;
-#[cfg(feature = "canvas")] pub mod canvas;
+#[cfg(windows)] pub mod window;
+#[cfg(windows)] pub mod menu;
+#[cfg(windows)] pub mod button;
+#[cfg(windows)] pub mod textinput;
+#[cfg(windows)] pub mod textbox;
+#[cfg(windows)] pub mod rich_textbox;
+#[cfg(windows)] pub mod numeric_input;
+#[cfg(windows)] pub mod checkbox;
+#[cfg(windows)] pub mod radiobutton;
+#[cfg(windows)] pub mod label;
+#[cfg(windows)] pub mod listbox;
+#[cfg(windows)] pub mod combobox;
+#[cfg(windows)] pub mod groupbox;
+#[cfg(windows)] pub mod progress_bar;
+#[cfg(windows)] pub mod slider;
+#[cfg(windows)] pub mod status_bar;
+#[cfg(windows)] pub mod toolbar;
+#[cfg(windows)] pub mod tooltip;
+#[cfg(windows)] pub mod tray_notification;
+#[cfg(windows)] pub mod datepicker;
+#[cfg(windows)] pub mod image_frame;
+#[cfg(windows)] pub mod file_dialog;
+#[cfg(windows)] pub mod color_dialog;
+#[cfg(windows)] pub mod font_dialog;
+#[cfg(windows)] pub mod print_dialog;
+#[cfg(windows)] pub mod timer;
+#[cfg(windows)] pub mod accelerator;
+#[cfg(windows)] pub mod treeview;
+#[cfg(windows)] pub mod frame;
+#[cfg(windows)] pub mod tabs;
+#[cfg(windows)] pub mod list_view;
+#[cfg(all(windows, feature = "canvas"))] pub mod canvas;
 
 use std::any::TypeId;
 use std::hash::Hash;
 
 use winapi::{HWND, HANDLE, HCURSOR, HICON, HMENU, UINT, HFONT, HTREEITEM};
 
-pub use controls::window::{WindowT, Window};
-pub use controls::menu::{MenuT, Menu, MenuItemT, MenuItem, SeparatorT, Separator, ContextMenuT, ContextMenu};
-pub use controls::button::{ButtonT, Button};
-pub use controls::textinput::{TextInputT, TextInput};
-pub use controls::textbox::{TextBoxT, TextBox};
-pub use controls::checkbox::{CheckBoxT, CheckBox};
-pub use controls::radiobutton::{RadioButtonT, RadioButton};
-pub use controls::label::{LabelT, Label};
-pub use controls::listbox::{ListBoxT, ListBox};
-pub use controls::combobox::{ComboBoxT, ComboBox};
-pub use controls::groupbox::{GroupBoxT, GroupBox};
-pub use controls::progress_bar::{ProgressBarT, ProgressBar};
-pub use controls::file_dialog::{FileDialogT, FileDialog};
-pub use controls::timer::{TimerT, Timer};
-pub use controls::datepicker::{DatePickerT, DatePicker};
-pub use controls::image_frame::{ImageFrameT, ImageFrame};
-pub use controls::treeview::{TreeViewT, TreeView, TreeViewItemT, TreeViewItem, TreeItemIterator};
-pub use controls::frame::{FrameT, Frame};
-pub use controls::tabs::{TabViewT, TabView, TabT, Tab};
-pub use controls::list_view::{ListView,ViewMode,ListViewT};
-#[cfg(feature = "canvas")] pub use controls::canvas::{CanvasT, Canvas, CanvasRenderer};
+#[cfg(windows)] pub use controls::window::{WindowT, Window};
+#[cfg(windows)] pub use controls::menu::{MenuT, Menu, MenuItemT, MenuItem, SeparatorT, Separator, ContextMenuT, ContextMenu};
+#[cfg(windows)] pub use controls::button::{ButtonT, Button};
+#[cfg(windows)] pub use controls::textinput::{TextInputT, TextInput};
+#[cfg(windows)] pub use controls::textbox::{TextBoxT, TextBox};
+#[cfg(windows)] pub use controls::rich_textbox::{RichTextBoxT, RichTextBox, StreamFormat, FindOptions};
+#[cfg(windows)] pub use controls::numeric_input::{NumericInputT, NumericInput};
+#[cfg(windows)] pub use controls::checkbox::{CheckBoxT, CheckBox};
+#[cfg(windows)] pub use controls::radiobutton::{RadioButtonT, RadioButton};
+#[cfg(windows)] pub use controls::label::{LabelT, Label};
+#[cfg(windows)] pub use controls::listbox::{ListBoxT, ListBox};
+#[cfg(windows)] pub use controls::combobox::{ComboBoxT, ComboBox};
+#[cfg(windows)] pub use controls::groupbox::{GroupBoxT, GroupBox};
+#[cfg(windows)] pub use controls::progress_bar::{ProgressBarT, ProgressBar};
+#[cfg(windows)] pub use controls::slider::{SliderT, Slider};
+#[cfg(windows)] pub use controls::status_bar::{StatusBarT, StatusBar, StatusPartWidth};
+#[cfg(windows)] pub use controls::toolbar::{ToolbarT, Toolbar, ToolbarButton, ToolbarButtonStyle};
+#[cfg(windows)] pub use controls::tooltip::{TooltipT, Tooltip, TooltipIcon, TooltipDelay};
+#[cfg(windows)] pub use controls::tray_notification::{TrayNotificationT, TrayNotification, TrayBalloonIcon};
+#[cfg(windows)] pub use controls::file_dialog::{FileDialogT, FileDialog};
+#[cfg(windows)] pub use controls::color_dialog::{ColorDialogT, ColorDialog};
+#[cfg(windows)] pub use controls::font_dialog::{FontDialogT, FontDialog};
+#[cfg(windows)] pub use controls::print_dialog::{PrintDialogT, PrintDialog, PrintSettings, PrintRange};
+#[cfg(windows)] pub use controls::timer::{TimerT, Timer};
+#[cfg(windows)] pub use controls::accelerator::{AcceleratorT, Accelerator};
+#[cfg(windows)] pub use controls::datepicker::{DatePickerT, DatePicker};
+#[cfg(windows)] pub use controls::image_frame::{ImageFrameT, ImageFrame};
+#[cfg(windows)] pub use controls::treeview::{TreeViewT, TreeView, TreeViewItemT, TreeViewItem, TreeItemIterator};
+#[cfg(windows)] pub use controls::frame::{FrameT, Frame};
+#[cfg(windows)] pub use controls::tabs::{TabViewT, TabView, TabT, Tab};
+#[cfg(windows)] pub use controls::list_view::{ListView, ViewMode, ListViewT, ListViewColumn, SortDirection, VirtualListViewT, VirtualListView,
+ ListViewDataSource};
+#[cfg(all(windows, feature = "canvas"))] pub use controls::canvas::{CanvasT, Canvas, CanvasRenderer};
 
 use ui::Ui;
 use error::Error;
@@ -128,6 +151,16 @@ pub enum ControlType {
     TabsView,
     Tab,
     ListView,
+    Accelerator,
+    Slider,
+    RichTextBox,
+    StatusBar,
+    Toolbar,
+    Tooltip,
+    TrayNotification,
+    ColorDialog,
+    FontDialog,
+    PrintDialog,
     Undefined  // Control is not a common control
 }
 
@@ -0,0 +1,483 @@
+/*!
+    A numeric input control. An edit box with up-down arrows
+*/
+
+use std::hash::Hash;
+use std::any::TypeId;
+use std::cell::Cell;
+
+use winapi::{HWND, HFONT, UINT, WPARAM, LPARAM, LRESULT, UINT_PTR, DWORD_PTR};
+use user32::SendMessageW;
+
+use ui::Ui;
+use controls::{Control, ControlT, ControlType, AnyHandle};
+use error::Error;
+
+/**
+    A template that creates a numeric input. The control is an edit box combined with up-down arrows.
+    The value is rounded to `decimals` digits after the decimal point. If `decimals` is 0, the control only accepts integers.
+    Text typed by the user is read when the control loses the focus. Invalid text is replaced by the last valid value.
+
+    Control specific events:  
+    `numeric_input::ValueChanged, numeric_input::Focus`  
+
+    Members:  
+    • `value`: The starting value of the numeric input  
+    • `range`: The minimum and the maximum value of the numeric input  
+    • `step`: Amount of value added or removed when the user clicks on an arrow  
+    • `decimals`: The number of digits displayed after the decimal point  
+    • `wrap`: If the value should wrap around when going over the minimum or the maximum value  
+    • `thousands_separator`: If the digits should be grouped by thousands (ex: "1,000,000")  
+    • `position`: The start position of the numeric input  
+    • `size`: The start size of the numeric input. Includes the arrows  
+    • `visible`: If the numeric input should be visible to the user  
+    • `disabled`: If the user can or can't edit the numeric input  
+    • `parent`: The numeric input parent  
+    • `font`: The numeric input font. If None, use the system default  
+*/
+#[derive(Clone)]
+pub struct NumericInputT<ID: Hash+Clone> {
+    pub value: f64,
+    pub range: (f64, f64),
+    pub step: f64,
+    pub decimals: u32,
+    pub wrap: bool,
+    pub thousands_separator: bool,
+    pub position: (i32, i32),
+    pub size: (u32, u32),
+    pub visible: bool,
+    pub disabled: bool,
+    pub parent: ID,
+    pub font: Option<ID>,
+}
+
+impl<ID: Hash+Clone> ControlT<ID> for NumericInputT<ID> {
+    fn type_id(&self) -> TypeId { TypeId::of::<NumericInput>() }
+
+    fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        use low::window_helper::{WindowParams, build_window, set_window_font_raw, handle_of_window, handle_of_font};
+        use low::defs::ES_AUTOHSCROLL;
+        use winapi::{DWORD, WS_VISIBLE, WS_DISABLED, WS_CHILD, WS_BORDER, UDS_ALIGNRIGHT, UDS_ARROWKEYS, UDS_HOTTRACK};
+        use user32::DestroyWindow;
+
+        if let Err(e) = check_range(self.range.0, self.range.1) { return Err(e); }
+        if let Err(e) = check_step(self.step) { return Err(e); }
+
+        let flags: DWORD =
+        if self.visible  { WS_VISIBLE }  else { 0 } |
+        if self.disabled { WS_DISABLED } else { 0 };
+
+        // Get the parent handle
+        let parent = match handle_of_window(ui, &self.parent, "The parent of a numeric input must be a window-like control.") {
+            Ok(h) => h,
+            Err(e) => { return Err(e); }
+        };
+
+        // Get the font handle (if any)
+        let font_handle: Option<HFONT> = match self.font.as_ref() {
+            Some(font_id) =>
+                match handle_of_font(ui, &font_id, "The font of a numeric input must be a font resource.") {
+                    Ok(h) => Some(h),
+                    Err(e) => { return Err(e); }
+                },
+            None => None
+        };
+
+        let params = WindowParams {
+            title: "",
+            class_name: "EDIT",
+            position: self.position.clone(),
+            size: self.size.clone(),
+            flags: WS_CHILD | WS_BORDER | ES_AUTOHSCROLL | flags,
+            ex_flags: Some(0),
+            parent: parent
+        };
+
+        let edit = match unsafe{ build_window(params) } {
+            Ok(h) => h,
+            Err(e) => { return Err(Error::System(e)); }
+        };
+
+        let params = WindowParams {
+            title: "",
+            class_name: "msctls_updown32",
+            position: (0, 0),
+            size: (0, 0),
+            flags: WS_CHILD | UDS_ALIGNRIGHT | UDS_ARROWKEYS | UDS_HOTTRACK | flags,
+            ex_flags: Some(0),
+            parent: parent
+        };
+
+        let updown = match unsafe{ build_window(params) } {
+            Ok(h) => h,
+            Err(e) => {
+                unsafe{ DestroyWindow(edit); }
+                return Err(Error::System(e));
+            }
+        };
+
+        let data = Box::new(NumericData {
+            edit: edit,
+            value: Cell::new(0.0),
+            range: Cell::new(self.range),
+            step: Cell::new(self.step),
+            decimals: Cell::new(self.decimals),
+            wrap: Cell::new(self.wrap),
+            thousands_separator: Cell::new(self.thousands_separator),
+        });
+
+        let value = data.round(self.value);
+        data.value.set(value);
+
+        let data = Box::into_raw(data);
+        unsafe {
+            set_window_font_raw(edit, font_handle, true);
+            set_buddy(updown, edit);
+            ::low::window_helper::set_window_text(edit, &(*data).format(value));
+            hook_parent(parent, updown, data);
+        }
+
+        Ok( Box::new(NumericInput{edit: edit, updown: updown, parent: parent, data: data}) )
+    }
+}
+
+/**
+    A numeric input. An edit box with up-down arrows that holds a number
+*/
+pub struct NumericInput {
+    edit: HWND,
+    updown: HWND,
+    parent: HWND,
+    data: *mut NumericData
+}
+
+impl NumericInput {
+
+    /// Return the value of the control. If the user is typing an invalid number, return the last valid value.
+    pub fn get_value(&self) -> f64 {
+        self.data().read()
+    }
+
+    /// Set the value of the control. The value is rounded and kept in the range of the control.
+    pub fn set_value(&self, value: f64) {
+        unsafe{ self.data().commit(value); }
+    }
+
+    /// Return the minimum and the maximum value of the control
+    pub fn get_range(&self) -> (f64, f64) {
+        self.data().range.get()
+    }
+
+    /**
+        Set the minimum and the maximum value of the control. If `min` is bigger than `max`, an error
+        will be returned. The current value is moved inside the new range.
+    */
+    pub fn set_range(&self, min: f64, max: f64) -> Result<(), Error> {
+        if let Err(e) = check_range(min, max) { return Err(e); }
+
+        let data = self.data();
+        let value = data.read();
+        data.range.set((min, max));
+        unsafe{ data.commit(value); }
+
+        Ok(())
+    }
+
+    /// Return the amount of value added or removed when the user clicks on an arrow
+    pub fn get_step(&self) -> f64 {
+        self.data().step.get()
+    }
+
+    /// Set the amount of value added or removed when the user clicks on an arrow. Returns an error if `step` is not positive.
+    pub fn set_step(&self, step: f64) -> Result<(), Error> {
+        if let Err(e) = check_step(step) { return Err(e); }
+        self.data().step.set(step);
+        Ok(())
+    }
+
+    /// Return the number of digits displayed after the decimal point
+    pub fn get_decimals(&self) -> u32 {
+        self.data().decimals.get()
+    }
+
+    /// Set the number of digits displayed after the decimal point. If `decimals` is 0, the control only accepts integers.
+    pub fn set_decimals(&self, decimals: u32) {
+        let data = self.data();
+        let value = data.read();
+        data.decimals.set(decimals);
+        unsafe{ data.commit(value); }
+    }
+
+    /// Return `true` if the value wraps around when going over the minimum or the maximum value
+    pub fn get_wrap(&self) -> bool {
+        self.data().wrap.get()
+    }
+
+    /// Set if the value wraps around when going over the minimum or the maximum value
+    pub fn set_wrap(&self, wrap: bool) {
+        self.data().wrap.set(wrap);
+    }
+
+    /// Return `true` if the digits are grouped by thousands
+    pub fn get_thousands_separator(&self) -> bool {
+        self.data().thousands_separator.get()
+    }
+
+    /// Set if the digits are grouped by thousands
+    pub fn set_thousands_separator(&self, separator: bool) {
+        let data = self.data();
+        let value = data.read();
+        data.thousands_separator.set(separator);
+        unsafe{ data.commit(value); }
+    }
+
+    /// Return the size of the control, including the arrows
+    pub fn get_size(&self) -> (u32, u32) {
+        use user32::GetWindowRect;
+        use winapi::RECT;
+
+        let mut edit = RECT{left: 0, top: 0, right: 0, bottom: 0};
+        let mut updown = RECT{left: 0, top: 0, right: 0, bottom: 0};
+        unsafe {
+            GetWindowRect(self.edit, &mut edit);
+            GetWindowRect(self.updown, &mut updown);
+        }
+
+        ((updown.right - edit.left) as u32, (edit.bottom - edit.top) as u32)
+    }
+
+    /// Set the size of the control, including the arrows
+    pub fn set_size(&self, w: u32, h: u32) {
+        unsafe{
+            ::low::window_helper::set_window_size(self.edit, w, h, false);
+            set_buddy(self.updown, self.edit);
+        }
+    }
+
+    /// Set the position of the control
+    pub fn set_position(&self, x: i32, y: i32) {
+        use low::window_helper::{get_window_position, set_window_position};
+
+        // The arrows keep their offset from the edit box. Attaching them again would shrink the edit box.
+        unsafe{
+            let (edit_x, edit_y) = get_window_position(self.edit);
+            let (updown_x, updown_y) = get_window_position(self.updown);
+            set_window_position(self.edit, x, y);
+            set_window_position(self.updown, x + (updown_x - edit_x), y + (updown_y - edit_y));
+        }
+    }
+
+    /// Set the visibility of the edit box and of the arrows
+    pub fn set_visibility(&self, visible: bool) {
+        unsafe{
+            ::low::window_helper::set_window_visibility(self.edit, visible);
+            ::low::window_helper::set_window_visibility(self.updown, visible);
+        }
+    }
+
+    /// Enable or disable the edit box and the arrows
+    pub fn set_enabled(&self, e: bool) {
+        unsafe{
+            ::low::window_helper::set_window_enabled(self.edit, e);
+            ::low::window_helper::set_window_enabled(self.updown, e);
+        }
+    }
+
+    pub fn get_text(&self) -> String { unsafe{ ::low::window_helper::get_window_text(self.edit) } }
+    pub fn get_visibility(&self) -> bool { unsafe{ ::low::window_helper::get_window_visibility(self.edit) } }
+    pub fn get_position(&self) -> (i32, i32) { unsafe{ ::low::window_helper::get_window_position(self.edit) } }
+    pub fn get_enabled(&self) -> bool { unsafe{ ::low::window_helper::get_window_enabled(self.edit) } }
+    pub fn get_font<ID: Hash+Clone>(&self, ui: &Ui<ID>) -> Option<ID> { unsafe{ ::low::window_helper::get_window_font(self.edit, ui) } }
+    pub fn set_font<ID: Hash+Clone>(&self, ui: &Ui<ID>, f: Option<&ID>) -> Result<(), Error> { unsafe{ ::low::window_helper::set_window_font(self.edit, ui, f) } }
+    pub fn update(&self) { unsafe{ ::low::window_helper::update(self.edit); ::low::window_helper::update(self.updown); } }
+    pub fn focus(&self) { unsafe{ ::user32::SetFocus(self.edit); } }
+
+    #[inline(always)]
+    fn data(&self) -> &NumericData {
+        unsafe{ &*self.data }
+    }
+}
+
+impl Control for NumericInput {
+
+    fn handle(&self) -> AnyHandle {
+        AnyHandle::HWND(self.edit)
+    }
+
+    fn control_type(&self) -> ControlType {
+        ControlType::NumericInput
+    }
+
+    fn free(&mut self) {
+        use user32::DestroyWindow;
+        unsafe{
+            unhook_parent(self.parent, self.updown);
+            DestroyWindow(self.updown);
+            DestroyWindow(self.edit);
+            drop(Box::from_raw(self.data));
+        }
+    }
+
+}
+
+/// The state of a numeric input. Shared by the control and the subclass of its parent.
+struct NumericData {
+    edit: HWND,
+    value: Cell<f64>,
+    range: Cell<(f64, f64)>,
+    step: Cell<f64>,
+    decimals: Cell<u32>,
+    wrap: Cell<bool>,
+    thousands_separator: Cell<bool>,
+}
+
+impl NumericData {
+
+    /// Round a value to the number of decimals of the control and keep it in the range
+    fn round(&self, value: f64) -> f64 {
+        let (min, max) = self.range.get();
+        let precision = 10f64.powi(self.decimals.get() as i32);
+        let value = (value * precision).round() / precision;
+
+        if value < min { min } else if value > max { max } else { value }
+    }
+
+    /// Add `delta` steps to a value. If the result is outside the range, wrap around or stop at the limit.
+    fn offset(&self, value: f64, delta: i32) -> f64 {
+        let (min, max) = self.range.get();
+        let new_value = value + self.step.get() * (delta as f64);
+
+        if self.wrap.get() {
+            if new_value > max && value >= max { return min; }
+            if new_value < min && value <= min { return max; }
+        }
+
+        self.round(new_value)
+    }
+
+    fn format(&self, value: f64) -> String {
+        let text = format!("{:.*}", self.decimals.get() as usize, value.abs());
+        let (int_part, dec_part) = match text.find('.') {
+            Some(i) => text.split_at(i),
+            None => (&text[..], "")
+        };
+
+        let mut out = String::with_capacity(text.len() + int_part.len() / 3 + 1);
+        if value < 0.0 && text.chars().any(|c| c >= '1' && c <= '9') {
+            out.push('-');
+        }
+
+        let separator = self.thousands_separator.get();
+        for (i, c) in int_part.chars().enumerate() {
+            if separator && i > 0 && (int_part.len() - i) % 3 == 0 {
+                out.push(',');
+            }
+            out.push(c);
+        }
+
+        out.push_str(dec_part);
+        out
+    }
+
+    /// Parse a number typed by the user. Spaces are ignored, and so are the thousands separators if the control displays them.
+    fn parse(&self, text: &str) -> Option<f64> {
+        let separator = self.thousands_separator.get();
+        let text: String = text.chars().filter(|&c| !(separator && c == ',') && !c.is_whitespace()).collect();
+        match text.parse::<f64>() {
+            Ok(v) if v.is_finite() => Some(v),
+            _ => None
+        }
+    }
+
+    /// Read the value in the edit box. If the text is not a valid number, return the last valid value.
+    fn read(&self) -> f64 {
+        let text = unsafe{ ::low::window_helper::get_window_text(self.edit) };
+        match self.parse(&text) {
+            Some(v) => self.round(v),
+            None => self.value.get()
+        }
+    }
+
+    /// Display a new value and send `ValueChanged` to the edit box if the value changed
+    unsafe fn commit(&self, value: f64) {
+        use low::defs::NWG_NUMERIC_CHANGED;
+        use low::window_helper::{get_window_text, set_window_text};
+
+        let value = self.round(value);
+        let text = self.format(value);
+        if get_window_text(self.edit) != text {
+            set_window_text(self.edit, &text);
+        }
+
+        if value != self.value.get() {
+            self.value.set(value);
+            SendMessageW(self.edit, NWG_NUMERIC_CHANGED, 0, &value as *const f64 as LPARAM);
+        }
+    }
+}
+
+fn check_range(min: f64, max: f64) -> Result<(), Error> {
+    if max < min || !min.is_finite() || !max.is_finite() {
+        let msg = "The numeric input range maximum value must be greater or equal to the minimum value";
+        Err(Error::UserError(msg.to_string()))
+    } else {
+        Ok(())
+    }
+}
+
+fn check_step(step: f64) -> Result<(), Error> {
+    if step > 0.0 && step.is_finite() {
+        Ok(())
+    } else {
+        Err(Error::UserError("The numeric input step must be a positive number".to_string()))
+    }
+}
+
+/// Attach the arrows to the edit box. This also moves the arrows next to the edit box and shrinks the edit box by the width of the arrows.
+/// The up-down position is never changed (the notifications are vetoed), so the range only needs to leave room for the deltas.
+unsafe fn set_buddy(updown: HWND, edit: HWND) {
+    use winapi::{UDM_SETBUDDY, UDM_SETRANGE32, UDM_SETPOS32};
+    SendMessageW(updown, UDM_SETRANGE32, (-0x10000i32) as WPARAM, 0x10000 as LPARAM);
+    SendMessageW(updown, UDM_SETPOS32, 0, 0);
+    SendMessageW(updown, UDM_SETBUDDY, edit as WPARAM, 0);
+}
+
+// The subclass id is the up-down handle, so a window can be the parent of many numeric inputs
+unsafe fn hook_parent(parent: HWND, updown: HWND, data: *mut NumericData) {
+    use comctl32::SetWindowSubclass;
+    SetWindowSubclass(parent, Some(numeric_input_proc), updown as UINT_PTR, data as DWORD_PTR);
+}
+
+unsafe fn unhook_parent(parent: HWND, updown: HWND) {
+    use comctl32::RemoveWindowSubclass;
+    RemoveWindowSubclass(parent, Some(numeric_input_proc), updown as UINT_PTR);
+}
+
+/// Apply the clicks on the arrows and read the value typed by the user when the edit box loses the focus
+unsafe extern "system" fn numeric_input_proc(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM, id: UINT_PTR, data: DWORD_PTR) -> LRESULT {
+    use comctl32::DefSubclassProc;
+    use winapi::{WM_NOTIFY, WM_COMMAND, NMHDR, NMUPDOWN, UDN_DELTAPOS, DWORD, HIWORD};
+    use low::defs::EN_KILLFOCUS;
+
+    let data = &*(data as *const NumericData);
+    match msg {
+        WM_NOTIFY => {
+            let nmhdr: &NMHDR = &*(l as *const NMHDR);
+            if nmhdr.hwndFrom as UINT_PTR == id && nmhdr.code == UDN_DELTAPOS {
+                let info: &NMUPDOWN = &*(l as *const NMUPDOWN);
+                let value = data.offset(data.read(), info.iDelta);
+                data.commit(value);
+                return 1;
+            }
+        },
+        WM_COMMAND => {
+            if l as HWND == data.edit && HIWORD(w as DWORD) == EN_KILLFOCUS {
+                let value = data.read();
+                data.commit(value);
+            }
+        },
+        _ => {}
+    }
+
+    DefSubclassProc(hwnd, msg, w, l)
+}
@@ -0,0 +1,334 @@
+/*!
+    Print dialog control definition
+*/
+
+use std::hash::Hash;
+use std::any::TypeId;
+use std::cell::{Cell, RefCell};
+use std::ptr;
+use std::mem;
+
+use winapi::{HWND, UINT, DWORD, WORD, HGLOBAL};
+
+use ui::Ui;
+use controls::{Control, ControlT, ControlType, AnyHandle};
+use low::defs::PRINTDLGW;
+use error::Error;
+
+static mut PRINT_DIALOG_ID: UINT = 0;
+
+/**
+    The pages to print
+
+    • `All`: Print all the pages  
+    • `Pages(from, to)`: Print the pages from `from` to `to` (inclusive)  
+    • `Selection`: Print the selected content  
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PrintRange {
+    All,
+    Pages(u16, u16),
+    Selection
+}
+
+/**
+    The printer settings selected in a print dialog
+
+    Members:  
+    • `printer`: The name of the printer  
+    • `driver`: The name of the printer driver  
+    • `port`: The name of the printer output port  
+    • `copies`: The number of copies  
+    • `collate`: If the copies should be collated  
+    • `landscape`: If the pages are printed in landscape orientation  
+    • `range`: The pages to print  
+    • `print_to_file`: If the user checked the "Print to file" option  
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct PrintSettings {
+    pub printer: String,
+    pub driver: String,
+    pub port: String,
+    pub copies: u16,
+    pub collate: bool,
+    pub landscape: bool,
+    pub range: PrintRange,
+    pub print_to_file: bool
+}
+
+/**
+    A template that creates a standard print dialog
+
+    Control specific events: None  
+
+    Members:  
+    • `parent`: The dialog parent window. While the dialog is running, the parent will be disabled.  
+    • `pages`: The first and the last page of the document `(min, max)`. If `None`, the user cannot select a page range  
+    • `selection`: If the user can choose to print the selection  
+    • `print_to_file`: If the "Print to file" option is displayed  
+
+    Failures:  
+    • `Error::UserError`: if the first page is greater than the last page  
+*/
+#[derive(Clone)]
+pub struct PrintDialogT<ID: Hash+Clone> {
+    pub parent: Option<ID>,
+    pub pages: Option<(u16, u16)>,
+    pub selection: bool,
+    pub print_to_file: bool
+}
+
+impl<ID: Hash+Clone> ControlT<ID> for PrintDialogT<ID> {
+    fn type_id(&self) -> TypeId { TypeId::of::<PrintDialog>() }
+
+    fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        use low::window_helper::handle_of_window;
+
+        let parent = match self.parent.as_ref() {
+            Some(id) =>
+            match handle_of_window(ui, id, "The parent of a PrintDialog must be a window-like control.") {
+                Ok(h) => h,
+                Err(e) => { return Err(e); }
+            },
+            None => ptr::null_mut()
+        };
+
+        if let Some((min, max)) = self.pages {
+            if min > max {
+                let msg = format!("The first page ({}) of a print dialog is greater than the last page ({})", min, max);
+                return Err(Error::UserError(msg));
+            }
+        }
+
+        Ok(Box::new(
+            PrintDialog{
+                parent: parent,
+                id: unsafe{ PRINT_DIALOG_ID+=1; PRINT_DIALOG_ID },
+                dev_mode: Cell::new(ptr::null_mut()),
+                dev_names: Cell::new(ptr::null_mut()),
+                pages: self.pages,
+                selection: self.selection,
+                print_to_file: self.print_to_file,
+                range: Cell::new(PrintRange::All),
+                settings: RefCell::new(None)
+            }
+        ) as Box<Control>)
+    }
+}
+
+/**
+    A print dialog control. The dialog remembers the printer settings selected by the user between runs.
+*/
+pub struct PrintDialog {
+    parent: HWND,
+    id: UINT,
+    dev_mode: Cell<HGLOBAL>,
+    dev_names: Cell<HGLOBAL>,
+    pages: Option<(u16, u16)>,
+    selection: bool,
+    print_to_file: bool,
+    range: Cell<PrintRange>,
+    settings: RefCell<Option<PrintSettings>>
+}
+
+impl PrintDialog {
+
+    /**
+        Display the dialog. Return true if the dialog was accepted or false if it was cancelled
+        or if there is no printer installed. If the dialog was accepted, the settings can be read with `get_settings`.
+    */
+    pub fn run(&self) -> bool {
+        self.print_dlg(0)
+    }
+
+    /**
+        Select the default printer of the system without displaying the dialog. Return false if there is no printer installed.
+        The settings can be read with `get_settings`.
+    */
+    pub fn load_default(&self) -> bool {
+        use winapi::PD_RETURNDEFAULT;
+
+        // PD_RETURNDEFAULT requires empty devmode and devnames handles
+        self.free_settings();
+        self.print_dlg(PD_RETURNDEFAULT)
+    }
+
+    /// Return the settings selected by the user. Return `None` if the dialog was never accepted.
+    pub fn get_settings(&self) -> Option<PrintSettings> {
+        self.settings.borrow().clone()
+    }
+
+    /**
+        Set the pages selected when the dialog is opened.
+
+        Failures:  
+        • `Error::UserError`: if the range is a selection and the dialog do not allow the selection  
+        • `Error::UserError`: if the range is a page range outside of the dialog pages  
+    */
+    pub fn set_range(&self, range: PrintRange) -> Result<(), Error> {
+        match range {
+            PrintRange::All => {},
+            PrintRange::Selection => if !self.selection {
+                return Err(Error::UserError("The print dialog do not allow the selection".to_string()));
+            },
+            PrintRange::Pages(from, to) => match self.pages {
+                Some((min, max)) if min <= from && from <= to && to <= max => {},
+                _ => {
+                    let msg = format!("The page range {}-{} is not valid for this print dialog", from, to);
+                    return Err(Error::UserError(msg));
+                }
+            }
+        }
+
+        self.range.set(range);
+        Ok(())
+    }
+
+    /// Return the pages selected when the dialog is opened. After the dialog ran, this is the range selected by the user.
+    pub fn get_range(&self) -> PrintRange {
+        self.range.get()
+    }
+
+    fn print_dlg(&self, flags: DWORD) -> bool {
+        use low::defs::PrintDlgW;
+        use winapi::{PD_USEDEVMODECOPIESANDCOLLATE, PD_NOPAGENUMS, PD_NOSELECTION, PD_PAGENUMS, PD_SELECTION, PD_HIDEPRINTTOFILE,
+          PD_PRINTTOFILE, PD_RETURNDEFAULT};
+
+        let mut flags = flags | PD_USEDEVMODECOPIESANDCOLLATE;
+        if self.pages.is_none() { flags |= PD_NOPAGENUMS; }
+        if !self.selection { flags |= PD_NOSELECTION; }
+        if !self.print_to_file { flags |= PD_HIDEPRINTTOFILE; }
+
+        let (min, max) = self.pages.unwrap_or((1, 1));
+        let (from, to) = match self.range.get() {
+            PrintRange::All => (min, max),
+            PrintRange::Pages(from, to) => { flags |= PD_PAGENUMS; (from, to) },
+            PrintRange::Selection => { flags |= PD_SELECTION; (min, max) }
+        };
+
+        let mut pd = PRINTDLGW {
+            lStructSize: mem::size_of::<PRINTDLGW>() as DWORD,
+            hwndOwner: self.parent,
+            hDevMode: self.dev_mode.get(),
+            hDevNames: self.dev_names.get(),
+            hDC: ptr::null_mut(),
+            Flags: flags,
+            nFromPage: from as WORD,
+            nToPage: to as WORD,
+            nMinPage: min as WORD,
+            nMaxPage: max as WORD,
+            nCopies: 1,
+            hInstance: ptr::null_mut(),
+            lCustData: 0,
+            lpfnPrintHook: None,
+            lpfnSetupHook: None,
+            lpPrintTemplateName: ptr::null(),
+            lpSetupTemplateName: ptr::null(),
+            hPrintTemplate: ptr::null_mut(),
+            hSetupTemplate: ptr::null_mut()
+        };
+
+        let accepted = unsafe{ PrintDlgW(&mut pd) } != 0;
+
+        // The dialog may reallocate the handles, even if it was cancelled
+        self.dev_mode.set(pd.hDevMode);
+        self.dev_names.set(pd.hDevNames);
+
+        if !accepted {
+            return false;
+        }
+
+        let pd_flags = pd.Flags;
+        let range = if pd_flags & PD_PAGENUMS == PD_PAGENUMS {
+            PrintRange::Pages(pd.nFromPage as u16, pd.nToPage as u16)
+        } else if pd_flags & PD_SELECTION == PD_SELECTION {
+            PrintRange::Selection
+        } else {
+            PrintRange::All
+        };
+
+        if flags & PD_RETURNDEFAULT == 0 {
+            self.range.set(range);
+        }
+
+        let print_to_file = pd_flags & PD_PRINTTOFILE == PD_PRINTTOFILE;
+        *self.settings.borrow_mut() = unsafe{ read_settings(pd.hDevMode, pd.hDevNames, range, print_to_file) };
+
+        true
+    }
+
+    fn free_settings(&self) {
+        use kernel32::GlobalFree;
+
+        unsafe {
+            if !self.dev_mode.get().is_null() { GlobalFree(self.dev_mode.get()); }
+            if !self.dev_names.get().is_null() { GlobalFree(self.dev_names.get()); }
+        }
+
+        self.dev_mode.set(ptr::null_mut());
+        self.dev_names.set(ptr::null_mut());
+    }
+}
+
+impl Control for PrintDialog {
+
+    fn handle(&self) -> AnyHandle {
+        AnyHandle::Custom(TypeId::of::<PrintDialog>(), self.id as usize)
+    }
+
+    fn control_type(&self) -> ControlType {
+        ControlType::PrintDialog
+    }
+
+    fn free(&mut self) {
+        self.free_settings();
+    }
+
+}
+
+/// Read the printer names in a DEVNAMES handle and the copies, collate and orientation fields of a DEVMODEW handle
+unsafe fn read_settings(dev_mode: HGLOBAL, dev_names: HGLOBAL, range: PrintRange, print_to_file: bool) -> Option<PrintSettings> {
+    use kernel32::{GlobalLock, GlobalUnlock};
+    use winapi::{DEVNAMES, DEVMODEW, DM_COPIES, DM_COLLATE, DM_ORIENTATION, c_short};
+    use low::defs::{DMORIENT_LANDSCAPE, DMCOLLATE_TRUE};
+    use low::other_helper::from_wide_ptr;
+
+    if dev_mode.is_null() || dev_names.is_null() {
+        return None;
+    }
+
+    let names = GlobalLock(dev_names) as *const DEVNAMES;
+    if names.is_null() {
+        return None;
+    }
+
+    let base = names as *mut u16;
+    let driver = from_wide_ptr(base.offset((*names).wDriverOffset as isize));
+    let printer = from_wide_ptr(base.offset((*names).wDeviceOffset as isize));
+    let port = from_wide_ptr(base.offset((*names).wOutputOffset as isize));
+    GlobalUnlock(dev_names);
+
+    let mode = GlobalLock(dev_mode) as *const DEVMODEW;
+    if mode.is_null() {
+        return None;
+    }
+
+    // dmOrientation and dmCopies are in the first union of DEVMODEW
+    let fields = (*mode).dmFields;
+    let union1 = (*mode).union1.as_ptr() as *const c_short;
+    let copies = if fields & DM_COPIES == DM_COPIES { *union1.offset(5) as u16 } else { 1 };
+    let landscape = fields & DM_ORIENTATION == DM_ORIENTATION && *union1 == DMORIENT_LANDSCAPE;
+    let collate = fields & DM_COLLATE == DM_COLLATE && (*mode).dmCollate == DMCOLLATE_TRUE;
+    GlobalUnlock(dev_mode);
+
+    Some(PrintSettings {
+        printer: printer,
+        driver: driver,
+        port: port,
+        copies: copies,
+        collate: collate,
+        landscape: landscape,
+        range: range,
+        print_to_file: print_to_file
+    })
+}
@@ -0,0 +1,718 @@
+/*!
+    A rich text box control built on the Msftedit rich edit control.
+
+    Unlike the `TextBox`, the text of a rich text box can be formatted (bold, color, font per range)
+    and can be loaded or saved as RTF. The formatted content can also be read as a `RichDocument`.
+*/
+
+use std::hash::Hash;
+use std::any::TypeId;
+use std::ptr;
+use std::mem;
+
+use winapi::{HWND, HFONT, WPARAM, LPARAM, DWORD, DWORD_PTR, LONG, BYTE};
+use user32::SendMessageW;
+
+use ui::Ui;
+use controls::{Control, ControlT, ControlType, AnyHandle};
+use rich_text::{RichDocument, CharFormat, ParagraphAlign};
+use low::defs::{CHARRANGE, CHARFORMATW, PARAFORMAT, EDITSTREAM, FINDTEXTEXW};
+use error::{Error, SystemError};
+
+/**
+    A template that creates a rich text box control
+
+    Control specific events:  
+    `rich_textbox::ValueChanged, rich_textbox::Focus, rich_textbox::Limit`  
+
+    Members:  
+    • `text`: The plain text of the rich text box  
+    • `position`: The start position of the rich text box  
+    • `size`: The start size of the rich text box  
+    • `visible`: If the rich text box should be visible to the user  
+    • `disabled`: If the user can or can't click on the rich text box  
+    • `readonly`: If the user can copy the text but can't edit the rich text box content  
+    • `limit`: The maximum number of characters that the control can hold  
+    • `auto_url`: If the control should detect and underline the urls in its text  
+    • `scrollbars`: A tuple to defined whether to show scrollbars or not (show horizontal, show vertical). Without an horizontal scrollbar, the text is wrapped  
+    • `parent`: The rich text box parent  
+    • `font`: The default font of the rich text box. If None, use the system default  
+*/
+#[derive(Clone)]
+pub struct RichTextBoxT<S1: Clone+Into<String>, ID: Hash+Clone> {
+    pub text: S1,
+    pub position: (i32, i32),
+    pub size: (u32, u32),
+    pub visible: bool,
+    pub disabled: bool,
+    pub readonly: bool,
+    pub limit: u32,
+    pub auto_url: bool,
+    pub scrollbars: (bool, bool),
+    pub parent: ID,
+    pub font: Option<ID>,
+}
+
+impl<S1: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for RichTextBoxT<S1, ID> {
+    fn type_id(&self) -> TypeId { TypeId::of::<RichTextBox>() }
+
+    fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        use low::window_helper::{WindowParams, build_window, set_window_font_raw, handle_of_window, handle_of_font};
+        use low::defs::{ES_AUTOHSCROLL, ES_AUTOVSCROLL, ES_READONLY, ES_MULTILINE, EM_EXLIMITTEXT, EM_SETEVENTMASK, EM_AUTOURLDETECT,
+          EM_SETTYPOGRAPHYOPTIONS, ENM_CHANGE, ENM_UPDATE, TO_ADVANCEDTYPOGRAPHY};
+        use winapi::{WS_VISIBLE, WS_DISABLED, WS_CHILD, WS_BORDER, WS_HSCROLL, WS_VSCROLL};
+
+        if !unsafe{ load_rich_edit() } {
+            return Err(Error::System(SystemError::SystemClassCreation));
+        }
+
+        let flags: DWORD = WS_CHILD | WS_BORDER | ES_MULTILINE | ES_AUTOVSCROLL |
+        if self.readonly { ES_READONLY } else { 0 } |
+        if self.visible  { WS_VISIBLE }  else { 0 } |
+        if self.scrollbars.0 { WS_HSCROLL | ES_AUTOHSCROLL } else { 0 } |
+        if self.scrollbars.1 { WS_VSCROLL } else { 0 } |
+        if self.disabled { WS_DISABLED } else { 0 };
+
+        // Get the parent handle
+        let parent = match handle_of_window(ui, &self.parent, "The parent of a rich text box must be a window-like control.") {
+            Ok(h) => h,
+            Err(e) => { return Err(e); }
+        };
+
+        // Get the font handle (if any)
+        let font_handle: Option<HFONT> = match self.font.as_ref() {
+            Some(font_id) =>
+                match handle_of_font(ui, &font_id, "The font of a rich text box must be a font resource.") {
+                    Ok(h) => Some(h),
+                    Err(e) => { return Err(e); }
+                },
+            None => None
+        };
+
+        let params = WindowParams {
+            title: self.text.clone().into(),
+            class_name: "RICHEDIT50W",
+            position: self.position.clone(),
+            size: self.size.clone(),
+            flags: flags,
+            ex_flags: Some(0),
+            parent: parent
+        };
+
+        match unsafe{ build_window(params) } {
+            Ok(h) => {
+                unsafe{
+                    set_window_font_raw(h, font_handle, true);
+
+                    // Rich edit controls do not send the EN_UPDATE & EN_CHANGE notifications by default
+                    SendMessageW(h, EM_SETEVENTMASK, 0, (ENM_CHANGE|ENM_UPDATE) as LPARAM);
+                    SendMessageW(h, EM_EXLIMITTEXT, 0, self.limit as LPARAM);
+                    SendMessageW(h, EM_AUTOURLDETECT, self.auto_url as WPARAM, 0);
+
+                    // Required for the justified alignment
+                    SendMessageW(h, EM_SETTYPOGRAPHYOPTIONS, TO_ADVANCEDTYPOGRAPHY as WPARAM, TO_ADVANCEDTYPOGRAPHY as LPARAM);
+                };
+
+                Ok( Box::new(RichTextBox{handle: h}) )
+            },
+            Err(e) => Err(Error::System(e))
+        }
+    }
+}
+
+/**
+    The format of the content streamed in or out of a rich text box
+
+    • `Rtf`: The content is a rich text format document  
+    • `PlainText`: The content is unformatted text  
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StreamFormat {
+    Rtf,
+    PlainText
+}
+
+/**
+    Options used when searching the text of a rich text box
+
+    Members:  
+    • `match_case`: If the search is case sensitive  
+    • `whole_word`: If only whole words should match  
+    • `backward`: If the search goes from the start position toward the beginning of the text  
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct FindOptions {
+    pub match_case: bool,
+    pub whole_word: bool,
+    pub backward: bool
+}
+
+/**
+    A multi line text control that supports formatted text.
+
+    Text ranges are expressed in characters as `(start, end)`. An end of `-1` means the end of the text.
+*/
+pub struct RichTextBox {
+    handle: HWND
+}
+
+impl RichTextBox {
+
+    /// Return the selected range as `(start, end)`
+    pub fn get_selection(&self) -> (i32, i32) {
+        use low::defs::EM_EXGETSEL;
+
+        let mut range = CHARRANGE{ cpMin: 0, cpMax: 0 };
+        unsafe{ SendMessageW(self.handle, EM_EXGETSEL, 0, &mut range as *mut CHARRANGE as LPARAM); }
+        (range.cpMin as i32, range.cpMax as i32)
+    }
+
+    /// Select a range of text. `(0, -1)` selects the whole text and `(-1, -1)` removes the selection.
+    pub fn set_selection(&self, range: (i32, i32)) {
+        use low::defs::EM_EXSETSEL;
+
+        let mut range = CHARRANGE{ cpMin: range.0 as LONG, cpMax: range.1 as LONG };
+        unsafe{ SendMessageW(self.handle, EM_EXSETSEL, 0, &mut range as *mut CHARRANGE as LPARAM); }
+    }
+
+    /// Return the selected text
+    pub fn get_selected_text(&self) -> String {
+        use low::defs::EM_GETSELTEXT;
+        use low::other_helper::from_utf16;
+
+        let (start, end) = self.get_selection();
+        let length = (end - start).max(0) as usize;
+        let mut buffer: Vec<u16> = Vec::with_capacity(length+1);
+        unsafe {
+            buffer.set_len(length+1);
+            let copied = SendMessageW(self.handle, EM_GETSELTEXT, 0, buffer.as_mut_ptr() as LPARAM) as usize;
+            buffer.set_len(copied.min(length));
+        }
+
+        from_utf16(&buffer)
+    }
+
+    /// Replace the selected text. If `undo` is true, the replacement can be undone by the user.
+    pub fn replace_selection<'a>(&self, text: &'a str, undo: bool) {
+        use winapi::EM_REPLACESEL;
+        use low::other_helper::to_utf16;
+
+        let text = to_utf16(text);
+        unsafe{ SendMessageW(self.handle, EM_REPLACESEL as u32, undo as WPARAM, text.as_ptr() as LPARAM); }
+    }
+
+    /**
+        Apply a character format to a range of text. Only the fields set in the format are changed,
+        meaning that the `false` members do not remove an existing style. Use `clear_char_format` to reset a range.
+        `font_size` is in points.
+    */
+    pub fn set_char_format(&self, range: (i32, i32), format: &CharFormat) {
+        use low::defs::{CFM_BOLD, CFM_ITALIC, CFM_UNDERLINE, CFM_STRIKEOUT, CFM_COLOR, CFM_FACE, CFM_SIZE, CFE_BOLD, CFE_ITALIC,
+          CFE_UNDERLINE, CFE_STRIKEOUT};
+
+        let mut cf = empty_char_format();
+        let styles = [(format.bold, CFM_BOLD, CFE_BOLD), (format.italic, CFM_ITALIC, CFE_ITALIC),
+          (format.underline, CFM_UNDERLINE, CFE_UNDERLINE), (format.strikeout, CFM_STRIKEOUT, CFE_STRIKEOUT)];
+
+        for &(set, mask, effect) in styles.iter() {
+            if set {
+                cf.dwMask |= mask;
+                cf.dwEffects |= effect;
+            }
+        }
+
+        if let Some([r, g, b]) = format.color {
+            cf.dwMask |= CFM_COLOR;
+            cf.crTextColor = rgb(r, g, b);
+        }
+
+        if let Some(ref family) = format.font_family {
+            cf.dwMask |= CFM_FACE;
+            set_face_name(&mut cf, family);
+        }
+
+        if let Some(size) = format.font_size {
+            cf.dwMask |= CFM_SIZE;
+            cf.yHeight = (size * 20) as LONG;
+        }
+
+        unsafe{ self.apply_char_format(range, &mut cf); }
+    }
+
+    /// Remove the bold, italic, underline and strikeout styles of a range of text and reset its color
+    pub fn clear_char_format(&self, range: (i32, i32)) {
+        use low::defs::{CFM_BOLD, CFM_ITALIC, CFM_UNDERLINE, CFM_STRIKEOUT, CFM_COLOR, CFE_AUTOCOLOR};
+
+        let mut cf = empty_char_format();
+        cf.dwMask = CFM_BOLD | CFM_ITALIC | CFM_UNDERLINE | CFM_STRIKEOUT | CFM_COLOR;
+        cf.dwEffects = CFE_AUTOCOLOR;
+
+        unsafe{ self.apply_char_format(range, &mut cf); }
+    }
+
+    /**
+        Return the character format of a range of text. A style is only `true` if it applies to the whole range
+        and the color, family and size are `None` if they are not the same on the whole range.
+    */
+    pub fn get_char_format(&self, range: (i32, i32)) -> CharFormat {
+        use low::defs::{EM_GETCHARFORMAT, SCF_SELECTION, CFM_BOLD, CFM_ITALIC, CFM_UNDERLINE, CFM_STRIKEOUT, CFM_COLOR, CFM_FACE, CFM_SIZE,
+          CFE_BOLD, CFE_ITALIC, CFE_UNDERLINE, CFE_STRIKEOUT, CFE_AUTOCOLOR};
+        use low::other_helper::from_utf16;
+
+        let mut cf = empty_char_format();
+        let old_selection = self.get_selection();
+        self.set_selection(range);
+        unsafe{ SendMessageW(self.handle, EM_GETCHARFORMAT, SCF_SELECTION as WPARAM, &mut cf as *mut CHARFORMATW as LPARAM); }
+        self.set_selection(old_selection);
+
+        let has = |mask: DWORD, effect: DWORD| (cf.dwMask & mask) == mask && (cf.dwEffects & effect) == effect;
+        let color = match (cf.dwMask & CFM_COLOR) == CFM_COLOR && (cf.dwEffects & CFE_AUTOCOLOR) == 0 {
+            true => Some([(cf.crTextColor & 0xFF) as u8, ((cf.crTextColor >> 8) & 0xFF) as u8, ((cf.crTextColor >> 16) & 0xFF) as u8]),
+            false => None
+        };
+
+        let family = match (cf.dwMask & CFM_FACE) == CFM_FACE {
+            true => {
+                let length = cf.szFaceName.iter().position(|&c| c == 0).unwrap_or(cf.szFaceName.len());
+                Some(from_utf16(&cf.szFaceName[0..length]))
+            },
+            false => None
+        };
+
+        CharFormat {
+            bold: has(CFM_BOLD, CFE_BOLD),
+            italic: has(CFM_ITALIC, CFE_ITALIC),
+            underline: has(CFM_UNDERLINE, CFE_UNDERLINE),
+            strikeout: has(CFM_STRIKEOUT, CFE_STRIKEOUT),
+            color: color,
+            font_family: family,
+            font_size: if (cf.dwMask & CFM_SIZE) == CFM_SIZE { Some((cf.yHeight / 20) as u32) } else { None }
+        }
+    }
+
+    /**
+        Apply the family, size and styles of a font resource to a range of text.
+
+        Returns an error if `font` is not the identifier of a font resource.
+    */
+    pub fn set_char_font<ID: Hash+Clone>(&self, ui: &Ui<ID>, range: (i32, i32), font: &ID) -> Result<(), Error> {
+        use low::window_helper::handle_of_font;
+        use low::defs::{CFM_BOLD, CFM_ITALIC, CFM_UNDERLINE, CFM_STRIKEOUT, CFM_FACE, CFM_SIZE, CFE_BOLD, CFE_ITALIC,
+          CFE_UNDERLINE, CFE_STRIKEOUT};
+        use low::other_helper::from_utf16;
+        use gdi32::{GetObjectW, GetDeviceCaps};
+        use user32::{GetDC, ReleaseDC};
+        use winapi::{LOGFONTW, LOGPIXELSY, FW_BOLD, c_int};
+
+        let font_handle = match handle_of_font(ui, font, "The font of a rich text box range must be a font resource.") {
+            Ok(h) => h,
+            Err(e) => { return Err(e); }
+        };
+
+        let mut cf = empty_char_format();
+        unsafe {
+            let mut log_font: LOGFONTW = mem::zeroed();
+            let size = mem::size_of::<LOGFONTW>() as c_int;
+            if GetObjectW(font_handle as _, size, &mut log_font as *mut LOGFONTW as _) != size {
+                let msg = "Could not read the font resource".to_string();
+                return Err(Error::System(SystemError::SystemMessageFailed(msg)));
+            }
+
+            // The height of the font is in logical units, rich edit controls use twips
+            let dc = GetDC(ptr::null_mut());
+            let dpi = GetDeviceCaps(dc, LOGPIXELSY);
+            ReleaseDC(ptr::null_mut(), dc);
+
+            cf.dwMask = CFM_BOLD | CFM_ITALIC | CFM_UNDERLINE | CFM_STRIKEOUT | CFM_FACE;
+            if log_font.lfWeight >= FW_BOLD { cf.dwEffects |= CFE_BOLD; }
+            if log_font.lfItalic != 0 { cf.dwEffects |= CFE_ITALIC; }
+            if log_font.lfUnderline != 0 { cf.dwEffects |= CFE_UNDERLINE; }
+            if log_font.lfStrikeOut != 0 { cf.dwEffects |= CFE_STRIKEOUT; }
+            if log_font.lfHeight != 0 && dpi > 0 {
+                cf.dwMask |= CFM_SIZE;
+                cf.yHeight = (log_font.lfHeight.abs() * 1440 / dpi) as LONG;
+            }
+
+            let length = log_font.lfFaceName.iter().position(|&c| c == 0).unwrap_or(log_font.lfFaceName.len());
+            set_face_name(&mut cf, &from_utf16(&log_font.lfFaceName[0..length]));
+
+            self.apply_char_format(range, &mut cf);
+        }
+
+        Ok(())
+    }
+
+    /// Set the alignment of the paragraphs touched by a range of text
+    pub fn set_paragraph_align(&self, range: (i32, i32), align: ParagraphAlign) {
+        use low::defs::{EM_SETPARAFORMAT, PFM_ALIGNMENT, PFA_LEFT, PFA_RIGHT, PFA_CENTER, PFA_JUSTIFY};
+
+        let mut pf = empty_para_format();
+        pf.dwMask = PFM_ALIGNMENT;
+        pf.wAlignment = match align {
+            ParagraphAlign::Left => PFA_LEFT,
+            ParagraphAlign::Center => PFA_CENTER,
+            ParagraphAlign::Right => PFA_RIGHT,
+            ParagraphAlign::Justify => PFA_JUSTIFY
+        };
+
+        let old_selection = self.get_selection();
+        self.set_selection(range);
+        unsafe{ SendMessageW(self.handle, EM_SETPARAFORMAT, 0, &mut pf as *mut PARAFORMAT as LPARAM); }
+        self.set_selection(old_selection);
+    }
+
+    /// Return the alignment of the first paragraph touched by a range of text
+    pub fn get_paragraph_align(&self, range: (i32, i32)) -> ParagraphAlign {
+        use low::defs::{EM_GETPARAFORMAT, PFA_RIGHT, PFA_CENTER, PFA_JUSTIFY};
+
+        let mut pf = empty_para_format();
+        let old_selection = self.get_selection();
+        self.set_selection(range);
+        unsafe{ SendMessageW(self.handle, EM_GETPARAFORMAT, 0, &mut pf as *mut PARAFORMAT as LPARAM); }
+        self.set_selection(old_selection);
+
+        match pf.wAlignment {
+            PFA_CENTER => ParagraphAlign::Center,
+            PFA_RIGHT => ParagraphAlign::Right,
+            PFA_JUSTIFY => ParagraphAlign::Justify,
+            _ => ParagraphAlign::Left
+        }
+    }
+
+    /**
+        Replace the content of the control with `data`. If `selection` is true, only the selected text is replaced.
+        Rtf data must be an ascii RTF document. Plain text is read as is.
+
+        Returns an error if the control could not read the data.
+    */
+    pub fn stream_in<'a>(&self, data: &'a str, format: StreamFormat, selection: bool) -> Result<(), Error> {
+        use low::defs::{EM_STREAMIN, SF_RTF, SF_TEXT, SF_UNICODE, SFF_SELECTION};
+
+        let bytes: Vec<u8> = match format {
+            StreamFormat::Rtf => data.as_bytes().to_vec(),
+            StreamFormat::PlainText => data.encode_utf16().flat_map(|c| vec![(c & 0xFF) as u8, (c >> 8) as u8]).collect()
+        };
+
+        let mut source = StreamSource{ data: bytes, pos: 0 };
+        let mut stream = EDITSTREAM{ dwCookie: &mut source as *mut StreamSource as DWORD_PTR, dwError: 0, pfnCallback: stream_in_callback };
+        let flags = match format { StreamFormat::Rtf => SF_RTF, StreamFormat::PlainText => SF_TEXT|SF_UNICODE } |
+                    if selection { SFF_SELECTION } else { 0 };
+
+        unsafe{ SendMessageW(self.handle, EM_STREAMIN, flags as WPARAM, &mut stream as *mut EDITSTREAM as LPARAM); }
+
+        let error = stream.dwError;
+        match error {
+            0 => Ok(()),
+            e => Err(Error::System(SystemError::SystemMessageFailed(format!("EM_STREAMIN failed with the code {}", e))))
+        }
+    }
+
+    /**
+        Return the content of the control. If `selection` is true, only the selected text is returned.
+
+        Returns an error if the control could not write the data.
+    */
+    pub fn stream_out(&self, format: StreamFormat, selection: bool) -> Result<String, Error> {
+        use low::defs::{EM_STREAMOUT, SF_RTF, SF_TEXT, SF_UNICODE, SFF_SELECTION};
+
+        let mut output: Vec<u8> = Vec::new();
+        let mut stream = EDITSTREAM{ dwCookie: &mut output as *mut Vec<u8> as DWORD_PTR, dwError: 0, pfnCallback: stream_out_callback };
+        let flags = match format { StreamFormat::Rtf => SF_RTF, StreamFormat::PlainText => SF_TEXT|SF_UNICODE } |
+                    if selection { SFF_SELECTION } else { 0 };
+
+        unsafe{ SendMessageW(self.handle, EM_STREAMOUT, flags as WPARAM, &mut stream as *mut EDITSTREAM as LPARAM); }
+
+        let error = stream.dwError;
+        if error != 0 {
+            let msg = format!("EM_STREAMOUT failed with the code {}", error);
+            return Err(Error::System(SystemError::SystemMessageFailed(msg)));
+        }
+
+        match format {
+            StreamFormat::Rtf => Ok(String::from_utf8_lossy(&output).into_owned()),
+            StreamFormat::PlainText => {
+                let wide: Vec<u16> = output.chunks(2).map(|c| (c[0] as u16) | ((*c.get(1).unwrap_or(&0) as u16) << 8) ).collect();
+                Ok(String::from_utf16_lossy(&wide))
+            }
+        }
+    }
+
+    /// Return the content of the control as a RTF document
+    pub fn get_rtf(&self) -> Result<String, Error> {
+        self.stream_out(StreamFormat::Rtf, false)
+    }
+
+    /// Replace the content of the control with a RTF document
+    pub fn set_rtf<'a>(&self, rtf: &'a str) -> Result<(), Error> {
+        self.stream_in(rtf, StreamFormat::Rtf, false)
+    }
+
+    /// Return the content of the control as a `RichDocument`
+    pub fn get_document(&self) -> Result<RichDocument, Error> {
+        match self.get_rtf() {
+            Ok(rtf) => RichDocument::from_rtf(&rtf),
+            Err(e) => Err(e)
+        }
+    }
+
+    /// Replace the content of the control with a `RichDocument`
+    pub fn set_document(&self, document: &RichDocument) -> Result<(), Error> {
+        self.set_rtf(&document.to_rtf())
+    }
+
+    /// Undo the last operation. Return `false` if there was nothing to undo.
+    pub fn undo(&self) -> bool {
+        use winapi::EM_UNDO;
+        unsafe{ SendMessageW(self.handle, EM_UNDO as u32, 0, 0) != 0 }
+    }
+
+    /// Redo the last undone operation. Return `false` if there was nothing to redo.
+    pub fn redo(&self) -> bool {
+        use low::defs::EM_REDO;
+        unsafe{ SendMessageW(self.handle, EM_REDO, 0, 0) != 0 }
+    }
+
+    /// Return `true` if there is an operation to undo
+    pub fn can_undo(&self) -> bool {
+        use winapi::EM_CANUNDO;
+        unsafe{ SendMessageW(self.handle, EM_CANUNDO as u32, 0, 0) != 0 }
+    }
+
+    /// Return `true` if there is an operation to redo
+    pub fn can_redo(&self) -> bool {
+        use low::defs::EM_CANREDO;
+        unsafe{ SendMessageW(self.handle, EM_CANREDO, 0, 0) != 0 }
+    }
+
+    /// Clear the undo and redo history of the control
+    pub fn clear_undo(&self) {
+        use winapi::EM_EMPTYUNDOBUFFER;
+        unsafe{ SendMessageW(self.handle, EM_EMPTYUNDOBUFFER as u32, 0, 0); }
+    }
+
+    /**
+        Search `text` from the position `start`. Return the range of the first match or `None` if the text was not found.
+    */
+    pub fn find<'a>(&self, text: &'a str, start: i32, options: FindOptions) -> Option<(i32, i32)> {
+        use low::defs::{EM_FINDTEXTEXW, FR_DOWN, FR_WHOLEWORD, FR_MATCHCASE};
+        use low::other_helper::to_utf16;
+
+        let text = to_utf16(text);
+        let end = if options.backward { 0 } else { -1 };
+        let mut find = FINDTEXTEXW {
+            chrg: CHARRANGE{ cpMin: start as LONG, cpMax: end },
+            lpstrText: text.as_ptr(),
+            chrgText: CHARRANGE{ cpMin: 0, cpMax: 0 }
+        };
+
+        let flags = if options.backward { 0 } else { FR_DOWN } |
+                    if options.whole_word { FR_WHOLEWORD } else { 0 } |
+                    if options.match_case { FR_MATCHCASE } else { 0 };
+
+        let found = unsafe{ SendMessageW(self.handle, EM_FINDTEXTEXW, flags as WPARAM, &mut find as *mut FINDTEXTEXW as LPARAM) };
+        match found {
+            -1 => None,
+            _ => Some((find.chrgText.cpMin as i32, find.chrgText.cpMax as i32))
+        }
+    }
+
+    /**
+        Search `text` from the current selection, replace the first match by `replacement` and select it.
+        Return `false` if the text was not found. An empty `text` is never replaced.
+    */
+    pub fn replace<'a>(&self, text: &'a str, replacement: &'a str, options: FindOptions) -> bool {
+        if text.is_empty() {
+            return false;
+        }
+
+        let (sel_start, sel_end) = self.get_selection();
+        let start = if options.backward { sel_start } else { sel_end };
+
+        // The current selection is the previous match
+        let selected = self.get_selected_text();
+        let start = match options.match_case {
+            true if selected == text => sel_start,
+            false if selected.to_lowercase() == text.to_lowercase() => sel_start,
+            _ => start
+        };
+
+        match self.find(text, start, options) {
+            Some(range) => {
+                self.set_selection(range);
+                self.replace_selection(replacement, true);
+                let end = range.0 + replacement.encode_utf16().count() as i32;
+                self.set_selection((range.0, end));
+                true
+            },
+            None => false
+        }
+    }
+
+    /// Replace every occurence of `text` by `replacement`. Return the number of replacements. An empty `text` is never replaced.
+    pub fn replace_all<'a>(&self, text: &'a str, replacement: &'a str, options: FindOptions) -> u32 {
+        if text.is_empty() {
+            return 0;
+        }
+
+        let options = FindOptions{ backward: false, ..options };
+        let replacement_length = replacement.encode_utf16().count() as i32;
+        let old_selection = self.get_selection();
+        let mut start = 0;
+        let mut count = 0;
+
+        while let Some(range) = self.find(text, start, options) {
+            self.set_selection(range);
+            self.replace_selection(replacement, true);
+            // The search continues after the inserted text. An empty match is skipped so that the search always moves forward.
+            start = range.0 + replacement_length + if range.1 == range.0 { 1 } else { 0 };
+            count += 1;
+        }
+
+        if count == 0 {
+            self.set_selection(old_selection);
+        }
+
+        count
+    }
+
+    /// Enable or disable the automatic detection of urls in the text
+    pub fn set_auto_url(&self, enabled: bool) {
+        use low::defs::EM_AUTOURLDETECT;
+        unsafe{ SendMessageW(self.handle, EM_AUTOURLDETECT, enabled as WPARAM, 0); }
+    }
+
+    /// Return `true` if the control detects the urls in its text
+    pub fn get_auto_url(&self) -> bool {
+        use low::defs::EM_GETAUTOURLDETECT;
+        unsafe{ SendMessageW(self.handle, EM_GETAUTOURLDETECT, 0, 0) != 0 }
+    }
+
+    /// Set or unset the readonly status on the control
+    pub fn set_readonly(&self, readonly: bool) {
+        use winapi::EM_SETREADONLY;
+        unsafe{ SendMessageW(self.handle, EM_SETREADONLY as u32, readonly as WPARAM, 0); }
+    }
+
+    /// Return `true` if the user cannot edit the content of the control or `false` if the user can
+    pub fn get_readonly(&self) -> bool {
+        use low::window_helper::get_window_long;
+        use low::defs::ES_READONLY;
+        use winapi::GWL_STYLE;
+
+        let style = get_window_long(self.handle, GWL_STYLE) as u32;
+
+        (style & ES_READONLY) == ES_READONLY
+    }
+
+    /// Set the maximum number of characters that the control can hold
+    pub fn set_limit(&self, limit: u32) {
+        use low::defs::EM_EXLIMITTEXT;
+        unsafe{ SendMessageW(self.handle, EM_EXLIMITTEXT, 0, limit as LPARAM); }
+    }
+
+    /// Return the maximum number of characters that the control can hold
+    pub fn get_limit(&self) -> u32 {
+        use low::defs::EM_GETLIMITTEXT;
+        unsafe{ SendMessageW(self.handle, EM_GETLIMITTEXT, 0, 0) as u32 }
+    }
+
+    unsafe fn apply_char_format(&self, range: (i32, i32), cf: &mut CHARFORMATW) {
+        use low::defs::{EM_SETCHARFORMAT, SCF_SELECTION};
+
+        let old_selection = self.get_selection();
+        self.set_selection(range);
+        SendMessageW(self.handle, EM_SETCHARFORMAT, SCF_SELECTION as WPARAM, cf as *mut CHARFORMATW as LPARAM);
+        self.set_selection(old_selection);
+    }
+
+    pub fn get_text(&self) -> String { unsafe{ ::low::window_helper::get_window_text(self.handle) } }
+    pub fn set_text<'a>(&self, text: &'a str) { unsafe{ ::low::window_helper::set_window_text(self.handle, text); } }
+    pub fn get_visibility(&self) -> bool { unsafe{ ::low::window_helper::get_window_visibility(self.handle) } }
+    pub fn set_visibility(&self, visible: bool) { unsafe{ ::low::window_helper::set_window_visibility(self.handle, visible); }}
+    pub fn get_position(&self) -> (i32, i32) { unsafe{ ::low::window_helper::get_window_position(self.handle) } }
+    pub fn set_position(&self, x: i32, y: i32) { unsafe{ ::low::window_helper::set_window_position(self.handle, x, y); }}
+    pub fn get_size(&self) -> (u32, u32) { unsafe{ ::low::window_helper::get_window_size(self.handle) } }
+    pub fn set_size(&self, w: u32, h: u32) { unsafe{ ::low::window_helper::set_window_size(self.handle, w, h, true); } }
+    pub fn get_enabled(&self) -> bool { unsafe{ ::low::window_helper::get_window_enabled(self.handle) } }
+    pub fn set_enabled(&self, e:bool) { unsafe{ ::low::window_helper::set_window_enabled(self.handle, e); } }
+    pub fn get_font<ID: Hash+Clone>(&self, ui: &Ui<ID>) -> Option<ID> { unsafe{ ::low::window_helper::get_window_font(self.handle, ui) } }
+    pub fn set_font<ID: Hash+Clone>(&self, ui: &Ui<ID>, f: Option<&ID>) -> Result<(), Error> { unsafe{ ::low::window_helper::set_window_font(self.handle, ui, f) } }
+    pub fn update(&self) { unsafe{ ::low::window_helper::update(self.handle); } }
+    pub fn focus(&self) { unsafe{ ::user32::SetFocus(self.handle); } }
+}
+
+impl Control for RichTextBox {
+
+    fn handle(&self) -> AnyHandle {
+        AnyHandle::HWND(self.handle)
+    }
+
+    fn control_type(&self) -> ControlType {
+        ControlType::RichTextBox
+    }
+
+    fn free(&mut self) {
+        use user32::DestroyWindow;
+        unsafe{ DestroyWindow(self.handle) };
+    }
+
+}
+
+// Private functions / structures
+
+/// Data read by the control in `stream_in`
+struct StreamSource {
+    data: Vec<u8>,
+    pos: usize
+}
+
+/// Load the dll that registers the RICHEDIT50W class. Return `false` if the dll could not be loaded.
+unsafe fn load_rich_edit() -> bool {
+    use kernel32::LoadLibraryW;
+    use low::other_helper::to_utf16;
+
+    // The library is never freed. Loading it more than once only increase its reference count.
+    let name = to_utf16("Msftedit.dll");
+    !LoadLibraryW(name.as_ptr()).is_null()
+}
+
+unsafe extern "system" fn stream_in_callback(cookie: DWORD_PTR, buffer: *mut BYTE, cb: LONG, pcb: *mut LONG) -> DWORD {
+    let source = &mut *(cookie as *mut StreamSource);
+    let count = (source.data.len() - source.pos).min(cb as usize);
+    ptr::copy_nonoverlapping(source.data[source.pos..].as_ptr(), buffer, count);
+    source.pos += count;
+    *pcb = count as LONG;
+    0
+}
+
+unsafe extern "system" fn stream_out_callback(cookie: DWORD_PTR, buffer: *mut BYTE, cb: LONG, pcb: *mut LONG) -> DWORD {
+    use std::slice;
+
+    let output = &mut *(cookie as *mut Vec<u8>);
+    output.extend_from_slice(slice::from_raw_parts(buffer, cb as usize));
+    *pcb = cb;
+    0
+}
+
+fn empty_char_format() -> CHARFORMATW {
+    let mut cf: CHARFORMATW = unsafe{ mem::zeroed() };
+    cf.cbSize = mem::size_of::<CHARFORMATW>() as u32;
+    cf
+}
+
+fn empty_para_format() -> PARAFORMAT {
+    let mut pf: PARAFORMAT = unsafe{ mem::zeroed() };
+    pf.cbSize = mem::size_of::<PARAFORMAT>() as u32;
+    pf
+}
+
+fn set_face_name(cf: &mut CHARFORMATW, family: &str) {
+    let max = cf.szFaceName.len() - 1;
+    for (i, c) in family.encode_utf16().take(max).enumerate() {
+        cf.szFaceName[i] = c;
+    }
+}
+
+fn rgb(r: u8, g: u8, b: u8) -> DWORD {
+    (r as DWORD) | ((g as DWORD) << 8) | ((b as DWORD) << 16)
+}
@@ -0,0 +1,386 @@
+/*!
+    The slider control definition
+*/
+
+use std::hash::Hash;
+use std::any::TypeId;
+use std::ptr;
+use std::cell::Cell;
+
+use winapi::{HWND, UINT, WPARAM, LPARAM, LRESULT, UINT_PTR, DWORD_PTR};
+use user32::SendMessageW;
+
+use ui::Ui;
+use controls::{Control, ControlT, ControlType, AnyHandle};
+use error::Error;
+
+/**
+    A template that creates a slider (also known as a trackbar). The user picks a value by moving the slider thumb.
+
+    Control specific events:  
+    `slider::ValueChanged`  
+
+    Members:  
+    • `position`: The start position of the slider  
+    • `size`: The start size of the slider  
+    • `visible`: If the slider should be visible to the user  
+    • `disabled`: If the user can or can't move the slider  
+    • `range`: The minimum and the maximum value of the slider  
+    • `value`: The starting value of the slider  
+    • `vertical`: If the slider should be vertical instead of horizontal  
+    • `tick_frequency`: The interval between the tick marks. If 0, the slider has no tick marks  
+    • `page_size`: Amount of value added or removed when the user clicks on the channel or uses PAGE UP/PAGE DOWN  
+    • `line_size`: Amount of value added or removed when the user uses the arrow keys  
+    • `selection`: A range of values highlighted in the slider channel. If None, the slider do not display a selection  
+    • `buddies`: Window-like controls (usually labels) displayed at the start and at the end of the slider  
+    • `parent`: The slider parent  
+*/
+#[derive(Clone)]
+pub struct SliderT<ID: Hash+Clone> {
+    pub position: (i32, i32),
+    pub size: (u32, u32),
+    pub visible: bool,
+    pub disabled: bool,
+    pub range: (i32, i32),
+    pub value: i32,
+    pub vertical: bool,
+    pub tick_frequency: u32,
+    pub page_size: u32,
+    pub line_size: u32,
+    pub selection: Option<(i32, i32)>,
+    pub buddies: (Option<ID>, Option<ID>),
+    pub parent: ID,
+}
+
+impl<ID: Hash+Clone> ControlT<ID> for SliderT<ID> {
+    fn type_id(&self) -> TypeId { TypeId::of::<Slider>() }
+
+    fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        use low::window_helper::{WindowParams, build_window, handle_of_window};
+        use winapi::{DWORD, WS_VISIBLE, WS_DISABLED, WS_CHILD, WS_TABSTOP, TBS_VERT, TBS_HORZ, TBS_AUTOTICKS,
+          TBS_NOTICKS, TBS_ENABLESELRANGE};
+
+        if self.range.1 < self.range.0 {
+            let msg = "The slider range maximum value must be greater or equal to the minimum value";
+            return Err(Error::UserError(msg.to_string()));
+        }
+
+        let flags: DWORD = WS_CHILD | WS_TABSTOP |
+        if self.visible  { WS_VISIBLE }  else { 0 } |
+        if self.disabled { WS_DISABLED } else { 0 } |
+        if self.vertical { TBS_VERT }    else { TBS_HORZ } |
+        if self.tick_frequency > 0  { TBS_AUTOTICKS } else { TBS_NOTICKS } |
+        if self.selection.is_some() { TBS_ENABLESELRANGE } else { 0 };
+
+        // Get the parent handle
+        let parent = match handle_of_window(ui, &self.parent, "The parent of a slider must be a window-like control.") {
+            Ok(h) => h,
+            Err(e) => { return Err(e); }
+        };
+
+        // Get the buddies handles (if any)
+        let mut buddies = [ptr::null_mut(), ptr::null_mut()];
+        for (i, buddy) in [&self.buddies.0, &self.buddies.1].iter().enumerate() {
+            if let &&Some(ref id) = buddy {
+                match handle_of_window(ui, id, "The buddy of a slider must be a window-like control.") {
+                    Ok(h) => { buddies[i] = h; },
+                    Err(e) => { return Err(e); }
+                }
+            }
+        }
+
+        let params = WindowParams {
+            title: "",
+            class_name: "msctls_trackbar32",
+            position: self.position.clone(),
+            size: self.size.clone(),
+            flags: flags,
+            ex_flags: Some(0),
+            parent: parent
+        };
+
+        match unsafe{ build_window(params) } {
+            Ok(h) => {
+                let slider = Slider{handle: h, parent: parent, last_value: Box::into_raw(Box::new(Cell::new(0)))};
+                unsafe{
+                    set_range(h, self.range.0, self.range.1);
+                    hook_parent(parent, h, slider.last_value);
+                }
+                slider.set_value(self.value);
+                slider.set_tick_frequency(self.tick_frequency);
+                slider.set_page_size(self.page_size);
+                slider.set_line_size(self.line_size);
+                slider.set_selection(self.selection);
+
+                unsafe{
+                    for (i, buddy) in buddies.iter().enumerate() {
+                        if !buddy.is_null() { set_buddy(h, i == 0, *buddy); }
+                    }
+                }
+
+                Ok( Box::new(slider) )
+            },
+            Err(e) => Err(Error::System(e))
+        }
+    }
+}
+
+/**
+    A slider (trackbar) control. The value of a slider is an integer between the minimum and the maximum value of its range.
+*/
+pub struct Slider {
+    handle: HWND,
+    parent: HWND,
+    last_value: *mut Cell<i32>
+}
+
+impl Slider {
+
+    /// Return the position of the slider thumb
+    pub fn get_value(&self) -> i32 {
+        use winapi::TBM_GETPOS;
+        unsafe{ SendMessageW(self.handle, TBM_GETPOS, 0, 0) as i32 }
+    }
+
+    /// Move the slider thumb. The value is kept in the range of the slider. Do not raise `ValueChanged`.
+    pub fn set_value(&self, value: i32) {
+        use winapi::TBM_SETPOS;
+        unsafe{ SendMessageW(self.handle, TBM_SETPOS, 1, value as LPARAM); }
+        self.sync_value();
+    }
+
+    /// Return the minimum and the maximum value of the slider
+    pub fn get_range(&self) -> (i32, i32) {
+        use winapi::{TBM_GETRANGEMIN, TBM_GETRANGEMAX};
+        unsafe{
+            let min = SendMessageW(self.handle, TBM_GETRANGEMIN, 0, 0) as i32;
+            let max = SendMessageW(self.handle, TBM_GETRANGEMAX, 0, 0) as i32;
+            (min, max)
+        }
+    }
+
+    /**
+        Set the minimum and the maximum value of the slider. If `min` is bigger than `max`, an error
+        will be returned.
+    */
+    pub fn set_range(&self, min: i32, max: i32) -> Result<(), Error> {
+        if max < min {
+            let msg = "The slider range maximum value must be greater or equal to the minimum value";
+            return Err(Error::UserError(msg.to_string()));
+        }
+        unsafe{ set_range(self.handle, min, max); }
+        self.sync_value();
+        Ok(())
+    }
+
+    /// Return the number of tick marks of the slider, including the first and the last tick marks
+    pub fn get_tick_count(&self) -> u32 {
+        use winapi::TBM_GETNUMTICS;
+        unsafe{ SendMessageW(self.handle, TBM_GETNUMTICS, 0, 0) as u32 }
+    }
+
+    /// Set the interval between the tick marks. If `frequency` is 0, the tick marks are hidden.
+    pub fn set_tick_frequency(&self, frequency: u32) {
+        use low::window_helper::{set_window_long, get_window_long};
+        use winapi::{TBM_SETTICFREQ, TBS_AUTOTICKS, TBS_NOTICKS, GWL_STYLE};
+
+        let old_style = get_window_long(self.handle, GWL_STYLE) as usize & !((TBS_AUTOTICKS|TBS_NOTICKS) as usize);
+        if frequency > 0 {
+            set_window_long(self.handle, GWL_STYLE, old_style | TBS_AUTOTICKS as usize);
+            unsafe{ SendMessageW(self.handle, TBM_SETTICFREQ, frequency as WPARAM, 0); }
+        } else {
+            set_window_long(self.handle, GWL_STYLE, old_style | TBS_NOTICKS as usize);
+        }
+
+        self.update();
+    }
+
+    /// Return the amount of value added or removed when the user clicks on the channel
+    pub fn get_page_size(&self) -> u32 {
+        use winapi::TBM_GETPAGESIZE;
+        unsafe{ SendMessageW(self.handle, TBM_GETPAGESIZE, 0, 0) as u32 }
+    }
+
+    /// Set the amount of value added or removed when the user clicks on the channel or uses PAGE UP/PAGE DOWN
+    pub fn set_page_size(&self, size: u32) {
+        use winapi::TBM_SETPAGESIZE;
+        unsafe{ SendMessageW(self.handle, TBM_SETPAGESIZE, 0, size as LPARAM); }
+    }
+
+    /// Return the amount of value added or removed when the user uses the arrow keys
+    pub fn get_line_size(&self) -> u32 {
+        use winapi::TBM_GETLINESIZE;
+        unsafe{ SendMessageW(self.handle, TBM_GETLINESIZE, 0, 0) as u32 }
+    }
+
+    /// Set the amount of value added or removed when the user uses the arrow keys
+    pub fn set_line_size(&self, size: u32) {
+        use winapi::TBM_SETLINESIZE;
+        unsafe{ SendMessageW(self.handle, TBM_SETLINESIZE, 0, size as LPARAM); }
+    }
+
+    /// Return the range of values highlighted in the slider channel. Returns None if the slider do not display a selection.
+    pub fn get_selection(&self) -> Option<(i32, i32)> {
+        use low::window_helper::get_window_long;
+        use winapi::{TBM_GETSELSTART, TBM_GETSELEND, TBS_ENABLESELRANGE, GWL_STYLE};
+
+        let style = get_window_long(self.handle, GWL_STYLE) as u32;
+        if style & TBS_ENABLESELRANGE == 0 {
+            return None;
+        }
+
+        unsafe{
+            let start = SendMessageW(self.handle, TBM_GETSELSTART, 0, 0) as i32;
+            let end = SendMessageW(self.handle, TBM_GETSELEND, 0, 0) as i32;
+            Some((start, end))
+        }
+    }
+
+    /// Highlight a range of values in the slider channel. If `selection` is None, the selection is removed.
+    pub fn set_selection(&self, selection: Option<(i32, i32)>) {
+        use low::window_helper::{set_window_long, get_window_long};
+        use winapi::{TBM_SETSELSTART, TBM_SETSELEND, TBM_CLEARSEL, TBS_ENABLESELRANGE, GWL_STYLE};
+
+        let old_style = get_window_long(self.handle, GWL_STYLE) as usize;
+        match selection {
+            Some((start, end)) => unsafe {
+                set_window_long(self.handle, GWL_STYLE, old_style | TBS_ENABLESELRANGE as usize);
+                SendMessageW(self.handle, TBM_SETSELSTART, 0, start as LPARAM);
+                SendMessageW(self.handle, TBM_SETSELEND, 1, end as LPARAM);
+            },
+            None => unsafe {
+                set_window_long(self.handle, GWL_STYLE, old_style & !(TBS_ENABLESELRANGE as usize));
+                SendMessageW(self.handle, TBM_CLEARSEL, 1, 0);
+            }
+        }
+    }
+
+    /// Return `true` if the slider is vertical
+    pub fn get_vertical(&self) -> bool {
+        use low::window_helper::get_window_long;
+        use winapi::{TBS_VERT, GWL_STYLE};
+
+        let style = get_window_long(self.handle, GWL_STYLE) as u32;
+        (style & TBS_VERT) == TBS_VERT
+    }
+
+    /**
+        Return the identifier of the buddy control displayed at the start (left or top) of the slider if `start` is true,
+        or at the end (right or bottom) of the slider otherwise. Returns None if there is no buddy or if the buddy is not in the Ui.
+    */
+    pub fn get_buddy<ID: Hash+Clone>(&self, ui: &Ui<ID>, start: bool) -> Option<ID> {
+        use winapi::TBM_GETBUDDY;
+
+        let buddy = unsafe{ SendMessageW(self.handle, TBM_GETBUDDY, start as WPARAM, 0) as HWND };
+        if buddy.is_null() { return None; }
+
+        match ui.id_from_handle(&AnyHandle::HWND(buddy)) {
+            Ok(id) => Some(id),
+            Err(_) => None
+        }
+    }
+
+    /**
+        Display a window-like control (usually a label) at the start (left or top) of the slider if `start` is true,
+        or at the end (right or bottom) of the slider otherwise. The slider moves its buddy next to itself.
+        If `buddy` is None, the current buddy is detached.
+    */
+    pub fn set_buddy<ID: Hash+Clone>(&self, ui: &Ui<ID>, start: bool, buddy: Option<&ID>) -> Result<(), Error> {
+        use low::window_helper::handle_of_window;
+
+        let handle = match buddy {
+            Some(id) => match handle_of_window(ui, id, "The buddy of a slider must be a window-like control.") {
+                Ok(h) => h,
+                Err(e) => { return Err(e); }
+            },
+            None => ptr::null_mut()
+        };
+
+        unsafe{ set_buddy(self.handle, start, handle); }
+        Ok(())
+    }
+
+    pub fn get_visibility(&self) -> bool { unsafe{ ::low::window_helper::get_window_visibility(self.handle) } }
+    pub fn set_visibility(&self, visible: bool) { unsafe{ ::low::window_helper::set_window_visibility(self.handle, visible); }}
+    pub fn get_position(&self) -> (i32, i32) { unsafe{ ::low::window_helper::get_window_position(self.handle) } }
+    pub fn set_position(&self, x: i32, y: i32) { unsafe{ ::low::window_helper::set_window_position(self.handle, x, y); }}
+    pub fn get_size(&self) -> (u32, u32) { unsafe{ ::low::window_helper::get_window_size(self.handle) } }
+    pub fn set_size(&self, w: u32, h: u32) { unsafe{ ::low::window_helper::set_window_size(self.handle, w, h, false); } }
+    pub fn get_enabled(&self) -> bool { unsafe{ ::low::window_helper::get_window_enabled(self.handle) } }
+    pub fn set_enabled(&self, e:bool) { unsafe{ ::low::window_helper::set_window_enabled(self.handle, e); } }
+    pub fn update(&self) { unsafe{ ::low::window_helper::update(self.handle); } }
+    pub fn focus(&self) { unsafe{ ::user32::SetFocus(self.handle); } }
+
+    /// Remember the current position so that the next move of the user is compared against it
+    fn sync_value(&self) {
+        unsafe{ (*self.last_value).set(self.get_value()); }
+    }
+}
+
+impl Control for Slider {
+
+    fn handle(&self) -> AnyHandle {
+        AnyHandle::HWND(self.handle)
+    }
+
+    fn control_type(&self) -> ControlType {
+        ControlType::Slider
+    }
+
+    fn free(&mut self) {
+        use user32::DestroyWindow;
+        unsafe{
+            unhook_parent(self.parent, self.handle);
+            DestroyWindow(self.handle);
+            drop(Box::from_raw(self.last_value));
+        }
+    }
+
+}
+
+// Private functions
+
+#[inline(always)]
+unsafe fn set_range(handle: HWND, min: i32, max: i32) {
+    use winapi::{TBM_SETRANGEMIN, TBM_SETRANGEMAX};
+    SendMessageW(handle, TBM_SETRANGEMIN, 0, min as LPARAM);
+    SendMessageW(handle, TBM_SETRANGEMAX, 1, max as LPARAM);
+}
+
+#[inline(always)]
+unsafe fn set_buddy(handle: HWND, start: bool, buddy: HWND) {
+    use winapi::TBM_SETBUDDY;
+    SendMessageW(handle, TBM_SETBUDDY, start as WPARAM, buddy as LPARAM);
+}
+
+// The subclass id is the slider handle, so a window can be the parent of many sliders
+unsafe fn hook_parent(parent: HWND, handle: HWND, last_value: *mut Cell<i32>) {
+    use comctl32::SetWindowSubclass;
+    SetWindowSubclass(parent, Some(slider_proc), handle as UINT_PTR, last_value as DWORD_PTR);
+}
+
+unsafe fn unhook_parent(parent: HWND, handle: HWND) {
+    use comctl32::RemoveWindowSubclass;
+    RemoveWindowSubclass(parent, Some(slider_proc), handle as UINT_PTR);
+}
+
+/**
+    Trackbars send WM_HSCROLL or WM_VSCROLL to their parent for every scroll code, even when the thumb did not move.
+    The position is read from the slider and `NWG_SLIDER_CHANGED` is sent to the slider only if it changed.
+*/
+unsafe extern "system" fn slider_proc(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM, id: UINT_PTR, data: DWORD_PTR) -> LRESULT {
+    use comctl32::DefSubclassProc;
+    use winapi::{WM_HSCROLL, WM_VSCROLL, TBM_GETPOS};
+    use low::defs::NWG_SLIDER_CHANGED;
+
+    if (msg == WM_HSCROLL || msg == WM_VSCROLL) && l as UINT_PTR == id {
+        let last_value = &*(data as *const Cell<i32>);
+        let value = SendMessageW(l as HWND, TBM_GETPOS, 0, 0) as i32;
+        if value != last_value.get() {
+            last_value.set(value);
+            SendMessageW(l as HWND, NWG_SLIDER_CHANGED, value as WPARAM, 0);
+        }
+    }
+
+    DefSubclassProc(hwnd, msg, w, l)
+}
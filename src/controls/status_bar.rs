@@ -0,0 +1,382 @@
+/*!
+    A status bar control. A horizontal bar at the bottom of a window that displays text and icons in parts
+*/
+
+use std::hash::Hash;
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::ptr;
+
+use winapi::{HWND, HFONT, HICON, UINT, WPARAM, LPARAM, LRESULT, UINT_PTR, DWORD_PTR, c_int};
+use user32::SendMessageW;
+
+use ui::Ui;
+use controls::{Control, ControlT, ControlType, AnyHandle};
+use error::Error;
+
+/**
+    The width of a status bar part
+
+    • `Fixed(width)`: The part is always `width` pixels wide  
+    • `Stretch(factor)`: The part shares the space left by the fixed parts with the other stretch parts, proportionally to `factor`  
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StatusPartWidth {
+    Fixed(u32),
+    Stretch(u32)
+}
+
+/**
+    A template that creates a status bar. The status bar always sits at the bottom of its parent
+    and its parts are resized when the parent is resized.
+
+    Members:  
+    • `text`: The text of the first part  
+    • `parts`: The width of the parts of the status bar. Must hold between 1 and 255 parts  
+    • `size_grip`: If the status bar should display a size grip in its right corner  
+    • `visible`: If the status bar should be visible to the user  
+    • `parent`: The status bar parent. Must be a window  
+    • `font`: The status bar font. If None, use the system default  
+*/
+#[derive(Clone)]
+pub struct StatusBarT<S: Clone+Into<String>, ID: Hash+Clone> {
+    pub text: S,
+    pub parts: Vec<StatusPartWidth>,
+    pub size_grip: bool,
+    pub visible: bool,
+    pub parent: ID,
+    pub font: Option<ID>,
+}
+
+impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for StatusBarT<S, ID> {
+    fn type_id(&self) -> TypeId { TypeId::of::<StatusBar>() }
+
+    fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        use low::window_helper::{WindowParams, build_window, set_window_font_raw, handle_of_window, handle_of_font};
+        use winapi::{DWORD, WS_VISIBLE, WS_CHILD, SBARS_SIZEGRIP};
+
+        if let Err(e) = check_parts(&self.parts) { return Err(e); }
+
+        let flags: DWORD = WS_CHILD |
+        if self.size_grip { SBARS_SIZEGRIP } else { 0 } |
+        if self.visible   { WS_VISIBLE }     else { 0 };
+
+        // Get the parent handle
+        let parent = match handle_of_window(ui, &self.parent, "The parent of a status bar must be a window-like control.") {
+            Ok(h) => h,
+            Err(e) => { return Err(e); }
+        };
+
+        // Get the font handle (if any)
+        let font_handle: Option<HFONT> = match self.font.as_ref() {
+            Some(font_id) =>
+                match handle_of_font(ui, &font_id, "The font of a status bar must be a font resource.") {
+                    Ok(h) => Some(h),
+                    Err(e) => { return Err(e); }
+                },
+            None => None
+        };
+
+        // The status bar computes its own position and size
+        let params = WindowParams {
+            title: "",
+            class_name: "msctls_statusbar32",
+            position: (0, 0),
+            size: (0, 0),
+            flags: flags,
+            ex_flags: Some(0),
+            parent: parent
+        };
+
+        let handle = match unsafe{ build_window(params) } {
+            Ok(h) => h,
+            Err(e) => { return Err(Error::System(e)); }
+        };
+
+        let data = Box::into_raw(Box::new(StatusData{ handle: handle, parts: RefCell::new(self.parts.clone()) }));
+        unsafe {
+            set_window_font_raw(handle, font_handle, true);
+            (*data).resize();
+            set_part_text(handle, 0, &self.text.clone().into());
+            hook_parent(parent, handle, data);
+        }
+
+        Ok( Box::new(StatusBar{handle: handle, parent: parent, data: data}) )
+    }
+}
+
+/**
+    A status bar control
+*/
+pub struct StatusBar {
+    handle: HWND,
+    parent: HWND,
+    data: *mut StatusData
+}
+
+impl StatusBar {
+
+    /// Return the width of the parts of the status bar
+    pub fn get_parts(&self) -> Vec<StatusPartWidth> {
+        self.data().parts.borrow().clone()
+    }
+
+    /**
+        Set the width of the parts of the status bar. The text and the icons of the existing parts are kept.
+        Returns an error if `parts` is empty or holds more than 255 parts.
+    */
+    pub fn set_parts(&self, parts: &[StatusPartWidth]) -> Result<(), Error> {
+        if let Err(e) = check_parts(parts) { return Err(e); }
+
+        *self.data().parts.borrow_mut() = parts.to_vec();
+        unsafe{ self.data().resize(); }
+        Ok(())
+    }
+
+    /// Return the number of parts in the status bar
+    pub fn get_part_count(&self) -> usize {
+        self.data().parts.borrow().len()
+    }
+
+    /// Return the size in pixels of a part. Return `None` if the part does not exist.
+    pub fn get_part_size(&self, part: usize) -> Option<(u32, u32)> {
+        use winapi::{RECT, SB_GETRECT};
+
+        if part >= self.get_part_count() { return None; }
+
+        let mut r = RECT{ left: 0, top: 0, right: 0, bottom: 0 };
+        unsafe{ SendMessageW(self.handle, SB_GETRECT, part as WPARAM, &mut r as *mut RECT as LPARAM); }
+        Some(((r.right - r.left) as u32, (r.bottom - r.top) as u32))
+    }
+
+    /// Return the text of a part. Return an empty string if the part does not exist.
+    pub fn get_text(&self, part: usize) -> String {
+        if part >= self.get_part_count() { return String::new(); }
+        unsafe{ get_part_text(self.handle, part as WPARAM) }
+    }
+
+    /// Set the text of a part. Does nothing if the part does not exist.
+    pub fn set_text<'a>(&self, part: usize, text: &'a str) {
+        if part >= self.get_part_count() { return; }
+        unsafe{ set_part_text(self.handle, part as WPARAM, text); }
+    }
+
+    /**
+        Display an icon at the left of the text of a part. Pass `None` to remove the icon.
+        Returns an error if the part does not exist or if the resource is not an icon `Image`.
+    */
+    pub fn set_icon<ID: Hash+Clone>(&self, ui: &Ui<ID>, part: usize, icon: Option<&ID>) -> Result<(), Error> {
+        use winapi::SB_SETICON;
+
+        if part >= self.get_part_count() {
+            return Err(Error::UserError(format!("The status bar has no part at index {}", part)));
+        }
+
+        let icon_handle = match icon {
+            Some(id) => match ui.handle_of(id) {
+                Ok(AnyHandle::HICON(h)) => h,
+                Ok(h) => { return Err(Error::BadResource(format!("An icon Image resource is required, got {:?}", h))); },
+                Err(e) => { return Err(e); }
+            },
+            None => ptr::null_mut()
+        };
+
+        unsafe{ SendMessageW(self.handle, SB_SETICON, part as WPARAM, icon_handle as LPARAM); }
+        Ok(())
+    }
+
+    /// Return the identifier of the icon of a part. Return `None` if the part has no icon or if the icon is not in the ui.
+    pub fn get_icon<ID: Hash+Clone>(&self, ui: &Ui<ID>, part: usize) -> Option<ID> {
+        use winapi::SB_GETICON;
+
+        if part >= self.get_part_count() { return None; }
+
+        let icon = unsafe{ SendMessageW(self.handle, SB_GETICON, part as WPARAM, 0) as HICON };
+        if icon.is_null() {
+            return None;
+        }
+
+        match ui.id_from_handle(&AnyHandle::HICON(icon)) {
+            Ok(id) => Some(id),
+            Err(_) => None
+        }
+    }
+
+    /// Enable or disable the simple mode. In simple mode, the parts are hidden and the status bar displays the simple text.
+    pub fn set_simple(&self, simple: bool) {
+        use winapi::SB_SIMPLE;
+        unsafe{ SendMessageW(self.handle, SB_SIMPLE, simple as WPARAM, 0); }
+    }
+
+    /// Return `true` if the status bar is in simple mode
+    pub fn get_simple(&self) -> bool {
+        use winapi::SB_ISSIMPLE;
+        unsafe{ SendMessageW(self.handle, SB_ISSIMPLE, 0, 0) != 0 }
+    }
+
+    /// Set the text displayed in simple mode
+    pub fn set_simple_text<'a>(&self, text: &'a str) {
+        use winapi::SB_SIMPLEID;
+        unsafe{ set_part_text(self.handle, SB_SIMPLEID, text); }
+    }
+
+    /// Return the text displayed in simple mode
+    pub fn get_simple_text(&self) -> String {
+        use winapi::SB_SIMPLEID;
+        unsafe{ get_part_text(self.handle, SB_SIMPLEID) }
+    }
+
+    /**
+        Display the help text of a menu item over the parts of the status bar (ex: in a `menu::Triggered` or a hover callback).
+        Pass `None` to display the parts again.
+    */
+    pub fn show_menu_help<'a>(&self, help: Option<&'a str>) {
+        match help {
+            Some(text) => {
+                self.set_simple_text(text);
+                self.set_simple(true);
+            },
+            None => self.set_simple(false)
+        }
+    }
+
+    /// Return `true` if the status bar displays a size grip
+    pub fn get_size_grip(&self) -> bool {
+        use low::window_helper::get_window_long;
+        use winapi::{GWL_STYLE, SBARS_SIZEGRIP};
+
+        let style = get_window_long(self.handle, GWL_STYLE) as u32;
+        (style & SBARS_SIZEGRIP) == SBARS_SIZEGRIP
+    }
+
+    pub fn get_visibility(&self) -> bool { unsafe{ ::low::window_helper::get_window_visibility(self.handle) } }
+    pub fn set_visibility(&self, visible: bool) { unsafe{ ::low::window_helper::set_window_visibility(self.handle, visible); }}
+    pub fn get_position(&self) -> (i32, i32) { unsafe{ ::low::window_helper::get_window_position(self.handle) } }
+    pub fn get_size(&self) -> (u32, u32) { unsafe{ ::low::window_helper::get_window_size(self.handle) } }
+    pub fn get_font<ID: Hash+Clone>(&self, ui: &Ui<ID>) -> Option<ID> { unsafe{ ::low::window_helper::get_window_font(self.handle, ui) } }
+    pub fn set_font<ID: Hash+Clone>(&self, ui: &Ui<ID>, f: Option<&ID>) -> Result<(), Error> { unsafe{ ::low::window_helper::set_window_font(self.handle, ui, f) } }
+    pub fn update(&self) { unsafe{ ::low::window_helper::update(self.handle); } }
+
+    #[inline(always)]
+    fn data(&self) -> &StatusData {
+        unsafe{ &*self.data }
+    }
+}
+
+impl Control for StatusBar {
+
+    fn handle(&self) -> AnyHandle {
+        AnyHandle::HWND(self.handle)
+    }
+
+    fn control_type(&self) -> ControlType {
+        ControlType::StatusBar
+    }
+
+    fn free(&mut self) {
+        use user32::DestroyWindow;
+        unsafe{
+            unhook_parent(self.parent, self.handle);
+            DestroyWindow(self.handle);
+            drop(Box::from_raw(self.data));
+        }
+    }
+
+}
+
+/// The parts of a status bar. Shared by the control and the subclass of its parent.
+struct StatusData {
+    handle: HWND,
+    parts: RefCell<Vec<StatusPartWidth>>
+}
+
+impl StatusData {
+
+    /// Move the status bar at the bottom of its parent and recompute the width of the parts
+    unsafe fn resize(&self) {
+        use winapi::{WM_SIZE, SB_SETPARTS};
+
+        SendMessageW(self.handle, WM_SIZE, 0, 0);
+
+        let (width, _) = ::low::window_helper::get_window_size(self.handle);
+        let edges = part_edges(&self.parts.borrow(), width);
+        SendMessageW(self.handle, SB_SETPARTS, edges.len() as WPARAM, edges.as_ptr() as LPARAM);
+    }
+
+}
+
+/// Return the right edge of every part. A stretch part at the end extends to the right border (`-1`).
+fn part_edges(parts: &[StatusPartWidth], width: u32) -> Vec<c_int> {
+    let fixed: u32 = parts.iter().map(|p| match p { &StatusPartWidth::Fixed(w) => w, _ => 0 }).sum();
+    let factors: u32 = parts.iter().map(|p| match p { &StatusPartWidth::Stretch(f) => f, _ => 0 }).sum();
+    let free = width.saturating_sub(fixed);
+
+    let mut edges = Vec::with_capacity(parts.len());
+    let mut edge: u32 = 0;
+    for part in parts.iter() {
+        edge += match part {
+            &StatusPartWidth::Fixed(w) => w,
+            &StatusPartWidth::Stretch(f) if factors > 0 => ((free as u64 * f as u64) / factors as u64) as u32,
+            &StatusPartWidth::Stretch(_) => 0
+        };
+        edges.push(edge as c_int);
+    }
+
+    if let Some(&StatusPartWidth::Stretch(_)) = parts.last() {
+        *edges.last_mut().unwrap() = -1;
+    }
+
+    edges
+}
+
+fn check_parts(parts: &[StatusPartWidth]) -> Result<(), Error> {
+    if parts.len() == 0 || parts.len() > 255 {
+        Err(Error::UserError(format!("A status bar must have between 1 and 255 parts, got {}", parts.len())))
+    } else {
+        Ok(())
+    }
+}
+
+unsafe fn set_part_text(handle: HWND, part: WPARAM, text: &str) {
+    use winapi::SB_SETTEXTW;
+    use low::other_helper::to_utf16;
+
+    let text = to_utf16(text);
+    SendMessageW(handle, SB_SETTEXTW, part, text.as_ptr() as LPARAM);
+}
+
+unsafe fn get_part_text(handle: HWND, part: WPARAM) -> String {
+    use winapi::{SB_GETTEXTW, SB_GETTEXTLENGTHW, LOWORD, DWORD};
+    use low::other_helper::from_utf16;
+
+    let length = LOWORD(SendMessageW(handle, SB_GETTEXTLENGTHW, part, 0) as DWORD) as usize;
+    let mut buffer: Vec<u16> = vec![0; length+1];
+    SendMessageW(handle, SB_GETTEXTW, part, buffer.as_mut_ptr() as LPARAM);
+    buffer.truncate(length);
+
+    from_utf16(&buffer)
+}
+
+// The subclass id is the status bar handle
+unsafe fn hook_parent(parent: HWND, handle: HWND, data: *mut StatusData) {
+    use comctl32::SetWindowSubclass;
+    SetWindowSubclass(parent, Some(status_bar_proc), handle as UINT_PTR, data as DWORD_PTR);
+}
+
+unsafe fn unhook_parent(parent: HWND, handle: HWND) {
+    use comctl32::RemoveWindowSubclass;
+    RemoveWindowSubclass(parent, Some(status_bar_proc), handle as UINT_PTR);
+}
+
+/// Keep the status bar at the bottom of its parent when the parent is resized
+unsafe extern "system" fn status_bar_proc(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM, _id: UINT_PTR, data: DWORD_PTR) -> LRESULT {
+    use comctl32::DefSubclassProc;
+    use winapi::WM_SIZE;
+
+    if msg == WM_SIZE {
+        let data = &*(data as *const StatusData);
+        data.resize();
+    }
+
+    DefSubclassProc(hwnd, msg, w, l)
+}
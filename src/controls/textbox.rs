@@ -14,6 +14,7 @@ use user32::SendMessageW;
 
 use ui::Ui;
 use controls::{Control, ControlT, ControlType, AnyHandle};
+use validation::{Validator, ErrorFeedback};
 use error::Error;
 
 /**
@@ -30,9 +31,13 @@ use error::Error;
     • `disabled`: If the user can or can't click on the textbox  
     • `readonly`: If the user can copty the text but can't edit the textbox content  
     • `limit`: The maximum number of characters that the control can hold  
+    • `validators`: The rules checked by `TextBox.validate`  
     • `scrollbars`: A tuple to defined whether to show scrollbars or not (show horizontal, show vertical)
     • `parent`: The textbox parent  
     • `font`: The textbox font. If None, use the system default  
+    • `tooltip`: A tip displayed when the mouse hovers the textbox. If None, the textbox has no tip  
+    • `text_color`: The text color of the textbox as `[red, green, blue]`. If None, use the color of the parent or the system default  
+    • `background_color`: The background color of the textbox as `[red, green, blue]`. If None, use the system default  
 */
 #[derive(Clone)]
 pub struct TextBoxT<S1: Clone+Into<String>, ID: Hash+Clone> {
@@ -43,15 +48,21 @@ pub struct TextBoxT<S1: Clone+Into<String>, ID: Hash+Clone> {
     pub disabled: bool,
     pub readonly: bool,
     pub limit: u32,
+    pub validators: Vec<Validator>,
     pub scrollbars: (bool, bool),
     pub parent: ID,
     pub font: Option<ID>,
+    pub tooltip: Option<String>,
+    pub text_color: Option<[u8; 3]>,
+    pub background_color: Option<[u8; 3]>,
 }
 
 impl<S1: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for TextBoxT<S1, ID> {
     fn type_id(&self) -> TypeId { TypeId::of::<TextBox>() }
 
     fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        use controls::tooltip::set_template_tip;
+        use low::color_helper::set_template_colors;
         use low::window_helper::{WindowParams, build_window, set_window_font_raw, handle_of_window, handle_of_font};
         use low::defs::{ES_AUTOHSCROLL, ES_AUTOVSCROLL, ES_READONLY, EM_LIMITTEXT, ES_MULTILINE};
         use winapi::{DWORD, WS_VISIBLE, WS_DISABLED, WS_CHILD, WS_BORDER, WS_HSCROLL, WS_VSCROLL};
@@ -96,7 +107,13 @@ impl<S1: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for TextBoxT<S1, ID> {
                     SendMessageW(h, EM_LIMITTEXT, self.limit as WPARAM, 0);
                 };
 
-                Ok( Box::new(TextBox{handle: h}) )
+                if let Err(e) = unsafe{ set_template_tip(ui, h, &self.tooltip) } {
+                    return Err(e);
+                }
+
+                unsafe{ set_template_colors(h, self.text_color, self.background_color); }
+
+                Ok( Box::new(TextBox{handle: h, validators: self.validators.clone(), feedback: ErrorFeedback::new()}) )
             },
             Err(e) => Err(Error::System(e))
         }
@@ -107,11 +124,58 @@ impl<S1: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for TextBoxT<S1, ID> {
     A multi line textinput control
 */
 pub struct TextBox {
-    handle: HWND
+    handle: HWND,
+    validators: Vec<Validator>,
+    feedback: ErrorFeedback
 }
 
 impl TextBox {
 
+    /**
+        Check the text with the validators of the control. If the text is rejected, an error icon is displayed next
+        to the control and a balloon tip shows the error message. Otherwise, the error feedback is hidden.  
+        Returns the error message if the text is rejected.
+    */
+    pub fn validate(&self) -> Result<(), String> {
+        let result = Validator::check_text(&self.validators, &self.get_text());
+        match result.as_ref() {
+            Ok(_) => self.clear_error(),
+            Err(e) => self.show_error(e)
+        }
+
+        result
+    }
+
+    /// Return true if the text is accepted by the validators of the control. The error feedback is not modified.
+    pub fn is_valid(&self) -> bool {
+        Validator::check_text(&self.validators, &self.get_text()).is_ok()
+    }
+
+    /// Return the rules checked by `validate`
+    pub fn get_validators(&self) -> &[Validator] {
+        &self.validators
+    }
+
+    /// Replace the rules checked by `validate`. The error feedback is not modified.
+    pub fn set_validators(&mut self, validators: Vec<Validator>) {
+        self.validators = validators;
+    }
+
+    /// Display an error icon next to the control and a balloon tip with a message
+    pub fn show_error<'a>(&self, message: &'a str) {
+        unsafe{ self.feedback.show(self.handle, "Invalid value", message); }
+    }
+
+    /// Hide the error icon and the balloon tip
+    pub fn clear_error(&self) {
+        unsafe{ self.feedback.hide(self.handle); }
+    }
+
+    /// Return true if the error icon is displayed
+    pub fn has_error(&self) -> bool {
+        self.feedback.visible()
+    }
+
     /// Set or unset the readonly status on the control
     pub fn set_readonly(&self, readonly: bool) {
         use low::window_helper::{set_window_long, get_window_long};
@@ -154,15 +218,19 @@ impl TextBox {
     pub fn get_visibility(&self) -> bool { unsafe{ ::low::window_helper::get_window_visibility(self.handle) } }
     pub fn set_visibility(&self, visible: bool) { unsafe{ ::low::window_helper::set_window_visibility(self.handle, visible); }}
     pub fn get_position(&self) -> (i32, i32) { unsafe{ ::low::window_helper::get_window_position(self.handle) } }
-    pub fn set_position(&self, x: i32, y: i32) { unsafe{ ::low::window_helper::set_window_position(self.handle, x, y); }}
+    pub fn set_position(&self, x: i32, y: i32) { unsafe{ ::low::window_helper::set_window_position(self.handle, x, y); self.feedback.place(self.handle); }}
     pub fn get_size(&self) -> (u32, u32) { unsafe{ ::low::window_helper::get_window_size(self.handle) } }
-    pub fn set_size(&self, w: u32, h: u32) { unsafe{ ::low::window_helper::set_window_size(self.handle, w, h, true); } }
+    pub fn set_size(&self, w: u32, h: u32) { unsafe{ ::low::window_helper::set_window_size(self.handle, w, h, true); self.feedback.place(self.handle); } }
     pub fn get_enabled(&self) -> bool { unsafe{ ::low::window_helper::get_window_enabled(self.handle) } }
     pub fn set_enabled(&self, e:bool) { unsafe{ ::low::window_helper::set_window_enabled(self.handle, e); } }
     pub fn get_font<ID: Hash+Clone>(&self, ui: &Ui<ID>) -> Option<ID> { unsafe{ ::low::window_helper::get_window_font(self.handle, ui) } }
     pub fn set_font<ID: Hash+Clone>(&self, ui: &Ui<ID>, f: Option<&ID>) -> Result<(), Error> { unsafe{ ::low::window_helper::set_window_font(self.handle, ui, f) } }
     pub fn update(&self) { unsafe{ ::low::window_helper::update(self.handle); } }
     pub fn focus(&self) { unsafe{ ::user32::SetFocus(self.handle); } }
+    pub fn get_text_color(&self) -> Option<[u8; 3]> { ::low::color_helper::get_text_color(self.handle) }
+    pub fn set_text_color(&self, color: Option<[u8; 3]>) { unsafe{ ::low::color_helper::set_text_color(self.handle, color); } }
+    pub fn get_background_color(&self) -> Option<[u8; 3]> { ::low::color_helper::get_background_color(self.handle) }
+    pub fn set_background_color(&self, color: Option<[u8; 3]>) { unsafe{ ::low::color_helper::set_background_color(self.handle, color); } }
 }
 
 impl Control for TextBox {
@@ -177,7 +245,14 @@ impl Control for TextBox {
 
     fn free(&mut self) {
         use user32::DestroyWindow;
-        unsafe{ DestroyWindow(self.handle) };
+        use controls::tooltip::clear_template_tip;
+        use low::color_helper::clear_colors;
+        unsafe{
+            clear_template_tip(self.handle);
+            clear_colors(self.handle);
+            self.feedback.free();
+            DestroyWindow(self.handle);
+        }
     }
 
 }
\ No newline at end of file
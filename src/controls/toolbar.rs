@@ -0,0 +1,510 @@
+/*!
+    A toolbar control. A row of buttons docked at the top of a window
+*/
+
+use std::hash::Hash;
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::ptr;
+use std::mem;
+
+use winapi::{HWND, HFONT, HMENU, HBITMAP, HIMAGELIST, UINT, WPARAM, LPARAM, LRESULT, UINT_PTR, DWORD_PTR, INT_PTR, BYTE, c_int};
+use user32::SendMessageW;
+
+use ui::Ui;
+use controls::{Control, ControlT, ControlType, AnyHandle, HandleSpec};
+use error::{Error, SystemError};
+
+/**
+    The style of a toolbar button
+
+    • `Normal`: A push button  
+    • `Check`: A button that stays pressed until it is clicked again  
+    • `CheckGroup`: A check button that unchecks the other buttons of its group when it is clicked. A group is a sequence of `CheckGroup` buttons  
+    • `Dropdown`: A button with an arrow. Clicking the arrow pops the `menu` of the button  
+    • `Separator`: A gap between two buttons  
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ToolbarButtonStyle {
+    Normal,
+    Check,
+    CheckGroup,
+    Dropdown,
+    Separator
+}
+
+/**
+    A toolbar button definition
+
+    Members:  
+    • `id`: The identifier of the button. Sent by `toolbar::Click` when the button is clicked. Windows sends 16 bits command ids  
+    • `text`: The label displayed under the button image. Can be empty  
+    • `image`: The identifier of the `Image` resource displayed in the button (a bitmap or an icon)  
+    • `style`: The style of the button  
+    • `tooltip`: The text displayed when the mouse hovers the button  
+    • `menu`: The `ContextMenu` popped by the arrow of a `Dropdown` button  
+    • `checked`: If a check button is initially checked  
+    • `enabled`: If the user can click on the button  
+*/
+#[derive(Clone, Debug)]
+pub struct ToolbarButton<ID: Hash+Clone> {
+    pub id: u16,
+    pub text: String,
+    pub image: Option<ID>,
+    pub style: ToolbarButtonStyle,
+    pub tooltip: Option<String>,
+    pub menu: Option<ID>,
+    pub checked: bool,
+    pub enabled: bool
+}
+
+impl<ID: Hash+Clone> ToolbarButton<ID> {
+
+    /// Create an enabled push button without image
+    pub fn new<'a>(id: u16, text: &'a str) -> ToolbarButton<ID> {
+        ToolbarButton {
+            id: id, text: text.to_string(), image: None,
+            style: ToolbarButtonStyle::Normal,
+            tooltip: None, menu: None,
+            checked: false, enabled: true
+        }
+    }
+
+    /// Create a separator
+    pub fn separator() -> ToolbarButton<ID> {
+        ToolbarButton{ style: ToolbarButtonStyle::Separator, ..ToolbarButton::new(0, "") }
+    }
+
+}
+
+/**
+    A template that creates a toolbar. The toolbar is docked at the top of its parent and follows its width.
+
+    Control specific events:  
+    `toolbar::Click`  
+
+    Members:  
+    • `buttons`: The buttons of the toolbar  
+    • `image_size`: The size of the button images. Bitmaps and icons of another size are stretched  
+    • `visible`: If the toolbar should be visible to the user  
+    • `disabled`: If the user can or can't click on the toolbar  
+    • `parent`: The toolbar parent. Must be a window  
+    • `font`: The font of the button labels. If None, use the system default  
+*/
+#[derive(Clone)]
+pub struct ToolbarT<ID: Hash+Clone> {
+    pub buttons: Vec<ToolbarButton<ID>>,
+    pub image_size: (u32, u32),
+    pub visible: bool,
+    pub disabled: bool,
+    pub parent: ID,
+    pub font: Option<ID>,
+}
+
+impl<ID: Hash+Clone> ControlT<ID> for ToolbarT<ID> {
+    fn type_id(&self) -> TypeId { TypeId::of::<Toolbar>() }
+
+    fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        use low::window_helper::{WindowParams, build_window, set_window_font_raw, handle_of_window, handle_of_font};
+        use winapi::{DWORD, WS_VISIBLE, WS_DISABLED, WS_CHILD, TBSTYLE_FLAT, TBSTYLE_TOOLTIPS, CCS_TOP, TB_BUTTONSTRUCTSIZE,
+          TB_SETEXTENDEDSTYLE, TB_SETIMAGELIST, TB_GETTOOLTIPS, TBSTYLE_EX_DRAWDDARROWS, TBBUTTON, ILC_COLOR32, ILC_MASK};
+        use comctl32::{ImageList_Create, ImageList_Destroy};
+        use user32::DestroyWindow;
+
+        let flags: DWORD = WS_CHILD | TBSTYLE_FLAT | TBSTYLE_TOOLTIPS | CCS_TOP |
+        if self.visible  { WS_VISIBLE }  else { 0 } |
+        if self.disabled { WS_DISABLED } else { 0 };
+
+        // Get the parent handle
+        let parent = match handle_of_window(ui, &self.parent, "The parent of a toolbar must be a window-like control.") {
+            Ok(h) => h,
+            Err(e) => { return Err(e); }
+        };
+
+        // Get the font handle (if any)
+        let font_handle: Option<HFONT> = match self.font.as_ref() {
+            Some(font_id) =>
+                match handle_of_font(ui, &font_id, "The font of a toolbar must be a font resource.") {
+                    Ok(h) => Some(h),
+                    Err(e) => { return Err(e); }
+                },
+            None => None
+        };
+
+        // The toolbar computes its own position and size
+        let params = WindowParams {
+            title: "",
+            class_name: "ToolbarWindow32",
+            position: (0, 0),
+            size: (0, 0),
+            flags: flags,
+            ex_flags: Some(0),
+            parent: parent
+        };
+
+        let handle = match unsafe{ build_window(params) } {
+            Ok(h) => h,
+            Err(e) => { return Err(Error::System(e)); }
+        };
+
+        let (w, h) = self.image_size;
+        let image_list = unsafe{ ImageList_Create(w as c_int, h as c_int, ILC_COLOR32 | ILC_MASK, self.buttons.len() as c_int, 4) };
+        if image_list.is_null() {
+            unsafe{ DestroyWindow(handle); }
+            return Err(Error::System(SystemError::ImageCreation));
+        }
+
+        let data = Box::into_raw(Box::new(ToolbarData {
+            handle: handle,
+            tooltips: unsafe{ SendMessageW(handle, TB_GETTOOLTIPS, 0, 0) as HWND },
+            image_list: image_list,
+            image_size: (w as c_int, h as c_int),
+            buttons: RefCell::new(Vec::new())
+        }));
+
+        unsafe {
+            SendMessageW(handle, TB_BUTTONSTRUCTSIZE, mem::size_of::<TBBUTTON>() as WPARAM, 0);
+            SendMessageW(handle, TB_SETEXTENDEDSTYLE, 0, TBSTYLE_EX_DRAWDDARROWS as LPARAM);
+            SendMessageW(handle, TB_SETIMAGELIST, 0, image_list as LPARAM);
+            set_window_font_raw(handle, font_handle, true);
+        }
+
+        let toolbar = Toolbar{handle: handle, parent: parent, data: data};
+        for button in self.buttons.iter() {
+            if let Err(e) = toolbar.add_button(ui, button) {
+                unsafe {
+                    DestroyWindow(handle);
+                    ImageList_Destroy(image_list);
+                    drop(Box::from_raw(data));
+                }
+                return Err(e);
+            }
+        }
+
+        unsafe {
+            (*data).resize();
+            hook_parent(parent, handle, data);
+        }
+
+        Ok( Box::new(toolbar) )
+    }
+}
+
+/**
+    A toolbar control. Buttons are identified by the `id` of their definition.
+*/
+pub struct Toolbar {
+    handle: HWND,
+    parent: HWND,
+    data: *mut ToolbarData
+}
+
+impl Toolbar {
+
+    /**
+        Add a button at the end of the toolbar.
+        Returns an error if the image is not a bitmap or an icon or if the menu is not a `ContextMenu`.
+    */
+    pub fn add_button<ID: Hash+Clone>(&self, ui: &Ui<ID>, button: &ToolbarButton<ID>) -> Result<(), Error> {
+        let count = self.len();
+        self.insert_button(ui, count, button)
+    }
+
+    /**
+        Insert a button at `index`. If `index` is greater than the number of buttons, the button is added at the end.
+        Returns an error if the image is not a bitmap or an icon or if the menu is not a `ContextMenu`.
+    */
+    pub fn insert_button<ID: Hash+Clone>(&self, ui: &Ui<ID>, index: usize, button: &ToolbarButton<ID>) -> Result<(), Error> {
+        use winapi::{TBBUTTON, TB_INSERTBUTTONW, TBSTATE_ENABLED, TBSTATE_CHECKED, BTNS_BUTTON, BTNS_CHECK, BTNS_CHECKGROUP,
+          BTNS_DROPDOWN, BTNS_SEP, BTNS_AUTOSIZE, I_IMAGENONE};
+        use comctl32::ImageList_ReplaceIcon;
+        use low::other_helper::to_utf16;
+
+        let menu: HMENU = match button.menu.as_ref() {
+            Some(id) => match ui.handle_of(id) {
+                Ok(AnyHandle::HMENU(h)) => h,
+                Ok(h) => { return Err(Error::BadResource(format!("The menu of a toolbar button must be a ContextMenu, got {:?}", h))); },
+                Err(e) => { return Err(e); }
+            },
+            None => ptr::null_mut()
+        };
+
+        let image: c_int = match button.image.as_ref() {
+            Some(id) => match ui.handle_of(id) {
+                Ok(AnyHandle::HICON(h)) => unsafe{ ImageList_ReplaceIcon(self.data().image_list, -1, h) },
+                Ok(AnyHandle::HANDLE(h, HandleSpec::Bitmap)) => unsafe{ self.data().add_bitmap(h as HBITMAP) },
+                Ok(h) => { return Err(Error::BadResource(format!("The image of a toolbar button must be a bitmap or an icon, got {:?}", h))); },
+                Err(e) => { return Err(e); }
+            },
+            None => I_IMAGENONE
+        };
+
+        let style = BTNS_AUTOSIZE | match button.style {
+            ToolbarButtonStyle::Normal => BTNS_BUTTON,
+            ToolbarButtonStyle::Check => BTNS_CHECK,
+            ToolbarButtonStyle::CheckGroup => BTNS_CHECKGROUP,
+            ToolbarButtonStyle::Dropdown => BTNS_DROPDOWN,
+            ToolbarButtonStyle::Separator => BTNS_SEP
+        };
+
+        let state = if button.enabled { TBSTATE_ENABLED } else { 0 } |
+                    if button.checked { TBSTATE_CHECKED } else { 0 };
+
+        let text = to_utf16(&button.text);
+        let mut info: TBBUTTON = unsafe{ mem::zeroed() };
+        info.iBitmap = if button.style == ToolbarButtonStyle::Separator { 0 } else { image };
+        info.idCommand = button.id as c_int;
+        info.fsState = state;
+        info.fsStyle = style as BYTE;
+        info.iString = if button.text.len() > 0 { text.as_ptr() as INT_PTR } else { -1 };
+
+        let index = if index > self.len() { self.len() } else { index };
+        unsafe{ SendMessageW(self.handle, TB_INSERTBUTTONW, index as WPARAM, &info as *const TBBUTTON as LPARAM); }
+
+        let tooltip = match button.tooltip.as_ref() {
+            Some(t) => to_utf16(t),
+            None => Vec::new()
+        };
+
+        self.data().buttons.borrow_mut().insert(index, ButtonData{ id: button.id, image: image, tooltip: tooltip, menu: menu });
+        unsafe{ self.data().resize(); }
+
+        Ok(())
+    }
+
+    /// Remove the first button identified by `id`. Return `false` if there was no button with this id.
+    pub fn remove_button(&self, id: u16) -> bool {
+        use winapi::TB_DELETEBUTTON;
+
+        let index = match self.index_of(id) {
+            Some(i) => i,
+            None => { return false; }
+        };
+
+        unsafe{ SendMessageW(self.handle, TB_DELETEBUTTON, index as WPARAM, 0); }
+        let button = self.data().buttons.borrow_mut().remove(index);
+        unsafe {
+            self.data().remove_image(button.image);
+            self.data().resize();
+        }
+        true
+    }
+
+    /// Return the number of buttons in the toolbar. Separators are counted.
+    pub fn len(&self) -> usize {
+        self.data().buttons.borrow().len()
+    }
+
+    /// Return the index of the first button identified by `id` or `None` if there is no button with this id
+    pub fn index_of(&self, id: u16) -> Option<usize> {
+        self.data().buttons.borrow().iter().position(|b| b.id == id)
+    }
+
+    /// Return `true` if the check button `id` is checked. Return `false` if the button does not exist.
+    pub fn get_checked(&self, id: u16) -> bool {
+        use winapi::TB_ISBUTTONCHECKED;
+        unsafe{ SendMessageW(self.handle, TB_ISBUTTONCHECKED, id as WPARAM, 0) > 0 }
+    }
+
+    /// Check or uncheck the check button `id`
+    pub fn set_checked(&self, id: u16, checked: bool) {
+        use winapi::TB_CHECKBUTTON;
+        unsafe{ SendMessageW(self.handle, TB_CHECKBUTTON, id as WPARAM, checked as LPARAM); }
+    }
+
+    /// Return `true` if the user can click on the button `id`. Return `false` if the button does not exist.
+    pub fn get_button_enabled(&self, id: u16) -> bool {
+        use winapi::TB_ISBUTTONENABLED;
+        unsafe{ SendMessageW(self.handle, TB_ISBUTTONENABLED, id as WPARAM, 0) > 0 }
+    }
+
+    /// Enable or disable the button `id`
+    pub fn set_button_enabled(&self, id: u16, enabled: bool) {
+        use winapi::TB_ENABLEBUTTON;
+        unsafe{ SendMessageW(self.handle, TB_ENABLEBUTTON, id as WPARAM, enabled as LPARAM); }
+    }
+
+    /// Return the tooltip of the button `id` or `None` if the button does not exist or has no tooltip
+    pub fn get_tooltip(&self, id: u16) -> Option<String> {
+        use low::other_helper::from_utf16;
+
+        let buttons = self.data().buttons.borrow();
+        match buttons.iter().find(|b| b.id == id) {
+            Some(b) if b.tooltip.len() > 1 => Some(from_utf16(&b.tooltip[0..b.tooltip.len()-1])),
+            _ => None
+        }
+    }
+
+    /// Set the tooltip of the button `id`. Pass `None` to remove the tooltip.
+    pub fn set_tooltip<'a>(&self, id: u16, tooltip: Option<&'a str>) {
+        use low::other_helper::to_utf16;
+
+        let mut buttons = self.data().buttons.borrow_mut();
+        if let Some(b) = buttons.iter_mut().find(|b| b.id == id) {
+            b.tooltip = match tooltip {
+                Some(t) => to_utf16(t),
+                None => Vec::new()
+            };
+        }
+    }
+
+    pub fn get_visibility(&self) -> bool { unsafe{ ::low::window_helper::get_window_visibility(self.handle) } }
+    pub fn set_visibility(&self, visible: bool) { unsafe{ ::low::window_helper::set_window_visibility(self.handle, visible); }}
+    pub fn get_position(&self) -> (i32, i32) { unsafe{ ::low::window_helper::get_window_position(self.handle) } }
+    pub fn get_size(&self) -> (u32, u32) { unsafe{ ::low::window_helper::get_window_size(self.handle) } }
+    pub fn get_enabled(&self) -> bool { unsafe{ ::low::window_helper::get_window_enabled(self.handle) } }
+    pub fn set_enabled(&self, e:bool) { unsafe{ ::low::window_helper::set_window_enabled(self.handle, e); } }
+    pub fn get_font<ID: Hash+Clone>(&self, ui: &Ui<ID>) -> Option<ID> { unsafe{ ::low::window_helper::get_window_font(self.handle, ui) } }
+    pub fn set_font<ID: Hash+Clone>(&self, ui: &Ui<ID>, f: Option<&ID>) -> Result<(), Error> { unsafe{ ::low::window_helper::set_window_font(self.handle, ui, f) } }
+    pub fn update(&self) { unsafe{ ::low::window_helper::update(self.handle); } }
+
+    #[inline(always)]
+    fn data(&self) -> &ToolbarData {
+        unsafe{ &*self.data }
+    }
+}
+
+impl Control for Toolbar {
+
+    fn handle(&self) -> AnyHandle {
+        AnyHandle::HWND(self.handle)
+    }
+
+    fn control_type(&self) -> ControlType {
+        ControlType::Toolbar
+    }
+
+    fn free(&mut self) {
+        use user32::DestroyWindow;
+        use comctl32::ImageList_Destroy;
+        unsafe{
+            unhook_parent(self.parent, self.handle);
+            DestroyWindow(self.handle);
+            ImageList_Destroy(self.data().image_list);
+            drop(Box::from_raw(self.data));
+        }
+    }
+
+}
+
+/// The per button data that the toolbar do not keep
+struct ButtonData {
+    id: u16,
+    image: c_int,
+    tooltip: Vec<u16>,
+    menu: HMENU
+}
+
+/// The state of a toolbar. Shared by the control and the subclass of its parent.
+struct ToolbarData {
+    handle: HWND,
+    tooltips: HWND,
+    image_list: HIMAGELIST,
+    image_size: (c_int, c_int),
+    buttons: RefCell<Vec<ButtonData>>
+}
+
+impl ToolbarData {
+
+    /// Move the toolbar at the top of its parent and resize it to the parent width
+    unsafe fn resize(&self) {
+        use winapi::TB_AUTOSIZE;
+        SendMessageW(self.handle, TB_AUTOSIZE, 0, 0);
+    }
+
+    /**
+        Add a bitmap to the image list, stretched to the image size. `ImageList_Add` would split a bigger bitmap
+        in many images. Return the index of the image or -1 if the bitmap could not be added.
+    */
+    unsafe fn add_bitmap(&self, bitmap: HBITMAP) -> c_int {
+        use comctl32::ImageList_Add;
+        use user32::CopyImage;
+        use gdi32::DeleteObject;
+        use winapi::{HANDLE, HGDIOBJ, IMAGE_BITMAP, LR_CREATEDIBSECTION};
+
+        let (w, h) = self.image_size;
+        let copy = CopyImage(bitmap as HANDLE, IMAGE_BITMAP, w, h, LR_CREATEDIBSECTION);
+        if copy.is_null() { return -1; }
+
+        let image = ImageList_Add(self.image_list, copy as HBITMAP, ptr::null_mut());
+        DeleteObject(copy as HGDIOBJ);
+        image
+    }
+
+    /// Remove the image of a deleted button from the image list and shift the images of the buttons that follow it
+    unsafe fn remove_image(&self, image: c_int) {
+        use comctl32::ImageList_Remove;
+        use winapi::TB_CHANGEBITMAP;
+
+        // Buttons without image hold I_IMAGENONE (-2), failed images hold -1
+        if image < 0 { return; }
+
+        ImageList_Remove(self.image_list, image);
+        for b in self.buttons.borrow_mut().iter_mut().filter(|b| b.image > image) {
+            b.image -= 1;
+            SendMessageW(self.handle, TB_CHANGEBITMAP, b.id as WPARAM, b.image as LPARAM);
+        }
+    }
+
+    /// Pop the menu of a dropdown button under the button. Return `false` if the button has no menu.
+    unsafe fn pop_menu(&self, id: u16, owner: HWND, button: &::winapi::RECT) -> bool {
+        use winapi::POINT;
+        use user32::ClientToScreen;
+        use low::defs::{TrackPopupMenuEx, TPM_NOANIMATION, TPM_RIGHTBUTTON, TPM_LEFTALIGN, TPM_TOPALIGN};
+
+        let menu = match self.buttons.borrow().iter().find(|b| b.id == id) {
+            Some(b) if !b.menu.is_null() => b.menu,
+            _ => { return false; }
+        };
+
+        let mut pos = POINT{ x: button.left, y: button.bottom };
+        ClientToScreen(self.handle, &mut pos);
+
+        let flags = TPM_NOANIMATION | TPM_RIGHTBUTTON | TPM_LEFTALIGN | TPM_TOPALIGN;
+        TrackPopupMenuEx(menu, flags, pos.x, pos.y, owner, ptr::null_mut());
+        true
+    }
+
+}
+
+// The subclass id is the toolbar handle, so a window can hold many toolbars
+unsafe fn hook_parent(parent: HWND, handle: HWND, data: *mut ToolbarData) {
+    use comctl32::SetWindowSubclass;
+    SetWindowSubclass(parent, Some(toolbar_proc), handle as UINT_PTR, data as DWORD_PTR);
+}
+
+unsafe fn unhook_parent(parent: HWND, handle: HWND) {
+    use comctl32::RemoveWindowSubclass;
+    RemoveWindowSubclass(parent, Some(toolbar_proc), handle as UINT_PTR);
+}
+
+/// Keep the toolbar docked when the parent is resized, pop the dropdown menus and answer the tooltips text requests
+unsafe extern "system" fn toolbar_proc(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM, _id: UINT_PTR, data: DWORD_PTR) -> LRESULT {
+    use comctl32::DefSubclassProc;
+    use winapi::{WM_SIZE, WM_NOTIFY, NMHDR, NMTOOLBARW, NMTTDISPINFOW, TBN_DROPDOWN, TTN_GETDISPINFOW, TBDDRET_DEFAULT};
+
+    let data = &*(data as *const ToolbarData);
+    match msg {
+        WM_SIZE => { data.resize(); },
+        WM_NOTIFY => {
+            let nmhdr: &NMHDR = &*(l as *const NMHDR);
+            if nmhdr.hwndFrom == data.handle && nmhdr.code == TBN_DROPDOWN {
+                let info: &NMTOOLBARW = &*(l as *const NMTOOLBARW);
+                if data.pop_menu(info.iItem as u16, hwnd, &info.rcButton) {
+                    return TBDDRET_DEFAULT;
+                }
+            } else if nmhdr.hwndFrom == data.tooltips && !data.tooltips.is_null() && nmhdr.code == TTN_GETDISPINFOW {
+                let info: &mut NMTTDISPINFOW = &mut *(l as *mut NMTTDISPINFOW);
+                if let Some(b) = data.buttons.borrow().iter().find(|b| b.id as UINT_PTR == nmhdr.idFrom) {
+                    if b.tooltip.len() > 0 {
+                        info.lpszText = b.tooltip.as_ptr() as *mut u16;
+                    }
+                }
+                return 0;
+            }
+        },
+        _ => {}
+    }
+
+    DefSubclassProc(hwnd, msg, w, l)
+}
@@ -0,0 +1,547 @@
+/*!
+    A tooltip control. A popup window that displays a short description when the mouse hovers a control
+*/
+
+use std::hash::Hash;
+use std::any::TypeId;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::mem;
+use std::ptr;
+
+use winapi::{HWND, UINT, WPARAM, LPARAM, LRESULT, UINT_PTR, DWORD_PTR, TOOLINFOW};
+use user32::SendMessageW;
+
+use ui::Ui;
+use controls::{Control, ControlT, ControlType, AnyHandle};
+use low::other_helper::{to_utf16, from_utf16};
+use error::Error;
+
+/// The width in pixels at which the text of a multiline tooltip is wrapped
+const MULTILINE_WIDTH: WPARAM = 400;
+
+/// The maximum number of characters returned by `Tooltip.get_text`
+const MAX_TEXT_LENGTH: usize = 1024;
+
+// The tooltips shared by the templates of a Ui: `(Ui message window, tooltip)`
+thread_local!(static TEMPLATE_TOOLTIPS: RefCell<Vec<(HWND, HWND)>> = RefCell::new(Vec::new()));
+
+/**
+    The icon displayed next to the title of a tooltip
+
+    • `None`: No icon  
+    • `Info`: The system information icon  
+    • `Warning`: The system warning icon  
+    • `Error`: The system error icon  
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TooltipIcon {
+    None,
+    Info,
+    Warning,
+    Error
+}
+
+/**
+    The delays of a tooltip
+
+    • `Initial`: The time the mouse must stay over a control before the tip is displayed  
+    • `AutoPop`: The time the tip stays visible if the mouse does not move  
+    • `Reshow`: The time before the next tip is displayed when the mouse moves to another control  
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TooltipDelay {
+    Initial,
+    AutoPop,
+    Reshow
+}
+
+/**
+    A template that creates a tooltip. The tips are registered on the controls after the tooltip is created
+    with `Tooltip.register` or `Tooltip.register_callback`.
+
+    Members:  
+    • `title`: The title displayed above the text of every tip. If empty, the tips have no title  
+    • `icon`: The icon displayed next to the title. Ignored if there is no title  
+    • `balloon`: If the tips should be displayed in a balloon  
+    • `multiline`: If the text of the tips should be broken on `\n` and wrapped when it is too long  
+    • `initial_delay`: The initial delay in milliseconds. If None, use the system default  
+    • `autopop_delay`: The autopop delay in milliseconds. If None, use the system default  
+    • `reshow_delay`: The reshow delay in milliseconds. If None, use the system default  
+*/
+#[derive(Clone)]
+pub struct TooltipT<S: Clone+Into<String>> {
+    pub title: S,
+    pub icon: TooltipIcon,
+    pub balloon: bool,
+    pub multiline: bool,
+    pub initial_delay: Option<u32>,
+    pub autopop_delay: Option<u32>,
+    pub reshow_delay: Option<u32>,
+}
+
+impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for TooltipT<S> {
+    fn type_id(&self) -> TypeId { TypeId::of::<Tooltip>() }
+
+    #[allow(unused_variables)]
+    fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        let handle = match unsafe{ build_tooltip(self.balloon, self.multiline) } {
+            Ok(h) => h,
+            Err(e) => { return Err(e); }
+        };
+
+        let data = Box::into_raw(Box::new(TooltipData{ handle: handle, controls: RefCell::new(Vec::new()), callbacks: RefCell::new(Vec::new()) }));
+        let tooltip = Tooltip{ handle: handle, enabled: Cell::new(true), data: data };
+
+        tooltip.set_title(&self.title.clone().into(), self.icon);
+        if self.initial_delay.is_some() { tooltip.set_delay(TooltipDelay::Initial, self.initial_delay); }
+        if self.autopop_delay.is_some() { tooltip.set_delay(TooltipDelay::AutoPop, self.autopop_delay); }
+        if self.reshow_delay.is_some() { tooltip.set_delay(TooltipDelay::Reshow, self.reshow_delay); }
+
+        Ok( Box::new(tooltip) )
+    }
+}
+
+/**
+    A tooltip control. A single tooltip can hold the tips of many controls.
+*/
+pub struct Tooltip {
+    handle: HWND,
+    enabled: Cell<bool>,
+    data: *mut TooltipData
+}
+
+impl Tooltip {
+
+    /**
+        Display `text` when the mouse hovers the control identified by `control`. If the control already has a tip
+        in this tooltip, its text is replaced. The tip is removed when the control is destroyed.
+        Returns an error if the control is not a window-like control.
+    */
+    pub fn register<'a, ID: Hash+Clone>(&self, ui: &Ui<ID>, control: &ID, text: &'a str) -> Result<(), Error> {
+        use low::window_helper::handle_of_window;
+
+        let control = match handle_of_window(ui, control, "A tooltip can only be registered on a window-like control.") {
+            Ok(h) => h,
+            Err(e) => { return Err(e); }
+        };
+
+        unsafe {
+            self.remove_callback(control);
+            self.hook(control);
+            set_tool_text(self.handle, control, text);
+        }
+
+        Ok(())
+    }
+
+    /**
+        Display the text returned by `callback` when the mouse hovers the control identified by `control`.
+        The callback is called every time the tip is about to be displayed. If the control already has a tip
+        in this tooltip, it is replaced. The tip is removed when the control is destroyed.
+        Returns an error if the control is not a window-like control.
+    */
+    pub fn register_callback<ID, F>(&self, ui: &Ui<ID>, control: &ID, callback: F) -> Result<(), Error>
+      where ID: Hash+Clone, F: Fn() -> String + 'static
+    {
+        use low::window_helper::handle_of_window;
+        use winapi::{TTM_ADDTOOLW, LPSTR_TEXTCALLBACKW};
+
+        let control = match handle_of_window(ui, control, "A tooltip can only be registered on a window-like control.") {
+            Ok(h) => h,
+            Err(e) => { return Err(e); }
+        };
+
+        unsafe {
+            self.remove_callback(control);
+            remove_tool(self.handle, control);
+
+            self.data().callbacks.borrow_mut().push(TipCallback{
+                control: control,
+                callback: Rc::new(callback),
+                text: RefCell::new(Vec::new())
+            });
+
+            self.hook(control);
+
+            let mut tool = tool_info(control, LPSTR_TEXTCALLBACKW);
+            SendMessageW(self.handle, TTM_ADDTOOLW, 0, &mut tool as *mut TOOLINFOW as LPARAM);
+        }
+
+        Ok(())
+    }
+
+    /**
+        Remove the tip of a control. Does nothing if the control has no tip in this tooltip.
+        Returns an error if the control is not a window-like control.
+    */
+    pub fn unregister<ID: Hash+Clone>(&self, ui: &Ui<ID>, control: &ID) -> Result<(), Error> {
+        use low::window_helper::handle_of_window;
+
+        let control = match handle_of_window(ui, control, "A tooltip can only be registered on a window-like control.") {
+            Ok(h) => h,
+            Err(e) => { return Err(e); }
+        };
+
+        unsafe {
+            self.remove_callback(control);
+            self.unhook(control);
+            remove_tool(self.handle, control);
+        }
+
+        Ok(())
+    }
+
+    /**
+        Return the text of the tip of a control. If the tip text is supplied by a callback, the callback is called.
+        Return `None` if the control has no tip in this tooltip or if the control is not in the ui.
+    */
+    pub fn get_text<ID: Hash+Clone>(&self, ui: &Ui<ID>, control: &ID) -> Option<String> {
+        let control = match ui.handle_of(control) {
+            Ok(AnyHandle::HWND(h)) => h,
+            _ => { return None; }
+        };
+
+        let callback = self.data().callbacks.borrow().iter().find(|c| c.control == control).map(|c| c.callback.clone());
+        match callback {
+            Some(callback) => Some(callback()),
+            None => unsafe{ get_tool_text(self.handle, control) }
+        }
+    }
+
+    /// Set the title and the title icon of the tips. An empty title removes the title and the icon.
+    pub fn set_title<'a>(&self, title: &'a str, icon: TooltipIcon) {
+        use winapi::{TTM_SETTITLEW, TTI_NONE, TTI_INFO, TTI_WARNING, TTI_ERROR};
+
+        let icon = match icon {
+            TooltipIcon::None => TTI_NONE,
+            TooltipIcon::Info => TTI_INFO,
+            TooltipIcon::Warning => TTI_WARNING,
+            TooltipIcon::Error => TTI_ERROR
+        };
+
+        let title = to_utf16(title);
+        unsafe{ SendMessageW(self.handle, TTM_SETTITLEW, icon, title.as_ptr() as LPARAM); }
+    }
+
+    /// Set a delay of the tooltip in milliseconds. Pass `None` to use the system default.
+    pub fn set_delay(&self, delay: TooltipDelay, time: Option<u32>) {
+        use winapi::{TTM_SETDELAYTIME, MAKELONG, WORD};
+
+        use user32::GetDoubleClickTime;
+
+        // The system defaults are derived from the double click time
+        let time = match (time, delay) {
+            (Some(t), _) => t,
+            (None, TooltipDelay::Initial) => unsafe{ GetDoubleClickTime() },
+            (None, TooltipDelay::AutoPop) => unsafe{ GetDoubleClickTime() * 10 },
+            (None, TooltipDelay::Reshow) => unsafe{ GetDoubleClickTime() / 5 }
+        };
+
+        let time = MAKELONG(::std::cmp::min(time, 0x7FFF) as WORD, 0) as LPARAM;
+
+        unsafe{ SendMessageW(self.handle, TTM_SETDELAYTIME, delay_param(delay), time); }
+    }
+
+    /// Return a delay of the tooltip in milliseconds
+    pub fn get_delay(&self, delay: TooltipDelay) -> u32 {
+        use winapi::TTM_GETDELAYTIME;
+        unsafe{ SendMessageW(self.handle, TTM_GETDELAYTIME, delay_param(delay), 0) as u32 }
+    }
+
+    /// Return `true` if the tips are displayed in a balloon
+    pub fn get_balloon(&self) -> bool {
+        use low::window_helper::get_window_long;
+        use winapi::{GWL_STYLE, TTS_BALLOON};
+
+        let style = get_window_long(self.handle, GWL_STYLE) as u32;
+        (style & TTS_BALLOON) == TTS_BALLOON
+    }
+
+    /// Return the number of controls that have a tip in this tooltip
+    pub fn len(&self) -> usize {
+        use winapi::TTM_GETTOOLCOUNT;
+        unsafe{ SendMessageW(self.handle, TTM_GETTOOLCOUNT, 0, 0) as usize }
+    }
+
+    /// Enable or disable the tooltip. A disabled tooltip keeps its tips but never displays them.
+    pub fn set_enabled(&self, enabled: bool) {
+        use winapi::TTM_ACTIVATE;
+        unsafe{ SendMessageW(self.handle, TTM_ACTIVATE, enabled as WPARAM, 0); }
+        self.enabled.set(enabled);
+    }
+
+    /// Return `true` if the tooltip is enabled
+    pub fn get_enabled(&self) -> bool {
+        self.enabled.get()
+    }
+
+    /// Hide the tip currently displayed, if any
+    pub fn hide(&self) {
+        use winapi::TTM_POP;
+        unsafe{ SendMessageW(self.handle, TTM_POP, 0, 0); }
+    }
+
+    /// Remove the text callback of a control, if any
+    unsafe fn remove_callback(&self, control: HWND) {
+        self.data().callbacks.borrow_mut().retain(|c| c.control != control);
+    }
+
+    unsafe fn hook(&self, control: HWND) {
+        let mut controls = self.data().controls.borrow_mut();
+        if !controls.contains(&control) {
+            hook_control(control, self.handle, self.data);
+            controls.push(control);
+        }
+    }
+
+    unsafe fn unhook(&self, control: HWND) {
+        let mut controls = self.data().controls.borrow_mut();
+        if let Some(index) = controls.iter().position(|&c| c == control) {
+            unhook_control(control, self.handle);
+            controls.remove(index);
+        }
+    }
+
+    #[inline(always)]
+    fn data(&self) -> &TooltipData {
+        unsafe{ &*self.data }
+    }
+}
+
+impl Control for Tooltip {
+
+    fn handle(&self) -> AnyHandle {
+        AnyHandle::HWND(self.handle)
+    }
+
+    fn control_type(&self) -> ControlType {
+        ControlType::Tooltip
+    }
+
+    fn free(&mut self) {
+        use user32::{DestroyWindow, IsWindow};
+        unsafe{
+            for &c in self.data().controls.borrow().iter() {
+                if IsWindow(c) != 0 {
+                    unhook_control(c, self.handle);
+                }
+            }
+
+            DestroyWindow(self.handle);
+            drop(Box::from_raw(self.data));
+        }
+    }
+
+}
+
+/// The controls that have a tip and the tips that get their text from a callback. Shared by the control and the subclass of the controls.
+struct TooltipData {
+    handle: HWND,
+    controls: RefCell<Vec<HWND>>,
+    callbacks: RefCell<Vec<TipCallback>>
+}
+
+struct TipCallback {
+    control: HWND,
+    callback: Rc<Fn() -> String>,
+    text: RefCell<Vec<u16>>
+}
+
+/**
+    Display the `tooltip` member of a template when the mouse hovers `control`. The tip is added to a tooltip shared by
+    the templates of the Ui, which is created with the first tip and destroyed with the Ui (see `free_template_tooltip`).
+    If the tip cannot be added, `control` is destroyed.
+*/
+pub unsafe fn set_template_tip<ID: Hash+Clone>(ui: &Ui<ID>, control: HWND, tooltip: &Option<String>) -> Result<(), Error> {
+    use user32::DestroyWindow;
+
+    let text = match tooltip.as_ref() {
+        Some(text) => text,
+        None => { return Ok(()); }
+    };
+
+    let owner = ui.message_handle();
+    let mut handle = TEMPLATE_TOOLTIPS.with(|t| t.borrow().iter().find(|t| t.0 == owner).map(|t| t.1).unwrap_or(ptr::null_mut()));
+    if handle.is_null() {
+        handle = match build_tooltip(false, true) {
+            Ok(h) => h,
+            Err(e) => {
+                DestroyWindow(control);
+                return Err(e);
+            }
+        };
+        TEMPLATE_TOOLTIPS.with(|t| t.borrow_mut().push((owner, handle)));
+    }
+
+    set_tool_text(handle, control, text);
+    Ok(())
+}
+
+/// Remove the template tip of a control. Must be called when a control that has a `tooltip` member is freed.
+pub unsafe fn clear_template_tip(control: HWND) {
+    TEMPLATE_TOOLTIPS.with(|t| {
+        for &(_, handle) in t.borrow().iter() {
+            remove_tool(handle, control);
+        }
+    });
+}
+
+/// Destroy the template tooltip of the Ui that owns the message window `owner`. Called when the Ui is freed.
+pub unsafe fn free_template_tooltip(owner: HWND) {
+    use user32::DestroyWindow;
+
+    let handle = TEMPLATE_TOOLTIPS.with(|t| {
+        let mut tooltips = t.borrow_mut();
+        match tooltips.iter().position(|t| t.0 == owner) {
+            Some(index) => tooltips.remove(index).1,
+            None => ptr::null_mut()
+        }
+    });
+
+    if !handle.is_null() {
+        DestroyWindow(handle);
+    }
+}
+
+unsafe fn build_tooltip(balloon: bool, multiline: bool) -> Result<HWND, Error> {
+    use low::window_helper::{WindowParams, build_window};
+    use winapi::{DWORD, WS_POPUP, WS_EX_TOPMOST, TTS_ALWAYSTIP, TTS_NOPREFIX, TTS_BALLOON, TTM_SETMAXTIPWIDTH};
+
+    let flags: DWORD = WS_POPUP | TTS_ALWAYSTIP | TTS_NOPREFIX |
+    if balloon { TTS_BALLOON } else { 0 };
+
+    // A tooltip is a top level window. The controls it describes are added as tools.
+    let params = WindowParams {
+        title: "",
+        class_name: "tooltips_class32",
+        position: (0, 0),
+        size: (0, 0),
+        flags: flags,
+        ex_flags: Some(WS_EX_TOPMOST),
+        parent: ptr::null_mut()
+    };
+
+    match build_window(params) {
+        Ok(h) => {
+            if multiline {
+                SendMessageW(h, TTM_SETMAXTIPWIDTH, 0, MULTILINE_WIDTH as LPARAM);
+            }
+            Ok(h)
+        },
+        Err(e) => Err(Error::System(e))
+    }
+}
+
+/// A tool that covers the whole control. The notifications of the tool are sent to the control itself.
+fn tool_info(control: HWND, text: *mut u16) -> TOOLINFOW {
+    use winapi::{RECT, TTF_IDISHWND, TTF_SUBCLASS};
+
+    TOOLINFOW {
+        cbSize: mem::size_of::<TOOLINFOW>() as UINT,
+        uFlags: TTF_IDISHWND | TTF_SUBCLASS,
+        hwnd: control,
+        uId: control as UINT_PTR,
+        rect: RECT{ left: 0, top: 0, right: 0, bottom: 0 },
+        hinst: ptr::null_mut(),
+        lpszText: text as *mut _,
+        lParam: 0,
+        lpReserved: ptr::null_mut()
+    }
+}
+
+/// Add a tool for `control` or update its text if it already exists
+unsafe fn set_tool_text(tooltip: HWND, control: HWND, text: &str) {
+    use winapi::{TTM_ADDTOOLW, TTM_GETTOOLINFOW, TTM_UPDATETIPTEXTW};
+
+    let mut text = to_utf16(text);
+    let mut tool = tool_info(control, ptr::null_mut());
+
+    let exists = SendMessageW(tooltip, TTM_GETTOOLINFOW, 0, &mut tool as *mut TOOLINFOW as LPARAM) != 0;
+    tool.lpszText = text.as_mut_ptr() as *mut _;
+    if exists {
+        SendMessageW(tooltip, TTM_UPDATETIPTEXTW, 0, &mut tool as *mut TOOLINFOW as LPARAM);
+    } else {
+        SendMessageW(tooltip, TTM_ADDTOOLW, 0, &mut tool as *mut TOOLINFOW as LPARAM);
+    }
+}
+
+unsafe fn get_tool_text(tooltip: HWND, control: HWND) -> Option<String> {
+    use winapi::{TTM_GETTOOLINFOW, TTM_GETTEXTW};
+
+    let mut tool = tool_info(control, ptr::null_mut());
+    if SendMessageW(tooltip, TTM_GETTOOLINFOW, 0, &mut tool as *mut TOOLINFOW as LPARAM) == 0 {
+        return None;
+    }
+
+    let mut buffer: Vec<u16> = vec![0; MAX_TEXT_LENGTH];
+    tool.lpszText = buffer.as_mut_ptr() as *mut _;
+    SendMessageW(tooltip, TTM_GETTEXTW, MAX_TEXT_LENGTH as WPARAM, &mut tool as *mut TOOLINFOW as LPARAM);
+
+    let length = buffer.iter().position(|&c| c == 0).unwrap_or(MAX_TEXT_LENGTH);
+    buffer.truncate(length);
+    Some(from_utf16(&buffer))
+}
+
+unsafe fn remove_tool(tooltip: HWND, control: HWND) {
+    use winapi::TTM_DELTOOLW;
+
+    let mut tool = tool_info(control, ptr::null_mut());
+    SendMessageW(tooltip, TTM_DELTOOLW, 0, &mut tool as *mut TOOLINFOW as LPARAM);
+}
+
+fn delay_param(delay: TooltipDelay) -> WPARAM {
+    use winapi::{TTDT_INITIAL, TTDT_AUTOPOP, TTDT_RESHOW};
+
+    match delay {
+        TooltipDelay::Initial => TTDT_INITIAL,
+        TooltipDelay::AutoPop => TTDT_AUTOPOP,
+        TooltipDelay::Reshow => TTDT_RESHOW
+    }
+}
+
+// The subclass id is the tooltip handle
+unsafe fn hook_control(control: HWND, tooltip: HWND, data: *mut TooltipData) {
+    use comctl32::SetWindowSubclass;
+    SetWindowSubclass(control, Some(tooltip_proc), tooltip as UINT_PTR, data as DWORD_PTR);
+}
+
+unsafe fn unhook_control(control: HWND, tooltip: HWND) {
+    use comctl32::RemoveWindowSubclass;
+    RemoveWindowSubclass(control, Some(tooltip_proc), tooltip as UINT_PTR);
+}
+
+/**
+    Answer the text requests of the tooltip with the value returned by the callback of the control.
+    Remove the tip of the control when it is destroyed, so that the tooltip does not keep a dangling tool.
+*/
+unsafe extern "system" fn tooltip_proc(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM, _id: UINT_PTR, data: DWORD_PTR) -> LRESULT {
+    use comctl32::DefSubclassProc;
+    use winapi::{WM_NOTIFY, WM_DESTROY, NMHDR, NMTTDISPINFOW, TTN_GETDISPINFOW};
+
+    if msg == WM_DESTROY {
+        let data = &*(data as *const TooltipData);
+        remove_tool(data.handle, hwnd);
+        data.callbacks.borrow_mut().retain(|c| c.control != hwnd);
+        data.controls.borrow_mut().retain(|&c| c != hwnd);
+        unhook_control(hwnd, data.handle);
+    } else if msg == WM_NOTIFY {
+        let data = &*(data as *const TooltipData);
+        let nmhdr: &NMHDR = &*(l as *const NMHDR);
+        if nmhdr.hwndFrom == data.handle && nmhdr.code == TTN_GETDISPINFOW {
+            // The callback is cloned so that it can use the tooltip without borrowing the callbacks twice
+            let callback = data.callbacks.borrow().iter().find(|c| c.control == hwnd).map(|c| c.callback.clone());
+            if let Some(callback) = callback {
+                let text = to_utf16(&callback());
+                let info: &mut NMTTDISPINFOW = &mut *(l as *mut NMTTDISPINFOW);
+                if let Some(c) = data.callbacks.borrow().iter().find(|c| c.control == hwnd) {
+                    *c.text.borrow_mut() = text;
+                    info.lpszText = c.text.borrow().as_ptr() as *mut u16;
+                }
+                return 0;
+            }
+        }
+    }
+
+    DefSubclassProc(hwnd, msg, w, l)
+}
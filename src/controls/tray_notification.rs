@@ -0,0 +1,357 @@
+/*!
+    An icon in the notification area of the taskbar (the system tray)
+*/
+
+use std::hash::Hash;
+use std::any::TypeId;
+use std::cell::Cell;
+use std::mem;
+use std::ptr;
+
+use winapi::{HWND, HICON, HMENU, UINT, DWORD, WPARAM, LPARAM, LRESULT, UINT_PTR, DWORD_PTR, NOTIFYICONDATAW};
+
+use ui::Ui;
+use controls::{Control, ControlT, ControlType, AnyHandle};
+use low::other_helper::to_utf16;
+use error::Error;
+
+static mut TRAY_ID: UINT = 0;
+
+/**
+    The icon displayed next to the title of a balloon notification
+
+    • `None`: No icon  
+    • `Info`: The system information icon  
+    • `Warning`: The system warning icon  
+    • `Error`: The system error icon  
+    • `Tray`: The icon of the tray notification  
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TrayBalloonIcon {
+    None,
+    Info,
+    Warning,
+    Error,
+    Tray
+}
+
+/**
+    A template that creates an icon in the notification area of the taskbar. The notifications messages
+    are received by the hidden window of the Ui.
+
+    Control specific events:  
+    `tray::Click, tray::DoubleClick, tray::BalloonClick`
+
+    Members:  
+    • `icon`: The icon displayed in the notification area. Must be an icon `Image`  
+    • `tooltip`: The text displayed when the mouse hovers the icon. Truncated to 127 characters  
+    • `menu`: A `ContextMenu` popped when the user right clicks the icon  
+    • `visible`: If the icon should be visible in the notification area  
+*/
+#[derive(Clone)]
+pub struct TrayNotificationT<S: Clone+Into<String>, ID: Hash+Clone> {
+    pub icon: ID,
+    pub tooltip: Option<S>,
+    pub menu: Option<ID>,
+    pub visible: bool,
+}
+
+impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for TrayNotificationT<S, ID> {
+    fn type_id(&self) -> TypeId { TypeId::of::<TrayNotification>() }
+
+    fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        use low::defs::{Shell_NotifyIconW, NWG_TRAY, NIS_HIDDEN};
+        use winapi::{NIM_ADD, NIM_SETVERSION, NIF_MESSAGE, NIF_ICON, NIF_TIP, NIF_STATE, NOTIFYICON_VERSION};
+
+        let icon = match handle_of_icon(ui, &self.icon) {
+            Ok(h) => h,
+            Err(e) => { return Err(e); }
+        };
+
+        let menu = match self.menu.as_ref() {
+            Some(id) => match handle_of_menu(ui, id) {
+                Ok(h) => h,
+                Err(e) => { return Err(e); }
+            },
+            None => ptr::null_mut()
+        };
+
+        let owner = unsafe{ ui.message_handle() };
+        let id = unsafe{ TRAY_ID += 1; TRAY_ID };
+
+        let mut data = notify_data(owner, id);
+        data.uFlags = NIF_MESSAGE | NIF_ICON | NIF_TIP | NIF_STATE;
+        data.uCallbackMessage = NWG_TRAY;
+        data.hIcon = icon;
+        data.dwStateMask = NIS_HIDDEN;
+        data.dwState = if self.visible { 0 } else { NIS_HIDDEN };
+        if let Some(tip) = self.tooltip.as_ref() {
+            copy_text(&mut data.szTip, &tip.clone().into());
+        }
+
+        unsafe {
+            if Shell_NotifyIconW(NIM_ADD, &mut data) == 0 {
+                return Err(Error::UserError("The tray notification could not be added to the notification area.".to_string()));
+            }
+
+            *data.uVersion_mut() = NOTIFYICON_VERSION;
+            Shell_NotifyIconW(NIM_SETVERSION, &mut data);
+        }
+
+        let tray_data = Box::into_raw(Box::new(TrayData{ owner: owner, menu: Cell::new(menu) }));
+        unsafe{ hook_owner(owner, id, tray_data); }
+
+        Ok( Box::new(TrayNotification{ id: id, owner: owner, visible: Cell::new(self.visible), data: tray_data }) )
+    }
+}
+
+/**
+    A tray notification control
+*/
+pub struct TrayNotification {
+    id: UINT,
+    owner: HWND,
+    visible: Cell<bool>,
+    data: *mut TrayData
+}
+
+impl TrayNotification {
+
+    /// Set the icon displayed in the notification area. Returns an error if the resource is not an icon `Image`.
+    pub fn set_icon<ID: Hash+Clone>(&self, ui: &Ui<ID>, icon: &ID) -> Result<(), Error> {
+        use winapi::NIF_ICON;
+
+        let icon = match handle_of_icon(ui, icon) {
+            Ok(h) => h,
+            Err(e) => { return Err(e); }
+        };
+
+        let mut data = notify_data(self.owner, self.id);
+        data.uFlags = NIF_ICON;
+        data.hIcon = icon;
+        unsafe{ self.modify(&mut data); }
+        Ok(())
+    }
+
+    /// Set the text displayed when the mouse hovers the icon. Pass `None` to remove the text.
+    pub fn set_tooltip<'a>(&self, tooltip: Option<&'a str>) {
+        use winapi::NIF_TIP;
+
+        let mut data = notify_data(self.owner, self.id);
+        data.uFlags = NIF_TIP;
+        copy_text(&mut data.szTip, tooltip.unwrap_or(""));
+        unsafe{ self.modify(&mut data); }
+    }
+
+    /**
+        Set the `ContextMenu` popped when the user right clicks the icon. Pass `None` to remove the menu.
+        Returns an error if the resource is not a `ContextMenu`.
+    */
+    pub fn set_menu<ID: Hash+Clone>(&self, ui: &Ui<ID>, menu: Option<&ID>) -> Result<(), Error> {
+        let menu = match menu {
+            Some(id) => match handle_of_menu(ui, id) {
+                Ok(h) => h,
+                Err(e) => { return Err(e); }
+            },
+            None => ptr::null_mut()
+        };
+
+        self.data().menu.set(menu);
+        Ok(())
+    }
+
+    /// Return the identifier of the menu popped by a right click. Return `None` if there is no menu or if the menu is not in the ui.
+    pub fn get_menu<ID: Hash+Clone>(&self, ui: &Ui<ID>) -> Option<ID> {
+        let menu = self.data().menu.get();
+        if menu.is_null() {
+            return None;
+        }
+
+        match ui.id_from_handle(&AnyHandle::HMENU(menu)) {
+            Ok(id) => Some(id),
+            Err(_) => None
+        }
+    }
+
+    /**
+        Display a balloon notification over the icon. On Windows 10, the notification is displayed as a toast.
+        The title is truncated to 63 characters and the text to 255 characters.
+        If `silent` is true, the notification does not play a sound.
+    */
+    pub fn show_balloon<'a>(&self, title: &'a str, text: &'a str, icon: TrayBalloonIcon, silent: bool) {
+        use winapi::NIF_INFO;
+        use low::defs::{NIIF_NONE, NIIF_INFO, NIIF_WARNING, NIIF_ERROR, NIIF_USER, NIIF_NOSOUND};
+
+        let mut data = notify_data(self.owner, self.id);
+        data.uFlags = NIF_INFO;
+        copy_text(&mut data.szInfoTitle, title);
+        copy_text(&mut data.szInfo, text);
+        data.dwInfoFlags = if silent { NIIF_NOSOUND } else { 0 } | match icon {
+            TrayBalloonIcon::None => NIIF_NONE,
+            TrayBalloonIcon::Info => NIIF_INFO,
+            TrayBalloonIcon::Warning => NIIF_WARNING,
+            TrayBalloonIcon::Error => NIIF_ERROR,
+            TrayBalloonIcon::Tray => NIIF_USER
+        };
+
+        unsafe{ self.modify(&mut data); }
+    }
+
+    /// Hide the balloon notification currently displayed, if any
+    pub fn hide_balloon(&self) {
+        use winapi::NIF_INFO;
+
+        let mut data = notify_data(self.owner, self.id);
+        data.uFlags = NIF_INFO;
+        unsafe{ self.modify(&mut data); }
+    }
+
+    /// Show or hide the icon in the notification area
+    pub fn set_visibility(&self, visible: bool) {
+        use winapi::NIF_STATE;
+        use low::defs::NIS_HIDDEN;
+
+        let mut data = notify_data(self.owner, self.id);
+        data.uFlags = NIF_STATE;
+        data.dwStateMask = NIS_HIDDEN;
+        data.dwState = if visible { 0 } else { NIS_HIDDEN };
+        unsafe{ self.modify(&mut data); }
+
+        self.visible.set(visible);
+    }
+
+    /// Return `true` if the icon is visible in the notification area
+    pub fn get_visibility(&self) -> bool {
+        self.visible.get()
+    }
+
+    /// Give the keyboard focus to the icon in the notification area
+    pub fn focus(&self) {
+        use winapi::NIM_SETFOCUS;
+        use low::defs::Shell_NotifyIconW;
+
+        let mut data = notify_data(self.owner, self.id);
+        unsafe{ Shell_NotifyIconW(NIM_SETFOCUS, &mut data); }
+    }
+
+    unsafe fn modify(&self, data: &mut NOTIFYICONDATAW) {
+        use winapi::NIM_MODIFY;
+        use low::defs::Shell_NotifyIconW;
+        Shell_NotifyIconW(NIM_MODIFY, data);
+    }
+
+    #[inline(always)]
+    fn data(&self) -> &TrayData {
+        unsafe{ &*self.data }
+    }
+}
+
+impl Control for TrayNotification {
+
+    fn handle(&self) -> AnyHandle {
+        AnyHandle::Custom(TypeId::of::<TrayNotification>(), self.id as usize)
+    }
+
+    fn control_type(&self) -> ControlType {
+        ControlType::TrayNotification
+    }
+
+    fn free(&mut self) {
+        use winapi::NIM_DELETE;
+        use low::defs::Shell_NotifyIconW;
+
+        let mut data = notify_data(self.owner, self.id);
+        unsafe{
+            Shell_NotifyIconW(NIM_DELETE, &mut data);
+            unhook_owner(self.owner, self.id);
+            drop(Box::from_raw(self.data));
+        }
+    }
+
+}
+
+/// The menu of a tray notification. Shared by the control and the subclass of the Ui hidden window.
+struct TrayData {
+    owner: HWND,
+    menu: Cell<HMENU>
+}
+
+impl TrayData {
+
+    /// Pop the menu at the cursor position. The owner must be the foreground window or the menu is not closed when the user clicks elsewhere.
+    unsafe fn pop_menu(&self) {
+        use winapi::{POINT, WM_NULL};
+        use user32::{GetCursorPos, SetForegroundWindow, PostMessageW};
+        use low::defs::{TrackPopupMenuEx, TPM_NOANIMATION, TPM_RIGHTBUTTON, TPM_LEFTALIGN, TPM_BOTTOMALIGN};
+
+        let menu = self.menu.get();
+        if menu.is_null() { return; }
+
+        let mut pos = POINT{ x: 0, y: 0 };
+        GetCursorPos(&mut pos);
+
+        SetForegroundWindow(self.owner);
+        let flags = TPM_NOANIMATION | TPM_RIGHTBUTTON | TPM_LEFTALIGN | TPM_BOTTOMALIGN;
+        TrackPopupMenuEx(menu, flags, pos.x, pos.y, self.owner, ptr::null_mut());
+        PostMessageW(self.owner, WM_NULL, 0, 0);
+    }
+
+}
+
+fn handle_of_icon<ID: Hash+Clone>(ui: &Ui<ID>, id: &ID) -> Result<HICON, Error> {
+    match ui.handle_of(id) {
+        Ok(AnyHandle::HICON(h)) => Ok(h),
+        Ok(h) => Err(Error::BadResource(format!("The icon of a tray notification must be an icon Image, got {:?}", h))),
+        Err(e) => Err(e)
+    }
+}
+
+fn handle_of_menu<ID: Hash+Clone>(ui: &Ui<ID>, id: &ID) -> Result<HMENU, Error> {
+    match ui.handle_of(id) {
+        Ok(AnyHandle::HMENU(h)) => Ok(h),
+        Ok(h) => Err(Error::BadResource(format!("The menu of a tray notification must be a ContextMenu, got {:?}", h))),
+        Err(e) => Err(e)
+    }
+}
+
+/// An empty notification data that identifies the icon `id` of `owner`
+fn notify_data(owner: HWND, id: UINT) -> NOTIFYICONDATAW {
+    let mut data: NOTIFYICONDATAW = unsafe{ mem::zeroed() };
+    data.cbSize = mem::size_of::<NOTIFYICONDATAW>() as DWORD;
+    data.hWnd = owner;
+    data.uID = id;
+    data
+}
+
+/// Copy `text` in a fixed size buffer. The text is truncated to keep the null terminator.
+fn copy_text(buffer: &mut [u16], text: &str) {
+    let text = to_utf16(text);
+    let length = ::std::cmp::min(text.len(), buffer.len()) - 1;
+    buffer[..length].copy_from_slice(&text[..length]);
+    buffer[length] = 0;
+}
+
+// The subclass id is the tray notification id, so the Ui can hold many tray notifications
+unsafe fn hook_owner(owner: HWND, id: UINT, data: *mut TrayData) {
+    use comctl32::SetWindowSubclass;
+    SetWindowSubclass(owner, Some(tray_proc), id as UINT_PTR, data as DWORD_PTR);
+}
+
+unsafe fn unhook_owner(owner: HWND, id: UINT) {
+    use comctl32::RemoveWindowSubclass;
+    RemoveWindowSubclass(owner, Some(tray_proc), id as UINT_PTR);
+}
+
+/// Pop the menu of the tray notification when the user right clicks the icon
+unsafe extern "system" fn tray_proc(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM, id: UINT_PTR, data: DWORD_PTR) -> LRESULT {
+    use comctl32::DefSubclassProc;
+    use winapi::{WM_CONTEXTMENU, LOWORD};
+    use low::defs::NWG_TRAY;
+
+    if msg == NWG_TRAY && w as UINT_PTR == id && LOWORD(l as DWORD) as UINT == WM_CONTEXTMENU {
+        let data = &*(data as *const TrayData);
+        data.pop_menu();
+    }
+
+    DefSubclassProc(hwnd, msg, w, l)
+}
@@ -2,11 +2,12 @@
     Window control definition
 */
 
-use std::any::TypeId;
+use std::any::{Any, TypeId};
 use std::hash::Hash;
+use std::cell::{Cell, RefCell};
 use std::ptr;
 
-use winapi::{HWND, UINT, WPARAM, LPARAM, LRESULT, WM_SETICON, WM_GETICON, HICON};
+use winapi::{HWND, UINT, WPARAM, LPARAM, LRESULT, WM_SETICON, WM_GETICON, HICON, MSG};
 use user32::SendMessageW;
 
 use ui::Ui;
@@ -16,6 +17,9 @@ use error::Error;
 /// System class identifier
 const WINDOW_CLASS_NAME: &'static str = "NWG_BUILTIN_WINDOW";
 
+// The buttons clicked when the user presses Enter or Esc in a window: `(window, default button, cancel button)`
+thread_local!(static DIALOG_BUTTONS: RefCell<Vec<(HWND, HWND, HWND)>> = RefCell::new(Vec::new()));
+
 /**
     A template that will create a window.
 
@@ -27,6 +31,11 @@ const WINDOW_CLASS_NAME: &'static str = "NWG_BUILTIN_WINDOW";
       • `visible` : If the user can see the window or not  
       • `disabled` : If the window is enabled or not. A disabled window do not process events  
       • `exit_on_close` : If NWG should break the event processing loop when this window is closed  
+      • `icon` : The window icon. Must be an icon `Image`  
+      • `owner` : The owner of the window. An owned window always stays above its owner and is disabled by `run_modal`  
+      • `tool_window` : If the window should have a small title bar and no taskbar button  
+      • `text_color` : The text color of the window children as `[red, green, blue]`. If None, use the system default  
+      • `background_color` : The background color of the window and of its children as `[red, green, blue]`. If None, use the system default  
 */
 #[derive(Clone)]
 pub struct WindowT<ID: Hash+Clone, S: Clone+Into<String>> {
@@ -37,14 +46,30 @@ pub struct WindowT<ID: Hash+Clone, S: Clone+Into<String>> {
     pub visible: bool,
     pub disabled: bool,
     pub exit_on_close: bool,
-    pub icon: Option<ID>
+    pub icon: Option<ID>,
+    pub owner: Option<ID>,
+    pub tool_window: bool,
+    pub text_color: Option<[u8; 3]>,
+    pub background_color: Option<[u8; 3]>
 }
 
 impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for WindowT<ID, S> {
     fn type_id(&self) -> TypeId { TypeId::of::<Window>() }
 
     fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        use low::window_helper::handle_of_window;
+        use low::color_helper::set_template_colors;
+
         unsafe{
+            // Extract the owner handle
+            let owner = match self.owner.as_ref() {
+                Some(id) => match handle_of_window(ui, id, "The owner of a window must be a window-like control.") {
+                    Ok(h) => h,
+                    Err(e) => { return Err(e); }
+                },
+                None => ptr::null_mut()
+            };
+
             // Extract the icon handle
             let icon = if let &Some(ref i) = &self.icon {
                 match ui.handle_of(i) {
@@ -58,10 +83,11 @@ impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for WindowT<ID, S> {
             
             // Build the window handle
             if let Err(e) = build_sysclass() { return Err(e); }
-            match build_window(&self) {
+            match build_window(&self, owner) {
                 Ok(h) => { 
                     SendMessageW(h, WM_SETICON, 0, icon as LPARAM);
-                    Ok( Box::new(Window{handle: h}) as Box<Control> ) 
+                    set_template_colors(h, self.text_color, self.background_color);
+                    Ok( Box::new(Window::new(h)) as Box<Control> ) 
                 },
                 Err(e) => Err(e)
             }
@@ -85,7 +111,7 @@ impl<ID: Hash+Clone> ControlT<ID> for ExternWindowT {
 
     #[allow(unused_variables)]
     fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
-        Ok( Box::new(Window{handle: self.handle}) as Box<Control> ) 
+        Ok( Box::new(Window::new(self.handle)) as Box<Control> ) 
     }
 }
 
@@ -95,9 +121,17 @@ impl<ID: Hash+Clone> ControlT<ID> for ExternWindowT {
 #[allow(dead_code)]
 pub struct Window {
     handle: HWND,
+    modal: Cell<bool>,
+    modal_owner: Cell<HWND>,
+    modal_result: RefCell<Option<Box<Any>>>
 }
 
 impl Window {
+
+    fn new(handle: HWND) -> Window {
+        Window{ handle: handle, modal: Cell::new(false), modal_owner: Cell::new(ptr::null_mut()), modal_result: RefCell::new(None) }
+    }
+
     /**
         Close the window as if the user clicked on the X button. This do **NOT** remove the window from the ui,
         it only set it hidden. In order to also destroy the window, add an unpack statement on the **Closed** event.
@@ -115,6 +149,156 @@ impl Window {
         SetForegroundWindow(self.handle); 
     } }
 
+    /**
+        Show the window and process the events until the window is closed or until `end_modal` is called.
+        While the window is running, its owner is disabled.
+
+        Do not unpack the window while it is running: the window is borrowed until `run_modal` returns.
+
+        Returns:  
+          • `Ok(Some(result))` if `end_modal` was called with a `R` value  
+          • `Ok(None)` if the window was closed by the user  
+          • `Error::BadType` if `end_modal` was called with a value that is not a `R`  
+          • `Error::UserError` if the window is already running  
+    */
+    pub fn run_modal<R: 'static>(&self) -> Result<Option<R>, Error> {
+        use user32::{GetWindow, IsWindow, IsWindowEnabled, IsWindowVisible, EnableWindow, ShowWindow, SetForegroundWindow};
+        use winapi::{GW_OWNER, SW_SHOW, SW_HIDE};
+        use low::events::dispatch_modal_events;
+
+        if self.modal.get() {
+            return Err(Error::UserError("The window is already running a modal loop.".to_string()));
+        }
+
+        let handle = self.handle;
+        unsafe {
+            // A nested dialog must not enable an owner that was disabled by another dialog
+            let owner = GetWindow(handle, GW_OWNER);
+            if !owner.is_null() && IsWindowEnabled(owner) != 0 {
+                self.modal_owner.set(owner);
+                EnableWindow(owner, 0);
+            }
+
+            *self.modal_result.borrow_mut() = None;
+            self.modal.set(true);
+
+            ShowWindow(handle, SW_SHOW);
+            SetForegroundWindow(handle);
+
+            let modal = &self.modal;
+            dispatch_modal_events(&|| modal.get() && IsWindow(handle) != 0 && IsWindowVisible(handle) != 0);
+
+            self.modal.set(false);
+            self.enable_modal_owner();
+            if IsWindow(handle) != 0 {
+                ShowWindow(handle, SW_HIDE);
+            }
+        }
+
+        match self.modal_result.borrow_mut().take() {
+            Some(result) => match result.downcast::<R>() {
+                Ok(r) => Ok(Some(*r)),
+                Err(_) => Err(Error::BadType)
+            },
+            None => Ok(None)
+        }
+    }
+
+    /**
+        Hide a window started with `run_modal`. `result` is returned by `run_modal`.
+        If the window is not running, it is only hidden.
+    */
+    pub fn end_modal<R: 'static>(&self, result: R) {
+        use user32::ShowWindow;
+        use winapi::SW_HIDE;
+
+        if self.modal.get() {
+            *self.modal_result.borrow_mut() = Some(Box::new(result));
+            self.modal.set(false);
+        }
+
+        // Enable the owner before hiding the window, so that the system activates the owner
+        unsafe {
+            self.enable_modal_owner();
+            ShowWindow(self.handle, SW_HIDE);
+        }
+    }
+
+    /// Return `true` if the window is running a modal loop started with `run_modal`
+    pub fn is_modal(&self) -> bool {
+        self.modal.get()
+    }
+
+    /// Return the identifier of the owner of the window. Return `None` if the window has no owner or if the owner is not in the ui.
+    pub fn get_owner<ID: Hash+Clone>(&self, ui: &Ui<ID>) -> Option<ID> {
+        use user32::GetWindow;
+        use winapi::GW_OWNER;
+
+        let owner = unsafe{ GetWindow(self.handle, GW_OWNER) };
+        if owner.is_null() {
+            return None;
+        }
+
+        match ui.id_from_handle(&AnyHandle::HWND(owner)) {
+            Ok(id) => Some(id),
+            Err(_) => None
+        }
+    }
+
+    /**
+        Set the button clicked when the user presses Enter in the window. The button is drawn with a bold border.
+        Pass `None` to remove the default button. Returns an error if the control is not a button of the window.
+    */
+    pub fn set_default_button<ID: Hash+Clone>(&self, ui: &Ui<ID>, button: Option<&ID>) -> Result<(), Error> {
+        let button = match self.dialog_button(ui, button) {
+            Ok(b) => b,
+            Err(e) => { return Err(e); }
+        };
+
+        let (old, _) = dialog_buttons(self.handle);
+        unsafe {
+            if !old.is_null() { set_button_default(old, false); }
+            if !button.is_null() { set_button_default(button, true); }
+        }
+
+        update_dialog_buttons(self.handle, |b| b.1 = button);
+        Ok(())
+    }
+
+    /// Return the identifier of the button clicked when the user presses Enter. Return `None` if there is no default button.
+    pub fn get_default_button<ID: Hash+Clone>(&self, ui: &Ui<ID>) -> Option<ID> {
+        let (button, _) = dialog_buttons(self.handle);
+        if button.is_null() { return None; }
+        match ui.id_from_handle(&AnyHandle::HWND(button)) {
+            Ok(id) => Some(id),
+            Err(_) => None
+        }
+    }
+
+    /**
+        Set the button clicked when the user presses Esc in the window. Pass `None` to remove the cancel button.
+        Returns an error if the control is not a button of the window.
+    */
+    pub fn set_cancel_button<ID: Hash+Clone>(&self, ui: &Ui<ID>, button: Option<&ID>) -> Result<(), Error> {
+        let button = match self.dialog_button(ui, button) {
+            Ok(b) => b,
+            Err(e) => { return Err(e); }
+        };
+
+        update_dialog_buttons(self.handle, |b| b.2 = button);
+        Ok(())
+    }
+
+    /// Return the identifier of the button clicked when the user presses Esc. Return `None` if there is no cancel button.
+    pub fn get_cancel_button<ID: Hash+Clone>(&self, ui: &Ui<ID>) -> Option<ID> {
+        let (_, button) = dialog_buttons(self.handle);
+        if button.is_null() { return None; }
+        match ui.id_from_handle(&AnyHandle::HWND(button)) {
+            Ok(id) => Some(id),
+            Err(_) => None
+        }
+    }
+
     /// Set the window icon. Pass `None` to remove the icon
     pub fn set_icon<ID: Hash+Clone>(&self, ui: &Ui<ID>, icon: Option<&ID>) -> Result<(), Error> {
         if !ui.has_handle(&self.handle()) {
@@ -161,6 +345,39 @@ impl Window {
     pub fn set_enabled(&self, e:bool) { unsafe{ ::low::window_helper::set_window_enabled(self.handle, e); } }
     pub fn update(&self) { unsafe{ ::low::window_helper::update(self.handle); } }
     pub fn focus(&self) { unsafe{ ::user32::SetFocus(self.handle); } }
+    pub fn get_text_color(&self) -> Option<[u8; 3]> { ::low::color_helper::get_text_color(self.handle) }
+    pub fn set_text_color(&self, color: Option<[u8; 3]>) { unsafe{ ::low::color_helper::set_text_color(self.handle, color); } }
+    pub fn get_background_color(&self) -> Option<[u8; 3]> { ::low::color_helper::get_background_color(self.handle) }
+    pub fn set_background_color(&self, color: Option<[u8; 3]>) { unsafe{ ::low::color_helper::set_background_color(self.handle, color); } }
+
+    /// Enable the owner disabled by `run_modal`, if any
+    unsafe fn enable_modal_owner(&self) {
+        use user32::EnableWindow;
+
+        let owner = self.modal_owner.get();
+        if !owner.is_null() {
+            EnableWindow(owner, 1);
+            self.modal_owner.set(ptr::null_mut());
+        }
+    }
+
+    /// Return the handle of a button of the window. Return a null handle if `button` is None.
+    fn dialog_button<ID: Hash+Clone>(&self, ui: &Ui<ID>, button: Option<&ID>) -> Result<HWND, Error> {
+        use user32::IsChild;
+
+        match button {
+            Some(id) => match ui.get::<::controls::Button>(id) {
+                Ok(_) => match ui.handle_of(id) {
+                    Ok(AnyHandle::HWND(h)) if unsafe{ IsChild(self.handle, h) != 0 } => Ok(h),
+                    Ok(_) => Err(Error::UserError("The default and cancel buttons must be children of the window.".to_string())),
+                    Err(e) => Err(e)
+                },
+                Err(Error::BadType) => Err(Error::UserError("The default and cancel buttons must be Button controls.".to_string())),
+                Err(e) => Err(e)
+            },
+            None => Ok(ptr::null_mut())
+        }
+    }
 }
 
 impl Control for Window {
@@ -180,9 +397,106 @@ impl Control for Window {
 
     fn free(&mut self) {
         use user32::DestroyWindow;
-        unsafe{ DestroyWindow(self.handle) };
+        use low::color_helper::clear_colors;
+        let handle = self.handle;
+        DIALOG_BUTTONS.with(|d| d.borrow_mut().retain(|b| b.0 != handle));
+        unsafe{
+            clear_colors(self.handle);
+            DestroyWindow(self.handle);
+        }
+    }
+
+}
+
+/**
+    Click the default button of a window if the user pressed Enter or its cancel button if the user pressed Esc.
+    The keys are not translated if the focused control processes them itself (ex: a multi line text box).
+    Returns `true` if the message was translated. In this case, the message must not be dispatched.
+*/
+pub unsafe fn translate_dialog_keys(msg: &MSG) -> bool {
+    use user32::{IsChild, IsWindowEnabled, SendMessageW};
+    use winapi::{WM_KEYDOWN, WM_GETDLGCODE, VK_RETURN, VK_ESCAPE};
+    use low::defs::{DLGC_WANTALLKEYS, BM_CLICK};
+
+    if msg.message != WM_KEYDOWN || (msg.wParam != VK_RETURN as WPARAM && msg.wParam != VK_ESCAPE as WPARAM) {
+        return false;
+    }
+
+    let buttons = DIALOG_BUTTONS.with(|d| d.borrow().iter().find(|b| b.0 == msg.hwnd || IsChild(b.0, msg.hwnd) != 0).cloned());
+    let (_, default, cancel) = match buttons {
+        Some(b) => b,
+        None => { return false; }
+    };
+
+    let code = SendMessageW(msg.hwnd, WM_GETDLGCODE, msg.wParam, msg as *const MSG as LPARAM);
+    if code as UINT & DLGC_WANTALLKEYS == DLGC_WANTALLKEYS {
+        return false;
+    }
+
+    let button = if msg.wParam == VK_RETURN as WPARAM { default } else { cancel };
+    if button.is_null() || IsWindowEnabled(button) == 0 {
+        return false;
     }
 
+    SendMessageW(button, BM_CLICK, 0, 0);
+    true
+}
+
+/// Return the default and the cancel buttons of a window. The handles are null if the buttons are not set.
+fn dialog_buttons(window: HWND) -> (HWND, HWND) {
+    DIALOG_BUTTONS.with(|d| match d.borrow().iter().find(|b| b.0 == window) {
+        Some(b) => (b.1, b.2),
+        None => (ptr::null_mut(), ptr::null_mut())
+    })
+}
+
+/// Remove a button from the default and the cancel buttons of its window. Must be called when a button is freed.
+pub fn clear_dialog_button(button: HWND) {
+    DIALOG_BUTTONS.with(|d| {
+        let mut buttons = d.borrow_mut();
+        for b in buttons.iter_mut() {
+            if b.1 == button { b.1 = ptr::null_mut(); }
+            if b.2 == button { b.2 = ptr::null_mut(); }
+        }
+        buttons.retain(|b| !b.1.is_null() || !b.2.is_null());
+    });
+}
+
+fn update_dialog_buttons<F: FnOnce(&mut (HWND, HWND, HWND))>(window: HWND, update: F) {
+    DIALOG_BUTTONS.with(|d| {
+        let mut buttons = d.borrow_mut();
+        if !buttons.iter().any(|b| b.0 == window) {
+            buttons.push((window, ptr::null_mut(), ptr::null_mut()));
+        }
+
+        let index = buttons.iter().position(|b| b.0 == window).unwrap();
+        update(&mut buttons[index]);
+        if buttons[index].1.is_null() && buttons[index].2.is_null() {
+            buttons.remove(index);
+        }
+    });
+}
+
+/**
+    Change the type of a button to its default variant (ex: from `BS_PUSHBUTTON` to `BS_DEFPUSHBUTTON`) or back and keep its other styles.
+    Owner drawn buttons keep their type.
+*/
+unsafe fn set_button_default(button: HWND, default: bool) {
+    use low::window_helper::get_window_long;
+    use winapi::{GWL_STYLE, BS_TYPEMASK, BS_PUSHBUTTON, BS_DEFPUSHBUTTON, BS_SPLITBUTTON, BS_DEFSPLITBUTTON,
+      BS_COMMANDLINK, BS_DEFCOMMANDLINK};
+    use low::defs::BM_SETSTYLE;
+
+    let style = get_window_long(button, GWL_STYLE) as ::winapi::DWORD;
+    let (normal, def) = match style & BS_TYPEMASK {
+        BS_PUSHBUTTON | BS_DEFPUSHBUTTON => (BS_PUSHBUTTON, BS_DEFPUSHBUTTON),
+        BS_SPLITBUTTON | BS_DEFSPLITBUTTON => (BS_SPLITBUTTON, BS_DEFSPLITBUTTON),
+        BS_COMMANDLINK | BS_DEFCOMMANDLINK => (BS_COMMANDLINK, BS_DEFCOMMANDLINK),
+        _ => { return; }
+    };
+
+    let button_type = if default { def } else { normal };
+    SendMessageW(button, BM_SETSTYLE, ((style & !BS_TYPEMASK) | button_type) as WPARAM, 1);
 }
 
 
@@ -242,10 +556,10 @@ unsafe fn build_sysclass() -> Result<(), Error> {
 }
 
 #[inline(always)]
-unsafe fn build_window<ID: Hash+Clone, S: Clone+Into<String>>(t: &WindowT<ID, S>) -> Result<HWND, Error> {
+unsafe fn build_window<ID: Hash+Clone, S: Clone+Into<String>>(t: &WindowT<ID, S>, owner: HWND) -> Result<HWND, Error> {
     use low::window_helper::{WindowParams, build_window, set_window_long};
     use winapi::{DWORD, WS_VISIBLE, WS_DISABLED, WS_OVERLAPPEDWINDOW, WS_CAPTION, WS_OVERLAPPED, WS_MINIMIZEBOX,
-      WS_MAXIMIZEBOX, WS_SYSMENU, GWL_USERDATA, WS_CLIPCHILDREN};
+      WS_MAXIMIZEBOX, WS_SYSMENU, GWL_USERDATA, WS_CLIPCHILDREN, WS_EX_TOOLWINDOW};
 
     let fixed_window: DWORD = WS_CLIPCHILDREN | WS_SYSMENU | WS_CAPTION | WS_OVERLAPPED | WS_MINIMIZEBOX | WS_MAXIMIZEBOX;
     let flags: DWORD = 
@@ -259,8 +573,8 @@ unsafe fn build_window<ID: Hash+Clone, S: Clone+Into<String>>(t: &WindowT<ID, S>
         position: t.position.clone(),
         size: t.size.clone(),
         flags: flags,
-        ex_flags: None,
-        parent: ::std::ptr::null_mut()
+        ex_flags: if t.tool_window { Some(WS_EX_TOOLWINDOW) } else { None },
+        parent: owner
     };
 
     match build_window(params) {
@@ -4,7 +4,7 @@
 
 use winapi::{c_int, DWORD, CW_USEDEFAULT};
 
-#[cfg(feature = "canvas")]
+#[cfg(all(windows, feature = "canvas"))]
 pub mod canvas {
     /*!
         Constants and types for the resources linked to a Canvas control
@@ -26,12 +26,23 @@ pub enum CheckState {
 /**
     Mouse buttons
 */
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum MouseButton {
     Left,
     Right,
     Middle
 }
 
+/**
+    State of the modifier keys when a keyboard event was received
+*/
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct KeyModifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool
+}
+
 /**
     Text align constant on the horizontal axis
 */
@@ -152,6 +163,42 @@ pub enum ProgressBarState {
     Error,
 }
 
+/**
+    The style of a button
+
+    Members:  
+    * `Push`: A standard push button  
+    * `Split`: A push button with a drop down arrow. Clicking the arrow sends `button::DropDown`  
+    * `CommandLink`: A large button with an arrow, a title and a note under the title (see `Button.set_note`)  
+    * `Flat`: A push button without the 3D border. When visual styles are enabled, this style has no visual effect  
+    * `OwnerDraw`: A button painted by the application in the `button::Draw` event  
+*/
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ButtonStyle {
+    Push,
+    Split,
+    CommandLink,
+    Flat,
+    OwnerDraw
+}
+
+/**
+    The state of an owner drawn button when it must be painted. Sent by `button::Draw`
+
+    Members:  
+    * `hot`: If the mouse is over the button  
+    * `pressed`: If the button is pushed  
+    * `focused`: If the button has the keyboard focus  
+    * `disabled`: If the button is disabled  
+*/
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct ButtonState {
+    pub hot: bool,
+    pub pressed: bool,
+    pub focused: bool,
+    pub disabled: bool
+}
+
 /**
     Define a type of image to use when importing an image resource
 */
@@ -15,6 +15,8 @@ pub enum SystemError {
     FontCreation,
     ImageCreation,
     TreeItemCreation,
+    AcceleratorCreation,
+    TaskDialogCreation,
     ComInstanceCreation(String),
     ComError(String),
     SystemMessageFailed(String)
@@ -22,9 +24,6 @@ pub enum SystemError {
 
 impl SystemError {
     fn translate(&self) -> String {
-        use low::other_helper::get_system_error;
-
-        let (code, code_txt) = unsafe{ get_system_error() };
         let tr = match self {
             &SystemError::SystemClassCreation => format!("Failed to create a system class for a control"),
             &SystemError::WindowCreationFail => format!("Failed to create a system window for a control"),
@@ -32,13 +31,28 @@ impl SystemError {
             &SystemError::FontCreation => format!("Failed to create a system font"),
             &SystemError::ImageCreation => format!("Failed to create a system image"),
             &SystemError::TreeItemCreation => format!("Failed to create a tree view item"),
+            &SystemError::AcceleratorCreation => format!("Failed to create an accelerator table"),
+            &SystemError::TaskDialogCreation => format!("Failed to display a task dialog"),
             &SystemError::ComInstanceCreation(ref name) => format!("Failed to create a COM instance for {}", name),
             &SystemError::ComError(ref details) => format!("An error ocurred while executing a COM method, {}", details),
             &SystemError::SystemMessageFailed(ref details) => format!("An error ocurred while executing a system message: {}", details)
         };
 
+        SystemError::with_system_error(tr)
+    }
+
+    #[cfg(windows)]
+    fn with_system_error(tr: String) -> String {
+        use low::other_helper::get_system_error;
+
+        let (code, code_txt) = unsafe{ get_system_error() };
         format!("{}.\nID {:?} - {}", tr, code, code_txt)
     }
+
+    #[cfg(not(windows))]
+    fn with_system_error(tr: String) -> String {
+        format!("{}.", tr)
+    }
 }
 
 impl fmt::Debug for SystemError {
@@ -58,6 +72,11 @@ pub enum Error {
     BadUi(String),
     BadParent(String),
     BadResource(String),
+    BadShortcut(String),
+    BadBinding(String),
+    BadPattern(String),
+    BadRtf(String),
+    BadFilter(String),
     BorrowError,
     ControlRequired,
     ControlOrResourceRequired,
@@ -78,6 +97,11 @@ impl Error {
             &Error::BadType => format!("The key exists in the Ui, but the type requested did not match the type of the underlying object"),
             &Error::BadParent(ref r) => format!("Could not make sense of the requested parent: {}", r),
             &Error::BadResource(ref r) => format!("Could not make sense of the requested resource: {}", r),
+            &Error::BadShortcut(ref r) => format!("Could not parse the shortcut: {}", r),
+            &Error::BadBinding(ref r) => format!("Could not bind the value: {}", r),
+            &Error::BadPattern(ref r) => format!("Could not parse the pattern: {}", r),
+            &Error::BadRtf(ref r) => format!("Could not read the RTF document: {}", r),
+            &Error::BadFilter(ref r) => format!("Could not parse the file filters: {}", r),
             &Error::BorrowError => format!("The Ui element was already borrowed"),
             &Error::ControlRequired => format!("The key passed to the command must identify a control"),
             &Error::ControlOrResourceRequired => format!("The key passed to the command must identify a control or a resource", ),
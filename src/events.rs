@@ -5,30 +5,40 @@
 use std::time::Duration;
 
 use ui::Ui;
-use defs::MouseButton;
+use defs::{MouseButton, KeyModifiers, PickerDate, ButtonState};
+use controls::AnyHandle;
+use shortcut::Shortcut;
 
-use winapi::{WPARAM, LPARAM};
+use winapi::{WPARAM, LPARAM, LRESULT, HDC};
 
 // System events that can be applied to any HWND based control
-pub use low::events::{Event, Destroyed, Paint, Closed, Moved, KeyDown, KeyUp, Resized, Char, MouseUp, MouseDown, MouseMove};
+pub use low::events::{Event, Destroyed, Paint, Closed, Moved, KeyDown, KeyUp, Resized, Char, MouseUp, MouseDown, MouseMove, MouseWheel};
 
 // Control specfic events
-pub mod button { pub use low::events::{BtnClick as Click, BtnDoubleClick as DoubleClick, BtnFocus as Focus}; }
+pub mod button { pub use low::events::{BtnClick as Click, BtnDoubleClick as DoubleClick, BtnFocus as Focus, BtnDropDown as DropDown, BtnDraw as Draw}; }
 pub use self::button as checkbox; // Checkboxes use the same events of the buttons
 pub use self::button as radiobutton; // Radiobuttons use the same events of the buttons
 pub mod combobox { pub use low::events::{CbnFocus as Focus, CbnSelectionChanged as SelectionChanged}; }
-pub mod label { pub use low::events::{StnClick as Click, StnDoubleClick as DoubleClick}; }
+pub mod label { pub use low::events::{StnClick as Click, StnDoubleClick as DoubleClick, StnDraw as Draw}; }
 pub use self::label as image_frame;
 pub mod datepicker { pub use low::events::DateChanged; }
 pub mod listbox { pub use low::events::{LbnSelectionChanged as SelectionChanged, LbnDoubleClick as DoubleClick, LbnFocus as Focus}; }
 pub mod textbox { pub use low::events::{EnFocus as Focus, EnLimit as Limit, EnValueChanged as ValueChanged}; }
 pub use self::textbox as textinput; // Textinput use the same events of the textbox
-pub mod menu { pub use low::events::MenuTrigger as Triggered; }
-pub mod timer { pub use low::events::TimerTick as Tick; }
+pub use self::textbox as rich_textbox;
+pub mod numeric_input { pub use low::events::{EnFocus as Focus, NumericValueChanged as ValueChanged}; }
+pub mod slider { pub use low::events::SliderValueChanged as ValueChanged; }
+pub mod toolbar { pub use low::events::ToolbarClick as Click; }
+#[cfg(windows)] pub mod menu { pub use low::events::MenuTrigger as Triggered; }
+#[cfg(windows)] pub mod timer { pub use low::events::TimerTick as Tick; }
+#[cfg(windows)] pub mod file_dialog { pub use low::events::{FileDialogFolderChanged as FolderChanged, FileDialogSelectionChanged as SelectionChanged}; }
+#[cfg(windows)] pub mod tray { pub use low::events::{TrayClick as Click, TrayDoubleClick as DoubleClick, TrayBalloonClick as BalloonClick}; }
+#[cfg(windows)] pub mod accelerator { pub use low::events::AcceleratorTriggered as Triggered; }
 pub mod treeview { pub use low::events::{TreeViewSelectionChanged as SelectionChanged, TreeViewClick as Click, TreeViewDoubleClick as DoubleClick,
  TreeViewFocus as Focus, TreeViewDeleteItem as DeleteItem, TreeViewItemChanged as ItemChanged, TreeViewItemChanging as ItemChanging,
  TreeViewItemExpanded as ItemExpanded, TreeViewItemExpanding as ItemExpanding}; }
-pub mod list_view{pub use low::events::ListViewCustomDraw;}
+pub mod list_view { pub use low::events::{ListViewCustomDraw, ListViewItemActivated as ItemActivated, ListViewColumnClick as ColumnClick,
+ ListViewSelectionChanged as SelectionChanged}; }
 
 pub use self::Event::Any as Any;
 
@@ -40,20 +50,297 @@ Arguments:
   • 2: A reference to the ID of the control  
   • 3: A reference to the event type that was called  
   • 4: A reference to the arguments passed with the controls  
+
+Returns: An `EventOutcome`. See `Ui.bind`
+*/
+pub type EventCallback<ID> = Fn(&Ui<ID>, &ID, &Event, &EventArgs) -> EventOutcome;
+
+/**
+    The value returned by an event callback. Callbacks that return `()` always continue.
+
+    Members:  
+    • `Continue`: Call the next callbacks and let the system process the message  
+    • `Stop`: Do not call the next callbacks of the event. If the event is vetoable (see `Event.vetoable`), the system action is also cancelled  
+    • `Return`: Do not call the next callbacks and return the value to the system. The default processing of the message is skipped  
 */
-pub type EventCallback<ID> = Fn(&Ui<ID>, &ID, &Event, &EventArgs) -> ();
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum EventOutcome {
+    Continue,
+    Stop,
+    Return(LRESULT)
+}
+
+impl From<()> for EventOutcome {
+    fn from(_: ()) -> EventOutcome { EventOutcome::Continue }
+}
 
 /**
     Events arguments definition. If an event do not have arguments, EventArgs::None is passed.
+
+    Members:  
+    • `KeyPress`: The virtual key code and the state of the modifier keys. Sent by `KeyDown` and `KeyUp`  
+    • `Key`: Deprecated, use `KeyPress`. A virtual key code. Not sent by the builtin events, kept so that older code still compiles  
+    • `Char`: A character typed by the user. Sent by `Char`  
+    • `MouseClick`: The button and the position of the mouse in the control client area. Sent by `MouseDown` and `MouseUp`  
+    • `MouseWheel`: The distance the wheel was rotated (a multiple of 120) and the position of the mouse in the screen. Sent by `MouseWheel`  
+    • `Focus`: `true` if the control gained the focus, `false` if it lost it  
+    • `Tick`: The time elapsed since the timer was started  
+    • `Position`: A position. Sent by `Moved` and `MouseMove`  
+    • `Size`: The new client size of a window. Sent by `Resized`  
+    • `SelectedIndex`: The selected index of a list, `None` if there is no selection. Sent by the `SelectionChanged` events of `ListBox` and `ComboBox`  
+    • `Date`: The new value of a date picker. Sent by `DateChanged`  
+    • `TreeItem`: The handle of a tree view item. Use `ui.id_from_handle` to get its ID. Sent by `DeleteItem`, `ItemChanged` and `ItemChanging`.
+      If the system does not give the deleted item, `DeleteItem` sends `None`  
+    • `TreeItemExpand`: The handle of a tree view item and `true` if it is expanded, `false` if it is collapsed. Sent by `ItemExpanded` and `ItemExpanding`  
+    • `TreeSelection`: The handles of the previous and of the new selected tree view items. Sent by the treeview `SelectionChanged`  
+    • `Shortcut`: The shortcut pressed by the user. Sent by `accelerator::Triggered`  
+    • `ListItem`: The index of a list view item and the index of the clicked subitem. Sent by the list view `ItemActivated`  
+    • `ListSelection`: The index of a list view item and its new selection state. If `item` is `None`, several items changed. Sent by the list view `SelectionChanged`  
+    • `Column`: The index of a list view column. Sent by `ColumnClick`  
+    • `Number`: The new value of a numeric input. Sent by the numeric input `ValueChanged`  
+    • `SliderPosition`: The new position of a slider. Sent by the slider `ValueChanged`  
+    • `ToolbarButton`: The id of the clicked toolbar button. Sent by the toolbar `Click`  
+    • `ButtonDraw`: The state of an owner drawn button or label, the device context to paint on and the control rectangle `(left, top, right, bottom)`. Sent by `button::Draw` and `label::Draw`  
+    • `CustomDraw`: The draw stage, the item index and the subitem index of a custom draw notification. `raw` holds the message parameters  
+    • `Raw`: The raw message parameters  
 */
 pub enum EventArgs {
+    KeyPress{key: u32, modifiers: KeyModifiers},
+    #[deprecated(note="Use `EventArgs::KeyPress` and `EventArgs.key()`")]
     Key(u32),
     Char(char),
     MouseClick{btn: MouseButton, pos: (i32, i32)},
+    MouseWheel{delta: i32, horizontal: bool, pos: (i32, i32)},
     Focus(bool),
     Tick(Duration),
     Position(i32, i32),
     Size(u32, u32),
+    SelectedIndex(Option<usize>),
+    Date(Option<PickerDate>),
+    TreeItem(AnyHandle),
+    TreeItemExpand{item: AnyHandle, expanded: bool},
+    TreeSelection{old: Option<AnyHandle>, new: Option<AnyHandle>},
+    Shortcut(Shortcut),
+    ListItem{item: usize, sub_item: usize},
+    ListSelection{item: Option<usize>, selected: bool},
+    Column(usize),
+    Number(f64),
+    SliderPosition(i32),
+    ToolbarButton(u16),
+    ButtonDraw{state: ButtonState, dc: HDC, rect: (i32, i32, i32, i32)},
+    CustomDraw{stage: u32, item: usize, sub_item: i32, raw: (u32, WPARAM, LPARAM)},
     Raw(u32, WPARAM, LPARAM), // MSG, WPARAM, LPARAM
     None
 }
+
+impl EventArgs {
+
+    /**
+        Return the virtual key code of a keyboard event.
+    */
+    #[allow(deprecated)]
+    pub fn key(&self) -> Option<u32> {
+        match self {
+            &EventArgs::KeyPress{key, ..} | &EventArgs::Key(key) => Some(key),
+            _ => None
+        }
+    }
+
+    /**
+        Return `true` if the arguments hold a system handle (a tree item, a device context or raw message parameters).
+        Those arguments are tied to the ui thread and cannot be sent by a `UiProxy`.
+    */
+    pub fn has_handles(&self) -> bool {
+        match self {
+            &EventArgs::TreeItem(_) | &EventArgs::TreeItemExpand{..} | &EventArgs::TreeSelection{..} |
+            &EventArgs::ButtonDraw{..} | &EventArgs::CustomDraw{..} | &EventArgs::Raw(..) => true,
+            _ => false
+        }
+    }
+
+    /**
+        Return the state of the modifier keys of a keyboard event.
+    */
+    pub fn modifiers(&self) -> Option<KeyModifiers> {
+        match self {
+            &EventArgs::KeyPress{modifiers, ..} => Some(modifiers),
+            _ => None
+        }
+    }
+
+    /**
+        Return the character of a `Char` event.
+    */
+    pub fn char(&self) -> Option<char> {
+        match self {
+            &EventArgs::Char(c) => Some(c),
+            _ => None
+        }
+    }
+
+    /**
+        Return the mouse button of a mouse click event.
+    */
+    pub fn mouse_button(&self) -> Option<MouseButton> {
+        match self {
+            &EventArgs::MouseClick{btn, ..} => Some(btn),
+            _ => None
+        }
+    }
+
+    /**
+        Return the wheel delta of a `MouseWheel` event.
+    */
+    pub fn wheel_delta(&self) -> Option<i32> {
+        match self {
+            &EventArgs::MouseWheel{delta, ..} => Some(delta),
+            _ => None
+        }
+    }
+
+    /**
+        Return the focus state of a focus event.
+    */
+    pub fn focus(&self) -> Option<bool> {
+        match self {
+            &EventArgs::Focus(f) => Some(f),
+            _ => None
+        }
+    }
+
+    /**
+        Return the position carried by the event. Works with `Position`, `MouseClick` and `MouseWheel`.
+    */
+    pub fn position(&self) -> Option<(i32, i32)> {
+        match self {
+            &EventArgs::Position(x, y) => Some((x, y)),
+            &EventArgs::MouseClick{pos, ..} | &EventArgs::MouseWheel{pos, ..} => Some(pos),
+            _ => None
+        }
+    }
+
+    /**
+        Return the size of a `Resized` event.
+    */
+    pub fn size(&self) -> Option<(u32, u32)> {
+        match self {
+            &EventArgs::Size(w, h) => Some((w, h)),
+            _ => None
+        }
+    }
+
+    /**
+        Return the selected index of a selection event. 
+        Returns `None` if the event is not a selection event or if nothing is selected.
+    */
+    pub fn selected_index(&self) -> Option<usize> {
+        match self {
+            &EventArgs::SelectedIndex(i) => i,
+            _ => None
+        }
+    }
+
+    /**
+        Return the date of a `DateChanged` event. Returns `None` if the date picker has no value.
+    */
+    pub fn date(&self) -> Option<PickerDate> {
+        match self {
+            &EventArgs::Date(ref d) => d.clone(),
+            _ => None
+        }
+    }
+
+    /**
+        Return the handle of the tree view item targeted by the event.
+        For a selection change, the new selected item is returned.
+    */
+    pub fn tree_item(&self) -> Option<AnyHandle> {
+        match self {
+            &EventArgs::TreeItem(ref h) | &EventArgs::TreeItemExpand{item: ref h, ..} => Some(h.clone()),
+            &EventArgs::TreeSelection{ref new, ..} => new.clone(),
+            _ => None
+        }
+    }
+
+    /**
+        Return the shortcut of an `accelerator::Triggered` event.
+    */
+    pub fn shortcut(&self) -> Option<Shortcut> {
+        match self {
+            &EventArgs::Shortcut(s) => Some(s),
+            _ => None
+        }
+    }
+
+    /**
+        Return the index of the list view item targeted by the event.
+    */
+    pub fn list_item(&self) -> Option<usize> {
+        match self {
+            &EventArgs::ListItem{item, ..} => Some(item),
+            &EventArgs::ListSelection{item, ..} => item,
+            _ => None
+        }
+    }
+
+    /**
+        Return the index of the list view column of a `ColumnClick` event.
+    */
+    pub fn column(&self) -> Option<usize> {
+        match self {
+            &EventArgs::Column(c) => Some(c),
+            _ => None
+        }
+    }
+
+    /**
+        Return the new value of a `numeric_input::ValueChanged` event.
+    */
+    pub fn number(&self) -> Option<f64> {
+        match self {
+            &EventArgs::Number(n) => Some(n),
+            _ => None
+        }
+    }
+
+    /**
+        Return the new position of a `slider::ValueChanged` event.
+    */
+    pub fn slider_position(&self) -> Option<i32> {
+        match self {
+            &EventArgs::SliderPosition(p) => Some(p),
+            _ => None
+        }
+    }
+
+    /**
+        Return the id of the button clicked in a `toolbar::Click` event.
+    */
+    pub fn toolbar_button(&self) -> Option<u16> {
+        match self {
+            &EventArgs::ToolbarButton(id) => Some(id),
+            _ => None
+        }
+    }
+
+    /**
+        Return the state of the control painted in a `button::Draw` or a `label::Draw` event.
+    */
+    pub fn button_state(&self) -> Option<ButtonState> {
+        match self {
+            &EventArgs::ButtonDraw{state, ..} => Some(state),
+            _ => None
+        }
+    }
+
+    /**
+        Return the raw message parameters (msg, wparam, lparam). Works with `Raw` and `CustomDraw`.
+    */
+    pub fn raw(&self) -> Option<(u32, WPARAM, LPARAM)> {
+        match self {
+            &EventArgs::Raw(msg, w, l) | &EventArgs::CustomDraw{raw: (msg, w, l), ..} => Some((msg, w, l)),
+            _ => None
+        }
+    }
+
+}
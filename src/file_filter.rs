@@ -0,0 +1,180 @@
+/*!
+    File type filters used by the file dialogs.
+
+    The parser in this module is pure rust. Filters are written as a list of `Name(patterns)` separated by `|`,
+    where the patterns are separated by `;`. Ex: "Text files(*.txt;*.md)|Any(*.*)"
+*/
+
+use std::fmt;
+use std::str::FromStr;
+
+use error::Error;
+
+/**
+    A file type filter displayed in a file dialog
+
+    Members:
+    • `name`: The name displayed to the user. Ex: "Text files"
+    • `patterns`: The patterns of the files accepted by the filter. Ex: `["*.txt", "*.md"]`
+*/
+#[derive(Clone, PartialEq, Debug)]
+pub struct FileFilter {
+    pub name: String,
+    pub patterns: Vec<String>
+}
+
+impl FileFilter {
+
+    /// Create a new filter
+    pub fn new<S: Into<String>>(name: S, patterns: &[&str]) -> FileFilter {
+        FileFilter {
+            name: name.into(),
+            patterns: patterns.iter().map(|p| p.to_string()).collect()
+        }
+    }
+
+    /// Return the patterns of the filter in the format used by the system. Ex: "*.txt;*.md"
+    pub fn spec(&self) -> String {
+        self.patterns.join(";")
+    }
+
+    /**
+        Check the filter. Returns `Err(Error::BadFilter)` if the name is empty or contains `|`,
+        if there are no patterns or if a pattern is empty or contains `|`, `;`, `(` or `)`.
+    */
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.name.trim().is_empty() {
+            return Err(Error::BadFilter(format!("Filter {:?} has no name", self.to_string())));
+        }
+
+        if self.name.contains('|') {
+            return Err(Error::BadFilter(format!("The name of filter {:?} contains '|'", self.name)));
+        }
+
+        if self.patterns.is_empty() {
+            return Err(Error::BadFilter(format!("Filter {:?} has no patterns", self.name)));
+        }
+
+        for pattern in self.patterns.iter() {
+            if pattern.trim().is_empty() {
+                return Err(Error::BadFilter(format!("Filter {:?} has an empty pattern", self.name)));
+            }
+
+            if pattern.contains(|c| c == '|' || c == ';' || c == '(' || c == ')') {
+                return Err(Error::BadFilter(format!("Pattern {:?} of filter {:?} contains a reserved character", pattern, self.name)));
+            }
+        }
+
+        Ok(())
+    }
+
+}
+
+impl fmt::Display for FileFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}({})", self.name, self.spec())
+    }
+}
+
+/**
+    A list of file type filters. The first filter is selected when the dialog is opened.
+
+    Use `FileFilters::new().add(name, patterns)` to build the list or `FileFilters::parse` to read it from a string.
+*/
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct FileFilters {
+    filters: Vec<FileFilter>
+}
+
+impl FileFilters {
+
+    /// Create an empty list of filters
+    pub fn new() -> FileFilters {
+        FileFilters{ filters: Vec::new() }
+    }
+
+    /// Add a filter at the end of the list
+    pub fn add<S: Into<String>>(mut self, name: S, patterns: &[&str]) -> FileFilters {
+        self.filters.push(FileFilter::new(name, patterns));
+        self
+    }
+
+    /**
+        Parse a filters string. Ex: "Text files(*.txt;*.md)|Any(*.*)". Spaces around the names and the patterns are ignored.
+
+        Returns `Err(Error::BadFilter)` if the string is empty, if a filter do not have the `Name(patterns)` format
+        or if a filter is not valid (see `FileFilter.validate`).
+    */
+    pub fn parse(text: &str) -> Result<FileFilters, Error> {
+        let mut filters = FileFilters::new();
+
+        for part in text.split('|') {
+            let part = part.trim();
+            let open = match part.rfind('(') {
+                Some(i) if part.ends_with(')') => i,
+                _ => { return Err(Error::BadFilter(format!("Filter {:?} in {:?} is not in the \"Name(patterns)\" format", part, text))); }
+            };
+
+            let filter = FileFilter {
+                name: part[..open].trim().to_string(),
+                patterns: part[open+1..part.len()-1].split(';').map(|p| p.trim().to_string()).collect()
+            };
+
+            match filter.validate() {
+                Ok(_) => filters.filters.push(filter),
+                Err(e) => { return Err(e); }
+            }
+        }
+
+        Ok(filters)
+    }
+
+    /**
+        Check every filter of the list. Returns `Err(Error::BadFilter)` if the list is empty or if a filter is not valid.
+    */
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.filters.is_empty() {
+            return Err(Error::BadFilter("The filter list is empty".to_string()));
+        }
+
+        for filter in self.filters.iter() {
+            match filter.validate() {
+                Ok(_) => {},
+                Err(e) => { return Err(e); }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Return the filters of the list
+    pub fn filters(&self) -> &[FileFilter] {
+        &self.filters
+    }
+
+    /// Return the number of filters in the list
+    pub fn len(&self) -> usize {
+        self.filters.len()
+    }
+
+    /// Return `true` if the list has no filters
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+
+}
+
+impl fmt::Display for FileFilters {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let filters: Vec<String> = self.filters.iter().map(|f| f.to_string()).collect();
+        write!(f, "{}", filters.join("|"))
+    }
+}
+
+impl FromStr for FileFilters {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<FileFilters, Error> {
+        FileFilters::parse(s)
+    }
+}
@@ -0,0 +1,625 @@
+/*!
+    Layout system that manage the geometry of the controls packed in a Ui.
+
+    The solver in this module is pure rust: a layout only compute the positions and the sizes
+    of its items from a parent area. Applying the result to the controls is done by the Ui
+    (see `Ui::set_layout`).
+*/
+
+use std::u32;
+use std::cmp;
+
+/**
+    Space reserved around the items of a layout
+
+    Members:
+    • `left`, `top`, `right`, `bottom`: The margin on each side, in pixels
+*/
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Margin {
+    pub left: u32,
+    pub top: u32,
+    pub right: u32,
+    pub bottom: u32
+}
+
+impl Margin {
+
+    /// Create a margin with the same value on each side
+    pub fn all(v: u32) -> Margin {
+        Margin{ left: v, top: v, right: v, bottom: v }
+    }
+
+    /// Create a margin from a horizontal and a vertical value
+    pub fn symmetric(h: u32, v: u32) -> Margin {
+        Margin{ left: h, top: v, right: h, bottom: v }
+    }
+
+}
+
+/**
+    Direction of a box layout
+*/
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Orientation {
+    Horizontal,
+    Vertical
+}
+
+/**
+    Side of the remaining area taken by an item in a dock layout
+*/
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DockSide {
+    Top,
+    Bottom,
+    Left,
+    Right,
+    Fill
+}
+
+/**
+    Content of a layout item
+*/
+#[derive(Clone, Debug)]
+pub enum LayoutContent<ID: Clone> {
+    /// A control packed in the Ui
+    Control(ID),
+
+    /// A nested layout
+    Layout(Box<Layout<ID>>),
+
+    /// An empty space
+    Spacer
+}
+
+/**
+    An item managed by a layout
+
+    Members:
+    • `content`: What the item holds
+    • `min_size`: The minimum size of the item. Items with a stretch factor of 0 are kept at their minimum size along the layout direction
+    • `max_size`: The maximum size of the item
+    • `stretch`: Share of the free space the item receives. Free space is divided between the items proportionally to their stretch factor.
+*/
+#[derive(Clone, Debug)]
+pub struct LayoutItem<ID: Clone> {
+    pub content: LayoutContent<ID>,
+    pub min_size: (u32, u32),
+    pub max_size: (u32, u32),
+    pub stretch: u32
+}
+
+impl<ID: Clone> LayoutItem<ID> {
+
+    /// Create an item that manage a control. The item stretch factor is set to 1.
+    pub fn control(id: ID) -> LayoutItem<ID> {
+        LayoutItem{ content: LayoutContent::Control(id), min_size: (0, 0), max_size: (u32::MAX, u32::MAX), stretch: 1 }
+    }
+
+    /// Create an item that holds a nested layout. The item minimum size is the minimum size of the layout.
+    pub fn layout<L: Into<Layout<ID>>>(layout: L) -> LayoutItem<ID> {
+        let layout = layout.into();
+        let min_size = layout.min_size();
+        LayoutItem{ content: LayoutContent::Layout(Box::new(layout)), min_size: min_size, max_size: (u32::MAX, u32::MAX), stretch: 1 }
+    }
+
+    /// Create an empty item that takes `size` pixels. The item stretch factor is set to 0.
+    pub fn spacer(size: (u32, u32)) -> LayoutItem<ID> {
+        LayoutItem{ content: LayoutContent::Spacer, min_size: size, max_size: (u32::MAX, u32::MAX), stretch: 0 }
+    }
+
+    /// Set the minimum size of the item
+    pub fn min_size(mut self, w: u32, h: u32) -> LayoutItem<ID> {
+        self.min_size = (w, h);
+        self
+    }
+
+    /// Set the maximum size of the item
+    pub fn max_size(mut self, w: u32, h: u32) -> LayoutItem<ID> {
+        self.max_size = (w, h);
+        self
+    }
+
+    /// Set the minimum and the maximum size of the item to `(w, h)` and its stretch factor to 0
+    pub fn fixed_size(mut self, w: u32, h: u32) -> LayoutItem<ID> {
+        self.min_size = (w, h);
+        self.max_size = (w, h);
+        self.stretch = 0;
+        self
+    }
+
+    /// Set the stretch factor of the item
+    pub fn stretch(mut self, stretch: u32) -> LayoutItem<ID> {
+        self.stretch = stretch;
+        self
+    }
+
+    /// Compute the geometry of the item in the area `(pos, size)` and push the result in `out`
+    fn solve(&self, pos: (i32, i32), size: (u32, u32), out: &mut Vec<LayoutResult<ID>>) {
+        let size = (clamp(size.0, self.min_size.0, self.max_size.0), clamp(size.1, self.min_size.1, self.max_size.1));
+        match &self.content {
+            &LayoutContent::Control(ref id) => out.push((id.clone(), pos, size)),
+            &LayoutContent::Layout(ref layout) => layout.solve_into(pos, size, out),
+            &LayoutContent::Spacer => {}
+        }
+    }
+}
+
+/**
+    A layout that puts its items in a single row or a single column.
+
+    Members:
+    • `orientation`: The direction of the layout
+    • `margin`: Space reserved around the items
+    • `spacing`: Space between two items
+    • `items`: The items of the layout
+*/
+#[derive(Clone, Debug)]
+pub struct BoxLayout<ID: Clone> {
+    pub orientation: Orientation,
+    pub margin: Margin,
+    pub spacing: u32,
+    pub items: Vec<LayoutItem<ID>>
+}
+
+impl<ID: Clone> BoxLayout<ID> {
+
+    /// Create an empty horizontal box layout
+    pub fn horizontal() -> BoxLayout<ID> {
+        BoxLayout{ orientation: Orientation::Horizontal, margin: Margin::default(), spacing: 0, items: Vec::new() }
+    }
+
+    /// Create an empty vertical box layout
+    pub fn vertical() -> BoxLayout<ID> {
+        BoxLayout{ orientation: Orientation::Vertical, margin: Margin::default(), spacing: 0, items: Vec::new() }
+    }
+
+    /// Set the margin of the layout
+    pub fn margin(mut self, margin: Margin) -> BoxLayout<ID> {
+        self.margin = margin;
+        self
+    }
+
+    /// Set the spacing of the layout
+    pub fn spacing(mut self, spacing: u32) -> BoxLayout<ID> {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Add an item at the end of the layout
+    pub fn add(mut self, item: LayoutItem<ID>) -> BoxLayout<ID> {
+        self.items.push(item);
+        self
+    }
+
+    fn min_size(&self) -> (u32, u32) {
+        let spacing = total_spacing(self.spacing, self.items.len());
+        let (main, cross) = self.items.iter().fold((spacing, 0u32), |(main, cross), item| {
+            let (m, c) = self.split(item.min_size);
+            (main.saturating_add(m), cmp::max(cross, c))
+        });
+
+        add_margin(self.join(main, cross), &self.margin)
+    }
+
+    fn solve_into(&self, pos: (i32, i32), size: (u32, u32), out: &mut Vec<LayoutResult<ID>>) {
+        let (pos, size) = remove_margin(pos, size, &self.margin);
+        let (main_pos, cross_pos) = self.split_pos(pos);
+        let (main_size, cross_size) = self.split(size);
+
+        let available = main_size.saturating_sub(total_spacing(self.spacing, self.items.len()));
+        let tracks: Vec<Track> = self.items.iter().map(|i| {
+            Track{ min: self.split(i.min_size).0, max: self.split(i.max_size).0, stretch: i.stretch }
+        }).collect();
+
+        let mut offset = main_pos;
+        for (item, length) in self.items.iter().zip(distribute(&tracks, available)) {
+            let item_pos = self.join_pos(offset, cross_pos);
+            item.solve(item_pos, self.join(length, cross_size), out);
+            offset += (length as i32) + (self.spacing as i32);
+        }
+    }
+
+    fn split(&self, v: (u32, u32)) -> (u32, u32) {
+        match self.orientation {
+            Orientation::Horizontal => v,
+            Orientation::Vertical => (v.1, v.0)
+        }
+    }
+
+    fn join(&self, main: u32, cross: u32) -> (u32, u32) {
+        self.split((main, cross))
+    }
+
+    fn split_pos(&self, v: (i32, i32)) -> (i32, i32) {
+        match self.orientation {
+            Orientation::Horizontal => v,
+            Orientation::Vertical => (v.1, v.0)
+        }
+    }
+
+    fn join_pos(&self, main: i32, cross: i32) -> (i32, i32) {
+        self.split_pos((main, cross))
+    }
+}
+
+/**
+    A cell of a grid layout
+
+    Members:
+    • `item`: The item in the cell
+    • `col`, `row`: The position of the cell in the grid
+    • `col_span`, `row_span`: The number of columns and rows covered by the cell
+*/
+#[derive(Clone, Debug)]
+pub struct GridCell<ID: Clone> {
+    pub item: LayoutItem<ID>,
+    pub col: u32,
+    pub row: u32,
+    pub col_span: u32,
+    pub row_span: u32
+}
+
+/**
+    A layout that puts its items in the cells of a grid.
+
+    The minimum size of a column (or a row) is the biggest minimum size of its single-span items.
+    The free space is divided between the columns and the rows using their stretch factor.
+
+    Members:
+    • `margin`: Space reserved around the grid
+    • `spacing`: Horizontal and vertical space between two cells
+    • `columns`: The stretch factor of each column. The length of the vector is the number of columns.
+    • `rows`: The stretch factor of each row. The length of the vector is the number of rows.
+    • `cells`: The cells of the grid
+*/
+#[derive(Clone, Debug)]
+pub struct GridLayout<ID: Clone> {
+    pub margin: Margin,
+    pub spacing: (u32, u32),
+    pub columns: Vec<u32>,
+    pub rows: Vec<u32>,
+    pub cells: Vec<GridCell<ID>>
+}
+
+impl<ID: Clone> GridLayout<ID> {
+
+    /// Create an empty grid with `cols` columns and `rows` rows. Every column and every row has a stretch factor of 1.
+    pub fn new(cols: u32, rows: u32) -> GridLayout<ID> {
+        GridLayout{
+            margin: Margin::default(), spacing: (0, 0),
+            columns: vec![1; cols as usize], rows: vec![1; rows as usize],
+            cells: Vec::new()
+        }
+    }
+
+    /// Set the margin of the layout
+    pub fn margin(mut self, margin: Margin) -> GridLayout<ID> {
+        self.margin = margin;
+        self
+    }
+
+    /// Set the horizontal and the vertical spacing of the layout
+    pub fn spacing(mut self, h: u32, v: u32) -> GridLayout<ID> {
+        self.spacing = (h, v);
+        self
+    }
+
+    /// Set the stretch factor of a column. Panics if the column is outside of the grid.
+    pub fn column_stretch(mut self, col: u32, stretch: u32) -> GridLayout<ID> {
+        self.columns[col as usize] = stretch;
+        self
+    }
+
+    /// Set the stretch factor of a row. Panics if the row is outside of the grid.
+    pub fn row_stretch(mut self, row: u32, stretch: u32) -> GridLayout<ID> {
+        self.rows[row as usize] = stretch;
+        self
+    }
+
+    /// Add an item in the cell at `(col, row)`
+    pub fn add(self, item: LayoutItem<ID>, col: u32, row: u32) -> GridLayout<ID> {
+        self.add_span(item, col, row, 1, 1)
+    }
+
+    /// Add an item that covers `col_span` columns and `row_span` rows starting at the cell `(col, row)`
+    pub fn add_span(mut self, item: LayoutItem<ID>, col: u32, row: u32, col_span: u32, row_span: u32) -> GridLayout<ID> {
+        self.cells.push(GridCell{ item: item, col: col, row: row, col_span: cmp::max(col_span, 1), row_span: cmp::max(row_span, 1) });
+        self
+    }
+
+    fn tracks(&self) -> (Vec<Track>, Vec<Track>) {
+        let mut cols: Vec<Track> = self.columns.iter().map(|&s| Track{ min: 0, max: u32::MAX, stretch: s }).collect();
+        let mut rows: Vec<Track> = self.rows.iter().map(|&s| Track{ min: 0, max: u32::MAX, stretch: s }).collect();
+
+        for cell in self.cells.iter() {
+            if cell.col_span == 1 {
+                if let Some(col) = cols.get_mut(cell.col as usize) {
+                    col.min = cmp::max(col.min, cell.item.min_size.0);
+                }
+            }
+            if cell.row_span == 1 {
+                if let Some(row) = rows.get_mut(cell.row as usize) {
+                    row.min = cmp::max(row.min, cell.item.min_size.1);
+                }
+            }
+        }
+
+        (cols, rows)
+    }
+
+    fn min_size(&self) -> (u32, u32) {
+        let (cols, rows) = self.tracks();
+        let w = cols.iter().fold(total_spacing(self.spacing.0, cols.len()), |acc, t| acc.saturating_add(t.min));
+        let h = rows.iter().fold(total_spacing(self.spacing.1, rows.len()), |acc, t| acc.saturating_add(t.min));
+        add_margin((w, h), &self.margin)
+    }
+
+    fn solve_into(&self, pos: (i32, i32), size: (u32, u32), out: &mut Vec<LayoutResult<ID>>) {
+        let (pos, size) = remove_margin(pos, size, &self.margin);
+        let (cols, rows) = self.tracks();
+
+        let widths = distribute(&cols, size.0.saturating_sub(total_spacing(self.spacing.0, cols.len())));
+        let heights = distribute(&rows, size.1.saturating_sub(total_spacing(self.spacing.1, rows.len())));
+        let xs = track_offsets(pos.0, &widths, self.spacing.0);
+        let ys = track_offsets(pos.1, &heights, self.spacing.1);
+
+        for cell in self.cells.iter() {
+            let (col, row) = (cell.col as usize, cell.row as usize);
+            if col >= cols.len() || row >= rows.len() { continue; }
+
+            let last_col = cmp::min(col + cell.col_span as usize, cols.len()) - 1;
+            let last_row = cmp::min(row + cell.row_span as usize, rows.len()) - 1;
+            let w = (xs[last_col] - xs[col]) as u32 + widths[last_col];
+            let h = (ys[last_row] - ys[row]) as u32 + heights[last_row];
+
+            cell.item.solve((xs[col], ys[row]), (w, h), out);
+        }
+    }
+}
+
+/**
+    A layout that docks its items against the sides of the remaining area.
+
+    Items are processed in order. An item docked at the top or the bottom takes the full width of
+    the remaining area and its minimum height (its maximum height if it has a stretch factor), an item
+    docked at the left or the right takes the full height and its minimum width (or maximum width).
+    A `Fill` item takes what is left.
+
+    Members:
+    • `margin`: Space reserved around the items
+    • `spacing`: Space between two items
+    • `items`: The items of the layout
+*/
+#[derive(Clone, Debug)]
+pub struct DockLayout<ID: Clone> {
+    pub margin: Margin,
+    pub spacing: u32,
+    pub items: Vec<(DockSide, LayoutItem<ID>)>
+}
+
+impl<ID: Clone> DockLayout<ID> {
+
+    /// Create an empty dock layout
+    pub fn new() -> DockLayout<ID> {
+        DockLayout{ margin: Margin::default(), spacing: 0, items: Vec::new() }
+    }
+
+    /// Set the margin of the layout
+    pub fn margin(mut self, margin: Margin) -> DockLayout<ID> {
+        self.margin = margin;
+        self
+    }
+
+    /// Set the spacing of the layout
+    pub fn spacing(mut self, spacing: u32) -> DockLayout<ID> {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Dock an item on a side of the remaining area
+    pub fn add(mut self, side: DockSide, item: LayoutItem<ID>) -> DockLayout<ID> {
+        self.items.push((side, item));
+        self
+    }
+
+    fn min_size(&self) -> (u32, u32) {
+        // Computed backward: each item must fit next to the items docked after it
+        let (w, h) = self.items.iter().rev().fold((0u32, 0u32), |(w, h), &(side, ref item)| {
+            let (iw, ih) = item.min_size;
+            match side {
+                DockSide::Top | DockSide::Bottom => (cmp::max(w, iw), h.saturating_add(ih).saturating_add(self.spacing)),
+                DockSide::Left | DockSide::Right => (w.saturating_add(iw).saturating_add(self.spacing), cmp::max(h, ih)),
+                DockSide::Fill => (cmp::max(w, iw), cmp::max(h, ih))
+            }
+        });
+
+        add_margin((w, h), &self.margin)
+    }
+
+    fn solve_into(&self, pos: (i32, i32), size: (u32, u32), out: &mut Vec<LayoutResult<ID>>) {
+        let ((mut x, mut y), (mut w, mut h)) = remove_margin(pos, size, &self.margin);
+        let spacing = self.spacing;
+
+        for &(side, ref item) in self.items.iter() {
+            let length = |available: u32, min: u32, max: u32| {
+                if item.stretch > 0 { clamp(available, min, max) } else { cmp::min(min, available) }
+            };
+
+            match side {
+                DockSide::Top => {
+                    let ih = length(h, item.min_size.1, item.max_size.1);
+                    item.solve((x, y), (w, ih), out);
+                    let taken = ih.saturating_add(spacing);
+                    y += cmp::min(taken, h) as i32;
+                    h = h.saturating_sub(taken);
+                },
+                DockSide::Bottom => {
+                    let ih = length(h, item.min_size.1, item.max_size.1);
+                    item.solve((x, y + (h.saturating_sub(ih) as i32)), (w, ih), out);
+                    h = h.saturating_sub(ih.saturating_add(spacing));
+                },
+                DockSide::Left => {
+                    let iw = length(w, item.min_size.0, item.max_size.0);
+                    item.solve((x, y), (iw, h), out);
+                    let taken = iw.saturating_add(spacing);
+                    x += cmp::min(taken, w) as i32;
+                    w = w.saturating_sub(taken);
+                },
+                DockSide::Right => {
+                    let iw = length(w, item.min_size.0, item.max_size.0);
+                    item.solve((x + (w.saturating_sub(iw) as i32), y), (iw, h), out);
+                    w = w.saturating_sub(iw.saturating_add(spacing));
+                },
+                DockSide::Fill => {
+                    item.solve((x, y), (w, h), out);
+                }
+            }
+        }
+    }
+}
+
+/**
+    A layout. See `BoxLayout`, `GridLayout` and `DockLayout`.
+*/
+#[derive(Clone, Debug)]
+pub enum Layout<ID: Clone> {
+    Box(BoxLayout<ID>),
+    Grid(GridLayout<ID>),
+    Dock(DockLayout<ID>)
+}
+
+/// A computed item geometry: `(control id, position, size)`
+pub type LayoutResult<ID> = (ID, (i32, i32), (u32, u32));
+
+impl<ID: Clone> Layout<ID> {
+
+    /**
+        Compute the geometry of the controls managed by the layout in the area starting at `pos` with a size of `size`.
+        Returns the id, the position and the size of each control. Spacers are not included.
+    */
+    pub fn solve(&self, pos: (i32, i32), size: (u32, u32)) -> Vec<LayoutResult<ID>> {
+        let mut out = Vec::new();
+        self.solve_into(pos, size, &mut out);
+        out
+    }
+
+    /**
+        Return the minimum size required to display the items of the layout.
+    */
+    pub fn min_size(&self) -> (u32, u32) {
+        match self {
+            &Layout::Box(ref l) => l.min_size(),
+            &Layout::Grid(ref l) => l.min_size(),
+            &Layout::Dock(ref l) => l.min_size()
+        }
+    }
+
+    fn solve_into(&self, pos: (i32, i32), size: (u32, u32), out: &mut Vec<LayoutResult<ID>>) {
+        match self {
+            &Layout::Box(ref l) => l.solve_into(pos, size, out),
+            &Layout::Grid(ref l) => l.solve_into(pos, size, out),
+            &Layout::Dock(ref l) => l.solve_into(pos, size, out)
+        }
+    }
+}
+
+impl<ID: Clone> From<BoxLayout<ID>> for Layout<ID> {
+    fn from(l: BoxLayout<ID>) -> Layout<ID> { Layout::Box(l) }
+}
+
+impl<ID: Clone> From<GridLayout<ID>> for Layout<ID> {
+    fn from(l: GridLayout<ID>) -> Layout<ID> { Layout::Grid(l) }
+}
+
+impl<ID: Clone> From<DockLayout<ID>> for Layout<ID> {
+    fn from(l: DockLayout<ID>) -> Layout<ID> { Layout::Dock(l) }
+}
+
+/// A column, a row or a box item, used when dividing the available space
+struct Track {
+    min: u32,
+    max: u32,
+    stretch: u32
+}
+
+/**
+    Divide `available` pixels between the tracks. Each track gets at least its minimum size
+    and the free space is divided between the tracks with a stretch factor, without exceeding their maximum size.
+*/
+fn distribute(tracks: &[Track], available: u32) -> Vec<u32> {
+    let mut sizes: Vec<u32> = tracks.iter().map(|t| cmp::min(t.min, t.max)).collect();
+    let used = sizes.iter().fold(0u64, |acc, &s| acc + s as u64);
+    let mut free = (available as u64).saturating_sub(used);
+
+    loop {
+        let active: Vec<usize> = (0..tracks.len()).filter(|&i| tracks[i].stretch > 0 && sizes[i] < tracks[i].max).collect();
+        if active.is_empty() || free == 0 { break; }
+
+        let total_stretch = active.iter().fold(0u64, |acc, &i| acc + tracks[i].stretch as u64);
+        let share = |i: usize, free: u64| free * (tracks[i].stretch as u64) / total_stretch;
+
+        // If a track reaches its maximum size, give it its maximum size and divide the space again
+        let clamped: Vec<usize> = active.iter().cloned().filter(|&i| (sizes[i] as u64) + share(i, free) >= tracks[i].max as u64).collect();
+        if !clamped.is_empty() {
+            for i in clamped {
+                free -= (tracks[i].max - sizes[i]) as u64;
+                sizes[i] = tracks[i].max;
+            }
+            continue;
+        }
+
+        let mut given = 0;
+        for &i in active.iter() {
+            let s = share(i, free);
+            sizes[i] += s as u32;
+            given += s;
+        }
+
+        // Give the rounding leftovers to the first tracks, one pixel each
+        let mut leftover = free - given;
+        for &i in active.iter() {
+            if leftover == 0 { break; }
+            if sizes[i] < tracks[i].max {
+                sizes[i] += 1;
+                leftover -= 1;
+            }
+        }
+
+        break;
+    }
+
+    sizes
+}
+
+fn track_offsets(start: i32, sizes: &[u32], spacing: u32) -> Vec<i32> {
+    let mut offset = start;
+    sizes.iter().map(|&s| {
+        let o = offset;
+        offset += (s + spacing) as i32;
+        o
+    }).collect()
+}
+
+#[inline(always)]
+fn total_spacing(spacing: u32, count: usize) -> u32 {
+    if count > 1 { spacing.saturating_mul((count - 1) as u32) } else { 0 }
+}
+
+#[inline(always)]
+fn clamp(v: u32, min: u32, max: u32) -> u32 {
+    cmp::max(cmp::min(v, max), cmp::min(min, max))
+}
+
+#[inline(always)]
+fn add_margin(size: (u32, u32), m: &Margin) -> (u32, u32) {
+    (size.0.saturating_add(m.left + m.right), size.1.saturating_add(m.top + m.bottom))
+}
+
+#[inline(always)]
+fn remove_margin(pos: (i32, i32), size: (u32, u32), m: &Margin) -> ((i32, i32), (u32, u32)) {
+    let pos = (pos.0 + m.left as i32, pos.1 + m.top as i32);
+    let size = (size.0.saturating_sub(m.left + m.right), size.1.saturating_sub(m.top + m.bottom));
+    (pos, size)
+}
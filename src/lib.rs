@@ -2,22 +2,31 @@
     A very high level native gui library for Windows.
 */
 
-#![cfg(windows)]
-extern crate winapi;
+#[cfg(windows)] extern crate winapi;
 
 extern crate user32;
 extern crate kernel32;
 extern crate comctl32;
 extern crate gdi32;
 extern crate ole32;
+extern crate regex;
+
+#[cfg(not(windows))] #[path = "low/winapi_shim.rs"] mod winapi;
 
 mod low;
 mod defs;
 mod error;
-mod cursor;
+#[cfg(windows)] mod cursor;
+#[cfg(windows)] mod task_dialog;
 mod controls;
 mod resources;
 mod ui;
+mod layout;
+mod shortcut;
+mod binding;
+mod validation;
+mod rich_text;
+mod file_filter;
 
 pub mod events;
 pub mod templates;
@@ -28,12 +37,20 @@ pub mod custom {
     */
     pub use controls::{ControlT, Control, AnyHandle, HandleSpec};
     pub use resources::{ResourceT, Resource};
+    pub use low::backend::Backend;
     pub use low::events::{Event, UnpackProc, HandleProc, event_unpack_no_args, hwnd_handle, command_handle, notify_handle};
-    pub use low::window_helper::{build_window, build_sysclass, SysclassParams, WindowParams, set_window_long, get_window_long,
+    #[cfg(windows)] pub use low::window_helper::{build_window, build_sysclass, SysclassParams, WindowParams, set_window_long, get_window_long,
     get_window_text, set_window_text, get_window_visibility, set_window_visibility, get_window_position, set_window_position,
     get_window_size, set_window_size, get_window_enabled, set_window_enabled, set_window_font, get_window_font, list_window_children};
-    pub use low::menu_helper::list_menu_children;
+    #[cfg(windows)] pub use low::menu_helper::list_menu_children;
+
+}
 
+pub mod mock {
+    /*!
+        Headless backend and controls. Can be used to test an application on any platform.
+    */
+    pub use low::mock::{MockBackend, MockControlT, MockControl};
 }
 
 pub mod constants {
@@ -44,21 +61,32 @@ pub mod constants {
     pub use controls::ControlType;
 }
 
-pub use events::EventArgs;
+pub use events::{EventArgs, EventOutcome};
 pub use error::{Error, SystemError};
-pub use low::other_helper::{message, simple_message, fatal_message, error_message};
+#[cfg(windows)] pub use low::other_helper::{message, simple_message, fatal_message, error_message};
+#[cfg(windows)] pub use task_dialog::{TaskDialog, TaskDialogButton, TaskDialogProgress, TaskDialogResult, TaskDialogHandle};
+#[cfg(windows)]
 pub use controls::{WindowT, Window, MenuT, Menu, MenuItemT, MenuItem, ButtonT, Button, ListBoxT, ListBox, CheckBoxT, CheckBox,
  RadioButtonT, RadioButton, TimerT, Timer, LabelT, Label, ComboBoxT, ComboBox, SeparatorT, Separator, TextInputT, TextInput,
- FileDialogT, FileDialog, TextBoxT, TextBox, GroupBoxT, GroupBox, ProgressBarT, ProgressBar, DatePickerT, DatePicker, ImageFrameT,
+ FileDialogT, FileDialog, ColorDialogT, ColorDialog, FontDialogT, FontDialog, PrintDialogT, PrintDialog, PrintSettings, PrintRange,
+ TextBoxT, TextBox, RichTextBoxT, RichTextBox, StreamFormat, FindOptions, NumericInputT, NumericInput, GroupBoxT, GroupBox, ProgressBarT, ProgressBar, SliderT, Slider, StatusBarT, StatusBar, StatusPartWidth, ToolbarT, Toolbar, ToolbarButton, ToolbarButtonStyle, TooltipT, Tooltip, TooltipIcon, TooltipDelay, TrayNotificationT, TrayNotification, TrayBalloonIcon, DatePickerT, DatePicker, ImageFrameT,
  ImageFrame, TreeViewT, TreeView, TreeViewItemT, TreeViewItem, TreeItemIterator, FrameT, Frame, ContextMenuT, ContextMenu,
- TabViewT, TabView, TabT, Tab, ListViewT, ListView, ViewMode};
+ TabViewT, TabView, TabT, Tab, ListViewT, ListView, ListViewColumn, SortDirection, ViewMode, VirtualListViewT, VirtualListView,
+ ListViewDataSource, AcceleratorT, Accelerator};
 
-pub use resources::{FontT, Font, ImageT, OemImageT, MemoryImageT, Image};
-pub use ui::{Ui, dispatch_events, exit, toggle_console};
-pub use cursor::Cursor;
+#[cfg(windows)] pub use resources::{FontT, Font, ImageT, OemImageT, MemoryImageT, Image};
+#[cfg(windows)] pub use ui::{dispatch_events, exit, toggle_console};
+#[cfg(windows)] pub use cursor::Cursor;
+pub use ui::{Ui, UiProxy};
+pub use shortcut::Shortcut;
+pub use binding::{Observable, Binding, BindingMode, Property};
+pub use validation::{Validator, Pattern};
+pub use file_filter::{FileFilter, FileFilters};
+pub use rich_text::{RichDocument, Paragraph, TextRun, CharFormat, ParagraphAlign};
+pub use layout::{Layout, LayoutResult, LayoutItem, LayoutContent, BoxLayout, GridLayout, GridCell, DockLayout, DockSide, Orientation, Margin};
 
-#[cfg(feature = "canvas")]
+#[cfg(all(windows, feature = "canvas"))]
 pub use controls::{CanvasT, Canvas, CanvasRenderer};
 
-#[cfg(feature = "canvas")]
+#[cfg(all(windows, feature = "canvas"))]
 pub use resources::{BrushT, Brush, PenT, Pen};
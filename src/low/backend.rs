@@ -0,0 +1,82 @@
+/*!
+    Backend trait definition. A backend queues the commands of a Ui and connects the Ui to the system events.
+
+    On Windows, `Ui::new` uses the `MessageHandler` backend. The `MockBackend` do not require any system
+    window and can be used on any platform with `Ui::with_backend`.
+*/
+
+use std::hash::Hash;
+use std::any::Any;
+
+use winapi::{HWND, UINT};
+
+use ui::UiInner;
+use controls::AnyHandle;
+use error::Error;
+
+/**
+    Structures implementing this trait can be used by a Ui to execute its commands and dispatch its events.
+*/
+pub trait Backend<ID: Hash+Clone+'static> {
+
+    /**
+        Called once the Ui that owns the backend is allocated. `ui` is valid until `free` is called.
+    */
+    fn init(&mut self, ui: *mut UiInner<ID>);
+
+    /**
+        Queue a NWG command. `msg` is the command identifier (ex: `NWG_PACK_CONTROL`) and `data` holds its arguments.
+        The command must be executed with `UiInner.execute_command` when the backend is committed.
+    */
+    fn post(&self, ui: *mut UiInner<ID>, msg: UINT, data: Box<Any>);
+
+    /**
+        Execute the waiting commands in the order they were posted.
+
+        * Returns `Ok(())` if everything was executed without errors
+        * Returns `Err(Error)` if an error was encountered. The following commands will not be touched.
+    */
+    fn commit(&mut self) -> Result<(), Error>;
+
+    /**
+        Start dispatching the system events of a newly packed control to the Ui. See `low::events::dispatch_message`
+    */
+    fn hook_events(&self, ui: *mut UiInner<ID>, handle: &AnyHandle);
+
+    /**
+        Stop dispatching the system events of a control that is being unpacked.
+    */
+    fn unhook_events(&self, handle: &AnyHandle);
+
+    /**
+        Return a function that can be called from any thread to wake up the Ui thread. Once woken up, the backend
+        must call `UiInner.run_proxy_tasks` from the Ui thread (either from its event loop or in `commit`).
+        The returned function must do nothing once the backend is freed.
+    */
+    fn proxy_waker(&self, ui: *mut UiInner<ID>) -> Box<Fn() + Send + Sync>;
+
+    /**
+        Return the handle of the window that receives the events not targeted at any control (ex: WM_TIMER)
+    */
+    fn message_handle(&self) -> HWND;
+
+    /**
+        Return the size of the client area of a window. Used by the layouts.
+    */
+    fn window_size(&self, handle: HWND) -> (u32, u32);
+
+    /**
+        Move and resize a window. `position` is relative to the window parent. Used by the layouts.
+    */
+    fn set_window_geometry(&self, handle: HWND, position: (i32, i32), size: (u32, u32));
+
+    /**
+        Return the visible direct children of a window. Used by the layouts to find the docked bars.
+    */
+    fn visible_children(&self, handle: HWND) -> Vec<HWND>;
+
+    /**
+        Free the resources allocated by the backend. Called when the Ui is dropped.
+    */
+    fn free(&mut self);
+}
@@ -0,0 +1,345 @@
+/*!
+    Text and background colors of the controls.
+
+    The colors are stored by control handle. The system asks the parent of a control for its colors with the `WM_CTLCOLOR*`
+    messages, which are answered by `paint_colors` in the events dispatcher. A control without colors uses the colors of its closest
+    ancestor that has them, except the edit controls that only inherit the text color. The NWG frames and tabs without colors
+    are also filled with the background color of their ancestors.
+
+    The visual styles paint the text of the check boxes, radio buttons and group boxes with the theme color. The theme of those
+    controls is removed while they have their own text color. A text color inherited from an ancestor is not displayed by them.
+
+    Push buttons ignore the `WM_CTLCOLORBTN` colors. A push button with its own colors is painted here in the `NM_CUSTOMDRAW`
+    notification sent to its parent.
+*/
+
+use std::ptr;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use winapi::{HWND, HBRUSH, HDC, UINT, WPARAM, LPARAM, LRESULT};
+
+use low::other_helper::to_colorref;
+
+/// The colors of a control. The brush is created when the background color is set and deleted when the color is changed.
+struct ControlColors {
+    text: Option<[u8; 3]>,
+    background: Option<[u8; 3]>,
+    brush: HBRUSH
+}
+
+thread_local!(static COLORS: RefCell<HashMap<HWND, ControlColors>> = RefCell::new(HashMap::new()));
+
+/// Return the text color of a control. Return `None` if the control uses the default color.
+pub fn get_text_color(handle: HWND) -> Option<[u8; 3]> {
+    COLORS.with(|c| c.borrow().get(&handle).and_then(|colors| colors.text))
+}
+
+/// Return the background color of a control. Return `None` if the control uses the default color.
+pub fn get_background_color(handle: HWND) -> Option<[u8; 3]> {
+    COLORS.with(|c| c.borrow().get(&handle).and_then(|colors| colors.background))
+}
+
+/// Set the text color of a control and its children. If `None`, use the default color.
+pub unsafe fn set_text_color(handle: HWND, color: Option<[u8; 3]>) {
+    use low::defs::SetWindowTheme;
+
+    if is_themed_button(handle) {
+        match color {
+            Some(_) => { let empty = [0u16]; SetWindowTheme(handle, empty.as_ptr(), empty.as_ptr()); },
+            None => { SetWindowTheme(handle, ptr::null(), ptr::null()); }
+        }
+    }
+
+    update_colors(handle, |colors| colors.text = color);
+}
+
+/// Set the background color of a control and its children. If `None`, use the default color.
+pub unsafe fn set_background_color(handle: HWND, color: Option<[u8; 3]>) {
+    use gdi32::{CreateSolidBrush, DeleteObject};
+    use winapi::HGDIOBJ;
+
+    update_colors(handle, |colors| {
+        if !colors.brush.is_null() {
+            DeleteObject(colors.brush as HGDIOBJ);
+            colors.brush = ptr::null_mut();
+        }
+
+        colors.background = color;
+        if let Some(color) = color {
+            colors.brush = CreateSolidBrush(to_colorref(color));
+        }
+    });
+}
+
+/**
+    Set the colors of a newly created control. Used by the controls templates that have the `text_color`
+    and `background_color` members.
+*/
+pub unsafe fn set_template_colors(handle: HWND, text: Option<[u8; 3]>, background: Option<[u8; 3]>) {
+    if text.is_some() { set_text_color(handle, text); }
+    if background.is_some() { set_background_color(handle, background); }
+}
+
+/// Free the brush of a control. Must be called when a control that has colors is freed.
+pub unsafe fn clear_colors(handle: HWND) {
+    use gdi32::DeleteObject;
+    use winapi::HGDIOBJ;
+
+    if let Some(colors) = COLORS.with(|c| c.borrow_mut().remove(&handle)) {
+        if !colors.brush.is_null() {
+            DeleteObject(colors.brush as HGDIOBJ);
+        }
+    }
+}
+
+/**
+    Answer the messages sent by the system to paint a control with its colors:
+
+    * `WM_CTLCOLORSTATIC`, `WM_CTLCOLOREDIT` and `WM_CTLCOLORBTN`: sent to `hwnd` to paint the child control `l`.
+    * `WM_ERASEBKGND`: fill `hwnd` with its background color.
+    * `WM_NOTIFY` with `NM_CUSTOMDRAW`: paint the push button that sent the notification with its own colors.
+
+    Return `None` if the control do not have colors. In this case, the message must be passed to the default window procedure.
+*/
+pub unsafe fn paint_colors(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<LRESULT> {
+    use comctl32::DefSubclassProc;
+    use gdi32::{SetTextColor, SetBkColor};
+    use user32::{GetClientRect, FillRect};
+    use winapi::{RECT, NMCUSTOMDRAW, WM_CTLCOLORSTATIC, WM_CTLCOLOREDIT, WM_CTLCOLORBTN, WM_ERASEBKGND, WM_NOTIFY, NM_CUSTOMDRAW,
+      CDDS_PREPAINT, CDRF_SKIPDEFAULT};
+
+    match msg {
+        WM_CTLCOLORSTATIC | WM_CTLCOLOREDIT | WM_CTLCOLORBTN => {
+            let (text, background, brush) = match child_colors(hwnd, l as HWND, msg != WM_CTLCOLOREDIT) {
+                Some(colors) => colors,
+                None => { return None; }
+            };
+
+            // The default procedure sets the default colors of the control in the device context
+            let hdc = w as HDC;
+            let result = DefSubclassProc(hwnd, msg, w, l);
+            if let Some(text) = text {
+                SetTextColor(hdc, to_colorref(text));
+            }
+
+            match background {
+                Some(background) => {
+                    SetBkColor(hdc, to_colorref(background));
+                    Some(brush as LRESULT)
+                },
+                None => Some(result)
+            }
+        },
+        WM_ERASEBKGND => {
+            let brush = match background_brush(hwnd) {
+                Some(brush) => brush,
+                None => { return None; }
+            };
+
+            let mut rect: RECT = RECT{ left: 0, top: 0, right: 0, bottom: 0 };
+            GetClientRect(hwnd, &mut rect);
+            FillRect(w as HDC, &rect, brush);
+            Some(1)
+        },
+        WM_NOTIFY => {
+            let nmcd: &NMCUSTOMDRAW = &*(l as *const NMCUSTOMDRAW);
+            if nmcd.hdr.code != NM_CUSTOMDRAW || nmcd.dwDrawStage != CDDS_PREPAINT || !is_push_button(nmcd.hdr.hwndFrom) {
+                return None;
+            }
+
+            match COLORS.with(|c| c.borrow().get(&nmcd.hdr.hwndFrom).map(|colors| (colors.text, colors.brush))) {
+                Some((text, brush)) => {
+                    draw_button(nmcd, text, brush);
+                    Some(CDRF_SKIPDEFAULT)
+                },
+                None => None
+            }
+        },
+        _ => None
+    }
+}
+
+/// Return the colors and the background brush of `child`. The missing colors are read from `parent` and its ancestors.
+fn child_colors(parent: HWND, child: HWND, inherit_background: bool) -> Option<(Option<[u8; 3]>, Option<[u8; 3]>, HBRUSH)> {
+    COLORS.with(|c| {
+        let colors = c.borrow();
+        if colors.is_empty() {
+            return None;
+        }
+
+        let own = colors.get(&child);
+        let mut text = own.and_then(|c| c.text);
+        let mut background = own.and_then(|c| c.background.map(|b| (b, c.brush)));
+
+        let mut ancestor = parent;
+        while !ancestor.is_null() && (text.is_none() || (inherit_background && background.is_none())) {
+            if let Some(a) = colors.get(&ancestor) {
+                text = text.or(a.text);
+                if inherit_background {
+                    background = background.or(a.background.map(|b| (b, a.brush)));
+                }
+            }
+            ancestor = unsafe{ parent_of(ancestor) };
+        }
+
+        match (text, background) {
+            (None, None) => None,
+            (text, Some((background, brush))) => Some((text, Some(background), brush)),
+            (text, None) => Some((text, None, ptr::null_mut()))
+        }
+    })
+}
+
+/**
+    Return the brush used to erase the background of `hwnd`. The NWG frames and tabs without a background color
+    use the background color of their closest ancestor that has one.
+*/
+fn background_brush(hwnd: HWND) -> Option<HBRUSH> {
+    COLORS.with(|c| {
+        let colors = c.borrow();
+        if colors.is_empty() {
+            return None;
+        }
+
+        if let Some(brush) = colors.get(&hwnd).and_then(|c| if c.brush.is_null() { None } else { Some(c.brush) }) {
+            return Some(brush);
+        }
+
+        if !unsafe{ class_name(hwnd) }.starts_with("NWG_BUILTIN_") {
+            return None;
+        }
+
+        let mut ancestor = unsafe{ parent_of(hwnd) };
+        while !ancestor.is_null() {
+            if let Some(brush) = colors.get(&ancestor).and_then(|c| if c.brush.is_null() { None } else { Some(c.brush) }) {
+                return Some(brush);
+            }
+            ancestor = unsafe{ parent_of(ancestor) };
+        }
+
+        None
+    })
+}
+
+/// Return the parent of a child window. Return a null handle for the top level windows.
+unsafe fn parent_of(handle: HWND) -> HWND {
+    use user32::GetParent;
+    use winapi::{GWL_STYLE, WS_CHILD};
+    use low::window_helper::get_window_long;
+
+    if get_window_long(handle, GWL_STYLE) as u32 & WS_CHILD == WS_CHILD {
+        GetParent(handle)
+    } else {
+        ptr::null_mut()
+    }
+}
+
+/// Return the window class name of a control
+unsafe fn class_name(handle: HWND) -> String {
+    use user32::GetClassNameW;
+    use low::other_helper::from_utf16;
+
+    let mut buffer: [u16; 64] = [0; 64];
+    GetClassNameW(handle, buffer.as_mut_ptr(), 64);
+    from_utf16(&buffer)
+}
+
+/// Return the button type of `handle` (`BS_PUSHBUTTON`, `BS_CHECKBOX`, ...). Return `None` if `handle` is not a button.
+unsafe fn button_type(handle: HWND) -> Option<u32> {
+    use winapi::{GWL_STYLE, BS_TYPEMASK};
+    use low::window_helper::get_window_long;
+
+    if class_name(handle).eq_ignore_ascii_case("button") {
+        Some(get_window_long(handle, GWL_STYLE) as u32 & BS_TYPEMASK)
+    } else {
+        None
+    }
+}
+
+/// Return `true` if `handle` is a check box, a radio button or a group box. The visual styles ignore the text color of those.
+unsafe fn is_themed_button(handle: HWND) -> bool {
+    use winapi::{BS_CHECKBOX, BS_AUTOCHECKBOX, BS_3STATE, BS_AUTO3STATE, BS_RADIOBUTTON, BS_AUTORADIOBUTTON, BS_GROUPBOX};
+
+    match button_type(handle) {
+        Some(BS_CHECKBOX) | Some(BS_AUTOCHECKBOX) | Some(BS_3STATE) | Some(BS_AUTO3STATE) |
+        Some(BS_RADIOBUTTON) | Some(BS_AUTORADIOBUTTON) | Some(BS_GROUPBOX) => true,
+        _ => false
+    }
+}
+
+/// Return `true` if `handle` is a push button (this includes the default and the flat push buttons)
+unsafe fn is_push_button(handle: HWND) -> bool {
+    use winapi::{BS_PUSHBUTTON, BS_DEFPUSHBUTTON};
+
+    match button_type(handle) {
+        Some(BS_PUSHBUTTON) | Some(BS_DEFPUSHBUTTON) => true,
+        _ => false
+    }
+}
+
+/**
+    Paint a push button with its text color and its background brush. If one of them is not set, the system color is used.
+    The button border is drawn as a 3D edge (or a flat edge for the flat buttons) and the button image is not painted.
+*/
+unsafe fn draw_button(nmcd: &::winapi::NMCUSTOMDRAW, text: Option<[u8; 3]>, brush: HBRUSH) {
+    use user32::{SendMessageW, GetSysColor, GetSysColorBrush, FillRect, DrawEdge, OffsetRect, InflateRect, DrawTextW, DrawFocusRect};
+    use gdi32::{SetTextColor, SetBkMode, SelectObject};
+    use winapi::{HGDIOBJ, GWL_STYLE, BS_FLAT, WM_GETFONT, CDIS_SELECTED, CDIS_DISABLED, CDIS_FOCUS, COLOR_BTNFACE, COLOR_BTNTEXT,
+      COLOR_GRAYTEXT, TRANSPARENT, DT_CENTER, DT_VCENTER, DT_SINGLELINE};
+    use low::defs::{EDGE_RAISED, EDGE_SUNKEN, BF_RECT, BF_ADJUST, BF_FLAT};
+    use low::window_helper::{get_window_long, get_window_text};
+    use low::other_helper::to_utf16;
+
+    let (hdc, handle) = (nmcd.hdc, nmcd.hdr.hwndFrom);
+    let state = nmcd.uItemState;
+    let pressed = state & CDIS_SELECTED == CDIS_SELECTED;
+    let flat = get_window_long(handle, GWL_STYLE) as u32 & BS_FLAT == BS_FLAT;
+    let mut rect = nmcd.rc;
+
+    FillRect(hdc, &rect, if brush.is_null() { GetSysColorBrush(COLOR_BTNFACE) } else { brush });
+    DrawEdge(hdc, &mut rect, if pressed { EDGE_SUNKEN } else { EDGE_RAISED }, BF_RECT | BF_ADJUST | if flat { BF_FLAT } else { 0 });
+
+    let text_color = match text {
+        _ if state & CDIS_DISABLED == CDIS_DISABLED => GetSysColor(COLOR_GRAYTEXT),
+        Some(text) => to_colorref(text),
+        None => GetSysColor(COLOR_BTNTEXT)
+    };
+
+    let font = SendMessageW(handle, WM_GETFONT, 0, 0) as HGDIOBJ;
+    let old_font = if font.is_null() { ptr::null_mut() } else { SelectObject(hdc, font) };
+    SetTextColor(hdc, text_color);
+    SetBkMode(hdc, TRANSPARENT);
+
+    if pressed { OffsetRect(&mut rect, 1, 1); }
+    let text = to_utf16(&get_window_text(handle));
+    DrawTextW(hdc, text.as_ptr(), -1, &mut rect, DT_CENTER | DT_VCENTER | DT_SINGLELINE);
+
+    if state & CDIS_FOCUS == CDIS_FOCUS {
+        InflateRect(&mut rect, -2, -2);
+        DrawFocusRect(hdc, &rect);
+    }
+
+    if !old_font.is_null() { SelectObject(hdc, old_font); }
+}
+
+/// Update the colors of a control and repaint the control and its children
+unsafe fn update_colors<F: FnOnce(&mut ControlColors)>(handle: HWND, update: F) {
+    use user32::RedrawWindow;
+    use winapi::{RDW_INVALIDATE, RDW_ERASE, RDW_ALLCHILDREN};
+
+    COLORS.with(|c| {
+        let mut colors = c.borrow_mut();
+        let empty = {
+            let entry = colors.entry(handle).or_insert(ControlColors{ text: None, background: None, brush: ptr::null_mut() });
+            update(entry);
+            entry.text.is_none() && entry.background.is_none()
+        };
+
+        if empty {
+            colors.remove(&handle);
+        }
+    });
+
+    RedrawWindow(handle, ptr::null(), ptr::null_mut(), RDW_INVALIDATE | RDW_ERASE | RDW_ALLCHILDREN);
+}
@@ -7,17 +7,21 @@
 use std::hash::Hash;
 use std::any::{Any, TypeId};
 
-use winapi::{UINT, LRESULT, DWORD, HBRUSH, ULONG_PTR, HMENU, BOOL, WORD, MENUITEMINFOW, IShellItem, HRESULT, IUnknownVtbl,
- IUnknown, PCWSTR, IBindCtx, REFIID, c_void, c_int, HWND};
+use winapi::{UINT, LRESULT, DWORD, WORD};
+#[cfg(windows)] use winapi::{HBRUSH, ULONG_PTR, HMENU, BOOL, MENUITEMINFOW, IShellItem, HRESULT, IUnknownVtbl,
+ IUnknown, PCWSTR, IBindCtx, REFIID, c_void, c_int, HWND, HACCEL, MSG, LONG, COLORREF, BYTE, WCHAR, SHORT, DWORD_PTR, LPCWSTR,
+ NOTIFYICONDATAW, HGLOBAL, HDC, HINSTANCE, LPARAM, LPPRINTHOOKPROC, LPSETUPHOOKPROC, CHOOSECOLORW, CHOOSEFONTW,
+ HICON, LONG_PTR, PFTASKDIALOGCALLBACK};
 
-#[cfg(feature = "canvas")] use winapi::{D2D1_FACTORY_TYPE, D2D1_FACTORY_OPTIONS, ID2D1Factory};
+#[cfg(all(windows, feature = "canvas"))] use winapi::{D2D1_FACTORY_TYPE, D2D1_FACTORY_OPTIONS, ID2D1Factory};
 
-use std::ops::{Deref, DerefMut};
+#[cfg(windows)] use std::ops::{Deref, DerefMut};
 
 
 use events::{Event, EventCallback, EventArgs};
 use controls::ControlT;
 use resources::ResourceT;
+use layout::Layout;
 
 // Custom message proc definitions
 
@@ -29,9 +33,20 @@ pub const NWG_BIND:              UINT = 0x403;  /// Message sent when binding an
 pub const NWG_UNBIND:            UINT = 0x404;  /// Message sent when unbinding an event from a control
 pub const NWG_PACK_RESOURCE:     UINT = 0x405;  /// Message sent when packing a resource
 pub const NWG_TRIGGER:           UINT = 0x406;  /// Message sent when triggering an event
-pub const NWG_CUSTOM_MAX:        UINT = 0x407;  /// Maximum custom event value
+pub const NWG_PROXY:             UINT = 0x407;  /// Message sent by a `UiProxy` when tasks are waiting to be executed
+pub const NWG_LAYOUT:            UINT = 0x408;  /// Message sent when setting or removing the layout of a window
+pub const NWG_CUSTOM_MAX:        UINT = 0x409;  /// Maximum custom event value
 
 pub const NWG_DESTROY:           UINT = 0x420;  /// NWG `Destroy` event identifier
+pub const NWG_NUMERIC_CHANGED:   UINT = 0x421;  /// NWG numeric input `ValueChanged` event identifier
+pub const NWG_TRAY:              UINT = 0x422;  /// Message sent by the shell to the tray notifications
+pub const NWG_FILE_DIALOG:       UINT = 0x423;  /// Message sent by the file dialogs when the folder or the selection changes
+pub const NWG_SLIDER_CHANGED:    UINT = 0x424;  /// NWG slider `ValueChanged` event identifier
+
+// Kind of event sent with NWG_FILE_DIALOG
+
+pub const FILE_DIALOG_FOLDER_CHANGED: UINT = 0;
+pub const FILE_DIALOG_SELECTION_CHANGED: UINT = 1;
 
 // Value returned by a window proc if the message execution failed/succeeded
 
@@ -44,15 +59,29 @@ pub const MIM_STYLE: DWORD = 0x00000010;
 
 pub const MIIM_STATE: DWORD = 0x00000001;
 pub const MIIM_ID: DWORD = 0x00000002;
+pub const MIIM_SUBMENU: DWORD = 0x00000004;
+pub const MIIM_STRING: DWORD = 0x00000040;
+pub const MIIM_BITMAP: DWORD = 0x00000080;
+pub const MIIM_FTYPE: DWORD = 0x00000100;
+
+pub const MFT_SEPARATOR: DWORD = 0x00000800;
+pub const MFT_RADIOCHECK: DWORD = 0x00000200;
 
 pub const MNS_NOTIFYBYPOS: DWORD = 0x08000000;
 
 pub const MFS_DISABLED: DWORD = 0x00000003;
 pub const MFS_ENABLED: DWORD = 0x00000000;
+pub const MFS_CHECKED: DWORD = 0x00000008;
+pub const MFS_DEFAULT: DWORD = 0x00001000;
 
 pub const MF_BYPOSITION: UINT = 0x00000400;
 pub const MF_SEPARATOR: UINT = 0x00000800;
 
+pub const FVIRTKEY: u8 = 0x01;
+pub const FSHIFT: u8 = 0x04;
+pub const FCONTROL: u8 = 0x08;
+pub const FALT: u8 = 0x10;
+
 pub const ACTCTX_FLAG_RESOURCE_NAME_VALID: u32 = 0x008;
 pub const ACTCTX_FLAG_SET_PROCESS_DEFAULT: u32 = 0x010;
 pub const ACTCTX_FLAG_ASSEMBLY_DIRECTORY_VALID: u32 = 0x004;
@@ -89,6 +118,28 @@ pub const LBN_KILLFOCUS: WORD = 5;
 
 pub const BM_SETCHECK: UINT = 241;
 pub const BM_GETCHECK: UINT = 240;
+pub const BM_SETSTYLE: UINT = 244;
+pub const BM_CLICK: UINT = 245;
+pub const BM_GETIMAGE: UINT = 246;
+pub const BM_SETIMAGE: UINT = 247;
+
+pub const ODT_BUTTON: UINT = 4;
+pub const ODT_STATIC: UINT = 5;
+pub const ODS_SELECTED: UINT = 0x0001;
+pub const ODS_DISABLED: UINT = 0x0004;
+pub const ODS_FOCUS: UINT = 0x0010;
+pub const ODS_HOTLIGHT: UINT = 0x0040;
+
+pub const EDGE_RAISED: UINT = 0x0005;
+pub const EDGE_SUNKEN: UINT = 0x000A;
+pub const BF_RECT: UINT = 0x000F;
+pub const BF_ADJUST: UINT = 0x2000;
+pub const BF_FLAT: UINT = 0x4000;
+
+pub const DLGC_WANTALLKEYS: UINT = 0x0004;
+
+pub const DMORIENT_LANDSCAPE: i16 = 2;
+pub const DMCOLLATE_TRUE: i16 = 1;
 
 pub const BST_CHECKED: UINT = 1;
 pub const BST_INDETERMINATE: UINT = 2;
@@ -104,6 +155,7 @@ pub const SS_BITMAP: UINT = 14;
 pub const SS_CENTERIMAGE: UINT = 512;
 pub const SS_ICON: UINT = 3;
 pub const SS_REALSIZEIMAGE: UINT = 0x800;
+pub const SS_OWNERDRAW: UINT = 13;
 
 pub const STM_SETIMAGE: UINT = 370;
 pub const STM_GETIMAGE: UINT = 371;
@@ -133,6 +185,58 @@ pub const ES_MULTILINE: UINT = 4;
 
 pub const EM_LIMITTEXT: UINT = 197;
 pub const EM_GETLIMITTEXT: UINT = 213;
+pub const EM_EXGETSEL: UINT = 0x0400 + 52;
+pub const EM_EXLIMITTEXT: UINT = 0x0400 + 53;
+pub const EM_EXSETSEL: UINT = 0x0400 + 55;
+pub const EM_GETCHARFORMAT: UINT = 0x0400 + 58;
+pub const EM_GETPARAFORMAT: UINT = 0x0400 + 61;
+pub const EM_GETSELTEXT: UINT = 0x0400 + 62;
+pub const EM_SETCHARFORMAT: UINT = 0x0400 + 68;
+pub const EM_SETEVENTMASK: UINT = 0x0400 + 69;
+pub const EM_SETPARAFORMAT: UINT = 0x0400 + 71;
+pub const EM_STREAMIN: UINT = 0x0400 + 73;
+pub const EM_STREAMOUT: UINT = 0x0400 + 74;
+pub const EM_REDO: UINT = 0x0400 + 84;
+pub const EM_CANREDO: UINT = 0x0400 + 85;
+pub const EM_AUTOURLDETECT: UINT = 0x0400 + 91;
+pub const EM_GETAUTOURLDETECT: UINT = 0x0400 + 92;
+pub const EM_FINDTEXTEXW: UINT = 0x0400 + 124;
+pub const EM_SETTYPOGRAPHYOPTIONS: UINT = 0x0400 + 202;
+
+pub const ENM_CHANGE: DWORD = 0x1;
+pub const ENM_UPDATE: DWORD = 0x2;
+pub const TO_ADVANCEDTYPOGRAPHY: UINT = 1;
+
+pub const SCF_DEFAULT: UINT = 0;
+pub const SCF_SELECTION: UINT = 1;
+
+pub const CFM_BOLD: DWORD = 0x1;
+pub const CFM_ITALIC: DWORD = 0x2;
+pub const CFM_UNDERLINE: DWORD = 0x4;
+pub const CFM_STRIKEOUT: DWORD = 0x8;
+pub const CFM_FACE: DWORD = 0x20000000;
+pub const CFM_COLOR: DWORD = 0x40000000;
+pub const CFM_SIZE: DWORD = 0x80000000;
+pub const CFE_BOLD: DWORD = 0x1;
+pub const CFE_ITALIC: DWORD = 0x2;
+pub const CFE_UNDERLINE: DWORD = 0x4;
+pub const CFE_STRIKEOUT: DWORD = 0x8;
+pub const CFE_AUTOCOLOR: DWORD = 0x40000000;
+
+pub const PFM_ALIGNMENT: DWORD = 0x8;
+pub const PFA_LEFT: WORD = 1;
+pub const PFA_RIGHT: WORD = 2;
+pub const PFA_CENTER: WORD = 3;
+pub const PFA_JUSTIFY: WORD = 4;
+
+pub const SF_TEXT: UINT = 0x1;
+pub const SF_RTF: UINT = 0x2;
+pub const SF_UNICODE: UINT = 0x10;
+pub const SFF_SELECTION: UINT = 0x8000;
+
+pub const FR_DOWN: UINT = 0x1;
+pub const FR_WHOLEWORD: UINT = 0x2;
+pub const FR_MATCHCASE: UINT = 0x4;
 
 pub const EN_SETFOCUS: WORD = 256;
 pub const EN_KILLFOCUS: WORD = 512;
@@ -159,6 +263,12 @@ pub const STATE_SYSTEM_INVISIBLE: u32 = 0x8000;
 
 pub const OIC_HAND: u16 = 32513;
 
+pub const IDCLOSE: i32 = 8;
+
+pub const TD_WARNING_ICON: u16 = 0xFFFF;
+pub const TD_ERROR_ICON: u16 = 0xFFFE;
+pub const TD_INFORMATION_ICON: u16 = 0xFFFD;
+
 pub const TPM_NOANIMATION: u32 = 0x4000;
 pub const TPM_RIGHTBUTTON: u32 = 0x0002;
 pub const TPM_LEFTALIGN: u32 = 0x0000;
@@ -168,7 +278,18 @@ pub const TPM_TOPALIGN: u32 = 0x0000;
 pub const TPM_VCENTERALIGN: u32 = 0x0010;
 pub const TPM_BOTTOMALIGN: u32 = 0x0020;
 
+pub const NIIF_NONE: DWORD = 0x00;
+pub const NIIF_INFO: DWORD = 0x01;
+pub const NIIF_WARNING: DWORD = 0x02;
+pub const NIIF_ERROR: DWORD = 0x03;
+pub const NIIF_USER: DWORD = 0x04;
+pub const NIIF_NOSOUND: DWORD = 0x10;
+pub const NIS_HIDDEN: DWORD = 0x01;
+pub const NIN_SELECT: UINT = 0x400;
+pub const NIN_BALLOONUSERCLICK: UINT = 0x405;
+
 // System structs
+#[cfg(windows)]
 #[repr(C)]
 #[allow(non_snake_case)]
 pub struct MENUINFO {
@@ -181,6 +302,7 @@ pub struct MENUINFO {
     pub dwMenuData: ULONG_PTR
 }
 
+#[cfg(windows)]
 #[repr(packed)]
 #[allow(non_snake_case)]
 #[derive(Debug)]
@@ -192,10 +314,144 @@ pub struct BITMAPFILEHEADER {
     pub bfOffBits: DWORD
 }
 
+// Rich edit structs. Richedit.h packs its structures on 4 bytes
+#[cfg(windows)]
+#[repr(C)]
+#[allow(non_snake_case)]
+#[derive(Clone, Copy)]
+pub struct CHARRANGE {
+    pub cpMin: LONG,
+    pub cpMax: LONG
+}
+
+#[cfg(windows)]
+#[repr(C)]
+#[allow(non_snake_case)]
+pub struct CHARFORMATW {
+    pub cbSize: UINT,
+    pub dwMask: DWORD,
+    pub dwEffects: DWORD,
+    pub yHeight: LONG,
+    pub yOffset: LONG,
+    pub crTextColor: COLORREF,
+    pub bCharSet: BYTE,
+    pub bPitchAndFamily: BYTE,
+    pub szFaceName: [WCHAR; 32]
+}
+
+#[cfg(windows)]
+#[repr(C)]
+#[allow(non_snake_case)]
+pub struct PARAFORMAT {
+    pub cbSize: UINT,
+    pub dwMask: DWORD,
+    pub wNumbering: WORD,
+    pub wEffects: WORD,
+    pub dxStartIndent: LONG,
+    pub dxRightIndent: LONG,
+    pub dxOffset: LONG,
+    pub wAlignment: WORD,
+    pub cTabCount: SHORT,
+    pub rgxTabs: [LONG; 32]
+}
+
+#[cfg(windows)]
+pub type EDITSTREAMCALLBACK = unsafe extern "system" fn(dwCookie: DWORD_PTR, pbBuff: *mut BYTE, cb: LONG, pcb: *mut LONG) -> DWORD;
+
+#[cfg(windows)]
+#[repr(C, packed(4))]
+#[allow(non_snake_case)]
+pub struct EDITSTREAM {
+    pub dwCookie: DWORD_PTR,
+    pub dwError: DWORD,
+    pub pfnCallback: EDITSTREAMCALLBACK
+}
+
+#[cfg(windows)]
+#[repr(C)]
+#[allow(non_snake_case)]
+pub struct FINDTEXTEXW {
+    pub chrg: CHARRANGE,
+    pub lpstrText: LPCWSTR,
+    pub chrgText: CHARRANGE
+}
+
+// The common dialog structures are packed on 32 bits windows. The winapi definition of PRINTDLGW is not.
+#[cfg(windows)]
+#[cfg_attr(target_arch = "x86", repr(C, packed))]
+#[cfg_attr(not(target_arch = "x86"), repr(C))]
+#[allow(non_snake_case)]
+pub struct PRINTDLGW {
+    pub lStructSize: DWORD,
+    pub hwndOwner: HWND,
+    pub hDevMode: HGLOBAL,
+    pub hDevNames: HGLOBAL,
+    pub hDC: HDC,
+    pub Flags: DWORD,
+    pub nFromPage: WORD,
+    pub nToPage: WORD,
+    pub nMinPage: WORD,
+    pub nMaxPage: WORD,
+    pub nCopies: WORD,
+    pub hInstance: HINSTANCE,
+    pub lCustData: LPARAM,
+    pub lpfnPrintHook: LPPRINTHOOKPROC,
+    pub lpfnSetupHook: LPSETUPHOOKPROC,
+    pub lpPrintTemplateName: LPCWSTR,
+    pub lpSetupTemplateName: LPCWSTR,
+    pub hPrintTemplate: HGLOBAL,
+    pub hSetupTemplate: HGLOBAL,
+}
+
+// The task dialog structures are packed on every architecture. The winapi definitions are not.
+#[cfg(windows)]
+#[repr(C, packed)]
+#[allow(non_snake_case)]
+pub struct TASKDIALOG_BUTTON {
+    pub nButtonID: c_int,
+    pub pszButtonText: PCWSTR
+}
+
+#[cfg(windows)]
+#[repr(C, packed)]
+#[allow(non_snake_case)]
+pub struct TASKDIALOGCONFIG {
+    pub cbSize: UINT,
+    pub hwndParent: HWND,
+    pub hInstance: HINSTANCE,
+    pub dwFlags: DWORD,
+    pub dwCommonButtons: DWORD,
+    pub pszWindowTitle: PCWSTR,
+    pub hMainIcon: HICON,
+    pub pszMainInstruction: PCWSTR,
+    pub pszContent: PCWSTR,
+    pub cButtons: UINT,
+    pub pButtons: *const TASKDIALOG_BUTTON,
+    pub nDefaultButton: c_int,
+    pub cRadioButtons: UINT,
+    pub pRadioButtons: *const TASKDIALOG_BUTTON,
+    pub nDefaultRadioButton: c_int,
+    pub pszVerificationText: PCWSTR,
+    pub pszExpandedInformation: PCWSTR,
+    pub pszExpandedControlText: PCWSTR,
+    pub pszCollapsedControlText: PCWSTR,
+    pub hFooterIcon: HICON,
+    pub pszFooter: PCWSTR,
+    pub pfCallback: PFTASKDIALOGCALLBACK,
+    pub lpCallbackData: LONG_PTR,
+    pub cxWidth: UINT
+}
+
+// TaskDialogIndirect only exists in comctl32 v6. It is loaded at runtime.
+#[cfg(windows)]
+pub type TaskDialogIndirectProc = unsafe extern "system" fn(pTaskConfig: *const TASKDIALOGCONFIG, pnButton: *mut c_int,
+  pnRadioButton: *mut c_int, pfVerificationFlagChecked: *mut BOOL) -> HRESULT;
+
 // COM interfaces
 // Unused functions have an empty signature
 
 // MACRO taken from winapi. Original author: Peter Atashian (retep998)
+#[cfg(windows)]
 macro_rules! RIDL {
     (interface $interface:ident ($vtbl:ident) {$(
         fn $method:ident(&mut self $(,$p:ident : $t:ty)*) -> $rtr:ty
@@ -279,6 +535,7 @@ macro_rules! RIDL {
     );
 }
 
+#[cfg(windows)]
 RIDL!(
 interface IShellItemArray(IShellItemArrayVtbl): IUnknown(IUnknownVtbl) {
     fn BindToHandler(&mut self) -> (),
@@ -292,6 +549,7 @@ interface IShellItemArray(IShellItemArrayVtbl): IUnknown(IUnknownVtbl) {
 );
 
 // System extern
+#[cfg(windows)]
 extern "system" {
     pub fn GetMenuItemCount(menu: HMENU) -> c_int;
     pub fn GetSubMenu(hMenu: HMENU, nPos: c_int) -> HMENU;
@@ -301,9 +559,18 @@ extern "system" {
     pub fn SetMenuItemInfoW(hMenu: HMENU, uItem: UINT, gByPosition: BOOL, lpmii: &mut MENUITEMINFOW) -> BOOL;
     pub fn GetMenuItemInfoW(hMenu: HMENU, uItem: UINT, gByPosition: BOOL, lpmii: &mut MENUITEMINFOW) -> BOOL;
     pub fn TrackPopupMenuEx(hmenu: HMENU, fuFlags: UINT, x: c_int, y: c_int, hwnd: HWND, lptpm: *mut c_void) -> BOOL;
+    pub fn SetMenuDefaultItem(hMenu: HMENU, uItem: UINT, fByPos: UINT) -> BOOL;
+    pub fn TranslateAcceleratorW(hWnd: HWND, hAccTable: HACCEL, lpMsg: *const MSG) -> c_int;
 
+    pub fn Shell_NotifyIconW(dwMessage: DWORD, lpData: *mut NOTIFYICONDATAW) -> BOOL;
     pub fn SHCreateItemFromParsingName(pszPath: PCWSTR, pbc: *mut IBindCtx, riid: REFIID, ppv: *mut *mut c_void) -> HRESULT;
 
+    pub fn ChooseColorW(lpcc: *mut CHOOSECOLORW) -> BOOL;
+    pub fn ChooseFontW(lpcf: *mut CHOOSEFONTW) -> BOOL;
+    pub fn PrintDlgW(lppd: *mut PRINTDLGW) -> BOOL;
+
+    pub fn SetWindowTheme(hwnd: HWND, pszSubAppName: LPCWSTR, pszSubIdList: LPCWSTR) -> HRESULT;
+
     #[cfg(feature = "canvas")]
     pub fn D2D1CreateFactory(
         factoryType: D2D1_FACTORY_TYPE,
@@ -342,6 +609,14 @@ pub struct BindArgs<ID: Hash+Clone+'static> {
     pub cb: Box<EventCallback<ID>>
 }
 
+pub struct LayoutArgs<ID: Hash+Clone+'static> {
+    pub id: u64,
+    pub layout: Option<Layout<ID>>
+}
+
+/// The reserved callback id of the layouts on the `Resized` event of their parent. Kept apart from the hashed user callback ids.
+pub const LAYOUT_CALLBACK_ID: u64 = 0x4E57_475F_4C41_594F;
+
 pub struct UnbindArgs {
     pub id: u64,
     pub cb_id: u64,
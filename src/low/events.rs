@@ -2,43 +2,45 @@
     Low level events functions
 */
 #![allow(non_upper_case_globals, unused_variables)]
-use std::{fmt, any, ptr, mem};
+use std::{fmt, mem};
 use std::hash::{Hash, Hasher};
 
-use winapi::{HWND, UINT, DWORD, WPARAM, LPARAM, UINT_PTR, DWORD_PTR, LRESULT, WORD, HIWORD, NMHDR,
- HMENU, c_int};
+use winapi::{HWND, UINT, DWORD, WPARAM, LPARAM, LRESULT, WORD, HIWORD, LOWORD, NMHDR};
+#[cfg(windows)] use winapi::{UINT_PTR, DWORD_PTR, HMENU, c_int, WM_TIMER, WM_MENUCOMMAND, WM_LBUTTONDBLCLK};
+#[cfg(windows)] use low::defs::{NWG_TRAY, NIN_BALLOONUSERCLICK, NWG_FILE_DIALOG, FILE_DIALOG_FOLDER_CHANGED, FILE_DIALOG_SELECTION_CHANGED};
 
 use winapi::{WM_MOVE, WM_SIZING, WM_SIZE, WM_EXITSIZEMOVE, WM_PAINT, WM_UNICHAR, WM_CHAR,
   WM_CLOSE, WM_LBUTTONUP, WM_RBUTTONUP, WM_MBUTTONUP, WM_LBUTTONDOWN, WM_RBUTTONDOWN,
   WM_MBUTTONDOWN, WM_KEYDOWN, WM_KEYUP, BN_CLICKED, BN_DBLCLK, BN_SETFOCUS, BN_KILLFOCUS,
-  DTN_CLOSEUP, WM_COMMAND, WM_NOTIFY, WM_TIMER, WM_MENUCOMMAND, TVN_SELCHANGEDW, WM_MOUSEMOVE,
+  DTN_CLOSEUP, WM_COMMAND, WM_NOTIFY, TVN_SELCHANGEDW, WM_MOUSEMOVE,
   NM_CLICK, NM_DBLCLK, NM_KILLFOCUS, NM_SETFOCUS, TVN_ITEMCHANGEDW, TVN_ITEMCHANGINGW, TVN_ITEMEXPANDEDW,
-  TVN_ITEMEXPANDINGW, TVN_DELETEITEMW};
+  TVN_ITEMEXPANDINGW, TVN_DELETEITEMW, WM_MOUSEWHEEL, WM_MOUSEHWHEEL, HTREEITEM, WM_DRAWITEM, DRAWITEMSTRUCT};
 
 use ui::UiInner;
-use events::EventArgs;
-use controls::{AnyHandle, Timer};
-use low::menu_helper::get_menu_id;
-use low::defs::{NWG_DESTROY, CBN_SELCHANGE, CBN_KILLFOCUS, CBN_SETFOCUS, STN_CLICKED, STN_DBLCLK,
+use events::{EventArgs, EventOutcome};
+use defs::{KeyModifiers, ButtonState};
+use controls::AnyHandle;
+use low::defs::{NWG_DESTROY, NWG_NUMERIC_CHANGED, NWG_SLIDER_CHANGED, CBN_SELCHANGE, CBN_KILLFOCUS, CBN_SETFOCUS, STN_CLICKED, STN_DBLCLK,
   LBN_SELCHANGE, LBN_DBLCLK, LBN_SETFOCUS, LBN_KILLFOCUS, EN_SETFOCUS, EN_KILLFOCUS, EN_UPDATE,
-  EN_MAXTEXT};
+  EN_MAXTEXT, ODT_BUTTON, ODT_STATIC};
 
-use winapi::commctrl::{NM_CUSTOMDRAW};
+use winapi::commctrl::{NM_CUSTOMDRAW, LVN_ITEMACTIVATE, LVN_COLUMNCLICK, LVN_ITEMCHANGED, LVN_ODSTATECHANGED, BCN_DROPDOWN};
 
 /// A magic number to identify the NWG subclass that dispatches events
+#[cfg(windows)]
 const EVENTS_DISPATCH_ID: UINT_PTR = 2465;
 
 /**
     A procedure signature that takes raw message parameters and output a EventArgs structure.
     Can return None if the parameters could not be parsed
 */
-pub type UnpackProc = Fn(HWND, UINT, WPARAM, LPARAM) -> Option<EventArgs>;
+pub type UnpackProc = Fn(HWND, UINT, WPARAM, LPARAM) -> Option<EventArgs> + Sync;
 
 /**
     A procedure signature that takes raw message parameters and output a Handle
     Can return None if the handle could not be parsed
 */
-pub type HandleProc = Fn(HWND, UINT, WPARAM, LPARAM) -> Option<AnyHandle>;
+pub type HandleProc = Fn(HWND, UINT, WPARAM, LPARAM) -> Option<AnyHandle> + Sync;
 
 /**
     An enum that define events that can be used by NWG
@@ -55,6 +57,37 @@ pub enum Event {
     Group(&'static [UINT], &'static UnpackProc, &'static HandleProc)
 }
 
+impl Event {
+
+    /**
+        Return the value sent back to the system when a callback returns `EventOutcome::Stop`.
+        Returns `None` if the event cannot be vetoed. In that case, `Stop` only skips the next callbacks.
+
+        Vetoable events:  
+          • `Closed`: The window is not closed  
+          • `Char`: The character is not sent to the control  
+          • `treeview::ItemChanging`: The item state is not changed  
+          • `treeview::ItemExpanding`: The item is not expanded or collapsed  
+    */
+    pub fn veto_result(&self) -> Option<LRESULT> {
+        if *self == Closed || *self == Char {
+            Some(0)
+        } else if *self == TreeViewItemChanging || *self == TreeViewItemExpanding {
+            Some(1)
+        } else {
+            None
+        }
+    }
+
+    /**
+        Return `true` if callbacks can cancel the system action of the event by returning `EventOutcome::Stop`
+    */
+    pub fn vetoable(&self) -> bool {
+        self.veto_result().is_some()
+    }
+
+}
+
 impl PartialEq for Event {
     fn eq(&self, other: &Event) -> bool {
         use std::collections::hash_map::DefaultHasher;
@@ -136,117 +169,229 @@ pub fn notify_2_handle(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM, cmd1: DWORD,
     }
 }
 
+#[cfg(windows)]
 fn menuitem_handle(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<AnyHandle> {
+    use low::menu_helper::{get_menu_id, find_menuitem_parent};
+    use shortcut::SHORTCUT_COMMAND_FLAG;
+    use user32::GetMenu;
+
     unsafe{
+        if msg == WM_COMMAND {
+            // A menu item triggered by an accelerator table sends a WM_COMMAND with its id instead of a WM_MENUCOMMAND
+            let id = LOWORD(w as DWORD);
+            if !is_accelerator_command(w, l) || id & SHORTCUT_COMMAND_FLAG != 0 { return None; }
+            return find_menuitem_parent(GetMenu(hwnd), id as UINT).map(|parent| AnyHandle::HMENU_ITEM(parent, id as UINT));
+        }
+
         let parent_menu: HMENU = mem::transmute(l);
         Some(AnyHandle::HMENU_ITEM(parent_menu, get_menu_id(parent_menu, w as c_int)))
     }
 }
 
+/// Check if a WM_COMMAND was sent by an accelerator table. Controls notifications use the same message, but they set `l` to their handle.
+fn is_accelerator_command(w: WPARAM, l: LPARAM) -> bool {
+    HIWORD(w as DWORD) == 1 && l == 0
+}
+
+#[cfg(windows)]
+fn accelerator_handle(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<AnyHandle> {
+    use shortcut::SHORTCUT_COMMAND_FLAG;
+
+    if is_accelerator_command(w, l) && LOWORD(w as DWORD) & SHORTCUT_COMMAND_FLAG != 0 {
+        Some( AnyHandle::Custom(::std::any::TypeId::of::<::controls::Accelerator>(), hwnd as usize) )
+    } else {
+        None
+    }
+}
+
 // Definition of common system events
-pub const Destroyed: Event = Event::Single(NWG_DESTROY, &event_unpack_no_args, &hwnd_handle);
-pub const Paint: Event = Event::Single(WM_PAINT, &event_unpack_no_args, &hwnd_handle);
-pub const Closed: Event = Event::Single(WM_CLOSE, &event_unpack_no_args, &hwnd_handle);
-pub const Moved: Event = Event::Single(WM_MOVE, &unpack_move, &hwnd_handle);
-pub const KeyDown: Event = Event::Single(WM_KEYDOWN, &unpack_key, &hwnd_handle);
-pub const KeyUp: Event = Event::Single(WM_KEYUP, &unpack_key, &hwnd_handle);
-pub const Resized: Event = Event::Group(&[WM_SIZING, WM_SIZE, WM_EXITSIZEMOVE], &unpack_size, &hwnd_handle);
-pub const Char: Event = Event::Group(&[WM_UNICHAR, WM_CHAR], &unpack_char, &hwnd_handle);
-pub const MouseUp: Event = Event::Group(&[WM_LBUTTONUP, WM_RBUTTONUP, WM_MBUTTONUP], &unpack_mouseclick, &hwnd_handle);
-pub const MouseDown: Event = Event::Group(&[WM_LBUTTONDOWN, WM_RBUTTONDOWN, WM_MBUTTONDOWN], &unpack_mouseclick, &hwnd_handle);
-pub const MouseMove: Event = Event::Single(WM_MOUSEMOVE, &unpack_mousemove, &hwnd_handle);
+// Events are statics (and not consts) because events are hashed using the address of their procedures. A const would have a different copy in every crate.
+pub static Destroyed: Event = Event::Single(NWG_DESTROY, &event_unpack_no_args, &hwnd_handle);
+pub static Paint: Event = Event::Single(WM_PAINT, &event_unpack_no_args, &hwnd_handle);
+pub static Closed: Event = Event::Single(WM_CLOSE, &event_unpack_no_args, &hwnd_handle);
+pub static Moved: Event = Event::Single(WM_MOVE, &unpack_move, &hwnd_handle);
+pub static KeyDown: Event = Event::Single(WM_KEYDOWN, &unpack_key, &hwnd_handle);
+pub static KeyUp: Event = Event::Single(WM_KEYUP, &unpack_key, &hwnd_handle);
+pub static Resized: Event = Event::Group(&[WM_SIZING, WM_SIZE, WM_EXITSIZEMOVE], &unpack_size, &hwnd_handle);
+pub static Char: Event = Event::Group(&[WM_UNICHAR, WM_CHAR], &unpack_char, &hwnd_handle);
+pub static MouseUp: Event = Event::Group(&[WM_LBUTTONUP, WM_RBUTTONUP, WM_MBUTTONUP], &unpack_mouseclick, &hwnd_handle);
+pub static MouseDown: Event = Event::Group(&[WM_LBUTTONDOWN, WM_RBUTTONDOWN, WM_MBUTTONDOWN], &unpack_mouseclick, &hwnd_handle);
+pub static MouseMove: Event = Event::Single(WM_MOUSEMOVE, &unpack_mousemove, &hwnd_handle);
+pub static MouseWheel: Event = Event::Group(&[WM_MOUSEWHEEL, WM_MOUSEHWHEEL], &unpack_mousewheel, &hwnd_handle);
 
 // Button events
 const btnclick_h:&'static HandleProc = &|h,m,w,l|{ command_handle(h,m,w,l,BN_CLICKED)};
-pub const BtnClick: Event = Event::Single(WM_COMMAND, &event_unpack_no_args,btnclick_h);
+pub static BtnClick: Event = Event::Single(WM_COMMAND, &event_unpack_no_args,btnclick_h);
 
 const btndoubleclick_h:&'static HandleProc = &|h,m,w,l|{ command_handle(h,m,w,l,BN_DBLCLK)};
-pub const BtnDoubleClick: Event = Event::Single(WM_COMMAND, &event_unpack_no_args, btndoubleclick_h);
+pub static BtnDoubleClick: Event = Event::Single(WM_COMMAND, &event_unpack_no_args, btndoubleclick_h);
 
 
 const btnfocus_h:&'static HandleProc = &|h,m,w,l|{ command_2_handle(h,m,w,l,BN_SETFOCUS,BN_KILLFOCUS) };
-pub const BtnFocus: Event = Event::Single(WM_COMMAND, &unpack_btn_focus, &btnclick_h);
+pub static BtnFocus: Event = Event::Single(WM_COMMAND, &unpack_btn_focus, &btnclick_h);
+
+const btndropdown_h:&'static HandleProc = &|h,m,w,l|{ notify_handle(h,m,w,l,BCN_DROPDOWN) };
+pub static BtnDropDown: Event = Event::Single(WM_NOTIFY, &event_unpack_no_args, btndropdown_h);
+
+const btndraw_h:&'static HandleProc = &|h,m,w,l|{ drawitem_handle(h,m,w,l,ODT_BUTTON) };
+pub static BtnDraw: Event = Event::Single(WM_DRAWITEM, &unpack_btn_draw, btndraw_h);
 
 // Combobox events
 const cbnfocus_h:&'static HandleProc = &|h,m,w,l|{ command_2_handle(h,m,w,l,CBN_SETFOCUS,CBN_KILLFOCUS) };
-pub const CbnFocus: Event = Event::Single(WM_COMMAND, &unpack_cbn_focus,cbnfocus_h);
+pub static CbnFocus: Event = Event::Single(WM_COMMAND, &unpack_cbn_focus,cbnfocus_h);
 
 const cbnselectionchanged_h:&'static HandleProc = &|h,m,w,l|{ command_handle(h,m,w,l,CBN_SELCHANGE) };
-pub const CbnSelectionChanged: Event = Event::Single(WM_COMMAND, &event_unpack_no_args, cbnselectionchanged_h);
+pub static CbnSelectionChanged: Event = Event::Single(WM_COMMAND, &unpack_cbn_selection, cbnselectionchanged_h);
 
 // Static events
 const stnclick_h: &'static HandleProc = &|h,m,w,l|{ command_handle(h,m,w,l,STN_CLICKED) };
-pub const StnClick: Event = Event::Single(WM_COMMAND, &event_unpack_no_args,stnclick_h);
+pub static StnClick: Event = Event::Single(WM_COMMAND, &event_unpack_no_args,stnclick_h);
 
 const stndoubleclick_h: &'static HandleProc = &|h,m,w,l|{ command_handle(h,m,w,l,STN_DBLCLK) };
-pub const StnDoubleClick: Event = Event::Single(WM_COMMAND, &event_unpack_no_args, stndoubleclick_h);
+pub static StnDoubleClick: Event = Event::Single(WM_COMMAND, &event_unpack_no_args, stndoubleclick_h);
+
+// Owner drawn labels send the same WM_DRAWITEM message as the owner drawn buttons. A label is never hot, pressed or focused.
+const stndraw_h: &'static HandleProc = &|h,m,w,l|{ drawitem_handle(h,m,w,l,ODT_STATIC) };
+pub static StnDraw: Event = Event::Single(WM_DRAWITEM, &unpack_btn_draw, stndraw_h);
 
 // Datepicker events
 const datechanged_h: &'static HandleProc = &|h,m,w,l|{ notify_handle(h,m,w,l, DTN_CLOSEUP) };
-pub const DateChanged: Event = Event::Single(WM_NOTIFY, &event_unpack_no_args,datechanged_h);
+pub static DateChanged: Event = Event::Single(WM_NOTIFY, &unpack_date, datechanged_h);
 
 // Listbox events
 const lbnselectionchange_h: &'static HandleProc = &|h,m,w,l|{ command_handle(h,m,w,l,LBN_SELCHANGE) };
-pub const LbnSelectionChanged: Event = Event::Single(WM_COMMAND, &event_unpack_no_args, lbnselectionchange_h);
+pub static LbnSelectionChanged: Event = Event::Single(WM_COMMAND, &unpack_lbn_selection, lbnselectionchange_h);
 
 const lbndoubleclick_h: &'static HandleProc = &|h,m,w,l|{ command_handle(h,m,w,l,LBN_DBLCLK) };
-pub const LbnDoubleClick: Event = Event::Single(WM_COMMAND, &event_unpack_no_args, lbndoubleclick_h);
+pub static LbnDoubleClick: Event = Event::Single(WM_COMMAND, &unpack_lbn_selection, lbndoubleclick_h);
 
 const lbnfocus_h: &'static HandleProc = &|h,m,w,l|{ command_2_handle(h,m,w,l,LBN_SETFOCUS,LBN_KILLFOCUS) };
-pub const LbnFocus: Event = Event::Single(WM_COMMAND, &unpack_lbn_focus, lbnfocus_h);
+pub static LbnFocus: Event = Event::Single(WM_COMMAND, &unpack_lbn_focus, lbnfocus_h);
 
 // Textedit events
 const envaluechanged_h: &'static HandleProc = &|h,m,w,l|{ command_handle(h,m,w,l,EN_UPDATE) };
-pub const EnValueChanged: Event = Event::Single(WM_COMMAND, &event_unpack_no_args, envaluechanged_h);
+pub static EnValueChanged: Event = Event::Single(WM_COMMAND, &event_unpack_no_args, envaluechanged_h);
 const enlimit_h: &'static HandleProc = &|h,m,w,l|{ command_handle(h,m,w,l,EN_MAXTEXT) };
-pub const EnLimit: Event = Event::Single(WM_COMMAND, &event_unpack_no_args, enlimit_h);
+pub static EnLimit: Event = Event::Single(WM_COMMAND, &event_unpack_no_args, enlimit_h);
 const enfocus_h: &'static HandleProc = &|h,m,w,l|{ command_2_handle(h,m,w,l,EN_SETFOCUS,EN_KILLFOCUS) };
-pub const EnFocus: Event = Event::Single(WM_COMMAND, &unpack_en_focus, enfocus_h);
+pub static EnFocus: Event = Event::Single(WM_COMMAND, &unpack_en_focus, enfocus_h);
+
+// Numeric input events
+// The numeric input sends this message to its edit control with a pointer to the new value
+pub static NumericValueChanged: Event = Event::Single(NWG_NUMERIC_CHANGED, &unpack_number, &hwnd_handle);
+
+// Slider events
+// The slider sends this message to itself with the new position when the user moves the thumb
+pub static SliderValueChanged: Event = Event::Single(NWG_SLIDER_CHANGED, &unpack_slider, &hwnd_handle);
+
+// Toolbar events
+// Toolbar buttons send a WM_COMMAND to the toolbar parent with the button id in the low word of wparam
+const toolbarclick_h: &'static HandleProc = &|h,m,w,l|{ command_handle(h,m,w,l,BN_CLICKED) };
+pub static ToolbarClick: Event = Event::Single(WM_COMMAND, &unpack_toolbar_click, toolbarclick_h);
 
 // Timer events
-const timertick_h: &'static HandleProc = &|h,m,w,l|{ Some( AnyHandle::Custom(any::TypeId::of::<Timer>(), w as usize) ) };
-pub const TimerTick: Event = Event::Single(WM_TIMER, &event_unpack_no_args, timertick_h);
+#[cfg(windows)]
+const timertick_h: &'static HandleProc = &|h,m,w,l|{ Some( AnyHandle::Custom(::std::any::TypeId::of::<::controls::Timer>(), w as usize) ) };
+#[cfg(windows)]
+pub static TimerTick: Event = Event::Single(WM_TIMER, &event_unpack_no_args, timertick_h);
+
+// Tray notification events
+// The shell sends NWG_TRAY to the Ui hidden window with the tray id in wparam and the mouse message in the low word of lparam
+#[cfg(windows)]
+fn tray_handle(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM, action: UINT) -> Option<AnyHandle> {
+    if LOWORD(l as DWORD) as UINT == action {
+        Some( AnyHandle::Custom(::std::any::TypeId::of::<::controls::TrayNotification>(), w as usize) )
+    } else {
+        None
+    }
+}
+
+#[cfg(windows)]
+const trayclick_h: &'static HandleProc = &|h,m,w,l|{ tray_handle(h,m,w,l,WM_LBUTTONUP) };
+#[cfg(windows)]
+pub static TrayClick: Event = Event::Single(NWG_TRAY, &event_unpack_no_args, trayclick_h);
+
+#[cfg(windows)]
+const traydoubleclick_h: &'static HandleProc = &|h,m,w,l|{ tray_handle(h,m,w,l,WM_LBUTTONDBLCLK) };
+#[cfg(windows)]
+pub static TrayDoubleClick: Event = Event::Single(NWG_TRAY, &event_unpack_no_args, traydoubleclick_h);
+
+#[cfg(windows)]
+const trayballoonclick_h: &'static HandleProc = &|h,m,w,l|{ tray_handle(h,m,w,l,NIN_BALLOONUSERCLICK) };
+#[cfg(windows)]
+pub static TrayBalloonClick: Event = Event::Single(NWG_TRAY, &event_unpack_no_args, trayballoonclick_h);
+
+// File dialog events
+// The file dialogs send NWG_FILE_DIALOG to the Ui hidden window with the kind of event in wparam and the dialog handle in lparam
+#[cfg(windows)]
+fn file_dialog_handle(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM, action: UINT) -> Option<AnyHandle> {
+    if w as UINT == action {
+        Some( AnyHandle::Custom(::std::any::TypeId::of::<::controls::FileDialog>(), l as usize) )
+    } else {
+        None
+    }
+}
+
+#[cfg(windows)]
+const filedialogfolder_h: &'static HandleProc = &|h,m,w,l|{ file_dialog_handle(h,m,w,l,FILE_DIALOG_FOLDER_CHANGED) };
+#[cfg(windows)]
+pub static FileDialogFolderChanged: Event = Event::Single(NWG_FILE_DIALOG, &event_unpack_no_args, filedialogfolder_h);
+
+#[cfg(windows)]
+const filedialogselection_h: &'static HandleProc = &|h,m,w,l|{ file_dialog_handle(h,m,w,l,FILE_DIALOG_SELECTION_CHANGED) };
+#[cfg(windows)]
+pub static FileDialogSelectionChanged: Event = Event::Single(NWG_FILE_DIALOG, &event_unpack_no_args, filedialogselection_h);
 
 // Menu item events
-pub const MenuTrigger: Event = Event::Single(WM_MENUCOMMAND, &event_unpack_no_args, &menuitem_handle);
+#[cfg(windows)]
+pub static MenuTrigger: Event = Event::Group(&[WM_MENUCOMMAND, WM_COMMAND], &event_unpack_no_args, &menuitem_handle);
+
+// Accelerator events
+#[cfg(windows)]
+pub static AcceleratorTriggered: Event = Event::Single(WM_COMMAND, &unpack_accelerator, &accelerator_handle);
 
 // TreeView events
 const treeviewselectionchanged_h: &'static HandleProc = &|h,m,w,l|{ notify_handle(h,m,w,l, TVN_SELCHANGEDW) };
-pub const TreeViewSelectionChanged: Event = Event::Single(WM_NOTIFY, &event_unpack_no_args, treeviewselectionchanged_h);
+pub static TreeViewSelectionChanged: Event = Event::Single(WM_NOTIFY, &unpack_tree_selection, treeviewselectionchanged_h);
 
 const treeviewclick_h: &'static HandleProc = &|h,m,w,l|{ notify_handle(h,m,w,l, NM_CLICK) };
-pub const TreeViewClick: Event = Event::Single(WM_NOTIFY, &event_unpack_no_args, treeviewselectionchanged_h);
+pub static TreeViewClick: Event = Event::Single(WM_NOTIFY, &event_unpack_no_args, treeviewclick_h);
 
 const treeviewdoubleclick_h: &'static HandleProc = &|h,m,w,l|{ notify_handle(h,m,w,l, NM_DBLCLK) };
-pub const TreeViewDoubleClick: Event = Event::Single(WM_NOTIFY, &event_unpack_no_args,treeviewselectionchanged_h );
+pub static TreeViewDoubleClick: Event = Event::Single(WM_NOTIFY, &event_unpack_no_args, treeviewdoubleclick_h);
 
 const treeviewfocus_h: &'static HandleProc = &|h,m,w,l|{ notify_2_handle(h,m,w,l, NM_KILLFOCUS, NM_SETFOCUS) };
-pub const TreeViewFocus: Event = Event::Single(WM_NOTIFY, &unpack_tree_focus, treeviewselectionchanged_h);
+pub static TreeViewFocus: Event = Event::Single(WM_NOTIFY, &unpack_tree_focus, treeviewfocus_h);
 
 const treeviewdeleteitem_h: &'static HandleProc = &|h,m,w,l|{ notify_handle(h,m,w,l, TVN_DELETEITEMW) };
-pub const TreeViewDeleteItem: Event = Event::Single(WM_NOTIFY, &unpack_tree_focus, treeviewdeleteitem_h);
+pub static TreeViewDeleteItem: Event = Event::Single(WM_NOTIFY, &unpack_tree_delete, treeviewdeleteitem_h);
 
 const treeviewitemchanged: &'static HandleProc = &|h,m,w,l|{ notify_handle(h,m,w,l, TVN_ITEMCHANGEDW) };
-pub const TreeViewItemChanged: Event = Event::Single(WM_NOTIFY, &unpack_tree_focus, treeviewitemchanged);
+pub static TreeViewItemChanged: Event = Event::Single(WM_NOTIFY, &unpack_tree_change, treeviewitemchanged);
 
 const treeviewitemchanging_h: &'static HandleProc = &|h,m,w,l|{ notify_handle(h,m,w,l, TVN_ITEMCHANGINGW) };
-pub const TreeViewItemChanging: Event = Event::Single(WM_NOTIFY, &unpack_tree_focus, treeviewitemchanging_h);
+pub static TreeViewItemChanging: Event = Event::Single(WM_NOTIFY, &unpack_tree_change, treeviewitemchanging_h);
 
 const treeviewitemexpanded_h: &'static HandleProc = &|h,m,w,l|{ notify_handle(h,m,w,l, TVN_ITEMEXPANDEDW) };
-pub const TreeViewItemExpanded: Event = Event::Single(WM_NOTIFY, &unpack_tree_focus, treeviewitemexpanded_h);
+pub static TreeViewItemExpanded: Event = Event::Single(WM_NOTIFY, &unpack_tree_expand, treeviewitemexpanded_h);
 
 const treeviewitemexpanding_h: &'static HandleProc = &|h,m,w,l|{ notify_handle(h,m,w,l, TVN_ITEMEXPANDINGW) };
-pub const TreeViewItemExpanding: Event = Event::Single(WM_NOTIFY, &unpack_tree_focus, treeviewitemexpanding_h);
+pub static TreeViewItemExpanding: Event = Event::Single(WM_NOTIFY, &unpack_tree_expand, treeviewitemexpanding_h);
 
 // ListView events
 // ListView will send this event to it's parent
 const listviewcustomdraw_h: &'static HandleProc = &|h,m,w,l|{ notify_handle(h,m,w,l, NM_CUSTOMDRAW) };
-pub const ListViewCustomDraw: Event = Event::Single(WM_NOTIFY, &unpack_raw, listviewcustomdraw_h);
+pub static ListViewCustomDraw: Event = Event::Single(WM_NOTIFY, &unpack_list_customdraw, listviewcustomdraw_h);
 
-// No unpack
-fn unpack_raw(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs> {
-    Some(EventArgs::Raw(msg,w,l))
-}
+const listviewitemactivated_h: &'static HandleProc = &|h,m,w,l|{ notify_handle(h,m,w,l, LVN_ITEMACTIVATE) };
+pub static ListViewItemActivated: Event = Event::Single(WM_NOTIFY, &unpack_list_activate, listviewitemactivated_h);
+
+const listviewcolumnclick_h: &'static HandleProc = &|h,m,w,l|{ notify_handle(h,m,w,l, LVN_COLUMNCLICK) };
+pub static ListViewColumnClick: Event = Event::Single(WM_NOTIFY, &unpack_list_column, listviewcolumnclick_h);
+
+// Virtual list views send LVN_ODSTATECHANGED when a range of items is selected
+const listviewselectionchanged_h: &'static HandleProc = &|h,m,w,l|{ notify_2_handle(h,m,w,l, LVN_ITEMCHANGED, LVN_ODSTATECHANGED) };
+pub static ListViewSelectionChanged: Event = Event::Single(WM_NOTIFY, &unpack_list_selection, listviewselectionchanged_h);
 
 // Event unpackers for the events defined above
 fn unpack_move(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs> {
@@ -256,6 +401,7 @@ fn unpack_move(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs>
     Some(EventArgs::Position(x as i32, y as i32))
 }
 
+#[cfg(windows)]
 fn unpack_size(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs> {
     use winapi::RECT;
     use user32::GetClientRect;
@@ -269,6 +415,13 @@ fn unpack_size(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs>
     Some(EventArgs::Size(w, h))
 }
 
+#[cfg(not(windows))]
+fn unpack_size(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs> {
+    use low::mock::mock_window_size;
+    let (w, h) = mock_window_size(hwnd);
+    Some(EventArgs::Size(w, h))
+}
+
 fn unpack_char(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs> {
     use winapi::UNICODE_NOCHAR;
 
@@ -307,8 +460,184 @@ fn unpack_mouseclick(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<Even
   Some(EventArgs::MouseClick{btn: btn, pos: (x, y)})
 }
 
+fn unpack_mousewheel(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs> {
+  use winapi::{GET_X_LPARAM, GET_Y_LPARAM};
+
+  // The wheel delta is a signed value stored in the high word of wparam
+  let delta = HIWORD(w as DWORD) as i16 as i32;
+  let x = GET_X_LPARAM(l) as i32; 
+  let y = GET_Y_LPARAM(l) as i32;
+
+  Some(EventArgs::MouseWheel{delta: delta, horizontal: msg == WM_MOUSEHWHEEL, pos: (x, y)})
+}
+
+#[cfg(windows)]
+fn key_modifiers() -> KeyModifiers {
+  use winapi::{VK_SHIFT, VK_CONTROL, VK_MENU};
+  use user32::GetKeyState;
+
+  // The high order bit is set if the key is down
+  unsafe {
+    KeyModifiers{
+      shift: GetKeyState(VK_SHIFT) < 0,
+      ctrl: GetKeyState(VK_CONTROL) < 0,
+      alt: GetKeyState(VK_MENU) < 0
+    }
+  }
+}
+
+#[cfg(not(windows))]
+fn key_modifiers() -> KeyModifiers {
+  KeyModifiers::default()
+}
+
 fn unpack_key(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs> {
-   Some(EventArgs::Key(w as u32))
+   Some(EventArgs::KeyPress{key: w as u32, modifiers: key_modifiers()})
+}
+
+#[cfg(windows)]
+fn unpack_date(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs> {
+    use winapi::{DTM_GETSYSTEMTIME, GDT_VALID, SYSTEMTIME};
+    use user32::SendMessageW;
+    use defs::PickerDate;
+
+    let nmhdr: &NMHDR = unsafe{ &*(l as *const NMHDR) };
+    let mut syst: SYSTEMTIME = unsafe{ mem::zeroed() };
+    let r = unsafe{ SendMessageW(nmhdr.hwndFrom, DTM_GETSYSTEMTIME, 0, &mut syst as *mut SYSTEMTIME as LPARAM) };
+    match r {
+      GDT_VALID => Some(EventArgs::Date(Some(PickerDate{ year: syst.wYear, month: syst.wMonth, day: syst.wDay }))),
+      _ => Some(EventArgs::Date(None))
+    }
+}
+
+#[cfg(not(windows))]
+fn unpack_date(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs> {
+    Some(EventArgs::Date(None))
+}
+
+#[cfg(windows)]
+fn unpack_selected_index(handle: HWND, get_cursel: UINT) -> Option<EventArgs> {
+    use user32::SendMessageW;
+    let index = unsafe{ SendMessageW(handle, get_cursel, 0, 0) };
+    if index < 0 { Some(EventArgs::SelectedIndex(None)) }
+    else { Some(EventArgs::SelectedIndex(Some(index as usize))) }
+}
+
+#[cfg(not(windows))]
+fn unpack_selected_index(handle: HWND, get_cursel: UINT) -> Option<EventArgs> {
+    Some(EventArgs::SelectedIndex(None))
+}
+
+fn unpack_cbn_selection(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs> {
+    use low::defs::CB_GETCURSEL;
+    unpack_selected_index(l as HWND, CB_GETCURSEL)
+}
+
+fn unpack_lbn_selection(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs> {
+    use low::defs::LB_GETCURSEL;
+    unpack_selected_index(l as HWND, LB_GETCURSEL)
+}
+
+/// Wrap a tree item handle sent by a treeview. Returns None if the item is null.
+fn tree_item_handle(item: HTREEITEM, tree: HWND) -> Option<AnyHandle> {
+    if item.is_null() { None }
+    else { Some(AnyHandle::HTREE_ITEM(item, tree)) }
+}
+
+fn unpack_tree_selection(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs> {
+    use winapi::NMTREEVIEWW;
+    let data: &NMTREEVIEWW = unsafe{ &*(l as *const NMTREEVIEWW) };
+    let tree = data.hdr.hwndFrom;
+    Some(EventArgs::TreeSelection{
+      old: tree_item_handle(data.itemOld.hItem, tree),
+      new: tree_item_handle(data.itemNew.hItem, tree)
+    })
+}
+
+fn unpack_tree_delete(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs> {
+    use winapi::NMTREEVIEWW;
+    let data: &NMTREEVIEWW = unsafe{ &*(l as *const NMTREEVIEWW) };
+    match tree_item_handle(data.itemOld.hItem, data.hdr.hwndFrom) {
+        Some(item) => Some(EventArgs::TreeItem(item)),
+        None => Some(EventArgs::None)
+    }
+}
+
+fn unpack_tree_expand(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs> {
+    use winapi::{NMTREEVIEWW, TVE_EXPAND};
+    let data: &NMTREEVIEWW = unsafe{ &*(l as *const NMTREEVIEWW) };
+    let expanded = (data.action as WPARAM) & TVE_EXPAND == TVE_EXPAND;
+    tree_item_handle(data.itemNew.hItem, data.hdr.hwndFrom).map(|item| EventArgs::TreeItemExpand{item: item, expanded: expanded})
+}
+
+fn unpack_tree_change(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs> {
+    use winapi::NMTVITEMCHANGE;
+    let data: &NMTVITEMCHANGE = unsafe{ &*(l as *const NMTVITEMCHANGE) };
+    tree_item_handle(data.hItem, data.hdr.hwndFrom).map(|item| EventArgs::TreeItem(item))
+}
+
+fn unpack_list_customdraw(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs> {
+    use winapi::commctrl::NMLVCUSTOMDRAW;
+    let data: &NMLVCUSTOMDRAW = unsafe{ &*(l as *const NMLVCUSTOMDRAW) };
+    Some(EventArgs::CustomDraw{
+      stage: data.nmcd.dwDrawStage as u32,
+      item: data.nmcd.dwItemSpec as usize,
+      sub_item: data.iSubItem as i32,
+      raw: (msg, w, l)
+    })
+}
+
+fn unpack_list_activate(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs> {
+    use winapi::commctrl::NMITEMACTIVATE;
+    let data: &NMITEMACTIVATE = unsafe{ &*(l as *const NMITEMACTIVATE) };
+    if data.iItem < 0 { return None; }
+    Some(EventArgs::ListItem{item: data.iItem as usize, sub_item: data.iSubItem.max(0) as usize})
+}
+
+fn unpack_list_column(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs> {
+    use winapi::commctrl::NMLISTVIEW;
+    let data: &NMLISTVIEW = unsafe{ &*(l as *const NMLISTVIEW) };
+    Some(EventArgs::Column(data.iSubItem as usize))
+}
+
+fn unpack_list_selection(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs> {
+    use winapi::commctrl::{NMLISTVIEW, NMLVODSTATECHANGE, LVIF_STATE, LVIS_SELECTED};
+
+    let nmhdr: &NMHDR = unsafe{ &*(l as *const NMHDR) };
+    if nmhdr.code == LVN_ODSTATECHANGED {
+      let data: &NMLVODSTATECHANGE = unsafe{ &*(l as *const NMLVODSTATECHANGE) };
+      if (data.uNewState ^ data.uOldState) & LVIS_SELECTED != LVIS_SELECTED {
+        return None;
+      }
+
+      return Some(EventArgs::ListSelection{item: None, selected: data.uNewState & LVIS_SELECTED == LVIS_SELECTED});
+    }
+
+    let data: &NMLISTVIEW = unsafe{ &*(l as *const NMLISTVIEW) };
+
+    // LVN_ITEMCHANGED is also sent when the focus, the check state or the text of an item changes
+    let changed = (data.uNewState ^ data.uOldState) & LVIS_SELECTED == LVIS_SELECTED;
+    if data.uChanged & LVIF_STATE != LVIF_STATE || !changed {
+      return None;
+    }
+
+    // An item index of -1 means that the change was applied to every item
+    let item = if data.iItem < 0 { None } else { Some(data.iItem as usize) };
+    Some(EventArgs::ListSelection{item: item, selected: data.uNewState & LVIS_SELECTED == LVIS_SELECTED})
+}
+
+fn unpack_slider(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs> {
+    Some(EventArgs::SliderPosition(w as i32))
+}
+
+fn unpack_toolbar_click(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs> {
+    Some(EventArgs::ToolbarButton(LOWORD(w as DWORD)))
+}
+
+fn unpack_number(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs> {
+    if l == 0 { return None; }
+    let value = unsafe{ *(l as *const f64) };
+    Some(EventArgs::Number(value))
 }
 
 fn unpack_tree_focus(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs> {
@@ -316,6 +645,47 @@ fn unpack_tree_focus(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<Even
     Some(EventArgs::Focus(nmhdr.code==NM_SETFOCUS))
 }
 
+fn unpack_accelerator(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs> {
+    use shortcut::command_shortcut;
+    command_shortcut(LOWORD(w as DWORD)).map(|s| EventArgs::Shortcut(s))
+}
+
+/// HandleProc for the WM_DRAWITEM messages sent by the owner drawn controls of the type `ctl_type`
+fn drawitem_handle(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM, ctl_type: UINT) -> Option<AnyHandle> {
+    let data: &DRAWITEMSTRUCT = unsafe{ &*(l as *const DRAWITEMSTRUCT) };
+    if data.CtlType == ctl_type {
+        Some(AnyHandle::HWND(data.hwndItem))
+    } else {
+        None
+    }
+}
+
+fn unpack_btn_draw(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs> {
+    use low::defs::{ODS_SELECTED, ODS_DISABLED, ODS_FOCUS, ODS_HOTLIGHT};
+
+    let data: &DRAWITEMSTRUCT = unsafe{ &*(l as *const DRAWITEMSTRUCT) };
+    let state = ButtonState {
+        hot: data.itemState & ODS_HOTLIGHT == ODS_HOTLIGHT || is_button_hot(data.hwndItem),
+        pressed: data.itemState & ODS_SELECTED == ODS_SELECTED,
+        focused: data.itemState & ODS_FOCUS == ODS_FOCUS,
+        disabled: data.itemState & ODS_DISABLED == ODS_DISABLED
+    };
+
+    let r = &data.rcItem;
+    Some(EventArgs::ButtonDraw{state: state, dc: data.hDC, rect: (r.left as i32, r.top as i32, r.right as i32, r.bottom as i32)})
+}
+
+// The system do not send the hot state of the owner drawn buttons. The button tracks the mouse itself.
+#[cfg(windows)]
+fn is_button_hot(handle: HWND) -> bool {
+    unsafe{ ::controls::button::is_hot(handle) }
+}
+
+#[cfg(not(windows))]
+fn is_button_hot(handle: HWND) -> bool {
+    false
+}
+
 fn unpack_btn_focus(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventArgs> {
     Some(EventArgs::Focus(HIWORD(w as DWORD)==BN_SETFOCUS))
 }
@@ -333,31 +703,39 @@ fn unpack_en_focus(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<EventA
 }
 
 /**
-  Proc that dispatches the NWG events
+    Dispatch a system message sent to `hwnd` to the callbacks bound in the ui.
+    The `Any` event is triggered for every message that is not a NWG command.
+
+    Returns `Some(result)` if a callback vetoed the event or returned `EventOutcome::Return`. In this case, the message
+    must not be passed to the default window procedure and `result` must be returned to the system.
 */
-#[allow(unused_variables)]
-unsafe extern "system" fn process_events<ID: Hash+Clone+'static>(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM, id: UINT_PTR, data: DWORD_PTR) -> LRESULT {
-    use comctl32::DefSubclassProc;
+pub fn dispatch_message<ID: Hash+Clone+'static>(inner: &mut UiInner<ID>, hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> Option<LRESULT> {
     use low::defs::{NWG_CUSTOM_MIN, NWG_CUSTOM_MAX};
 
-    let inner: &mut UiInner<ID> = mem::transmute(data);
-    let inner_id: u64;
-
-    let trigger_event = |inner: &mut UiInner<ID>, evt: &Event, get_handle: &HandleProc, get_params: &UnpackProc| {
+    let trigger_event = |inner: &mut UiInner<ID>, evt: &Event, get_handle: &HandleProc, get_params: &UnpackProc| -> Option<LRESULT> {
         if let Some(handle) = (get_handle)(hwnd, msg, w, l) {
             if let Some(inner_id) = inner.inner_id_from_handle( &handle ) {
                 if let Some(args) = get_params(hwnd, msg, w, l) {
-                    inner.trigger(inner_id, *evt, args);
+                    return match inner.trigger_outcome(inner_id, *evt, args) {
+                        Ok(EventOutcome::Stop) => evt.veto_result(),
+                        Ok(EventOutcome::Return(result)) => Some(result),
+                        _ => None
+                    };
                 }
             }
         }
+        None
     };
 
     if let Some(events) = inner.event_handlers(msg as u32) {
         for event in events.iter() {
-            match event {
+            let result = match event {
                 &Event::Single(_, p, h) | &Event::Group(_, p, h) => trigger_event(inner, event, h, p),
                 &Event::Any => unreachable!() // Any event is not stored by bind
+            };
+
+            if result.is_some() {
+                return result;
             }
         }
     }
@@ -365,16 +743,38 @@ unsafe extern "system" fn process_events<ID: Hash+Clone+'static>(hwnd: HWND, msg
     // Trigger the `Any` event 
     if msg < NWG_CUSTOM_MIN || msg > NWG_CUSTOM_MAX {
       if let Some(inner_id) = inner.inner_id_from_handle( &AnyHandle::HWND(hwnd) ) {
-        inner.trigger(inner_id, Event::Any, EventArgs::Raw(msg, w, l));
+        if let Ok(EventOutcome::Return(result)) = inner.trigger_outcome(inner_id, Event::Any, EventArgs::Raw(msg, w, l)) {
+          return Some(result);
+        }
       }
     }
 
-    DefSubclassProc(hwnd, msg, w, l)
+    None
+}
+
+/**
+  Proc that dispatches the NWG events. If no callback returned a value, the controls colors are painted.
+*/
+#[cfg(windows)]
+#[allow(unused_variables)]
+unsafe extern "system" fn process_events<ID: Hash+Clone+'static>(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM, id: UINT_PTR, data: DWORD_PTR) -> LRESULT {
+    use comctl32::DefSubclassProc;
+    use low::color_helper::paint_colors;
+
+    let inner: &mut UiInner<ID> = mem::transmute(data);
+    match dispatch_message(inner, hwnd, msg, w, l) {
+        Some(result) => result,
+        None => match paint_colors(hwnd, msg, w, l) {
+            Some(result) => result,
+            None => DefSubclassProc(hwnd, msg, w, l)
+        }
+    }
 }
 
 /**
     Add a subclass that dispatches the system event to the application callbacks to a window control.
 */
+#[cfg(windows)]
 pub fn hook_window_events<ID: Hash+Clone+'static>(uiinner: &mut UiInner<ID>, handle: HWND) { unsafe {
   // While definitely questionable in term of safety, the reference to the UiInner is actually (always)
   // a raw pointer belonging to a Ui. Also, when the Ui goes out of scope, every window control
@@ -386,6 +786,7 @@ pub fn hook_window_events<ID: Hash+Clone+'static>(uiinner: &mut UiInner<ID>, han
 /**
   Remove a subclass and free the associated data
 */
+#[cfg(windows)]
 pub fn unhook_window_events<ID: Hash+Clone+'static>(handle: HWND) { unsafe {
   use comctl32::RemoveWindowSubclass;
   use winapi::{TRUE, DWORD_PTR};
@@ -401,24 +802,59 @@ pub fn unhook_window_events<ID: Hash+Clone+'static>(handle: HWND) { unsafe {
 
     Return once a quit event was received.
 */
+#[cfg(windows)]
 #[inline(always)]
 pub unsafe fn dispatch_events() {
+  use std::ptr;
   use winapi::MSG;
   use user32::{GetMessageW, TranslateMessage, DispatchMessageW};
+  use controls::accelerator::translate_accelerator;
+  use controls::window::translate_dialog_keys;
 
   let mut msg: MSG = mem::uninitialized();
   while GetMessageW(&mut msg, ptr::null_mut(), 0, 0) != 0 {
+      if translate_accelerator(&msg) { continue; }
+      if translate_dialog_keys(&msg) { continue; }
       TranslateMessage(&msg); 
       DispatchMessageW(&msg); 
       // TODO dispatch events sent from other thread / other processes ( after first stable release )
   }
 }
 
+/**
+    Dispatch the messages waiting in the system message queue while `running` returns `true`. Used by `Window.run_modal`.
+
+    If a quit event is received, it is posted again so that the outer `dispatch_events` loop also returns.
+*/
+#[cfg(windows)]
+pub unsafe fn dispatch_modal_events(running: &Fn() -> bool) {
+  use std::ptr;
+  use winapi::{MSG, c_int};
+  use user32::{GetMessageW, TranslateMessage, DispatchMessageW, PostQuitMessage};
+  use controls::accelerator::translate_accelerator;
+  use controls::window::translate_dialog_keys;
+
+  let mut msg: MSG = mem::zeroed();
+  while running() {
+      if GetMessageW(&mut msg, ptr::null_mut(), 0, 0) == 0 {
+          PostQuitMessage(msg.wParam as c_int);
+          return;
+      }
+
+      if translate_accelerator(&msg) { continue; }
+      if translate_dialog_keys(&msg) { continue; }
+      TranslateMessage(&msg); 
+      DispatchMessageW(&msg); 
+  }
+}
+
 /**
     Send a WM_QUIT to the system queue. Breaks the dispatch_events loop.
 */
+#[cfg(windows)]
 #[inline(always)]
 pub unsafe fn exit() {
+  use std::ptr;
   use user32::PostMessageW;
   use winapi::WM_QUIT;
 
@@ -439,7 +875,7 @@ pub fn hash_fn_ptr<T: Sized, H: Hasher>(fnptr: &T, state: &mut H) {
 
 // GetWindowSubclass workaround
 
-#[cfg(target_env="gnu")]
+#[cfg(all(windows, target_env="gnu"))]
 mod hackyty_hack {
     use std::sync::Mutex;
     use std::collections::HashSet;
@@ -494,7 +930,7 @@ mod hackyty_hack {
     }
 }
 
-#[cfg(target_env="gnu")] use self::hackyty_hack::{get_window_subclass, set_window_subclass};
+#[cfg(all(windows, target_env="gnu"))] use self::hackyty_hack::{get_window_subclass, set_window_subclass};
 
 
 #[cfg(target_env="msvc")] use winapi::{SUBCLASSPROC, BOOL};
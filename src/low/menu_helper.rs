@@ -4,7 +4,7 @@
 use std::ptr;
 use std::mem;
 
-use winapi::{HMENU, DWORD, HBRUSH, c_int, UINT, BOOL};
+use winapi::{HMENU, DWORD, HBRUSH, HBITMAP, MENUITEMINFOW, c_int, UINT, BOOL};
 
 use controls::AnyHandle;
 
@@ -131,8 +131,7 @@ pub unsafe fn use_menu_command(h: HMENU) {
 */
 #[inline(always)]
 pub unsafe fn enable_menuitem(h: HMENU, pos: Option<UINT>, id: Option<UINT>, enabled: bool) {
-    use winapi::MENUITEMINFOW;
-    use low::defs::{SetMenuItemInfoW, GetMenuItemCount, MIIM_STATE, MFS_DISABLED, MFS_ENABLED};
+    use low::defs::{SetMenuItemInfoW, GetMenuItemInfoW, GetMenuItemCount, MIIM_STATE, MFS_DISABLED, MFS_ENABLED};
     
     let use_position = id.is_none();
     let choice = if use_position { pos } else { id };
@@ -146,13 +145,10 @@ pub unsafe fn enable_menuitem(h: HMENU, pos: Option<UINT>, id: Option<UINT>, ena
          false => MFS_DISABLED
     };
 
-    let mut info = MENUITEMINFOW { 
-        cbSize: mem::size_of::<MENUITEMINFOW>() as UINT,
-        fMask: MIIM_STATE, fType: 0, fState: state,
-        wID: 0, hSubMenu: ptr::null_mut(), hbmpChecked: ptr::null_mut(),
-        hbmpUnchecked: ptr::null_mut(), dwItemData: 0, dwTypeData: ptr::null_mut(),
-        cch: 0, hbmpItem: ptr::null_mut()
-    };
+    // Keep the other state flags (ex: checked)
+    let mut info = menuitem_info(MIIM_STATE);
+    GetMenuItemInfoW(h, value, use_position as BOOL, &mut info);
+    info.fState = (info.fState & !MFS_DISABLED) | state;
 
     SetMenuItemInfoW(h, value, use_position as BOOL, &mut info);
 }
@@ -213,4 +209,170 @@ pub unsafe fn is_menu_enabled(menu: HMENU, parent: &AnyHandle) -> bool {
 #[inline(always)]
 pub unsafe fn get_menu_id(parent_h: HMENU, index: c_int) -> UINT {
     ::low::defs::GetMenuItemID(parent_h, index)
-}
\ No newline at end of file
+}
+
+/**
+    Search a menu and its submenus for the menu item identified by `id`. Returns the handle of the menu that
+    directly contains the item or None if the item was not found.
+*/
+pub unsafe fn find_menuitem_parent(menu: HMENU, id: UINT) -> Option<HMENU> {
+    use low::defs::{GetMenuItemCount, GetSubMenu, GetMenuItemID};
+
+    if menu.is_null() { return None; }
+
+    for i in 0..GetMenuItemCount(menu) {
+        let sub_menu = GetSubMenu(menu, i as c_int);
+        if sub_menu.is_null() {
+            if GetMenuItemID(menu, i) == id { return Some(menu); }
+        } else if let Some(parent) = find_menuitem_parent(sub_menu, id) {
+            return Some(parent);
+        }
+    }
+
+    None
+}
+
+/**
+    Return the text of a menu item. Returns an empty string if the item was not found.
+*/
+pub unsafe fn get_menuitem_text(h: HMENU, id: UINT) -> String {
+    use winapi::MENUITEMINFOW;
+    use low::defs::{GetMenuItemInfoW, MIIM_STRING};
+    use low::other_helper::from_utf16;
+
+    let mut info = MENUITEMINFOW { 
+        cbSize: mem::size_of::<MENUITEMINFOW>() as UINT,
+        fMask: MIIM_STRING, fType: 0, fState: 0,
+        wID: 0, hSubMenu: ptr::null_mut(), hbmpChecked: ptr::null_mut(),
+        hbmpUnchecked: ptr::null_mut(), dwItemData: 0, dwTypeData: ptr::null_mut(),
+        cch: 0, hbmpItem: ptr::null_mut()
+    };
+
+    // The first call returns the length of the text, not including the null char
+    if GetMenuItemInfoW(h, id, false as BOOL, &mut info) == 0 { return String::new(); }
+
+    let mut buffer: Vec<u16> = vec![0; (info.cch + 1) as usize];
+    info.cch += 1;
+    info.dwTypeData = buffer.as_mut_ptr();
+    GetMenuItemInfoW(h, id, false as BOOL, &mut info);
+
+    from_utf16(&buffer)
+}
+
+/**
+    Set the text of a menu item
+*/
+pub unsafe fn set_menuitem_text(h: HMENU, id: UINT, text: &str) {
+    use winapi::MENUITEMINFOW;
+    use low::defs::{SetMenuItemInfoW, MIIM_STRING};
+    use low::other_helper::to_utf16;
+
+    let mut text = to_utf16(text);
+    let mut info = MENUITEMINFOW { 
+        cbSize: mem::size_of::<MENUITEMINFOW>() as UINT,
+        fMask: MIIM_STRING, fType: 0, fState: 0,
+        wID: 0, hSubMenu: ptr::null_mut(), hbmpChecked: ptr::null_mut(),
+        hbmpUnchecked: ptr::null_mut(), dwItemData: 0, dwTypeData: text.as_mut_ptr(),
+        cch: 0, hbmpItem: ptr::null_mut()
+    };
+
+    SetMenuItemInfoW(h, id, false as BOOL, &mut info);
+}
+
+/**
+    Return a MENUITEMINFOW structure that reads or writes the members selected by `mask`
+*/
+#[inline(always)]
+pub fn menuitem_info(mask: UINT) -> MENUITEMINFOW {
+    MENUITEMINFOW { 
+        cbSize: mem::size_of::<MENUITEMINFOW>() as UINT,
+        fMask: mask, fType: 0, fState: 0,
+        wID: 0, hSubMenu: ptr::null_mut(), hbmpChecked: ptr::null_mut(),
+        hbmpUnchecked: ptr::null_mut(), dwItemData: 0, dwTypeData: ptr::null_mut(),
+        cch: 0, hbmpItem: ptr::null_mut()
+    }
+}
+
+/**
+    Add or remove state flags (ex: `MFS_CHECKED`) of the menu item identified by `id`
+*/
+pub unsafe fn set_menuitem_state_flag(h: HMENU, id: UINT, flag: UINT, value: bool) {
+    use low::defs::{GetMenuItemInfoW, SetMenuItemInfoW, MIIM_STATE};
+
+    let mut info = menuitem_info(MIIM_STATE);
+    GetMenuItemInfoW(h, id, false as BOOL, &mut info);
+    info.fState = match value {
+        true => info.fState | flag,
+        false => info.fState & !flag
+    };
+
+    SetMenuItemInfoW(h, id, false as BOOL, &mut info);
+}
+
+/**
+    Check if a state flag (ex: `MFS_CHECKED`) of the menu item identified by `id` is set
+*/
+pub unsafe fn get_menuitem_state_flag(h: HMENU, id: UINT, flag: UINT) -> bool {
+    use low::defs::{GetMenuItemInfoW, MIIM_STATE};
+
+    let mut info = menuitem_info(MIIM_STATE);
+    GetMenuItemInfoW(h, id, false as BOOL, &mut info);
+    info.fState & flag == flag
+}
+
+/**
+    Display a bullet instead of a check mark when the menu item identified by `id` is checked
+*/
+pub unsafe fn set_menuitem_radio(h: HMENU, id: UINT, radio: bool) {
+    use low::defs::{GetMenuItemInfoW, SetMenuItemInfoW, MIIM_FTYPE, MFT_RADIOCHECK};
+
+    let mut info = menuitem_info(MIIM_FTYPE);
+    GetMenuItemInfoW(h, id, false as BOOL, &mut info);
+    info.fType = match radio {
+        true => info.fType | MFT_RADIOCHECK,
+        false => info.fType & !MFT_RADIOCHECK
+    };
+
+    SetMenuItemInfoW(h, id, false as BOOL, &mut info);
+}
+
+/**
+    Uncheck the radio menu items next to the radio menu item identified by `id`. A radio group ends at the first 
+    item that is not a radio item (ex: a separator or a submenu).
+*/
+pub unsafe fn uncheck_radio_group(h: HMENU, id: UINT) {
+    use low::defs::{GetMenuItemInfoW, GetMenuItemCount, MIIM_FTYPE, MIIM_ID, MIIM_SUBMENU, MIIM_STATE, MFT_RADIOCHECK, MFS_CHECKED};
+
+    let count = GetMenuItemCount(h);
+    let items: Vec<(UINT, bool)> = (0..count).map(|i| {
+        let mut info = menuitem_info(MIIM_FTYPE|MIIM_ID|MIIM_SUBMENU);
+        GetMenuItemInfoW(h, i as UINT, true as BOOL, &mut info);
+        (info.wID, info.hSubMenu.is_null() && info.fType & MFT_RADIOCHECK == MFT_RADIOCHECK)
+    }).collect();
+
+    let index = match items.iter().position(|&(item_id, _)| item_id == id) {
+        Some(i) => i,
+        None => { return; }
+    };
+
+    let before = items[..index].iter().rev().take_while(|&&(_, radio)| radio);
+    let after = items[index+1..].iter().take_while(|&&(_, radio)| radio);
+    for &(item_id, _) in before.chain(after) {
+        let mut info = menuitem_info(MIIM_STATE);
+        GetMenuItemInfoW(h, item_id, false as BOOL, &mut info);
+        if info.fState & MFS_CHECKED == MFS_CHECKED {
+            set_menuitem_state_flag(h, item_id, MFS_CHECKED, false);
+        }
+    }
+}
+
+/**
+    Set the bitmap displayed next to the text of the menu item identified by `id`. If `bitmap` is null, the bitmap is removed.
+*/
+pub unsafe fn set_menuitem_bitmap(h: HMENU, id: UINT, bitmap: HBITMAP) {
+    use low::defs::{SetMenuItemInfoW, MIIM_BITMAP};
+
+    let mut info = menuitem_info(MIIM_BITMAP);
+    info.hbmpItem = bitmap;
+    SetMenuItemInfoW(h, id, false as BOOL, &mut info);
+}
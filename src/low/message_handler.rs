@@ -11,6 +11,8 @@ use std::any::Any;
 use winapi::{HWND, UINT, WPARAM, LPARAM, LRESULT};
 
 use ui::UiInner;
+use controls::AnyHandle;
+use low::backend::Backend;
 use low::other_helper::to_utf16;
 use error::{Error, SystemError};
 
@@ -18,13 +20,12 @@ use error::{Error, SystemError};
 const MESSAGE_HANDLE_CLASS_NAME: &'static str = "NWG_MESSAGE";
 
 /**
-    Object that dispatch events not targeted at any control.
+    Object that dispatch events not targeted at any control. This is the backend used by `Ui::new`.
 
     No automatic resources freeing, `MessageHandle.free` must be called before the struct goes out of scope.
 */
 pub struct MessageHandler<ID: Hash+Clone+'static> {
     pub hwnd: HWND,
-    pub last_error: Option<Error>,
     pub p: PhantomData<ID>
 }
 
@@ -43,7 +44,6 @@ impl<ID: Hash+Clone+'static> MessageHandler<ID> {
             Ok( 
                 MessageHandler::<ID>{ 
                     hwnd: h, 
-                    last_error: None,
                     p: PhantomData,
                 } 
             ),
@@ -51,6 +51,34 @@ impl<ID: Hash+Clone+'static> MessageHandler<ID> {
         }
     }
 
+}
+
+impl<ID: Hash+Clone+'static> Backend<ID> for MessageHandler<ID> {
+
+    /**
+        Hook the message window. This is basically a SAFE hack to process non nwg events that are sent to ui (ie: WM_TIMER)
+        Window gets unhooked just before inner gets dropped.
+    */
+    fn init(&mut self, ui: *mut UiInner<ID>) {
+        use low::events::hook_window_events;
+        unsafe{ hook_window_events(&mut *ui, self.hwnd); }
+    }
+
+    /**
+        Post a message to the message only queue.
+    */
+    fn post(&self, ui: *mut UiInner<ID>, msg: UINT, data: Box<Any>) {
+        use user32::PostMessageW;
+
+        unsafe {
+            let ui_wparam: WPARAM = mem::transmute(ui);
+            let data_ptr: *mut Any = Box::into_raw(data);
+            let data_ptr: *mut *mut Any = Box::into_raw(Box::new(data_ptr));
+            let data_lparam: LPARAM = mem::transmute(data_ptr);
+            PostMessageW(self.hwnd, msg, ui_wparam, data_lparam);
+        }
+    }
+
     /**
         Execute the waiting custom commands in the message queue.
 
@@ -58,45 +86,57 @@ impl<ID: Hash+Clone+'static> MessageHandler<ID> {
         * Returns `Err(Error)` if an error was encountered while executing the waiting events.
           The following events will not be touched.
     */
-    pub fn commit(&mut self) -> Result<(), Error> {
+    fn commit(&mut self) -> Result<(), Error> {
         use winapi::{MSG, PM_REMOVE};
         use user32::{PeekMessageW, DispatchMessageW};
         use low::defs::{NWG_CUSTOM_MAX, NWG_CUSTOM_MIN, COMMIT_FAILED};
 
-        let ok = unsafe{
-            let mut ok = true;
+        unsafe{
             let mut msg: MSG = mem::uninitialized();
             while PeekMessageW(&mut msg, self.hwnd, NWG_CUSTOM_MIN, NWG_CUSTOM_MAX, PM_REMOVE) != 0 {
                 if DispatchMessageW(&msg) == COMMIT_FAILED {
-                    ok = false;
-                    break;
+                    // if the commit failed, it is certain that the last error of the ui is not null
+                    let ui: &mut UiInner<ID> = mem::transmute(msg.wParam);
+                    return Err(ui.last_error.take().unwrap());
                 }
             }
+        }
+
+        Ok(())
+    }
 
-            ok
-        };
+    fn hook_events(&self, ui: *mut UiInner<ID>, handle: &AnyHandle) {
+        use low::events::hook_window_events;
 
-        if ok {
-            Ok(())
-        } else {
-            // if the commit failed, it is certain that last_error is not null
-            Err(self.last_error.take().unwrap())
+        match handle {
+            &AnyHandle::HWND(h) => unsafe{ hook_window_events(&mut *ui, h) }, // Hook the window events if the handle is a HWND
+            _ => { /* Nothing to do for the other controls */}
+        }
+    }
+
+    fn unhook_events(&self, handle: &AnyHandle) {
+        use low::events::unhook_window_events;
+
+        match handle {
+            &AnyHandle::HWND(h) => unhook_window_events::<ID>(h),
+            _ => {/* Nothing to free here */}
         }
     }
 
     /**
-        Post a message to the message only queue.
+        Post a `NWG_PROXY` message to the message only window. Messages posted after the window is destroyed are ignored by the system.
     */
-    pub fn post(&self, ui: *mut UiInner<ID>, msg: UINT, data: Box<Any>) {
+    fn proxy_waker(&self, ui: *mut UiInner<ID>) -> Box<Fn() + Send + Sync> {
         use user32::PostMessageW;
+        use low::defs::NWG_PROXY;
 
-        unsafe {
-            let ui_wparam: WPARAM = mem::transmute(ui);
-            let data_ptr: *mut Any = Box::into_raw(data);
-            let data_ptr: *mut *mut Any = Box::into_raw(Box::new(data_ptr));
-            let data_lparam: LPARAM = mem::transmute(data_ptr);
-            PostMessageW(self.hwnd, msg, ui_wparam, data_lparam);
-        }
+        // Raw pointers are not Send. The values are only sent back to the Ui thread.
+        let (hwnd, ui) = (self.hwnd as usize, ui as usize);
+        Box::new(move || unsafe{ PostMessageW(hwnd as HWND, NWG_PROXY, ui as WPARAM, 0); })
+    }
+
+    fn message_handle(&self) -> HWND {
+        self.hwnd
     }
 
     /**
@@ -105,12 +145,46 @@ impl<ID: Hash+Clone+'static> MessageHandler<ID> {
         If multiple UI were created, the class destruction will silently fail (and it's ok).
         The class will be freed when the last Ui is freed.
     */
-    pub fn free(&self) {
+    fn window_size(&self, handle: HWND) -> (u32, u32) {
+        unsafe{ ::low::window_helper::get_window_size(handle) }
+    }
+
+    fn set_window_geometry(&self, handle: HWND, position: (i32, i32), size: (u32, u32)) {
+        use low::window_helper::{set_window_position, set_window_size};
+        unsafe{
+            set_window_position(handle, position.0, position.1);
+            set_window_size(handle, size.0, size.1, false);
+        }
+    }
+
+    fn visible_children(&self, handle: HWND) -> Vec<HWND> {
+        use user32::{GetWindow, IsWindowVisible};
+        use winapi::{GW_CHILD, GW_HWNDNEXT};
+
+        let mut children = Vec::new();
+        unsafe {
+            let mut child = GetWindow(handle, GW_CHILD);
+            while !child.is_null() {
+                if IsWindowVisible(child) != 0 { children.push(child); }
+                child = GetWindow(child, GW_HWNDNEXT);
+            }
+        }
+
+        children
+    }
+
+    fn free(&mut self) {
         use kernel32::GetModuleHandleW;
         use user32::{DestroyWindow, UnregisterClassW};
+        use low::events::unhook_window_events;
+        use controls::tooltip::free_template_tooltip;
 
         let class_name = to_utf16(MESSAGE_HANDLE_CLASS_NAME);
 
+        unhook_window_events::<ID>(self.hwnd);
+
+        unsafe{ free_template_tooltip(self.hwnd); }
+
         unsafe{ DestroyWindow(self.hwnd); }
         unsafe{ UnregisterClassW(class_name.as_ptr(), GetModuleHandleW(ptr::null_mut())); }
     }
@@ -121,92 +195,35 @@ impl<ID: Hash+Clone+'static> MessageHandler<ID> {
 
     * `msg` holds the nwg command identifier
     * `w`   holds a pointer to the Ui
-    * `l`   holds the parameters for the messages
+    * `l`   holds the parameters for the messages (`NWG_PROXY` has no parameters)
 */
 #[allow(unused_variables)]
 unsafe extern "system" fn message_window_proc<ID: Hash+Clone+'static>(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> LRESULT {
     use user32::{DefWindowProcW};
-    use low::defs::{NWG_PACK_USER_VALUE, NWG_PACK_CONTROL, NWG_UNPACK, NWG_BIND, NWG_UNBIND, NWG_TRIGGER, NWG_PACK_RESOURCE, COMMIT_SUCCESS, COMMIT_FAILED};
-    use low::defs::{PackUserValueArgs, PackControlArgs, UnpackArgs, BindArgs, UnbindArgs, PackResourceArgs, TriggerArgs};
+    use low::defs::{NWG_CUSTOM_MIN, NWG_CUSTOM_MAX, NWG_PROXY, COMMIT_SUCCESS, COMMIT_FAILED};
+
+    // Eval NWG messages
+    if msg < NWG_CUSTOM_MIN || msg >= NWG_CUSTOM_MAX {
+        return DefWindowProcW(hwnd, msg, w, l);
+    }
 
     let ui: &mut UiInner<ID> = mem::transmute(w);
-    let args: *mut *mut Any = mem::transmute::<LPARAM, *mut *mut Any>(l);
-        match msg {
-        WM_NOTIFY => {println!("WM_NOTIFY get in message_handler");return COMMIT_SUCCESS;},
-        _ => {}
-        }
 
-    // Eval NWG messages
-    let (processed, error): (bool, Option<Error>) = match msg {
-        NWG_PACK_USER_VALUE => {
-            let args: Box<Any> = Box::from_raw(*Box::from_raw(args));
-            if let Ok(params) = args.downcast::<PackUserValueArgs<ID>>() {
-                (true, ui.pack_user_value(*params))
-            } else {
-                panic!("Could not downcast command PACK_USER_VALUE args into a PackUserValueArgs struct.");
-            }
-        },
-        NWG_PACK_CONTROL => {
-            let args: Box<Any> = Box::from_raw(*Box::from_raw(args));
-            if let Ok(params) = args.downcast::<PackControlArgs<ID>>() {
-                (true, ui.pack_control(*params))
-            } else {
-                panic!("Could not downcast command PACK_CONTROL args into a PackControlArgs struct.");
-            }
-        },
-        NWG_PACK_RESOURCE => {
-            let args: Box<Any> = Box::from_raw(*Box::from_raw(args));
-            if let Ok(params) = args.downcast::<PackResourceArgs<ID>>() {
-                (true, ui.pack_resource(*params))
-            } else {
-                panic!("Could not downcast command NWG_UNBIND args into a UnbindArgs struct.");
-            }
-        },
-        NWG_UNPACK => {
-            let args: Box<Any> = Box::from_raw(*Box::from_raw(args));
-            if let Ok(params) = args.downcast::<UnpackArgs>() {
-                (true, ui.unpack(*params))
-            } else {
-                panic!("Could not downcast command NWG_UNPACK_CONTROL args into a inner id.");
-            }
-        },
-        NWG_BIND => {
-            let args: Box<Any> = Box::from_raw(*Box::from_raw(args));
-            if let Ok(params) = args.downcast::<BindArgs<ID>>() {
-                (true, ui.bind(*params))
-            } else {
-                panic!("Could not downcast command NWG_BIND args into a BindArgs struct.");
-            }
-        },
-        NWG_UNBIND => {
-            let args: Box<Any> = Box::from_raw(*Box::from_raw(args));
-            if let Ok(params) = args.downcast::<UnbindArgs>() {
-                (true, ui.unbind(*params))
-            } else {
-                panic!("Could not downcast command NWG_UNBIND args into a UnbindArgs struct.");
-            }
-        },
-        NWG_TRIGGER => {
-            let args: Box<Any> = Box::from_raw(*Box::from_raw(args));
-            if let Ok(params) = args.downcast::<TriggerArgs>() {
-                let TriggerArgs{ id, event, args } = *params;
-                (true, ui.trigger(id, event, args))
-            } else {
-                panic!("Could not downcast command NWG_UNBIND args into a UnbindArgs struct.");
-            }
-        },
-        _ => (false, None)
-    };
+    // Proxy messages do not have parameters
+    if msg == NWG_PROXY {
+        ui.run_proxy_tasks();
+        return COMMIT_SUCCESS;
+    }
 
-    if processed {
-        if error.is_some() {
-            ui.messages.last_error = error;
+    let args: *mut *mut Any = mem::transmute::<LPARAM, *mut *mut Any>(l);
+    let args: Box<Any> = Box::from_raw(*Box::from_raw(args));
+
+    match ui.execute_command(msg, args) {
+        Some(e) => {
+            ui.last_error = Some(e);
             COMMIT_FAILED
-        } else {
-            COMMIT_SUCCESS
-        }
-    } else {
-        DefWindowProcW(hwnd, msg, w, l)
+        },
+        None => COMMIT_SUCCESS
     }
 }
 
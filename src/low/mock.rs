@@ -0,0 +1,258 @@
+/*!
+    A backend that do not require any system window. Used to test the logic of an application on any platform.
+
+    Mock controls are stored in a thread local window list that mimics the system window manager.
+*/
+
+use std::hash::Hash;
+use std::any::{Any, TypeId};
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::ptr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use winapi::{HWND, UINT};
+
+use ui::{Ui, UiInner};
+use controls::{ControlT, Control, ControlType, AnyHandle};
+use low::backend::Backend;
+use error::Error;
+
+struct MockWindow {
+    handle: HWND,
+    parent: HWND,
+    text: String,
+    position: (i32, i32),
+    size: (u32, u32),
+    visible: bool,
+    enabled: bool
+}
+
+thread_local!(static MOCK_WINDOWS: RefCell<Vec<MockWindow>> = RefCell::new(Vec::new()));
+thread_local!(static MOCK_NEXT_HANDLE: Cell<usize> = Cell::new(1));
+
+fn create_mock_window(parent: HWND, text: String, position: (i32, i32), size: (u32, u32), visible: bool, enabled: bool) -> HWND {
+    let handle = MOCK_NEXT_HANDLE.with(|next| { let h = next.get(); next.set(h+1); h as HWND });
+    let window = MockWindow{ handle: handle, parent: parent, text: text, position: position, size: size, visible: visible, enabled: enabled };
+    MOCK_WINDOWS.with(|windows| windows.borrow_mut().push(window));
+    handle
+}
+
+/// Destroy a mock window and its children
+fn destroy_mock_window(handle: HWND) {
+    let mut to_destroy = list_mock_children(handle);
+    to_destroy.push(handle);
+    MOCK_WINDOWS.with(|windows| windows.borrow_mut().retain(|w| !to_destroy.contains(&w.handle)));
+}
+
+/// Return the handles of the children of a mock window, recursively
+fn list_mock_children(handle: HWND) -> Vec<HWND> {
+    MOCK_WINDOWS.with(|windows| {
+        let windows = windows.borrow();
+        let mut children: Vec<HWND> = Vec::new();
+        let mut parents = vec![handle];
+        while let Some(parent) = parents.pop() {
+            for w in windows.iter().filter(|w| w.parent == parent) {
+                children.push(w.handle);
+                parents.push(w.handle);
+            }
+        }
+        children
+    })
+}
+
+fn with_mock_window<T, F: FnOnce(&mut MockWindow) -> T>(handle: HWND, f: F) -> T {
+    MOCK_WINDOWS.with(|windows| {
+        let mut windows = windows.borrow_mut();
+        let window = windows.iter_mut().find(|w| w.handle == handle).expect("Mock window was destroyed");
+        f(window)
+    })
+}
+
+/// Return the size of a mock window. Returns (0, 0) if the handle is not a mock window.
+#[cfg(not(windows))]
+pub fn mock_window_size(handle: HWND) -> (u32, u32) {
+    MOCK_WINDOWS.with(|windows| {
+        windows.borrow().iter().find(|w| w.handle == handle).map(|w| w.size).unwrap_or((0, 0))
+    })
+}
+
+/**
+    A Ui backend that keeps the commands in memory and do not require any system window.
+    Only controls that do not call the system (ex: `MockControlT`) can be packed in a ui using this backend.
+
+    System events can be simulated with `Ui::simulate_message`. The tasks sent by a `UiProxy` are executed in `Ui::commit`.
+
+    Usage: `let ui: Ui<&'static str> = Ui::with_backend(Box::new(MockBackend::new()));`
+*/
+pub struct MockBackend<ID: Hash+Clone+'static> {
+    queue: RefCell<VecDeque<(*mut UiInner<ID>, UINT, Box<Any>)>>,
+    proxy_pending: Arc<AtomicBool>,
+    ui: *mut UiInner<ID>,
+    hwnd: HWND
+}
+
+impl<ID: Hash+Clone+'static> MockBackend<ID> {
+
+    /**
+        Create a new mock backend
+    */
+    pub fn new() -> MockBackend<ID> {
+        MockBackend{
+            queue: RefCell::new(VecDeque::new()),
+            proxy_pending: Arc::new(AtomicBool::new(false)),
+            ui: ptr::null_mut(),
+            hwnd: create_mock_window(0 as HWND, String::new(), (0, 0), (0, 0), false, true)
+        }
+    }
+
+}
+
+impl<ID: Hash+Clone+'static> Backend<ID> for MockBackend<ID> {
+
+    fn init(&mut self, ui: *mut UiInner<ID>) {
+        self.ui = ui;
+    }
+
+    fn post(&self, ui: *mut UiInner<ID>, msg: UINT, data: Box<Any>) {
+        self.queue.borrow_mut().push_back((ui, msg, data));
+    }
+
+    fn commit(&mut self) -> Result<(), Error> {
+        loop {
+            // The queue must not be borrowed while the command is executed because callbacks can post new commands
+            let command = self.queue.borrow_mut().pop_front();
+            match command {
+                Some((ui, msg, data)) => {
+                    let ui: &mut UiInner<ID> = unsafe{ &mut *ui };
+                    if let Some(e) = ui.execute_command(msg, data) {
+                        return Err(e);
+                    }
+                },
+                None if self.proxy_pending.swap(false, Ordering::SeqCst) => {
+                    let ui: &mut UiInner<ID> = unsafe{ &mut *self.ui };
+                    ui.run_proxy_tasks();
+                },
+                None => { return Ok(()); }
+            }
+        }
+    }
+
+    fn hook_events(&self, _ui: *mut UiInner<ID>, _handle: &AnyHandle) {
+        // Nothing to do. Events are dispatched with `Ui::simulate_message`
+    }
+
+    fn unhook_events(&self, _handle: &AnyHandle) {}
+
+    fn proxy_waker(&self, _ui: *mut UiInner<ID>) -> Box<Fn() + Send + Sync> {
+        let pending = self.proxy_pending.clone();
+        Box::new(move || pending.store(true, Ordering::SeqCst))
+    }
+
+    fn message_handle(&self) -> HWND {
+        self.hwnd
+    }
+
+    fn window_size(&self, handle: HWND) -> (u32, u32) {
+        with_mock_window(handle, |w| w.size)
+    }
+
+    fn set_window_geometry(&self, handle: HWND, position: (i32, i32), size: (u32, u32)) {
+        with_mock_window(handle, |w| { w.position = position; w.size = size; })
+    }
+
+    fn visible_children(&self, handle: HWND) -> Vec<HWND> {
+        MOCK_WINDOWS.with(|windows| {
+            windows.borrow().iter().filter(|w| w.parent == handle && w.visible).map(|w| w.handle).collect()
+        })
+    }
+
+    fn free(&mut self) {
+        self.queue.borrow_mut().clear();
+        destroy_mock_window(self.hwnd);
+    }
+}
+
+/**
+    A template that creates a mock control. Mock controls do not call the system and can be packed
+    in a Ui using a `MockBackend`.
+
+    Members:
+    • `control_type`: The type returned by `ui.type_of_control`
+    • `text`: The text of the control
+    • `position`: The start position of the control
+    • `size`: The start size of the control
+    • `visible`: If the control should be visible to the user
+    • `disabled`: If the control is disabled
+    • `parent`: The control parent. If None, the control is a top level window
+*/
+#[derive(Clone)]
+pub struct MockControlT<S: Clone+Into<String>, ID: Hash+Clone> {
+    pub control_type: ControlType,
+    pub text: S,
+    pub position: (i32, i32),
+    pub size: (u32, u32),
+    pub visible: bool,
+    pub disabled: bool,
+    pub parent: Option<ID>
+}
+
+impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for MockControlT<S, ID> {
+    fn type_id(&self) -> TypeId { TypeId::of::<MockControl>() }
+
+    fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        let parent = match self.parent.as_ref() {
+            Some(p) => match ui.handle_of(p) {
+                Ok(AnyHandle::HWND(h)) => h,
+                Ok(_) => { return Err(Error::BadParent("The parent of a mock control must be a window-like control.".to_string())); },
+                Err(e) => { return Err(e); }
+            },
+            None => 0 as HWND
+        };
+
+        let handle = create_mock_window(parent, self.text.clone().into(), self.position, self.size, self.visible, !self.disabled);
+        Ok( Box::new(MockControl{handle: handle, control_type: self.control_type.clone()}) )
+    }
+}
+
+/**
+    A mock control
+*/
+pub struct MockControl {
+    handle: HWND,
+    control_type: ControlType
+}
+
+impl MockControl {
+    pub fn get_text(&self) -> String { with_mock_window(self.handle, |w| w.text.clone()) }
+    pub fn set_text<'a>(&self, text: &'a str) { with_mock_window(self.handle, |w| w.text = text.to_string()) }
+    pub fn get_visibility(&self) -> bool { with_mock_window(self.handle, |w| w.visible) }
+    pub fn set_visibility(&self, visible: bool) { with_mock_window(self.handle, |w| w.visible = visible) }
+    pub fn get_position(&self) -> (i32, i32) { with_mock_window(self.handle, |w| w.position) }
+    pub fn set_position(&self, x: i32, y: i32) { with_mock_window(self.handle, |w| w.position = (x, y)) }
+    pub fn get_size(&self) -> (u32, u32) { with_mock_window(self.handle, |w| w.size) }
+    pub fn set_size(&self, w: u32, h: u32) { with_mock_window(self.handle, |win| win.size = (w, h)) }
+    pub fn get_enabled(&self) -> bool { with_mock_window(self.handle, |w| w.enabled) }
+    pub fn set_enabled(&self, e:bool) { with_mock_window(self.handle, |w| w.enabled = e) }
+}
+
+impl Control for MockControl {
+
+    fn handle(&self) -> AnyHandle {
+        AnyHandle::HWND(self.handle)
+    }
+
+    fn control_type(&self) -> ControlType {
+        self.control_type.clone()
+    }
+
+    fn children(&self) -> Vec<AnyHandle> {
+        list_mock_children(self.handle).into_iter().map(|h| AnyHandle::HWND(h)).collect()
+    }
+
+    fn free(&mut self) {
+        destroy_mock_window(self.handle);
+    }
+
+}
@@ -3,9 +3,12 @@
 */
 
 pub mod defs;
-pub mod clsid;
 pub mod events;
-pub mod message_handler;
-pub mod menu_helper;
-pub mod window_helper;
-pub mod other_helper;
\ No newline at end of file
+pub mod backend;
+pub mod mock;
+#[cfg(windows)] pub mod clsid;
+#[cfg(windows)] pub mod message_handler;
+#[cfg(windows)] pub mod menu_helper;
+#[cfg(windows)] pub mod window_helper;
+#[cfg(windows)] pub mod other_helper;
+#[cfg(windows)] pub mod color_helper;
\ No newline at end of file
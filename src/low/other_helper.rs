@@ -5,7 +5,7 @@
 use std::ptr;
 use std::mem;
 
-use winapi::DWORD;
+use winapi::{DWORD, COLORREF};
 
 use defs::{MessageParams, MessageButtons, MessageIcons, MessageChoice};
 
@@ -50,6 +50,20 @@ pub unsafe fn from_wide_ptr(ptr: *mut u16) -> String {
     from_utf16(array)
 }
 
+/**
+    Convert a `[red, green, blue]` color into a system COLORREF
+*/
+pub fn to_colorref(color: [u8; 3]) -> COLORREF {
+    (color[0] as COLORREF) | ((color[1] as COLORREF) << 8) | ((color[2] as COLORREF) << 16)
+}
+
+/**
+    Convert a system COLORREF into a `[red, green, blue]` color
+*/
+pub fn from_colorref(color: COLORREF) -> [u8; 3] {
+    [(color & 0xFF) as u8, ((color >> 8) & 0xFF) as u8, ((color >> 16) & 0xFF) as u8]
+}
+
 /**
     Return a formatted output of the last system error that was raised.
 
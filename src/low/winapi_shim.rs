@@ -0,0 +1,269 @@
+/*!
+    Stand-in for the `winapi` crate on non-windows targets.
+
+    Only the types and the constants used by the portable part of NWG (the Ui, the events definitions
+    and the mock backend) are defined here. Values are copied from winapi.
+*/
+#![allow(non_camel_case_types, non_snake_case, dead_code)]
+
+pub use std::os::raw::{c_void, c_int, c_uint, c_short};
+
+pub type UINT = c_uint;
+pub type WORD = u16;
+pub type DWORD = u32;
+pub type UINT_PTR = usize;
+pub type ULONG_PTR = usize;
+pub type DWORD_PTR = ULONG_PTR;
+pub type LONG_PTR = isize;
+pub type WPARAM = UINT_PTR;
+pub type LPARAM = LONG_PTR;
+pub type LRESULT = LONG_PTR;
+
+pub enum HWND__ {}
+pub enum HDC__ {}
+pub enum HMENU__ {}
+pub enum HFONT__ {}
+pub enum HICON__ {}
+pub enum TREEITEM {}
+
+pub type HWND = *mut HWND__;
+pub type HDC = *mut HDC__;
+pub type HMENU = *mut HMENU__;
+pub type HFONT = *mut HFONT__;
+pub type HICON = *mut HICON__;
+pub type HCURSOR = HICON;
+pub type HANDLE = *mut c_void;
+pub type HTREEITEM = *mut TREEITEM;
+pub type LPWSTR = *mut u16;
+pub type COLORREF = DWORD;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct NMHDR {
+    pub hwndFrom: HWND,
+    pub idFrom: UINT_PTR,
+    pub code: UINT,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct POINT {
+    pub x: c_int,
+    pub y: c_int,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct RECT {
+    pub left: c_int,
+    pub top: c_int,
+    pub right: c_int,
+    pub bottom: c_int,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct TVITEMW {
+    pub mask: UINT,
+    pub hItem: HTREEITEM,
+    pub state: UINT,
+    pub stateMask: UINT,
+    pub pszText: LPWSTR,
+    pub cchTextMax: c_int,
+    pub iImage: c_int,
+    pub iSelectedImage: c_int,
+    pub cChildren: c_int,
+    pub lParam: LPARAM,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct NMTREEVIEWW {
+    pub hdr: NMHDR,
+    pub action: UINT,
+    pub itemOld: TVITEMW,
+    pub itemNew: TVITEMW,
+    pub ptDrag: POINT,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct NMTVITEMCHANGE {
+    pub hdr: NMHDR,
+    pub uChanged: UINT,
+    pub hItem: HTREEITEM,
+    pub uStateNew: UINT,
+    pub uStateOld: UINT,
+    pub lParam: LPARAM,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct NMCUSTOMDRAW {
+    pub hdr: NMHDR,
+    pub dwDrawStage: DWORD,
+    pub hdc: HDC,
+    pub rc: RECT,
+    pub dwItemSpec: DWORD_PTR,
+    pub uItemState: UINT,
+    pub lItemlParam: LPARAM,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct NMLVCUSTOMDRAW {
+    pub nmcd: NMCUSTOMDRAW,
+    pub clrText: COLORREF,
+    pub clrTextBk: COLORREF,
+    pub iSubItem: c_int,
+    pub dwItemType: DWORD,
+    pub clrFace: COLORREF,
+    pub iIconEffect: c_int,
+    pub iIconPhase: c_int,
+    pub iPartId: c_int,
+    pub iStateId: c_int,
+    pub rcText: RECT,
+    pub uAlign: UINT,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct NMLISTVIEW {
+    pub hdr: NMHDR,
+    pub iItem: c_int,
+    pub iSubItem: c_int,
+    pub uNewState: UINT,
+    pub uOldState: UINT,
+    pub uChanged: UINT,
+    pub ptAction: POINT,
+    pub lParam: LPARAM,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct NMITEMACTIVATE {
+    pub hdr: NMHDR,
+    pub iItem: c_int,
+    pub iSubItem: c_int,
+    pub uNewState: UINT,
+    pub uOldState: UINT,
+    pub uChanged: UINT,
+    pub ptAction: POINT,
+    pub lParam: LPARAM,
+    pub uKeyFlags: UINT,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct NMLVODSTATECHANGE {
+    pub hdr: NMHDR,
+    pub iFrom: c_int,
+    pub iTo: c_int,
+    pub uNewState: UINT,
+    pub uOldState: UINT,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct DRAWITEMSTRUCT {
+    pub CtlType: UINT,
+    pub CtlID: UINT,
+    pub itemID: UINT,
+    pub itemAction: UINT,
+    pub itemState: UINT,
+    pub hwndItem: HWND,
+    pub hDC: HDC,
+    pub rcItem: RECT,
+    pub itemData: ULONG_PTR,
+}
+
+pub const CW_USEDEFAULT: c_int = 0x80000000u32 as c_int;
+pub const UNICODE_NOCHAR: WPARAM = 0xffff;
+
+pub const WM_MOVE: UINT = 0x0003;
+pub const WM_SIZE: UINT = 0x0005;
+pub const WM_PAINT: UINT = 0x000F;
+pub const WM_CLOSE: UINT = 0x0010;
+pub const WM_DRAWITEM: UINT = 0x002B;
+pub const WM_NOTIFY: UINT = 0x004E;
+pub const WM_KEYDOWN: UINT = 0x0100;
+pub const WM_KEYUP: UINT = 0x0101;
+pub const WM_CHAR: UINT = 0x0102;
+pub const WM_UNICHAR: UINT = 0x0109;
+pub const WM_COMMAND: UINT = 0x0111;
+pub const WM_TIMER: UINT = 0x0113;
+pub const WM_HSCROLL: UINT = 0x0114;
+pub const WM_VSCROLL: UINT = 0x0115;
+pub const WM_MENUCOMMAND: UINT = 0x0126;
+pub const WM_MOUSEMOVE: UINT = 0x0200;
+pub const WM_LBUTTONDOWN: UINT = 0x0201;
+pub const WM_LBUTTONUP: UINT = 0x0202;
+pub const WM_RBUTTONDOWN: UINT = 0x0204;
+pub const WM_RBUTTONUP: UINT = 0x0205;
+pub const WM_MBUTTONDOWN: UINT = 0x0207;
+pub const WM_MBUTTONUP: UINT = 0x0208;
+pub const WM_MOUSEWHEEL: UINT = 0x020A;
+pub const WM_MOUSEHWHEEL: UINT = 0x020E;
+pub const WM_SIZING: UINT = 0x0214;
+pub const WM_EXITSIZEMOVE: UINT = 0x0232;
+
+pub const BN_CLICKED: WORD = 0;
+pub const BN_DBLCLK: WORD = 5;
+pub const BN_SETFOCUS: WORD = 6;
+pub const BN_KILLFOCUS: WORD = 7;
+
+pub const NM_FIRST: UINT = 0;
+pub const NM_CLICK: UINT = (NM_FIRST as i32 - 2) as UINT;
+pub const NM_DBLCLK: UINT = (NM_FIRST as i32 - 3) as UINT;
+pub const NM_SETFOCUS: UINT = (NM_FIRST as i32 - 7) as UINT;
+pub const NM_KILLFOCUS: UINT = (NM_FIRST as i32 - 8) as UINT;
+
+pub const DTN_FIRST2: UINT = -753i32 as UINT;
+pub const DTN_CLOSEUP: UINT = DTN_FIRST2;
+
+pub const TVN_FIRST: UINT = -400i32 as UINT;
+pub const TVN_ITEMCHANGINGW: UINT = TVN_FIRST - 17;
+pub const TVN_ITEMCHANGEDW: UINT = TVN_FIRST - 19;
+pub const TVN_SELCHANGEDW: UINT = TVN_FIRST - 51;
+pub const TVN_ITEMEXPANDINGW: UINT = TVN_FIRST - 54;
+pub const TVN_ITEMEXPANDEDW: UINT = TVN_FIRST - 55;
+pub const TVN_DELETEITEMW: UINT = TVN_FIRST - 58;
+
+pub const TVE_COLLAPSE: WPARAM = 0x0001;
+pub const TVE_EXPAND: WPARAM = 0x0002;
+
+pub mod commctrl {
+    use super::{UINT, WPARAM, NM_FIRST};
+    pub const NM_CUSTOMDRAW: UINT = (NM_FIRST as i32 - 12) as UINT;
+    pub use super::{NMLVCUSTOMDRAW, NMLISTVIEW, NMITEMACTIVATE, NMLVODSTATECHANGE};
+
+    pub const LVN_FIRST: UINT = -100i32 as UINT;
+    pub const LVN_ITEMCHANGED: UINT = LVN_FIRST - 1;
+    pub const LVN_COLUMNCLICK: UINT = LVN_FIRST - 8;
+    pub const LVN_ITEMACTIVATE: UINT = LVN_FIRST - 14;
+    pub const LVN_ODSTATECHANGED: UINT = LVN_FIRST - 15;
+
+    pub const LVIF_STATE: UINT = 0x00000008;
+    pub const LVIS_SELECTED: UINT = 0x0002;
+
+    pub const TB_ENDTRACK: WPARAM = 8;
+
+    pub const BCN_FIRST: UINT = -1250i32 as UINT;
+    pub const BCN_DROPDOWN: UINT = BCN_FIRST + 0x0002;
+}
+
+pub fn LOWORD(l: DWORD) -> WORD {
+    (l & 0xffff) as WORD
+}
+
+pub fn HIWORD(l: DWORD) -> WORD {
+    ((l >> 16) & 0xffff) as WORD
+}
+
+pub fn GET_X_LPARAM(lp: LPARAM) -> c_int {
+    LOWORD(lp as DWORD) as c_short as c_int
+}
+
+pub fn GET_Y_LPARAM(lp: LPARAM) -> c_int {
+    HIWORD(lp as DWORD) as c_short as c_int
+}
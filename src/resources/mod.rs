@@ -2,9 +2,9 @@
     Resources trait definition
 */
 
-pub mod font;
-pub mod image;
-#[cfg(feature = "canvas")] pub mod canvas;
+#[cfg(windows)] pub mod font;
+#[cfg(windows)] pub mod image;
+#[cfg(all(windows, feature = "canvas"))] pub mod canvas;
 
 use std::any::TypeId;
 use std::hash::Hash;
@@ -13,9 +13,9 @@ use ui::Ui;
 use controls::AnyHandle;
 use error::Error;
 
-pub use self::font::{FontT, Font};
-pub use self::image::{ImageT, OemImageT, MemoryImageT, Image};
-#[cfg(feature = "canvas")] pub use self::canvas::{BrushT, Brush, PenT, Pen};
+#[cfg(windows)] pub use self::font::{FontT, Font};
+#[cfg(windows)] pub use self::image::{ImageT, OemImageT, MemoryImageT, Image};
+#[cfg(all(windows, feature = "canvas"))] pub use self::canvas::{BrushT, Brush, PenT, Pen};
 
 /**
     Structures implementing this trait can be used by a Ui to build a Resource
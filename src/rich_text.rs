@@ -0,0 +1,572 @@
+/*!
+    The document model of the `RichTextBox` control and its RTF reader/writer.
+
+    A `RichDocument` is a list of paragraphs. Each paragraph holds runs of text that share the same character formatting.
+    This module is pure rust: documents can be built, saved and loaded as RTF without any window.
+*/
+
+use error::Error;
+
+/**
+    The character formatting of a run of text. The fields set to `None` use the default value of the control.
+
+    Members:
+    • `bold`, `italic`, `underline`, `strikeout`: The font effects
+    • `color`: The text color as `[red, green, blue]`
+    • `font_family`: The name of the font family (ex: "Consolas")
+    • `font_size`: The size of the font in points
+*/
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct CharFormat {
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub strikeout: bool,
+    pub color: Option<[u8; 3]>,
+    pub font_family: Option<String>,
+    pub font_size: Option<u32>,
+}
+
+/**
+    The alignment of a paragraph
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ParagraphAlign {
+    Left,
+    Center,
+    Right,
+    Justify
+}
+
+/**
+    A run of text that share the same character formatting
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextRun {
+    pub text: String,
+    pub format: CharFormat
+}
+
+/**
+    A paragraph of a `RichDocument`. The text of the runs do not contain line breaks.
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct Paragraph {
+    pub align: ParagraphAlign,
+    pub runs: Vec<TextRun>
+}
+
+impl Paragraph {
+
+    /// Return the text of the paragraph without the formatting
+    pub fn text(&self) -> String {
+        self.runs.iter().map(|r| r.text.as_str()).collect()
+    }
+
+    /// Add text at the end of the paragraph. The text is merged with the last run if they share the same formatting.
+    fn push(&mut self, text: &str, format: &CharFormat) {
+        if text.is_empty() { return; }
+
+        if let Some(last) = self.runs.last_mut() {
+            if last.format == *format {
+                last.text.push_str(text);
+                return;
+            }
+        }
+
+        self.runs.push(TextRun{ text: text.to_string(), format: format.clone() });
+    }
+}
+
+/**
+    A formatted text document. Use `RichTextBox.get_document` and `RichTextBox.set_document` to exchange documents with a control.
+
+    Usage:
+    ```rust
+    extern crate native_windows_gui as nwg;
+    use nwg::{RichDocument, CharFormat, ParagraphAlign};
+
+    # fn main() {
+    let mut doc = RichDocument::new();
+    doc.push_paragraph(ParagraphAlign::Center);
+    doc.push_text("Title", &CharFormat{ bold: true, font_size: Some(16), ..Default::default() });
+    doc.push_paragraph(ParagraphAlign::Left);
+    doc.push_text("Some text", &CharFormat::default());
+
+    let rtf = doc.to_rtf();
+    assert_eq!(RichDocument::from_rtf(&rtf).unwrap(), doc);
+    # }
+    ```
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct RichDocument {
+    pub paragraphs: Vec<Paragraph>
+}
+
+impl RichDocument {
+
+    /// Create an empty document
+    pub fn new() -> RichDocument {
+        RichDocument{ paragraphs: Vec::new() }
+    }
+
+    /// Create a document with a single unformatted paragraph per line of `text`
+    pub fn from_text(text: &str) -> RichDocument {
+        let mut doc = RichDocument::new();
+        doc.push_text(text, &CharFormat::default());
+        doc
+    }
+
+    /// Start a new paragraph at the end of the document
+    pub fn push_paragraph(&mut self, align: ParagraphAlign) {
+        self.paragraphs.push(Paragraph{ align: align, runs: Vec::new() });
+    }
+
+    /**
+        Add text at the end of the last paragraph of the document. Line breaks (`\n`) start new paragraphs with the same alignment.
+        If the document is empty, a left aligned paragraph is created.
+    */
+    pub fn push_text(&mut self, text: &str, format: &CharFormat) {
+        if self.paragraphs.is_empty() {
+            self.push_paragraph(ParagraphAlign::Left);
+        }
+
+        for (i, line) in text.split('\n').enumerate() {
+            if i > 0 {
+                let align = self.paragraphs.last().unwrap().align;
+                self.push_paragraph(align);
+            }
+
+            let line = line.trim_end_matches('\r');
+            self.paragraphs.last_mut().unwrap().push(line, format);
+        }
+    }
+
+    /// Return the text of the document without the formatting. The paragraphs are separated by `\n`.
+    pub fn plain_text(&self) -> String {
+        let lines: Vec<String> = self.paragraphs.iter().map(|p| p.text()).collect();
+        lines.join("\n")
+    }
+
+    /**
+        Write the document as RTF. Non-ASCII characters are written as unicode escapes, so the output is always ASCII.
+    */
+    pub fn to_rtf(&self) -> String {
+        let mut fonts: Vec<&str> = Vec::new();
+        let mut colors: Vec<[u8; 3]> = Vec::new();
+        for run in self.paragraphs.iter().flat_map(|p| p.runs.iter()) {
+            if let Some(ref f) = run.format.font_family {
+                if !fonts.contains(&f.as_str()) { fonts.push(f); }
+            }
+            if let Some(c) = run.format.color {
+                if !colors.contains(&c) { colors.push(c); }
+            }
+        }
+
+        let mut out = String::from("{\\rtf1\\ansi\\ansicpg1252\\deff0\\uc1\n{\\fonttbl");
+        if fonts.is_empty() {
+            out.push_str("{\\f0\\fnil Segoe UI;}");
+        }
+        for (i, f) in fonts.iter().enumerate() {
+            out.push_str(&format!("{{\\f{}\\fnil ", i));
+            write_escaped(&mut out, f);
+            out.push_str(";}");
+        }
+        out.push_str("}\n{\\colortbl ;");
+        for c in colors.iter() {
+            out.push_str(&format!("\\red{}\\green{}\\blue{};", c[0], c[1], c[2]));
+        }
+        out.push_str("}\n");
+
+        for p in self.paragraphs.iter() {
+            out.push_str(match p.align {
+                ParagraphAlign::Left => "\\pard\\ql ",
+                ParagraphAlign::Center => "\\pard\\qc ",
+                ParagraphAlign::Right => "\\pard\\qr ",
+                ParagraphAlign::Justify => "\\pard\\qj ",
+            });
+
+            for run in p.runs.iter() {
+                let f = &run.format;
+                let mut words = String::new();
+                if f.bold { words.push_str("\\b"); }
+                if f.italic { words.push_str("\\i"); }
+                if f.underline { words.push_str("\\ul"); }
+                if f.strikeout { words.push_str("\\strike"); }
+                if let Some(ref family) = f.font_family {
+                    let index = fonts.iter().position(|x| x == family).unwrap();
+                    words.push_str(&format!("\\f{}", index));
+                }
+                if let Some(size) = f.font_size {
+                    // The parameter of a RTF control word is a signed 16 bits value in half points
+                    words.push_str(&format!("\\fs{}", size.min(0x7FFF / 2) * 2));
+                }
+                if let Some(color) = f.color {
+                    let index = colors.iter().position(|&x| x == color).unwrap();
+                    words.push_str(&format!("\\cf{}", index + 1));
+                }
+
+                out.push('{');
+                if !words.is_empty() {
+                    out.push_str(&words);
+                    out.push(' ');
+                }
+                write_escaped(&mut out, &run.text);
+                out.push('}');
+            }
+
+            out.push_str("\\par\n");
+        }
+
+        out.push('}');
+        out
+    }
+
+    /**
+        Read a RTF document. Only the text, the character formatting supported by `CharFormat` and the paragraph alignment are kept.
+        Pictures, tables, styles and the other destinations are ignored. Line breaks (`\line`) start a new paragraph.
+
+        Returns `Err(Error::BadRtf)` if the text is not a RTF document.
+    */
+    pub fn from_rtf(rtf: &str) -> Result<RichDocument, Error> {
+        RtfReader::new(rtf).read()
+    }
+
+}
+
+/// Escape the RTF special characters. Non-ASCII characters are written as `\uN?`.
+fn write_escaped(out: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '{' => out.push_str("\\{"),
+            '}' => out.push_str("\\}"),
+            '\t' => out.push_str("\\tab "),
+            c if (c as u32) < 0x80 => out.push(c),
+            c => {
+                let mut buffer = [0u16; 2];
+                for unit in encode_utf16(c, &mut buffer) {
+                    out.push_str(&format!("\\u{}?", *unit as i16));
+                }
+            }
+        }
+    }
+}
+
+fn encode_utf16(c: char, buffer: &mut [u16; 2]) -> &[u16] {
+    let mut code = c as u32;
+    if code < 0x10000 {
+        buffer[0] = code as u16;
+        &buffer[..1]
+    } else {
+        code -= 0x10000;
+        buffer[0] = 0xD800 | ((code >> 10) as u16);
+        buffer[1] = 0xDC00 | ((code & 0x3FF) as u16);
+        &buffer[..]
+    }
+}
+
+/// Windows-1252 characters in the range 0x80-0x9F. The other bytes match latin-1.
+const CP1252: [u16; 32] = [
+    0x20AC, 0x81, 0x201A, 0x0192, 0x201E, 0x2026, 0x2020, 0x2021, 0x02C6, 0x2030, 0x0160, 0x2039, 0x0152, 0x8D, 0x017D, 0x8F,
+    0x90, 0x2018, 0x2019, 0x201C, 0x201D, 0x2022, 0x2013, 0x2014, 0x02DC, 0x2122, 0x0161, 0x203A, 0x0153, 0x9D, 0x017E, 0x0178
+];
+
+/// Destinations that are ignored by the reader
+const IGNORED_DESTINATIONS: &'static [&'static str] = &["info", "stylesheet", "pict", "header", "footer", "headerl", "headerr",
+  "footerl", "footerr", "object", "listtable", "listoverridetable", "rsidtbl", "generator", "themedata", "colorschememapping",
+  "latentstyles", "datastore", "xmlnstbl", "mmathPr", "filetbl", "revtbl", "fldinst", "footnote", "pntext", "pntxta", "pntxtb"];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Destination {
+    Text,
+    FontTable,
+    ColorTable,
+    Ignored
+}
+
+#[derive(Clone)]
+struct GroupState {
+    format: CharFormat,
+    align: ParagraphAlign,
+    destination: Destination,
+    uc: usize,
+
+    // Number of characters left to skip after a unicode character (`\u`)
+    skip: usize
+}
+
+struct RtfReader<'a> {
+    chars: ::std::iter::Peekable<::std::str::Chars<'a>>,
+    stack: Vec<GroupState>,
+    state: GroupState,
+    fonts: Vec<(i32, String)>,
+    font_name: String,
+    font_index: i32,
+    colors: Vec<Option<[u8; 3]>>,
+    color: Option<[u8; 3]>,
+    paragraphs: Vec<Paragraph>,
+    current: Paragraph,
+    high_surrogate: Option<u16>
+}
+
+impl<'a> RtfReader<'a> {
+
+    fn new(rtf: &'a str) -> RtfReader<'a> {
+        let state = GroupState{ format: CharFormat::default(), align: ParagraphAlign::Left, destination: Destination::Text, uc: 1, skip: 0 };
+        RtfReader {
+            chars: rtf.chars().peekable(),
+            stack: Vec::new(),
+            state: state,
+            fonts: Vec::new(),
+            font_name: String::new(),
+            font_index: 0,
+            colors: Vec::new(),
+            color: None,
+            paragraphs: Vec::new(),
+            current: Paragraph{ align: ParagraphAlign::Left, runs: Vec::new() },
+            high_surrogate: None
+        }
+    }
+
+    fn read(mut self) -> Result<RichDocument, Error> {
+        // Skip the whitespaces before the header
+        while let Some(&c) = self.chars.peek() {
+            if c.is_whitespace() { self.chars.next(); } else { break; }
+        }
+
+        if self.chars.next() != Some('{') || self.chars.next() != Some('\\') || self.read_word() != "rtf" {
+            return Err(Error::BadRtf("Missing the RTF header".to_string()));
+        }
+        self.read_param();
+        self.stack.push(self.state.clone());
+
+        while let Some(c) = self.chars.next() {
+            match c {
+                '{' => {
+                    // A group ends the characters that replace a unicode character
+                    self.state.skip = 0;
+                    self.stack.push(self.state.clone());
+                },
+                '}' => {
+                    self.end_group();
+                    match self.stack.pop() {
+                        Some(state) => { self.state = state; },
+                        None => { return Err(Error::BadRtf("Unbalanced braces".to_string())); }
+                    }
+                    if self.stack.is_empty() {
+                        return Ok(self.finish());
+                    }
+                },
+                '\\' => {
+                    self.read_control();
+                },
+                '\r' | '\n' => {},
+                c => {
+                    self.push_char(c);
+                }
+            }
+        }
+
+        Err(Error::BadRtf("Unbalanced braces".to_string()))
+    }
+
+    fn finish(mut self) -> RichDocument {
+        // The last paragraph mark of a document is always followed by an empty paragraph
+        if !self.current.runs.is_empty() {
+            self.paragraphs.push(self.current);
+        }
+
+        RichDocument{ paragraphs: self.paragraphs }
+    }
+
+    fn read_word(&mut self) -> String {
+        let mut word = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if c.is_ascii_alphabetic() { word.push(c); self.chars.next(); } else { break; }
+        }
+        word
+    }
+
+    /// Read the numeric parameter of a control word and the space that delimits the control word
+    fn read_param(&mut self) -> Option<i32> {
+        let mut digits = String::new();
+        if self.chars.peek() == Some(&'-') {
+            digits.push('-');
+            self.chars.next();
+        }
+
+        while let Some(&c) = self.chars.peek() {
+            if c.is_ascii_digit() { digits.push(c); self.chars.next(); } else { break; }
+        }
+
+        if self.chars.peek() == Some(&' ') {
+            self.chars.next();
+        }
+
+        digits.parse::<i32>().ok()
+    }
+
+    fn read_control(&mut self) {
+        let c = match self.chars.peek() {
+            Some(&c) => c,
+            None => { return; }
+        };
+
+        if !c.is_ascii_alphabetic() {
+            self.chars.next();
+            match c {
+                '\\' | '{' | '}' => self.push_char(c),
+                '~' => self.push_char('\u{A0}'),
+                '_' => self.push_char('\u{2011}'),
+                '*' => { self.state.destination = Destination::Ignored; },
+                '\'' => {
+                    let hex: String = self.chars.by_ref().take(2).collect();
+                    if self.state.skip > 0 {
+                        self.state.skip -= 1;
+                    } else if let Ok(byte) = u8::from_str_radix(&hex, 16) {
+                        let code = if byte >= 0x80 && byte < 0xA0 { CP1252[(byte - 0x80) as usize] } else { byte as u16 };
+                        self.push_unit(code);
+                    }
+                },
+                '\r' | '\n' => self.new_paragraph(),
+                _ => {}
+            }
+            return;
+        }
+
+        let word = self.read_word();
+        let param = self.read_param();
+
+        // A control word replaces one of the characters that follow a unicode character, in every destination
+        if self.state.skip > 0 {
+            self.state.skip -= 1;
+            return;
+        }
+
+        let on = param != Some(0);
+        match word.as_str() {
+            "b" => { self.state.format.bold = on; },
+            "i" => { self.state.format.italic = on; },
+            "ul" => { self.state.format.underline = on; },
+            "ulnone" => { self.state.format.underline = false; },
+            "strike" => { self.state.format.strikeout = on; },
+            "plain" => { self.state.format = CharFormat::default(); },
+            "fs" => { self.state.format.font_size = param.map(|p| (p.max(0) / 2) as u32); },
+            "f" => {
+                let index = param.unwrap_or(0);
+                if self.state.destination == Destination::FontTable {
+                    self.font_index = index;
+                    self.font_name.clear();
+                } else {
+                    self.state.format.font_family = self.fonts.iter().find(|f| f.0 == index).map(|f| f.1.clone());
+                }
+            },
+            "cf" => {
+                let index = param.unwrap_or(0) as usize;
+                self.state.format.color = match self.colors.get(index) { Some(&c) => c, None => None };
+            },
+            "red" => { self.color.get_or_insert([0, 0, 0])[0] = param.unwrap_or(0) as u8; },
+            "green" => { self.color.get_or_insert([0, 0, 0])[1] = param.unwrap_or(0) as u8; },
+            "blue" => { self.color.get_or_insert([0, 0, 0])[2] = param.unwrap_or(0) as u8; },
+            "pard" => { self.state.align = ParagraphAlign::Left; self.current.align = ParagraphAlign::Left; },
+            "ql" => { self.set_align(ParagraphAlign::Left); },
+            "qc" => { self.set_align(ParagraphAlign::Center); },
+            "qr" => { self.set_align(ParagraphAlign::Right); },
+            "qj" => { self.set_align(ParagraphAlign::Justify); },
+            "par" | "line" => { self.new_paragraph(); },
+            "tab" => { self.push_char('\t'); },
+            "emdash" => { self.push_char('\u{2014}'); },
+            "endash" => { self.push_char('\u{2013}'); },
+            "bullet" => { self.push_char('\u{2022}'); },
+            "lquote" => { self.push_char('\u{2018}'); },
+            "rquote" => { self.push_char('\u{2019}'); },
+            "ldblquote" => { self.push_char('\u{201C}'); },
+            "rdblquote" => { self.push_char('\u{201D}'); },
+            "uc" => { self.state.uc = param.unwrap_or(1).max(0) as usize; },
+            "u" => {
+                if let Some(p) = param {
+                    self.push_unit(p as i16 as u16);
+                    self.state.skip = self.state.uc;
+                }
+            },
+            "fonttbl" => { self.state.destination = Destination::FontTable; },
+            "colortbl" => { self.state.destination = Destination::ColorTable; },
+            w if IGNORED_DESTINATIONS.contains(&w) => { self.state.destination = Destination::Ignored; },
+            _ => {}
+        }
+    }
+
+    fn set_align(&mut self, align: ParagraphAlign) {
+        self.state.align = align;
+        self.current.align = align;
+    }
+
+    fn end_group(&mut self) {
+        // The font name of the last entry of the font table may not be terminated by a semicolon
+        if self.state.destination == Destination::FontTable && !self.font_name.trim().is_empty() {
+            self.push_font();
+        }
+    }
+
+    fn push_font(&mut self) {
+        let name = self.font_name.trim().to_string();
+        self.fonts.push((self.font_index, name));
+        self.font_name.clear();
+    }
+
+    fn new_paragraph(&mut self) {
+        if self.state.destination != Destination::Text { return; }
+
+        let align = self.state.align;
+        let paragraph = ::std::mem::replace(&mut self.current, Paragraph{ align: align, runs: Vec::new() });
+        self.paragraphs.push(paragraph);
+    }
+
+    fn push_unit(&mut self, unit: u16) {
+        if unit >= 0xD800 && unit < 0xDC00 {
+            self.high_surrogate = Some(unit);
+            return;
+        }
+
+        let code = match self.high_surrogate.take() {
+            Some(high) if unit >= 0xDC00 && unit < 0xE000 => 0x10000 + (((high as u32) - 0xD800) << 10) + ((unit as u32) - 0xDC00),
+            _ => unit as u32
+        };
+
+        if let Some(c) = ::std::char::from_u32(code) {
+            self.push_text_char(c);
+        }
+    }
+
+    fn push_char(&mut self, c: char) {
+        if self.state.skip > 0 {
+            self.state.skip -= 1;
+            return;
+        }
+
+        self.push_text_char(c);
+    }
+
+    fn push_text_char(&mut self, c: char) {
+        match self.state.destination {
+            Destination::Text => {
+                let mut buffer = [0u8; 4];
+                let format = self.state.format.clone();
+                self.current.push(c.encode_utf8(&mut buffer), &format);
+            },
+            Destination::FontTable => {
+                if c == ';' { self.push_font(); } else { self.font_name.push(c); }
+            },
+            Destination::ColorTable => {
+                // An empty entry (usually the first one) is the automatic color
+                if c == ';' {
+                    let color = self.color.take();
+                    self.colors.push(color);
+                }
+            },
+            Destination::Ignored => {}
+        }
+    }
+
+}
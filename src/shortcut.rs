@@ -0,0 +1,187 @@
+/*!
+    Keyboard shortcuts used by the accelerator tables.
+
+    The parser in this module is pure rust. A shortcut is written as a list of modifiers followed by a key,
+    separated by `+`. Ex: "Ctrl+S", "Ctrl+Shift+N", "Alt+F4", "F5", "Ctrl+Plus"
+*/
+
+use std::fmt;
+use std::str::FromStr;
+
+use defs::KeyModifiers;
+use error::Error;
+
+/// Names and virtual key codes of the keys that are not letters, digits or function keys
+const NAMED_KEYS: &'static [(&'static str, u32)] = &[
+    ("Backspace", 0x08), ("Tab", 0x09), ("Enter", 0x0D), ("Esc", 0x1B), ("Space", 0x20),
+    ("PageUp", 0x21), ("PageDown", 0x22), ("End", 0x23), ("Home", 0x24),
+    ("Left", 0x25), ("Up", 0x26), ("Right", 0x27), ("Down", 0x28),
+    ("Insert", 0x2D), ("Delete", 0x2E),
+    ("Plus", 0xBB), ("Comma", 0xBC), ("Minus", 0xBD), ("Period", 0xBE),
+];
+
+/// Alternative names accepted by the parser
+const KEY_ALIASES: &'static [(&'static str, &'static str)] = &[
+    ("Return", "Enter"), ("Escape", "Esc"), ("PgUp", "PageUp"), ("PgDn", "PageDown"),
+    ("Ins", "Insert"), ("Del", "Delete"), ("Back", "Backspace"),
+];
+
+/**
+    A key combination. Use `Shortcut::parse` or `str::parse` to create one from a string.
+
+    Members:
+    • `key`: The virtual key code of the key
+    • `modifiers`: The modifier keys that must be held down
+*/
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Shortcut {
+    pub key: u32,
+    pub modifiers: KeyModifiers
+}
+
+impl Shortcut {
+
+    /**
+        Parse a shortcut string. The modifiers (`Ctrl`, `Shift` and `Alt`) and the key names are case insensitive.
+
+        Accepted keys:
+        • Letters `A` to `Z` and digits `0` to `9`
+        • Function keys `F1` to `F24`
+        • `Enter`, `Esc`, `Space`, `Tab`, `Backspace`, `Insert`, `Delete`, `Home`, `End`, `PageUp`, `PageDown`
+        • `Left`, `Up`, `Right`, `Down`, `Plus`, `Minus`, `Comma`, `Period`
+        • Any other virtual key code written in hexadecimal, from `0x01` to `0xFE`. Ex: "Ctrl+0xC0"
+
+        Returns `Err(Error::BadShortcut)` if the string is empty, if a part is unknown, if a modifier is repeated
+        or if the string do not end with a key.
+    */
+    pub fn parse(text: &str) -> Result<Shortcut, Error> {
+        let mut modifiers = KeyModifiers::default();
+        let parts: Vec<&str> = text.split('+').map(|p| p.trim()).collect();
+        let (key_name, modifier_names) = parts.split_last().unwrap();
+
+        for name in modifier_names.iter() {
+            let flag = match name.to_lowercase().as_ref() {
+                "ctrl" | "control" => &mut modifiers.ctrl,
+                "shift" => &mut modifiers.shift,
+                "alt" => &mut modifiers.alt,
+                "" => { return Err(Error::BadShortcut(format!("Empty part in \"{}\"", text))); }
+                _ => { return Err(Error::BadShortcut(format!("Unknown modifier \"{}\" in \"{}\"", name, text))); }
+            };
+
+            if *flag {
+                return Err(Error::BadShortcut(format!("Modifier \"{}\" is repeated in \"{}\"", name, text)));
+            }
+            *flag = true;
+        }
+
+        match parse_key(key_name) {
+            Some(key) => Ok(Shortcut{ key: key, modifiers: modifiers }),
+            None if key_name.is_empty() => Err(Error::BadShortcut(format!("Missing key in \"{}\"", text))),
+            None => Err(Error::BadShortcut(format!("Unknown key \"{}\" in \"{}\"", key_name, text)))
+        }
+    }
+
+    /**
+        Return the name of the shortcut key. Ex: "N", "F5", "Enter".
+        Returns the hexadecimal code if the key has no name (ex: "0xC0"). `Shortcut::parse` reads it back.
+    */
+    pub fn key_name(&self) -> String {
+        match self.key {
+            0x30..=0x39 | 0x41..=0x5A => (self.key as u8 as char).to_string(),
+            0x70..=0x87 => format!("F{}", self.key - 0x6F),
+            k => match NAMED_KEYS.iter().find(|&&(_, code)| code == k) {
+                Some(&(name, _)) => name.to_string(),
+                None => format!("0x{:02X}", k)
+            }
+        }
+    }
+
+}
+
+fn parse_key(name: &str) -> Option<u32> {
+    let upper = name.to_uppercase();
+    let bytes = upper.as_bytes();
+
+    if bytes.is_empty() {
+        return None;
+    }
+
+    // Letters and digits use their ascii value as virtual key code
+    if bytes.len() == 1 {
+        return match bytes[0] {
+            b @ b'A'..=b'Z' | b @ b'0'..=b'9' => Some(b as u32),
+            _ => None
+        };
+    }
+
+    // Virtual key codes without a name, as written by `Shortcut::key_name`
+    if upper.starts_with("0X") {
+        return match u32::from_str_radix(&upper[2..], 16) {
+            Ok(code) if code >= 0x01 && code <= 0xFE => Some(code),
+            _ => None
+        };
+    }
+
+    // Function keys
+    if bytes[0] == b'F' {
+        if let Ok(index) = upper[1..].parse::<u32>() {
+            return if index >= 1 && index <= 24 { Some(0x6F + index) } else { None };
+        }
+    }
+
+    let name = KEY_ALIASES.iter()
+      .find(|&&(alias, _)| alias.eq_ignore_ascii_case(name))
+      .map(|&(_, n)| n)
+      .unwrap_or(name);
+
+    NAMED_KEYS.iter()
+      .find(|&&(n, _)| n.eq_ignore_ascii_case(name))
+      .map(|&(_, code)| code)
+}
+
+impl FromStr for Shortcut {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Shortcut, Error> {
+        Shortcut::parse(s)
+    }
+}
+
+/**
+    Format the shortcut the way it is displayed in the menus. Ex: "Ctrl+Shift+N"
+*/
+impl fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut text = String::new();
+        if self.modifiers.ctrl { text.push_str("Ctrl+"); }
+        if self.modifiers.shift { text.push_str("Shift+"); }
+        if self.modifiers.alt { text.push_str("Alt+"); }
+        text.push_str(&self.key_name());
+        write!(f, "{}", text)
+    }
+}
+
+/// Flag set on the command identifiers of the accelerators that are not bound to a menu item
+pub const SHORTCUT_COMMAND_FLAG: u16 = 0x8000;
+
+/**
+    Pack a shortcut into the command identifier sent by an accelerator table. The identifier has the `SHORTCUT_COMMAND_FLAG`
+    bit set in order to be distinguished from the menu items identifiers.
+*/
+pub fn shortcut_command(shortcut: &Shortcut) -> u16 {
+    let m = &shortcut.modifiers;
+    let modifiers = (m.ctrl as u16) << 10 | (m.shift as u16) << 9 | (m.alt as u16) << 8;
+    SHORTCUT_COMMAND_FLAG | modifiers | (shortcut.key as u16 & 0xFF)
+}
+
+/**
+    Unpack a command identifier created by `shortcut_command`. Returns None if the identifier was not created by `shortcut_command`.
+*/
+pub fn command_shortcut(cmd: u16) -> Option<Shortcut> {
+    if cmd & SHORTCUT_COMMAND_FLAG == 0 {
+        return None;
+    }
+
+    let modifiers = KeyModifiers{ ctrl: cmd & (1 << 10) != 0, shift: cmd & (1 << 9) != 0, alt: cmd & (1 << 8) != 0 };
+    Some(Shortcut{ key: (cmd & 0xFF) as u32, modifiers: modifiers })
+}
@@ -0,0 +1,598 @@
+/*!
+    A task dialog. A richer message box with command links, radio buttons, a verification checkbox,
+    an expandable details section, a footer and a progress bar.
+*/
+use std::hash::Hash;
+use std::ptr;
+use std::mem;
+
+use winapi::{HWND, HICON, UINT, WPARAM, LPARAM, LONG_PTR, HRESULT, BOOL, PCWSTR, c_int};
+
+use ui::Ui;
+use defs::{MessageIcons, ProgressBarState};
+use low::defs::{TASKDIALOG_BUTTON, IDOK, IDCANCEL, IDRETRY, IDYES, IDNO, IDCLOSE};
+use low::other_helper::to_utf16;
+use error::{Error, SystemError};
+
+/// The system id of the first custom button and of the first radio button
+const CUSTOM_ID_BASE: c_int = 1000;
+
+/**
+    The buttons of a task dialog
+
+    • `Ok`, `Yes`, `No`, `Cancel`, `Retry`, `Close`: The common buttons. Their text is translated by the system  
+    • `Custom(index)`: A custom button, by order of creation (see `TaskDialog.button`)  
+*/
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TaskDialogButton {
+    Ok,
+    Yes,
+    No,
+    Cancel,
+    Retry,
+    Close,
+    Custom(usize)
+}
+
+impl TaskDialogButton {
+
+    fn id(&self) -> c_int {
+        match self {
+            &TaskDialogButton::Ok => IDOK,
+            &TaskDialogButton::Yes => IDYES,
+            &TaskDialogButton::No => IDNO,
+            &TaskDialogButton::Cancel => IDCANCEL,
+            &TaskDialogButton::Retry => IDRETRY,
+            &TaskDialogButton::Close => IDCLOSE,
+            &TaskDialogButton::Custom(i) => CUSTOM_ID_BASE + i as c_int
+        }
+    }
+
+    fn from_id(id: c_int) -> TaskDialogButton {
+        match id {
+            IDOK => TaskDialogButton::Ok,
+            IDYES => TaskDialogButton::Yes,
+            IDNO => TaskDialogButton::No,
+            IDRETRY => TaskDialogButton::Retry,
+            IDCLOSE => TaskDialogButton::Close,
+            id if id >= CUSTOM_ID_BASE => TaskDialogButton::Custom((id - CUSTOM_ID_BASE) as usize),
+            _ => TaskDialogButton::Cancel
+        }
+    }
+
+    fn flag(&self) -> UINT {
+        use winapi::{TDCBF_OK_BUTTON, TDCBF_YES_BUTTON, TDCBF_NO_BUTTON, TDCBF_CANCEL_BUTTON, TDCBF_RETRY_BUTTON, TDCBF_CLOSE_BUTTON};
+
+        match self {
+            &TaskDialogButton::Ok => TDCBF_OK_BUTTON.0,
+            &TaskDialogButton::Yes => TDCBF_YES_BUTTON.0,
+            &TaskDialogButton::No => TDCBF_NO_BUTTON.0,
+            &TaskDialogButton::Cancel => TDCBF_CANCEL_BUTTON.0,
+            &TaskDialogButton::Retry => TDCBF_RETRY_BUTTON.0,
+            &TaskDialogButton::Close => TDCBF_CLOSE_BUTTON.0,
+            &TaskDialogButton::Custom(_) => 0
+        }
+    }
+}
+
+/**
+    The progress bar of a task dialog
+
+    • `None`: No progress bar  
+    • `Range(min, max)`: A progress bar with a range. The position is set with `TaskDialogHandle.set_progress`  
+    • `Marquee`: A marquee progress bar that scrolls continuously  
+*/
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TaskDialogProgress {
+    None,
+    Range(u16, u16),
+    Marquee
+}
+
+/**
+    Return value of `TaskDialog.run`
+
+    Members:  
+    • `button`: The button clicked by the user. If the user closed the dialog, this is `TaskDialogButton::Cancel`  
+    • `radio`: The index of the selected radio button, if any  
+    • `verification_checked`: The state of the verification checkbox  
+*/
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TaskDialogResult {
+    pub button: TaskDialogButton,
+    pub radio: Option<usize>,
+    pub verification_checked: bool
+}
+
+/**
+    A handle to a running task dialog. Passed to the task dialog callbacks.
+*/
+pub struct TaskDialogHandle {
+    handle: HWND
+}
+
+impl TaskDialogHandle {
+
+    /// Set the position of the progress bar. Only works if the dialog was created with `TaskDialogProgress::Range`.
+    pub fn set_progress(&self, position: u16) {
+        use winapi::TDM_SET_PROGRESS_BAR_POS;
+        self.send(TDM_SET_PROGRESS_BAR_POS.0, position as WPARAM, 0);
+    }
+
+    /// Set the state of the progress bar
+    pub fn set_progress_state(&self, state: ProgressBarState) {
+        use winapi::{TDM_SET_PROGRESS_BAR_STATE, PBST_NORMAL, PBST_ERROR, PBST_PAUSED};
+
+        let state = match state {
+            ProgressBarState::Normal => PBST_NORMAL,
+            ProgressBarState::Error => PBST_ERROR,
+            ProgressBarState::Paused => PBST_PAUSED
+        };
+
+        self.send(TDM_SET_PROGRESS_BAR_STATE.0, state as WPARAM, 0);
+    }
+
+    /// Switch the progress bar between a marquee progress bar and a progress bar with a range
+    pub fn set_marquee(&self, marquee: bool) {
+        use winapi::{TDM_SET_MARQUEE_PROGRESS_BAR, TDM_SET_PROGRESS_BAR_MARQUEE};
+
+        self.send(TDM_SET_MARQUEE_PROGRESS_BAR.0, marquee as WPARAM, 0);
+        self.send(TDM_SET_PROGRESS_BAR_MARQUEE.0, marquee as WPARAM, 0);
+    }
+
+    /// Set the main instruction of the dialog
+    pub fn set_main_instruction<'a>(&self, text: &'a str) {
+        use winapi::TDE_MAIN_INSTRUCTION;
+        self.set_element_text(TDE_MAIN_INSTRUCTION.0, text);
+    }
+
+    /// Set the content of the dialog
+    pub fn set_content<'a>(&self, text: &'a str) {
+        use winapi::TDE_CONTENT;
+        self.set_element_text(TDE_CONTENT.0, text);
+    }
+
+    /// Set the text of the expandable details section. Only works if the dialog was created with a details section.
+    pub fn set_expanded_information<'a>(&self, text: &'a str) {
+        use winapi::TDE_EXPANDED_INFORMATION;
+        self.set_element_text(TDE_EXPANDED_INFORMATION.0, text);
+    }
+
+    /// Set the footer of the dialog. Only works if the dialog was created with a footer.
+    pub fn set_footer<'a>(&self, text: &'a str) {
+        use winapi::TDE_FOOTER;
+        self.set_element_text(TDE_FOOTER.0, text);
+    }
+
+    /// Enable or disable a button of the dialog
+    pub fn set_button_enabled(&self, button: TaskDialogButton, enabled: bool) {
+        use winapi::TDM_ENABLE_BUTTON;
+        self.send(TDM_ENABLE_BUTTON.0, button.id() as WPARAM, enabled as LPARAM);
+    }
+
+    /// Click a button of the dialog. This closes the dialog and `TaskDialog.run` returns the button.
+    pub fn click_button(&self, button: TaskDialogButton) {
+        use winapi::TDM_CLICK_BUTTON;
+        self.send(TDM_CLICK_BUTTON.0, button.id() as WPARAM, 0);
+    }
+
+    /// Select a radio button of the dialog
+    pub fn click_radio(&self, index: usize) {
+        use winapi::TDM_CLICK_RADIO_BUTTON;
+        self.send(TDM_CLICK_RADIO_BUTTON.0, (CUSTOM_ID_BASE + index as c_int) as WPARAM, 0);
+    }
+
+    /// Check or uncheck the verification checkbox
+    pub fn set_verification_checked(&self, checked: bool) {
+        use winapi::TDM_CLICK_VERIFICATION;
+        self.send(TDM_CLICK_VERIFICATION.0, checked as WPARAM, 0);
+    }
+
+    fn set_element_text<'a>(&self, element: UINT, text: &'a str) {
+        use winapi::TDM_SET_ELEMENT_TEXT;
+
+        let text = to_utf16(text);
+        self.send(TDM_SET_ELEMENT_TEXT.0, element as WPARAM, text.as_ptr() as LPARAM);
+    }
+
+    #[inline(always)]
+    fn send(&self, msg: UINT, w: WPARAM, l: LPARAM) {
+        use user32::SendMessageW;
+        unsafe{ SendMessageW(self.handle, msg, w, l); }
+    }
+}
+
+/**
+    A task dialog builder. A task dialog is a richer message box (see `message`).
+
+    ```ignore
+    let result = TaskDialog::new("Save changes?")
+        .title("Editor")
+        .content("The document was modified")
+        .button("Save\nSave the document before closing")
+        .button("Discard\nClose without saving")
+        .command_links(true)
+        .common_buttons(&[TaskDialogButton::Cancel])
+        .verification("Don't show again", false)
+        .run();
+    ```
+
+    The text of the dialog (the content, the details and the footer) can contain hyperlinks written as `<a href="target">text</a>`
+    if `hyperlinks` is enabled. The dialog do not open the links, use `on_hyperlink` to handle them.
+
+    Failures when running the dialog:  
+    • `Error::UserError`: if a common button is `TaskDialogButton::Custom`  
+    • `Error::UserError`: if the default button or the default radio button do not exist  
+    • `Error::UserError`: if the progress bar range is empty  
+    • `Error::System`: if the task dialog is not available (it requires Windows Vista and the common controls v6)  
+*/
+pub struct TaskDialog {
+    title: String,
+    main_instruction: String,
+    content: String,
+    icon: MessageIcons,
+    common_buttons: Vec<TaskDialogButton>,
+    buttons: Vec<String>,
+    command_links: bool,
+    default_button: Option<TaskDialogButton>,
+    radio_buttons: Vec<String>,
+    default_radio: Option<usize>,
+    verification: Option<(String, bool)>,
+    expanded_information: Option<(String, bool)>,
+    footer: Option<String>,
+    footer_icon: MessageIcons,
+    hyperlinks: bool,
+    progress: TaskDialogProgress,
+    on_timer: Option<Box<Fn(&TaskDialogHandle, u32)>>,
+    on_hyperlink: Option<Box<Fn(&TaskDialogHandle, &str)>>
+}
+
+impl TaskDialog {
+
+    /// Create a new task dialog with a main instruction. By default, the dialog has an Ok button.
+    pub fn new<S: Into<String>>(main_instruction: S) -> TaskDialog {
+        TaskDialog {
+            title: String::new(),
+            main_instruction: main_instruction.into(),
+            content: String::new(),
+            icon: MessageIcons::None,
+            common_buttons: Vec::new(),
+            buttons: Vec::new(),
+            command_links: false,
+            default_button: None,
+            radio_buttons: Vec::new(),
+            default_radio: None,
+            verification: None,
+            expanded_information: None,
+            footer: None,
+            footer_icon: MessageIcons::None,
+            hyperlinks: false,
+            progress: TaskDialogProgress::None,
+            on_timer: None,
+            on_hyperlink: None
+        }
+    }
+
+    /// Set the title of the dialog window. If empty, the title is the name of the executable.
+    pub fn title<S: Into<String>>(mut self, title: S) -> TaskDialog {
+        self.title = title.into();
+        self
+    }
+
+    /// Set the text displayed under the main instruction
+    pub fn content<S: Into<String>>(mut self, content: S) -> TaskDialog {
+        self.content = content.into();
+        self
+    }
+
+    /// Set the icon displayed next to the main instruction
+    pub fn icon(mut self, icon: MessageIcons) -> TaskDialog {
+        self.icon = icon;
+        self
+    }
+
+    /// Set the common buttons of the dialog. If there are no buttons at all, the dialog has an Ok button.
+    pub fn common_buttons(mut self, buttons: &[TaskDialogButton]) -> TaskDialog {
+        self.common_buttons = buttons.to_vec();
+        self
+    }
+
+    /**
+        Add a custom button. The button is returned as `TaskDialogButton::Custom(index)` where index is the
+        number of custom buttons added before it. With command links, the text after the first new line is displayed as a note under the button.
+    */
+    pub fn button<S: Into<String>>(mut self, text: S) -> TaskDialog {
+        self.buttons.push(text.into());
+        self
+    }
+
+    /// Display the custom buttons as command links instead of push buttons
+    pub fn command_links(mut self, command_links: bool) -> TaskDialog {
+        self.command_links = command_links;
+        self
+    }
+
+    /// Set the button that has the focus when the dialog is opened
+    pub fn default_button(mut self, button: TaskDialogButton) -> TaskDialog {
+        self.default_button = Some(button);
+        self
+    }
+
+    /// Add a radio button. The selected radio button is returned as its index in `TaskDialogResult.radio`.
+    pub fn radio<S: Into<String>>(mut self, text: S) -> TaskDialog {
+        self.radio_buttons.push(text.into());
+        self
+    }
+
+    /// Set the radio button selected when the dialog is opened. By default, no radio button is selected.
+    pub fn default_radio(mut self, index: usize) -> TaskDialog {
+        self.default_radio = Some(index);
+        self
+    }
+
+    /// Add a verification checkbox at the bottom of the dialog. Ex: "Don't show this message again"
+    pub fn verification<S: Into<String>>(mut self, text: S, checked: bool) -> TaskDialog {
+        self.verification = Some((text.into(), checked));
+        self
+    }
+
+    /// Add an expandable details section under the content. If `expanded` is true, the section is visible when the dialog is opened.
+    pub fn expanded_information<S: Into<String>>(mut self, text: S, expanded: bool) -> TaskDialog {
+        self.expanded_information = Some((text.into(), expanded));
+        self
+    }
+
+    /// Add a footer at the bottom of the dialog
+    pub fn footer<S: Into<String>>(mut self, text: S, icon: MessageIcons) -> TaskDialog {
+        self.footer = Some(text.into());
+        self.footer_icon = icon;
+        self
+    }
+
+    /// Enable the hyperlinks in the content, the details and the footer
+    pub fn hyperlinks(mut self, hyperlinks: bool) -> TaskDialog {
+        self.hyperlinks = hyperlinks;
+        self
+    }
+
+    /// Add a progress bar to the dialog
+    pub fn progress(mut self, progress: TaskDialogProgress) -> TaskDialog {
+        self.progress = progress;
+        self
+    }
+
+    /**
+        Set a callback called about every 200 milliseconds while the dialog is opened. The callback receives the number
+        of milliseconds since the dialog was opened. Use the handle to update the progress bar or to close the dialog.
+    */
+    pub fn on_timer<F>(mut self, callback: F) -> TaskDialog where F: Fn(&TaskDialogHandle, u32)+'static {
+        self.on_timer = Some(Box::new(callback));
+        self
+    }
+
+    /// Set a callback called when the user clicks a hyperlink. The callback receives the `href` of the link. Enables the hyperlinks.
+    pub fn on_hyperlink<F>(mut self, callback: F) -> TaskDialog where F: Fn(&TaskDialogHandle, &str)+'static {
+        self.on_hyperlink = Some(Box::new(callback));
+        self.hyperlinks = true;
+        self
+    }
+
+    /**
+        Display the dialog and wait until the user closes it. The dialog is not owned by any window.
+    */
+    pub fn run(&self) -> Result<TaskDialogResult, Error> {
+        unsafe{ self.run_inner(ptr::null_mut()) }
+    }
+
+    /**
+        Display the dialog and wait until the user closes it. The dialog is owned by the window identified by `parent`.
+        While the dialog is running, the parent is disabled.
+
+        Failures (in addition to the failures of `run`):  
+        • `Error::KeyNotFound`: if `parent` is not in the Ui  
+        • `Error::BadParent`: if `parent` is not a window-like control  
+    */
+    pub fn run_with_parent<ID: Hash+Clone>(&self, ui: &Ui<ID>, parent: &ID) -> Result<TaskDialogResult, Error> {
+        use low::window_helper::handle_of_window;
+
+        match handle_of_window(ui, parent, "The parent of a TaskDialog must be a window-like control.") {
+            Ok(h) => unsafe{ self.run_inner(h) },
+            Err(e) => Err(e)
+        }
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if self.common_buttons.iter().any(|b| match b { &TaskDialogButton::Custom(_) => true, _ => false }) {
+            return Err(Error::UserError("The common buttons of a task dialog cannot be custom buttons".to_string()));
+        }
+
+        match self.default_button {
+            Some(TaskDialogButton::Custom(i)) if i >= self.buttons.len() => {
+                return Err(Error::UserError(format!("The task dialog do not have a custom button at index {}", i)));
+            },
+            Some(TaskDialogButton::Custom(_)) | None => {},
+            Some(b) => if !self.common_buttons.contains(&b) && !(b == TaskDialogButton::Ok && self.has_no_buttons()) {
+                return Err(Error::UserError(format!("The default button {:?} is not a button of the task dialog", b)));
+            }
+        }
+
+        match self.default_radio {
+            Some(i) if i >= self.radio_buttons.len() => {
+                return Err(Error::UserError(format!("The task dialog do not have a radio button at index {}", i)));
+            },
+            _ => {}
+        }
+
+        match self.progress {
+            TaskDialogProgress::Range(min, max) if min >= max => {
+                return Err(Error::UserError(format!("The progress range {}-{} of the task dialog is empty", min, max)));
+            },
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn has_no_buttons(&self) -> bool {
+        self.common_buttons.is_empty() && self.buttons.is_empty()
+    }
+
+    unsafe fn run_inner(&self, parent: HWND) -> Result<TaskDialogResult, Error> {
+        use low::defs::TASKDIALOGCONFIG;
+        use winapi::{TDF_ENABLE_HYPERLINKS, TDF_ALLOW_DIALOG_CANCELLATION, TDF_USE_COMMAND_LINKS, TDF_EXPANDED_BY_DEFAULT,
+          TDF_VERIFICATION_FLAG_CHECKED, TDF_SHOW_PROGRESS_BAR, TDF_SHOW_MARQUEE_PROGRESS_BAR, TDF_CALLBACK_TIMER,
+          TDF_POSITION_RELATIVE_TO_WINDOW, TDF_NO_DEFAULT_RADIO_BUTTON, TDF_USE_HICON_MAIN, TDF_USE_HICON_FOOTER, S_OK};
+
+        match self.validate() {
+            Ok(_) => {},
+            Err(e) => { return Err(e); }
+        }
+
+        let task_dialog_indirect = match load_task_dialog() {
+            Some(f) => f,
+            None => { return Err(Error::System(SystemError::TaskDialogCreation)); }
+        };
+
+        // The strings must live until the dialog is closed
+        let title = to_utf16(&self.title);
+        let main_instruction = to_utf16(&self.main_instruction);
+        let content = to_utf16(&self.content);
+        let buttons_text: Vec<Vec<u16>> = self.buttons.iter().map(|t| to_utf16(t)).collect();
+        let radio_text: Vec<Vec<u16>> = self.radio_buttons.iter().map(|t| to_utf16(t)).collect();
+        let verification = self.verification.as_ref().map(|&(ref t, _)| to_utf16(t));
+        let expanded_information = self.expanded_information.as_ref().map(|&(ref t, _)| to_utf16(t));
+        let footer = self.footer.as_ref().map(|t| to_utf16(t));
+
+        let buttons = task_dialog_buttons(&buttons_text);
+        let (main_icon, main_hicon) = task_dialog_icon(&self.icon);
+        let (footer_icon, footer_hicon) = task_dialog_icon(&self.footer_icon);
+        let radio_buttons = task_dialog_buttons(&radio_text);
+
+        let mut flags = TDF_ALLOW_DIALOG_CANCELLATION.0;
+        if self.hyperlinks { flags |= TDF_ENABLE_HYPERLINKS.0; }
+        if self.command_links && !self.buttons.is_empty() { flags |= TDF_USE_COMMAND_LINKS.0; }
+        if self.default_radio.is_none() { flags |= TDF_NO_DEFAULT_RADIO_BUTTON.0; }
+        if !parent.is_null() { flags |= TDF_POSITION_RELATIVE_TO_WINDOW.0; }
+        if self.on_timer.is_some() { flags |= TDF_CALLBACK_TIMER.0; }
+        if main_hicon { flags |= TDF_USE_HICON_MAIN.0; }
+        if footer_hicon { flags |= TDF_USE_HICON_FOOTER.0; }
+        if let Some((_, true)) = self.verification { flags |= TDF_VERIFICATION_FLAG_CHECKED.0; }
+        if let Some((_, true)) = self.expanded_information { flags |= TDF_EXPANDED_BY_DEFAULT.0; }
+        match self.progress {
+            TaskDialogProgress::None => {},
+            TaskDialogProgress::Range(_, _) => { flags |= TDF_SHOW_PROGRESS_BAR.0; },
+            TaskDialogProgress::Marquee => { flags |= TDF_SHOW_MARQUEE_PROGRESS_BAR.0; }
+        }
+
+        let config = TASKDIALOGCONFIG {
+            cbSize: mem::size_of::<TASKDIALOGCONFIG>() as UINT,
+            hwndParent: parent,
+            hInstance: ptr::null_mut(),
+            dwFlags: flags,
+            dwCommonButtons: self.common_buttons.iter().fold(0, |acc, b| acc | b.flag()),
+            pszWindowTitle: if self.title.is_empty() { ptr::null() } else { title.as_ptr() },
+            hMainIcon: main_icon,
+            pszMainInstruction: main_instruction.as_ptr(),
+            pszContent: if self.content.is_empty() { ptr::null() } else { content.as_ptr() },
+            cButtons: buttons.len() as UINT,
+            pButtons: buttons.as_ptr(),
+            nDefaultButton: self.default_button.map(|b| b.id()).unwrap_or(0),
+            cRadioButtons: radio_buttons.len() as UINT,
+            pRadioButtons: radio_buttons.as_ptr(),
+            nDefaultRadioButton: self.default_radio.map(|i| CUSTOM_ID_BASE + i as c_int).unwrap_or(0),
+            pszVerificationText: optional_text(&verification),
+            pszExpandedInformation: optional_text(&expanded_information),
+            pszExpandedControlText: ptr::null(),
+            pszCollapsedControlText: ptr::null(),
+            hFooterIcon: footer_icon,
+            pszFooter: optional_text(&footer),
+            pfCallback: Some(task_dialog_proc),
+            lpCallbackData: self as *const TaskDialog as LONG_PTR,
+            cxWidth: 0
+        };
+
+        let mut button: c_int = 0;
+        let mut radio: c_int = 0;
+        let mut checked: BOOL = 0;
+        if task_dialog_indirect(&config, &mut button, &mut radio, &mut checked) != S_OK {
+            return Err(Error::System(SystemError::TaskDialogCreation));
+        }
+
+        Ok(TaskDialogResult {
+            button: TaskDialogButton::from_id(button),
+            radio: if radio >= CUSTOM_ID_BASE { Some((radio - CUSTOM_ID_BASE) as usize) } else { None },
+            verification_checked: checked != 0
+        })
+    }
+}
+
+/// Load `TaskDialogIndirect` from comctl32. The function do not exist in the common controls v5.
+unsafe fn load_task_dialog() -> Option<::low::defs::TaskDialogIndirectProc> {
+    use kernel32::{LoadLibraryW, GetProcAddress};
+
+    // The library is never freed. Loading it more than once only increase its reference count.
+    let name = to_utf16("comctl32.dll");
+    let module = LoadLibraryW(name.as_ptr());
+    if module.is_null() {
+        return None;
+    }
+
+    let proc_ = GetProcAddress(module, b"TaskDialogIndirect\0".as_ptr() as *const i8);
+    if proc_.is_null() {
+        None
+    } else {
+        Some(mem::transmute(proc_))
+    }
+}
+
+fn task_dialog_buttons(text: &[Vec<u16>]) -> Vec<TASKDIALOG_BUTTON> {
+    text.iter().enumerate().map(|(i, t)| TASKDIALOG_BUTTON{ nButtonID: CUSTOM_ID_BASE + i as c_int, pszButtonText: t.as_ptr() }).collect()
+}
+
+fn optional_text(text: &Option<Vec<u16>>) -> PCWSTR {
+    match text {
+        &Some(ref t) => t.as_ptr(),
+        &None => ptr::null()
+    }
+}
+
+/// Return the icon of a task dialog and `true` if the icon is a HICON. The question icon do not have a task dialog resource id and is loaded from the system.
+unsafe fn task_dialog_icon(icon: &MessageIcons) -> (HICON, bool) {
+    use user32::LoadIconW;
+    use winapi::IDI_QUESTION;
+    use low::defs::{TD_WARNING_ICON, TD_ERROR_ICON, TD_INFORMATION_ICON};
+
+    match icon {
+        &MessageIcons::Warning => (TD_WARNING_ICON as usize as HICON, false),
+        &MessageIcons::Error => (TD_ERROR_ICON as usize as HICON, false),
+        &MessageIcons::Info => (TD_INFORMATION_ICON as usize as HICON, false),
+        &MessageIcons::Question => (LoadIconW(ptr::null_mut(), IDI_QUESTION), true),
+        &MessageIcons::None => (ptr::null_mut(), false)
+    }
+}
+
+unsafe extern "system" fn task_dialog_proc(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM, data: LONG_PTR) -> HRESULT {
+    use winapi::{TDN_CREATED, TDN_TIMER, TDN_HYPERLINK_CLICKED, TDM_SET_PROGRESS_BAR_RANGE, TDM_SET_PROGRESS_BAR_MARQUEE, S_OK};
+    use low::other_helper::from_wide_ptr;
+
+    let dialog = &*(data as *const TaskDialog);
+    let handle = TaskDialogHandle{ handle: hwnd };
+
+    if msg == TDN_CREATED.0 {
+        match dialog.progress {
+            TaskDialogProgress::Range(min, max) => handle.send(TDM_SET_PROGRESS_BAR_RANGE.0, 0, ((max as LPARAM) << 16) | min as LPARAM),
+            TaskDialogProgress::Marquee => handle.send(TDM_SET_PROGRESS_BAR_MARQUEE.0, 1, 0),
+            TaskDialogProgress::None => {}
+        }
+    } else if msg == TDN_TIMER.0 {
+        if let Some(ref callback) = dialog.on_timer {
+            callback(&handle, w as u32);
+        }
+    } else if msg == TDN_HYPERLINK_CLICKED.0 {
+        if let Some(ref callback) = dialog.on_hyperlink {
+            callback(&handle, &from_wide_ptr(l as *mut u16));
+        }
+    }
+
+    S_OK
+}
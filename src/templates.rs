@@ -131,6 +131,10 @@ pub fn $n(ui: &$crate::Ui<$t>) -> Result<(), $crate::Error> {
     • disabled: `false`  
     • exit_on_close: `true`  
     • icon: `None`  
+    • owner: `None`  
+    • tool_window: `false`  
+    • text_color: `None`  
+    • background_color: `None`  
 
     Usage:  
     `nwg_window!()`  
@@ -145,7 +149,9 @@ macro_rules! nwg_window {
             title: "Native Windows GUI", 
             position: (100, 100), size: (800, 600), 
             resizable: false, visible: true, disabled: false, 
-            exit_on_close: true, icon: None
+            exit_on_close: true, icon: None,
+            owner: None, tool_window: false,
+            text_color: None, background_color: None
         };
         
         $( t.$i = $v; );*
@@ -163,7 +169,12 @@ macro_rules! nwg_window {
     • size: `(100, 30)`  
     • visible: `true`  
     • disabled: `false`  
-    • font: `None`
+    • font: `None`  
+    • tooltip: `None`  
+    • style: `ButtonStyle::Push`  
+    • image: `None`  
+    • text_color: `None`  
+    • background_color: `None`
 
     Usage:  
     `nwg_button!(parent="MyParent";)`  
@@ -178,7 +189,11 @@ macro_rules! nwg_button {
             text: "", 
             position: (0, 0), size: (100, 30), 
             visible: true, disabled: false, 
-            parent: $p, font: None
+            parent: $p, font: None,
+            tooltip: None,
+            style: $crate::constants::ButtonStyle::Push,
+            image: None,
+            text_color: None, background_color: None
         };
         
         $( t.$i = $v; );*
@@ -198,7 +213,10 @@ macro_rules! nwg_button {
     • disabled: `false`  
     • checkstate: `CheckState::Unchecked`  
     • tristate: `false`  
-    • font: `None`
+    • font: `None`  
+    • tooltip: `None`  
+    • text_color: `None`  
+    • background_color: `None`
 
     Usage:  
     `nwg_checkbox!(parent="MyParent";)`  
@@ -215,7 +233,9 @@ macro_rules! nwg_checkbox {
             visible: true, disabled: false, 
             checkstate: $crate::constants::CheckState::Unchecked,
             tristate: false,
-            parent: $p, font: None
+            parent: $p, font: None,
+            tooltip: None,
+            text_color: None, background_color: None
         };
         
         $( t.$i = $v; );*
@@ -236,7 +256,8 @@ macro_rules! nwg_checkbox {
     • visible: `true`  
     • disabled: `false`  
     • placeholder: `None`  
-    • font: `None`
+    • font: `None`  
+    • tooltip: `None`
 
     Usage:  
     `nwg_combobox!(data=String; parent="MyParent";)`  
@@ -252,7 +273,8 @@ macro_rules! nwg_combobox {
             position: (0, 0), size: (100, 30), 
             visible: true, disabled: false, 
             placeholder: None,
-            parent: $p, font: None
+            parent: $p, font: None,
+            tooltip: None
         };
         
         $( t.$i = $v; );*
@@ -266,7 +288,8 @@ macro_rules! nwg_combobox {
             position: (0, 0), size: (100, 30), 
             visible: true, disabled: false, 
             placeholder: None,
-            parent: $p, font: None
+            parent: $p, font: None,
+            tooltip: None
         };
         
         $( t.$i = $v; );*
@@ -285,7 +308,11 @@ macro_rules! nwg_combobox {
     • visible: `true`  
     • disabled: `false`  
     • align: `HTextAlign::Left`  
-    • font: `None`
+    • font: `None`  
+    • tooltip: `None`  
+    • text_color: `None`  
+    • background_color: `None`  
+    • owner_draw: `false`
 
     Usage:  
     `nwg_label!(parent="MyParent";)`  
@@ -301,7 +328,10 @@ macro_rules! nwg_label {
             position: (0, 0), size: (100, 30), 
             visible: true, disabled: false, 
             align: $crate::constants::HTextAlign::Left,
-            parent: $p, font: None
+            parent: $p, font: None,
+            tooltip: None,
+            text_color: None, background_color: None,
+            owner_draw: false
         };
         
         $( t.$i = $v; );*
@@ -365,16 +395,16 @@ macro_rules! nwg_listbox {
     Sane defaults for the ListView control. Requires a parent.
 
     Defaults:  
-    • column: `[]`  
-    • view_mode: `[]`  
+    • columns: `[]`  
+    • view_mode: `Report`  
     • position: `(0, 0)`  
     • size: `(100, 30)`  
     • visible: `true`  
     • disabled: `false`  
-    • align: `Left`
+    • checkboxes: `false`  
+    • multi_select: `false`  
     • font: `None`
 
-
     Usage:  
     `nwg_list_view!(parent="MyParent";)`  
     `nwg_list_view!(parent="MyParent"; visible=false; size=(10, 10))`  
@@ -385,12 +415,13 @@ macro_rules! nwg_list_view {
     (parent=$p:expr; $( $i:ident=$v:expr );* ) => { {
         let mut t = 
         $crate::ListViewT::<_>{ 
-            column: vec![],
+            columns: vec![],
             view_mode: $crate::ViewMode::Report,
             position: (0, 0), size: (100, 30), 
             visible: true,
             disabled: false, 
-            align: $crate::constants::HTextAlign::Left,
+            checkboxes: false,
+            multi_select: false,
             parent: $p, font: None
         };
         
@@ -401,12 +432,54 @@ macro_rules! nwg_list_view {
 }
 
 
+/**
+    Sane defaults for the VirtualListView control. Requires a parent and a data source.
+
+    Defaults:  
+    • columns: `[]`  
+    • view_mode: `Report`  
+    • position: `(0, 0)`  
+    • size: `(100, 30)`  
+    • visible: `true`  
+    • disabled: `false`  
+    • checkboxes: `false`  
+    • multi_select: `false`  
+    • font: `None`
+
+    Usage:  
+    `nwg_virtual_list_view!(parent="MyParent"; data=my_data_source;)`  
+    `nwg_virtual_list_view!(parent="MyParent"; data=my_data_source; visible=false; size=(10, 10))`  
+    `nwg_virtual_list_view!(parent="MyParent"; data=my_data_source; \* Any combinations of the template properties*\)`    
+*/
+#[macro_export]
+macro_rules! nwg_virtual_list_view {
+    (parent=$p:expr; data=$d:expr; $( $i:ident=$v:expr );* ) => { {
+        let mut t = 
+        $crate::VirtualListViewT::<_>{ 
+            columns: vec![],
+            data: $d,
+            view_mode: $crate::ViewMode::Report,
+            position: (0, 0), size: (100, 30), 
+            visible: true,
+            disabled: false, 
+            checkboxes: false,
+            multi_select: false,
+            parent: $p, font: None
+        };
+        
+        $( t.$i = $v; );*
+
+        t
+    }}
+}
+
 /**
     Sane defaults for the Menu control. Requires a window parent.
 
     Defaults:  
     • text: `"Menu"`  
     • disabled: `false`  
+    • index: `None`  
 
     Usage:  
     `nwg_menu!(parent="MyParent";)`  
@@ -417,7 +490,7 @@ macro_rules! nwg_list_view {
 macro_rules! nwg_menu {
     (parent=$p:expr; $( $i:ident=$v:expr );* ) => { {
         let mut t = 
-        $crate::MenuT{  text: "Menu", parent: $p, disabled: false  };
+        $crate::MenuT{  text: "Menu", parent: $p, disabled: false, index: None  };
         $( t.$i = $v; );*
         t
     }}
@@ -429,6 +502,11 @@ macro_rules! nwg_menu {
     Defaults:  
     • text: `"Menuitem"`  
     • disabled: `false`  
+    • checked: `false`  
+    • radio: `false`  
+    • default: `false`  
+    • image: `None`  
+    • index: `None`  
 
     Usage:  
     `nwg_menuitem!(parent="MyParent";)`  
@@ -438,7 +516,8 @@ macro_rules! nwg_menu {
 #[macro_export]
 macro_rules! nwg_menuitem {
     (parent=$p:expr; $( $i:ident=$v:expr );* ) => { {
-        let mut t =  $crate::MenuItemT{  text: "Menuitem", parent: $p, disabled: false };
+        let mut t =  $crate::MenuItemT{  text: "Menuitem", parent: $p, disabled: false, checked: false, radio: false,
+          default: false, image: None, index: None };
         $( t.$i = $v; );*
         t
     }}
@@ -483,7 +562,10 @@ macro_rules! nwg_contextmenu {
     • visible: `true`  
     • disabled: `false`  
     • checkstate: `CheckState::Unchecked`  
-    • font: `None`
+    • font: `None`  
+    • tooltip: `None`  
+    • text_color: `None`  
+    • background_color: `None`
 
     Usage:  
     `nwg_radiobutton!(parent="MyParent";)`  
@@ -500,7 +582,9 @@ macro_rules! nwg_radiobutton {
             visible: true, disabled: false, 
             parent: $p,
             checkstate: $crate::constants::CheckState::Unchecked,
-            font: None
+            font: None,
+            tooltip: None,
+            text_color: None, background_color: None
         };
         $( t.$i = $v; );*
         t
@@ -529,6 +613,31 @@ macro_rules! nwg_timer {
     }}
 }
 
+/**
+    Sane defaults for the Accelerator control. Requires a window. Must be packed after the window and the menu items.
+
+    Defaults:  
+    • items: `vec![]`  
+    • shortcuts: `vec![]`  
+
+    Usage:  
+    `nwg_accelerator!(window="MyWindow"; items=vec![("Ctrl+S", "SaveItem")])`  
+    `nwg_accelerator!(window="MyWindow"; shortcuts=vec!["F5", "Ctrl+Shift+N"])`  
+*/
+#[macro_export]
+macro_rules! nwg_accelerator {
+    (window=$w:expr; $( $i:ident=$v:expr );* ) => { #[allow(unused_mut)] {
+        let mut t = 
+        $crate::AcceleratorT::<&'static str, _>{
+            window: $w,
+            items: vec![],
+            shortcuts: vec![]
+        };
+        $( t.$i = $v; );*
+        t
+    }}
+}
+
 /**
     Sane defaults for the TextInput control. Requires a parent.
 
@@ -542,7 +651,11 @@ macro_rules! nwg_timer {
     • password: `false`  
     • limit: `32_767`  
     • placeholder: `None`  
-    • font: `None`
+    • validators: `[]`  
+    • font: `None`  
+    • tooltip: `None`  
+    • text_color: `None`  
+    • background_color: `None`
 
     Usage:  
     `nwg_textinput!(parent="MyParent";)`  
@@ -559,8 +672,11 @@ macro_rules! nwg_textinput {
             visible: true, disabled: false, readonly: false, password: false,
             limit: 32_767,
             placeholder: None,
+            validators: vec![],
             parent: $p,
-            font: None
+            font: None,
+            tooltip: None,
+            text_color: None, background_color: None
         };
         $( t.$i = $v; );*
         t
@@ -579,7 +695,11 @@ macro_rules! nwg_textinput {
     • readonly: `false`  
     • limit: `32_767`  
     • scrollbars: `(false, false)`  
-    • font: `None`
+    • validators: `[]`  
+    • font: `None`  
+    • tooltip: `None`  
+    • text_color: `None`  
+    • background_color: `None`
 
     Usage:  
     `nwg_textbox!(parent="MyParent";)`  
@@ -596,6 +716,85 @@ macro_rules! nwg_textbox {
             visible: true, disabled: false, readonly: false,
             limit: 32_767,
             scrollbars: (false, false),
+            validators: vec![],
+            parent: $p,
+            font: None,
+            tooltip: None,
+            text_color: None, background_color: None
+        };
+        $( t.$i = $v; );*
+        t
+    }}
+}
+
+/**
+    Sane defaults for the RichTextBox control. Requires a parent.
+
+    Defaults:  
+    • text: `""`  
+    • position: `(0, 0)`  
+    • size: `(200, 100)`  
+    • visible: `true`  
+    • disabled: `false`  
+    • readonly: `false`  
+    • limit: `1_000_000`  
+    • auto_url: `false`  
+    • scrollbars: `(false, true)`  
+    • font: `None`
+
+    Usage:  
+    `nwg_rich_textbox!(parent="MyParent";)`  
+    `nwg_rich_textbox!(parent="MyParent"; auto_url=true; size=(300, 200))`  
+    `nwg_rich_textbox!(parent="MyParent"; \* Any combinations of the template properties*\)`    
+*/
+#[macro_export]
+macro_rules! nwg_rich_textbox {
+    (parent=$p:expr; $( $i:ident=$v:expr );* ) => { {
+        let mut t = 
+        $crate::RichTextBoxT::<_, _> {
+            text: "",
+            position: (0, 0), size: (200, 100), 
+            visible: true, disabled: false, readonly: false,
+            limit: 1_000_000, auto_url: false,
+            scrollbars: (false, true),
+            parent: $p,
+            font: None
+        };
+        $( t.$i = $v; );*
+        t
+    }}
+}
+
+/**
+    Sane defaults for the NumericInput control. Requires a parent.
+
+    Defaults:  
+    • value: `0.0`  
+    • range: `(0.0, 100.0)`  
+    • step: `1.0`  
+    • decimals: `0`  
+    • wrap: `false`  
+    • thousands_separator: `false`  
+    • position: `(0, 0)`  
+    • size: `(100, 25)`  
+    • visible: `true`  
+    • disabled: `false`  
+    • font: `None`
+
+    Usage:  
+    `nwg_numeric_input!(parent="MyParent";)`  
+    `nwg_numeric_input!(parent="MyParent"; range=(-1.0, 1.0); step=0.1; decimals=1)`  
+    `nwg_numeric_input!(parent="MyParent"; \* Any combinations of the template properties*\)`    
+*/
+#[macro_export]
+macro_rules! nwg_numeric_input {
+    (parent=$p:expr; $( $i:ident=$v:expr );* ) => { {
+        let mut t = 
+        $crate::NumericInputT::<_> {
+            value: 0.0, range: (0.0, 100.0), step: 1.0,
+            decimals: 0, wrap: false, thousands_separator: false,
+            position: (0, 0), size: (100, 25), 
+            visible: true, disabled: false,
             parent: $p,
             font: None
         };
@@ -614,7 +813,9 @@ macro_rules! nwg_textbox {
     • visible: `true`  
     • disabled: `false`  
     • align: `HTextAlign::Left`  
-    • font: `None`
+    • font: `None`  
+    • text_color: `None`  
+    • background_color: `None`
 
     Usage:  
     `nwg_groupbox!(parent="MyParent";)`  
@@ -631,7 +832,8 @@ macro_rules! nwg_groupbox {
             visible: true, disabled: false,
             align: $crate::constants::HTextAlign::Left,
             parent: $p,
-            font: None
+            font: None,
+            text_color: None, background_color: None
         };
         $( t.$i = $v; );*
         t
@@ -677,6 +879,174 @@ macro_rules! nwg_progressbar {
     }} 
 }
 
+/**
+    Sane defaults for the Slider control. Requires a parent.
+
+    Defaults:  
+    • position: `(0, 0)`  
+    • size: `(100, 30)`  
+    • visible: `true`  
+    • disabled: `false`  
+    • range: `(0, 100)`  
+    • value: `0`  
+    • vertical: `false`  
+    • tick_frequency: `10`  
+    • page_size: `10`  
+    • line_size: `1`  
+    • selection: `None`  
+    • buddies: `(None, None)`
+
+    Usage:  
+    `nwg_slider!(parent="MyParent";)`  
+    `nwg_slider!(parent="MyParent"; range=(-50, 50); vertical=true)`  
+    `nwg_slider!(parent="MyParent"; \* Any combinations of the template properties*\)`    
+*/
+#[macro_export]
+macro_rules! nwg_slider {
+    (parent=$p:expr; $( $i:ident=$v:expr );* ) => { {
+        let mut t = 
+        $crate::SliderT {
+            position: (0, 0), size: (100, 30), 
+            visible: true, disabled: false,
+            range: (0, 100),
+            value: 0,
+            vertical: false,
+            tick_frequency: 10,
+            page_size: 10, line_size: 1,
+            selection: None,
+            buddies: (None, None),
+            parent: $p,
+        };
+        $( t.$i = $v; );*
+        t
+    }} 
+}
+
+/**
+    Sane defaults for the Toolbar control. Requires a parent.
+
+    Defaults:  
+    • buttons: `[]`  
+    • image_size: `(16, 16)`  
+    • visible: `true`  
+    • disabled: `false`  
+    • font: `None`
+
+    Usage:  
+    `nwg_toolbar!(parent="MyParent";)`  
+    `nwg_toolbar!(parent="MyParent"; buttons=vec![ToolbarButton::new(1, "Open"), ToolbarButton::separator()])`  
+    `nwg_toolbar!(parent="MyParent"; \* Any combinations of the template properties*\)`    
+*/
+#[macro_export]
+macro_rules! nwg_toolbar {
+    (parent=$p:expr; $( $i:ident=$v:expr );* ) => { {
+        let mut t = 
+        $crate::ToolbarT::<_> {
+            buttons: vec![],
+            image_size: (16, 16),
+            visible: true, disabled: false,
+            parent: $p,
+            font: None
+        };
+        $( t.$i = $v; );*
+        t
+    }}
+}
+
+/**
+    Sane defaults for the StatusBar control. Requires a parent.
+
+    Defaults:  
+    • text: `""`  
+    • parts: `[StatusPartWidth::Stretch(1)]`  
+    • size_grip: `true`  
+    • visible: `true`  
+    • font: `None`
+
+    Usage:  
+    `nwg_status_bar!(parent="MyParent";)`  
+    `nwg_status_bar!(parent="MyParent"; text="Ready"; parts=vec![StatusPartWidth::Stretch(1), StatusPartWidth::Fixed(100)])`  
+    `nwg_status_bar!(parent="MyParent"; \* Any combinations of the template properties*\)`    
+*/
+#[macro_export]
+macro_rules! nwg_status_bar {
+    (parent=$p:expr; $( $i:ident=$v:expr );* ) => { {
+        let mut t = 
+        $crate::StatusBarT::<_, _> {
+            text: "",
+            parts: vec![$crate::StatusPartWidth::Stretch(1)],
+            size_grip: true, visible: true,
+            parent: $p,
+            font: None
+        };
+        $( t.$i = $v; );*
+        t
+    }}
+}
+
+/**
+    Sane defaults for the Tooltip control. The tips are registered after the tooltip is created.
+
+    Defaults:  
+    • title: `""`  
+    • icon: `TooltipIcon::None`  
+    • balloon: `false`  
+    • multiline: `true`  
+    • initial_delay: `None`  
+    • autopop_delay: `None`  
+    • reshow_delay: `None`
+
+    Usage:  
+    `nwg_tooltip!()`  
+    `nwg_tooltip!(balloon=true; title="Help"; icon=TooltipIcon::Info)`  
+    `nwg_tooltip!(\* Any combinations of the template properties*\)`    
+*/
+#[macro_export]
+macro_rules! nwg_tooltip {
+    ($( $i:ident=$v:expr );*) => { #[allow(unused_mut)] {
+        let mut t = 
+        $crate::TooltipT{
+            title: "",
+            icon: $crate::TooltipIcon::None,
+            balloon: false,
+            multiline: true,
+            initial_delay: None,
+            autopop_delay: None,
+            reshow_delay: None
+        };
+        $( t.$i = $v; );*
+        t
+    }}
+}
+
+/**
+    Sane defaults for the TrayNotification control. Requires an icon `Image`.
+
+    Defaults:  
+    • tooltip: `None`  
+    • menu: `None`  
+    • visible: `true`
+
+    Usage:  
+    `nwg_tray_notification!(icon="AppIcon";)`  
+    `nwg_tray_notification!(icon="AppIcon"; tooltip=Some("Sync"); menu=Some("TrayMenu"))`  
+    `nwg_tray_notification!(icon="AppIcon"; \* Any combinations of the template properties*\)`    
+*/
+#[macro_export]
+macro_rules! nwg_tray_notification {
+    (icon=$i:expr; $( $f:ident=$v:expr );* ) => { #[allow(unused_mut)] {
+        let mut t = 
+        $crate::TrayNotificationT::<&'static str, _>{
+            icon: $i,
+            tooltip: None,
+            menu: None,
+            visible: true
+        };
+        $( t.$f = $v; );*
+        t
+    }}
+}
+
 /**
     Sane defaults for the DatePicker control. Requires a parent.
 
@@ -726,6 +1096,9 @@ macro_rules! nwg_datepicker {
     • multiselect: `false`  
     • default_folder: `None`  
     • filters: `None`  
+    • default_name: `None`  
+    • default_extension: `None`  
+    • overwrite_prompt: `true`  
 
     Usage:  
     `nwg_filedialog!()`  
@@ -742,7 +1115,129 @@ macro_rules! nwg_filedialog {
             action: $crate::constants::FileDialogAction::Open,
             multiselect: false,
             default_folder: None,
-            filters: None
+            filters: None,
+            default_name: None,
+            default_extension: None,
+            overwrite_prompt: true
+        };
+        
+        $( t.$i = $v; );*
+
+        t
+    }}
+}
+
+/**
+    Sane defaults for a FileDialog that browses for a folder. The dialog is a `FileDialog` with the `OpenDirectory` action.
+    Use `get_selected_path` to read the selected folder.
+
+    Defaults:  
+    • parent: `None`  
+    • title: `"Select a folder"`  
+    • action: `FileDialogAction::OpenDirectory`  
+    • multiselect: `false`  
+    • default_folder: `None`  
+
+    Usage:  
+    `nwg_folderdialog!()`  
+    `nwg_folderdialog!(parent=Some("MyParent"); default_folder=Some("C:\\Users"))`  
+*/
+#[macro_export]
+macro_rules! nwg_folderdialog {
+    ($( $i:ident=$v:expr );*) => { #[allow(unused_mut)] {
+        let mut t = nwg_filedialog!(title="Select a folder"; action=$crate::constants::FileDialogAction::OpenDirectory);
+        $( t.$i = $v; );*
+        t
+    }}
+}
+
+/**
+    Sane defaults for the ColorDialog control.
+
+    Defaults:  
+    • parent: `None`  
+    • color: `[0, 0, 0]`  
+    • custom_colors: `vec![]`  
+    • full_open: `false`  
+
+    Usage:  
+    `nwg_colordialog!()`  
+    `nwg_colordialog!(parent=Some("MyParent"); color=[255, 0, 0])`  
+    `nwg_colordialog!(\* Any combinations of the template properties*\)`    
+*/
+#[macro_export]
+macro_rules! nwg_colordialog {
+    ($( $i:ident=$v:expr );*) => { {
+        let mut t = 
+        $crate::ColorDialogT::<_>{ 
+            parent: None,
+            color: [0, 0, 0],
+            custom_colors: vec![],
+            full_open: false
+        };
+        
+        $( t.$i = $v; );*
+
+        t
+    }}
+}
+
+/**
+    Sane defaults for the FontDialog control.
+
+    Defaults:  
+    • parent: `None`  
+    • font: `None`  
+    • color: `[0, 0, 0]`  
+    • effects: `true`  
+    • fixed_pitch_only: `false`  
+
+    Usage:  
+    `nwg_fontdialog!()`  
+    `nwg_fontdialog!(parent=Some("MyParent"); font=Some(nwg_font!(family="Arial"; size=20)))`  
+    `nwg_fontdialog!(\* Any combinations of the template properties*\)`    
+*/
+#[macro_export]
+macro_rules! nwg_fontdialog {
+    ($( $i:ident=$v:expr );*) => { {
+        let mut t = 
+        $crate::FontDialogT::<&'static str, _>{ 
+            parent: None,
+            font: None,
+            color: [0, 0, 0],
+            effects: true,
+            fixed_pitch_only: false
+        };
+        
+        $( t.$i = $v; );*
+
+        t
+    }}
+}
+
+/**
+    Sane defaults for the PrintDialog control.
+
+    Defaults:  
+    • parent: `None`  
+    • pages: `None`  
+    • selection: `false`  
+    • print_to_file: `false`  
+
+    Usage:  
+    `nwg_printdialog!()`  
+    `nwg_printdialog!(parent=Some("MyParent"); pages=Some((1, 10)))`  
+    `nwg_printdialog!(\* Any combinations of the template properties*\)`    
+*/
+#[macro_export]
+macro_rules! nwg_printdialog {
+    ($( $i:ident=$v:expr );*) => { {
+        let mut t = 
+        $crate::PrintDialogT::<_>{ 
+            parent: None,
+            pages: None,
+            selection: false,
+            print_to_file: false
         };
         
         $( t.$i = $v; );*
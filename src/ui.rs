@@ -4,16 +4,22 @@
 
 use std::hash::Hash;
 use std::ptr;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::any::{Any, TypeId};
-use std::cell::{RefCell, Ref, RefMut};
+use std::cell::{Cell, RefCell, Ref, RefMut};
 use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker, Wake};
 
-use low::message_handler::MessageHandler;
-use low::defs::{PackUserValueArgs, PackControlArgs, PackResourceArgs, UnpackArgs, BindArgs, UnbindArgs, TriggerArgs};
+use low::backend::Backend;
+use low::defs::{PackUserValueArgs, PackControlArgs, PackResourceArgs, UnpackArgs, BindArgs, UnbindArgs, TriggerArgs, LayoutArgs, LAYOUT_CALLBACK_ID};
 use controls::{ControlT, Control, AnyHandle, ControlType};
 use resources::{ResourceT, Resource};
-use events::{Event, EventCallback, EventArgs, Destroyed};
+use events::{Event, EventCallback, EventArgs, EventOutcome, Destroyed, Resized};
+use layout::Layout;
+use binding::{Observable, Binding, BindingMode};
 use error::Error;
 
 type InnerId = u64;
@@ -26,13 +32,82 @@ pub type EventCollection<ID> = HashMap<Event, CallbackCollection<ID>>;
 pub type EventHandlerCollection = Rc<Vec<Event>>;
 pub type EventDefinitionsCollection = HashMap<u32, EventHandlerCollection>;
 
+pub type ProxyTask<ID> = Box<FnOnce(&Ui<ID>) -> () + Send>;
+
+/**
+    Tasks sent to a Ui by its proxies. Shared between the Ui thread and the proxies.
+*/
+pub struct ProxyQueue<ID: Hash+Clone+'static> {
+    // Tasks waiting to be executed. `None` once the ui is freed.
+    tasks: Mutex<Option<VecDeque<ProxyTask<ID>>>>,
+
+    // Wake up the ui thread. See `Backend.proxy_waker`
+    wake: Box<Fn() + Send + Sync>
+}
+
+impl<ID: Hash+Clone> ProxyQueue<ID> {
+
+    /**
+        Queue a task and wake up the ui thread. Returns `Err(Error::BadUi)` if the ui was freed.
+    */
+    fn push(&self, task: ProxyTask<ID>) -> Result<(), Error> {
+        match self.tasks.lock().unwrap().as_mut() {
+            Some(tasks) => tasks.push_back(task),
+            None => { return Err(Error::BadUi("The ui of the proxy was freed".to_string())); }
+        }
+
+        (self.wake)();
+        Ok(())
+    }
+
+}
+
+pub type LocalTask = Pin<Box<Future<Output=()>>>;
+
+/**
+    Waker of a task spawned with `Ui.spawn_local`. Waking the task queue a poll of the task in the ui proxy queue.
+*/
+struct LocalTaskWaker<ID: Hash+Clone+'static> {
+    queue: Arc<ProxyQueue<ID>>,
+    task: u64
+}
+
+impl<ID: Hash+Clone> Wake for LocalTaskWaker<ID> {
+    fn wake(self: Arc<Self>) {
+        let task = self.task;
+
+        // If the ui was freed, its local tasks were dropped with it. Nothing to do.
+        self.queue.push(Box::new(move |ui: &Ui<ID>| {
+            let inner = unsafe{ &mut *ui.inner };
+            inner.poll_local_task(task);
+        })).ok();
+    }
+}
+
+
+/**
+    A binding between a user value and a control installed by `Ui.bind_value`
+*/
+pub struct ValueBinding {
+    pub value: InnerId,
+    pub control: InnerId,
+
+    // The control event that updates the value. Its callback id is the value id.
+    pub event: Option<Event>,
+
+    // Remove the listener added to the observable value
+    pub release: Box<Fn()>
+}
 
 /**
     Inner window data shared within the thread
 */
 pub struct UiInner<ID: Hash+Clone+'static> {
-    // Underlying message only window that process the Ui events and some other windows events not tied to windows (ex: WM_TIMER)
-    pub messages: MessageHandler<ID>,
+    // Backend that queues the Ui commands. On Windows, a message only window that process the Ui events and some other windows events not tied to windows (ex: WM_TIMER)
+    pub messages: Box<Backend<ID>>,
+
+    // Error raised by the last failed command
+    pub last_error: Option<Error>,
 
     // Map of inner id to a cell containing the control.
     pub controls: HashMap<InnerId, RefCell<Box<Control>>>,
@@ -47,32 +122,174 @@ pub struct UiInner<ID: Hash+Clone+'static> {
     pub inner_public_map: HashMap<InnerId, (ID, TypeId)>,
 
     // Map the handle of the controls to its ui inner id. Used when matching controls from within the events proc
-    pub handle_inner_map: HashMap<HandleHash, InnerId>
-}
+    pub handle_inner_map: HashMap<HandleHash, InnerId>,
 
-impl<ID: Hash+Clone> UiInner<ID> {
+    // Tasks queue shared with the proxies of the ui. Created by the first call to `Ui.proxy` or `Ui.spawn_local`
+    pub proxy: Option<Arc<ProxyQueue<ID>>>,
 
-    pub fn new() -> Result<UiInner<ID>, Error> {
-        use low::other_helper::{enable_visual_styles, enable_com};
-        let messages: MessageHandler<ID> = match MessageHandler::new() {
-            Ok(msg) => msg,
-            Err(e) => { return Err(e); }
-        };
+    // Futures spawned with `Ui.spawn_local` that did not complete yet
+    pub local_tasks: HashMap<u64, LocalTask>,
+    pub next_local_task: u64,
 
-        unsafe{ 
-            enable_visual_styles(); 
-            enable_com();
-        }
+    // Bindings between user values and controls. Removed when the value or the control is unpacked
+    pub bindings: Vec<ValueBinding>,
 
-        Ok(UiInner{
+    // Layouts set with `Ui.set_layout`, by parent id. Removed when the parent is unpacked
+    pub layouts: HashMap<InnerId, Rc<Layout<ID>>>
+}
+
+impl<ID: Hash+Clone> UiInner<ID> {
+
+    pub fn new(messages: Box<Backend<ID>>) -> UiInner<ID> {
+        UiInner{
             messages: messages,
+            last_error: None,
             user_values: HashMap::with_capacity(16),
             controls: HashMap::with_capacity(32),
             control_events: HashMap::with_capacity(32),
             events_definitions: HashMap::with_capacity(32),
             resources: HashMap::with_capacity(16),
             inner_public_map: HashMap::with_capacity(64),
-            handle_inner_map: HashMap::with_capacity(32) })
+            handle_inner_map: HashMap::with_capacity(32),
+            proxy: None,
+            local_tasks: HashMap::new(),
+            next_local_task: 0,
+            bindings: Vec::new(),
+            layouts: HashMap::new() }
+    }
+
+    /**
+        Return the tasks queue shared with the proxies of the ui. The queue is created on the first call.
+    */
+    pub fn proxy_queue(&mut self) -> Arc<ProxyQueue<ID>> {
+        if self.proxy.is_none() {
+            let self_ptr = self as *mut UiInner<ID>;
+            let wake = self.messages.proxy_waker(self_ptr);
+            self.proxy = Some(Arc::new(ProxyQueue{ tasks: Mutex::new(Some(VecDeque::new())), wake: wake }));
+        }
+
+        self.proxy.as_ref().unwrap().clone()
+    }
+
+    /**
+        Queue a poll of a local task in the proxy queue
+    */
+    pub fn wake_local_task(&mut self, task: u64) {
+        let waker = Waker::from(Arc::new(LocalTaskWaker{ queue: self.proxy_queue(), task: task }));
+        waker.wake();
+    }
+
+    /**
+        Poll a task spawned with `Ui.spawn_local`. The task is dropped once it completes.
+        Does nothing if the task already completed.
+    */
+    pub fn poll_local_task(&mut self, task_id: u64) {
+        // The task is removed while it is polled because it may spawn new tasks
+        let mut task = match self.local_tasks.remove(&task_id) {
+            Some(t) => t,
+            None => { return; }
+        };
+
+        let waker = Waker::from(Arc::new(LocalTaskWaker{ queue: self.proxy_queue(), task: task_id }));
+        let mut context = Context::from_waker(&waker);
+        if let Poll::Pending = task.as_mut().poll(&mut context) {
+            self.local_tasks.insert(task_id, task);
+        }
+    }
+
+    /**
+        Execute the tasks sent by the proxies of the ui. Must be called by the backend from the Ui thread.
+    */
+    pub fn run_proxy_tasks(&mut self) {
+        let queue = match self.proxy.as_ref() {
+            Some(q) => q.clone(),
+            None => { return; }
+        };
+
+        let tmp_ui: Ui<ID> = Ui{inner: self as *mut UiInner<ID>};
+        loop {
+            // The lock must be released before the task is executed because a task can send new tasks
+            let task = match queue.tasks.lock().unwrap().as_mut() {
+                Some(tasks) => tasks.pop_front(),
+                None => None
+            };
+
+            match task {
+                Some(task) => task(&tmp_ui),
+                None => break
+            }
+        }
+
+        ::std::mem::forget(tmp_ui);
+    }
+
+    /**
+        Execute a NWG command posted to the backend. `msg` is the command identifier and `args` its arguments.
+        Returns the error raised by the command, if any.
+    */
+    pub fn execute_command(&mut self, msg: u32, args: Box<Any>) -> Option<Error> {
+        use low::defs::{NWG_PACK_USER_VALUE, NWG_PACK_CONTROL, NWG_UNPACK, NWG_BIND, NWG_UNBIND, NWG_TRIGGER, NWG_PACK_RESOURCE, NWG_LAYOUT};
+
+        match msg {
+            NWG_PACK_USER_VALUE => {
+                if let Ok(params) = args.downcast::<PackUserValueArgs<ID>>() {
+                    self.pack_user_value(*params)
+                } else {
+                    panic!("Could not downcast command PACK_USER_VALUE args into a PackUserValueArgs struct.");
+                }
+            },
+            NWG_PACK_CONTROL => {
+                if let Ok(params) = args.downcast::<PackControlArgs<ID>>() {
+                    self.pack_control(*params)
+                } else {
+                    panic!("Could not downcast command PACK_CONTROL args into a PackControlArgs struct.");
+                }
+            },
+            NWG_PACK_RESOURCE => {
+                if let Ok(params) = args.downcast::<PackResourceArgs<ID>>() {
+                    self.pack_resource(*params)
+                } else {
+                    panic!("Could not downcast command NWG_PACK_RESOURCE args into a PackResourceArgs struct.");
+                }
+            },
+            NWG_UNPACK => {
+                if let Ok(params) = args.downcast::<UnpackArgs>() {
+                    self.unpack(*params)
+                } else {
+                    panic!("Could not downcast command NWG_UNPACK_CONTROL args into a inner id.");
+                }
+            },
+            NWG_BIND => {
+                if let Ok(params) = args.downcast::<BindArgs<ID>>() {
+                    self.bind(*params)
+                } else {
+                    panic!("Could not downcast command NWG_BIND args into a BindArgs struct.");
+                }
+            },
+            NWG_UNBIND => {
+                if let Ok(params) = args.downcast::<UnbindArgs>() {
+                    self.unbind(*params)
+                } else {
+                    panic!("Could not downcast command NWG_UNBIND args into a UnbindArgs struct.");
+                }
+            },
+            NWG_TRIGGER => {
+                if let Ok(params) = args.downcast::<TriggerArgs>() {
+                    let TriggerArgs{ id, event, args } = *params;
+                    self.trigger(id, event, args)
+                } else {
+                    panic!("Could not downcast command NWG_TRIGGER args into a TriggerArgs struct.");
+                }
+            },
+            NWG_LAYOUT => {
+                if let Ok(params) = args.downcast::<LayoutArgs<ID>>() {
+                    self.set_layout(*params)
+                } else {
+                    panic!("Could not downcast command NWG_LAYOUT args into a LayoutArgs struct.");
+                }
+            },
+            _ => panic!("Unknown NWG command: {}", msg)
+        }
     }
 
     pub fn pack_user_value(&mut self, params: PackUserValueArgs<ID>) -> Option<Error> {
@@ -87,8 +304,6 @@ impl<ID: Hash+Clone> UiInner<ID> {
     }
 
     pub fn pack_control(&mut self, params: PackControlArgs<ID>) -> Option<Error> {
-        use low::events::hook_window_events;
-
         let inner_id = UiInner::hash_id(&params.id);
         if self.inner_public_map.contains_key(&inner_id) {
             Some(Error::KeyExists)
@@ -98,15 +313,13 @@ impl<ID: Hash+Clone> UiInner<ID> {
                 Ok(control) => {
                     let handle_hash = UiInner::<ID>::hash_handle(&control.handle());
 
-                    match control.handle() {
-                        AnyHandle::HWND(h) => hook_window_events(self, h), // Hook the window events if the handle is a HWND
-                        _ => { /* Nothing to do for the other controls */}
-                    }
+                    let self_ptr = self as *mut UiInner<ID>;
+                    self.messages.hook_events(self_ptr, &control.handle());
 
                     // Init events
                     let event_collection: EventCollection<ID> = HashMap::new();
 
-                    self.inner_public_map.insert(inner_id, (params.id, params.value.type_id()));
+                    self.inner_public_map.insert(inner_id, (params.id, ControlT::type_id(&*params.value)));
                     self.controls.insert(inner_id, RefCell::new(control) );
                     self.control_events.insert(inner_id, event_collection);
                     self.handle_inner_map.insert(handle_hash, inner_id);
@@ -130,7 +343,7 @@ impl<ID: Hash+Clone> UiInner<ID> {
                 Ok(resource) => {
                     let handle_hash = UiInner::<ID>::hash_handle(&resource.handle());
 
-                    self.inner_public_map.insert(inner_id, (params.id, params.value.type_id()));
+                    self.inner_public_map.insert(inner_id, (params.id, ResourceT::type_id(&*params.value)));
                     self.resources.insert(inner_id, RefCell::new(resource) );
                     self.handle_inner_map.insert(handle_hash, inner_id);
 
@@ -143,8 +356,6 @@ impl<ID: Hash+Clone> UiInner<ID> {
     }
 
     fn unpack_control(&mut self, id: InnerId) -> Option<Error> {
-        use low::events::unhook_window_events;
-
         // Check if one of the control events is currently being executed
         {
             let events_collection = self.control_events.get_mut(&id).unwrap();
@@ -177,17 +388,15 @@ impl<ID: Hash+Clone> UiInner<ID> {
             // Removes stuff
             self.inner_public_map.remove(&id).unwrap();
             self.control_events.remove(&id).unwrap();
+            self.layouts.remove(&id);
             let control = self.controls.remove(&id).unwrap();
             let mut control = control.into_inner();
 
             let handle_hash = *self.handle_inner_map.iter().find(|&(_, ref v)| *v == id).expect("Could not match handle while unpacking control").0;
             self.handle_inner_map.remove(&handle_hash);
 
-            // Unhook the events dispatcher if its a window
-            match control.handle() {
-                AnyHandle::HWND(h) => unhook_window_events::<ID>(h),
-                _ => {/* Nothing to free here */}
-            };
+            // Unhook the events dispatcher
+            self.messages.unhook_events(&control.handle());
             
             // Free the control custom resources
             control.free();
@@ -230,7 +439,7 @@ impl<ID: Hash+Clone> UiInner<ID> {
         let id = params.id;
 
         // Test if everything is valid
-        if !self.inner_public_map.contains_key(&id) {
+        let result = if !self.inner_public_map.contains_key(&id) {
             Some(Error::KeyNotFound)
         } else {
             if self.controls.contains_key(&id) {
@@ -242,7 +451,32 @@ impl<ID: Hash+Clone> UiInner<ID> {
             } else {
                 Some(Error::BadType)
             }
+        };
+
+        if result.is_none() {
+            self.remove_bindings(|b| b.value == id || b.control == id);
+        }
+
+        result
+    }
+
+    /**
+        Remove the value bindings matching `filter`. The listeners of the values and the callbacks of the controls are removed.
+        Returns the number of removed bindings.
+    */
+    pub fn remove_bindings<F: Fn(&ValueBinding) -> bool>(&mut self, filter: F) -> usize {
+        let (removed, kept): (Vec<ValueBinding>, Vec<ValueBinding>) = self.bindings.drain(..).partition(|b| filter(b));
+        self.bindings = kept;
+
+        for binding in removed.iter() {
+            (binding.release)();
+            if let Some(event) = binding.event {
+                // The callback is already gone if the control was unpacked
+                self.unbind(UnbindArgs{ id: binding.control, cb_id: binding.value, event: event });
+            }
         }
+
+        removed.len()
     }
 
     pub fn bind(&mut self, params: BindArgs<ID>) -> Option<Error> {
@@ -340,21 +574,72 @@ impl<ID: Hash+Clone> UiInner<ID> {
         // Note that event definition are not removed
     }
 
+    /**
+        Set (`Some`) or remove (`None`) the layout of a parent. The layout callback is bound to the `Resized` event of the
+        parent with a reserved callback id, so the user callbacks are never touched. A new layout is applied right away.
+    */
+    pub fn set_layout(&mut self, params: LayoutArgs<ID>) -> Option<Error> {
+        let LayoutArgs{ id, layout } = params;
+
+        if !self.inner_public_map.contains_key(&id) {
+            return Some(Error::KeyNotFound);
+        }
+
+        if !self.controls.contains_key(&id) {
+            return Some(Error::ControlRequired);
+        }
+
+        let layout = match layout {
+            Some(l) => l,
+            None => {
+                return match self.layouts.remove(&id) {
+                    Some(_) => self.unbind(UnbindArgs{ id: id, cb_id: LAYOUT_CALLBACK_ID, event: Resized }),
+                    None => Some(Error::KeyNotFound)
+                };
+            }
+        };
+
+        // The callback reads the current layout of the parent, so it is only bound once
+        if !self.layouts.contains_key(&id) {
+            let cb = |ui: &Ui<ID>, parent: &ID, _: &Event, _: &EventArgs| { apply_layout(ui, parent); EventOutcome::Continue };
+            if let Some(e) = self.bind(BindArgs{ id: id, cb_id: LAYOUT_CALLBACK_ID, event: Resized, cb: Box::new(cb) }) {
+                return Some(e);
+            }
+        }
+
+        self.layouts.insert(id, Rc::new(layout));
+
+        let parent = self.inner_public_map.get(&id).unwrap().0.clone();
+        let tmp_ui: Ui<ID> = Ui{inner: self as *mut UiInner<ID>};
+        apply_layout(&tmp_ui, &parent);
+        ::std::mem::forget(tmp_ui);
+
+        None
+    }
+
     pub fn trigger(&mut self, id: InnerId, event: Event, args: EventArgs) -> Option<Error> {
+        self.trigger_outcome(id, event, args).err()
+    }
+
+    /**
+        Call the callbacks bound to an event until one of them returns something else than `EventOutcome::Continue`.
+        Returns the outcome of the last callback called.
+    */
+    pub fn trigger_outcome(&mut self, id: InnerId, event: Event, args: EventArgs) -> Result<EventOutcome, Error> {
 
         let pub_id = match self.inner_public_map.get_mut(&id) {
             Some(&mut (ref pub_id, _)) => pub_id.clone(),
-            None => { return Some(Error::KeyNotFound); }
+            None => { return Err(Error::KeyNotFound); }
         };
 
         let callback_list = {
             // Get the event collection of the control
             let events_collection = self.control_events.get_mut(&id);
-            if events_collection.is_none() { return Some(Error::ControlRequired); }
+            if events_collection.is_none() { return Err(Error::ControlRequired); }
 
             // Get the callback list for the requested event
             let callbacks = events_collection.unwrap().get_mut(&event);
-            if callbacks.is_none() { return None; }
+            if callbacks.is_none() { return Ok(EventOutcome::Continue); }
 
             // Return a reference to the callback list. While the reference exists, it will be impossible
             // to push new callback into the event.
@@ -362,12 +647,14 @@ impl<ID: Hash+Clone> UiInner<ID> {
         };
 
         let tmp_ui: Ui<ID> = Ui{inner: self as *mut UiInner<ID>};
+        let mut outcome = EventOutcome::Continue;
         for &( _, ref callback) in callback_list.iter() {
-            (callback)(&tmp_ui, &pub_id, &event, &args);
+            outcome = (callback)(&tmp_ui, &pub_id, &event, &args);
+            if outcome != EventOutcome::Continue { break; }
         }
 
         ::std::mem::forget(tmp_ui);
-        None
+        Ok(outcome)
     }
 
     pub fn handle_of(&self, id: InnerId) -> Result<AnyHandle, Error> {
@@ -479,8 +766,12 @@ impl<ID: Hash+Clone> UiInner<ID> {
 impl<ID: Hash+Clone> Drop for UiInner<ID> {
 
     fn drop(&mut self) {
-        use low::events::unhook_window_events;
-        
+        // Pending futures may hold references to the ui elements
+        self.local_tasks.clear();
+
+        // The observable values may outlive the ui
+        self.remove_bindings(|_| true);
+
         let controls_ids: Vec<u64> = self.controls.keys().map(|k| *k).collect();
         for id in controls_ids {
             self.unpack(UnpackArgs{id: id});
@@ -490,8 +781,11 @@ impl<ID: Hash+Clone> Drop for UiInner<ID> {
         for id in resources_ids {
             self.unpack(UnpackArgs{id: id});
         }
-        
-        unhook_window_events::<ID>(self.messages.hwnd);
+
+        // The proxies can no longer send tasks to the ui
+        if let Some(proxy) = self.proxy.take() {
+            *proxy.tasks.lock().unwrap() = None;
+        }
 
         self.messages.free();
     }
@@ -514,23 +808,36 @@ impl<ID:Hash+Clone> Ui<ID> {
         Returns `Ok(ui)` if the initialization was successful  
         Returns `Err(Error::System)` if the system could not initialize the ui
     */
+    #[cfg(windows)]
     pub fn new() -> Result<Ui<ID>, Error> {
-        use low::events::hook_window_events;
+        use low::message_handler::MessageHandler;
+        use low::other_helper::{enable_visual_styles, enable_com};
 
-        let inner = match UiInner::new() {
-            Ok(inner) => Box::into_raw(Box::new(inner)),
+        let messages: MessageHandler<ID> = match MessageHandler::new() {
+            Ok(msg) => msg,
             Err(e) => { return Err(e); }
         };
 
-        // Hook the inner message window. This is basically a SAFE hack to process non nwg events that are sent to ui (ie: WM_TIMER)
-        // Window gets unhooked just before inner gets dropped.
-        unsafe{
-            let inner = &mut *inner;
-            let hwnd = inner.messages.hwnd;
-            hook_window_events(inner, hwnd);
+        unsafe{ 
+            enable_visual_styles(); 
+            enable_com();
         }
 
-        Ok( Ui{inner: inner} )
+        Ok( Ui::with_backend(Box::new(messages)) )
+    }
+
+    /**
+        Create a new Ui that uses a custom backend to execute its commands and to receive the system events.
+        See `nwg::mock::MockBackend` to create a Ui that do not require any system window.
+
+        Params:  
+          • messages: The backend of the ui  
+    */
+    pub fn with_backend(messages: Box<Backend<ID>>) -> Ui<ID> {
+        let inner = Box::into_raw(Box::new(UiInner::new(messages)));
+        unsafe{ (&mut *inner).messages.init(inner); }
+
+        Ui{inner: inner}
     }
 
     /**
@@ -729,6 +1036,9 @@ impl<ID:Hash+Clone> Ui<ID> {
           • event: Type of event to target  
           • cb: The callback  
 
+        The callback can return `()` or an `EventOutcome`. Callbacks are called in the order they were bound until one of them
+        returns `EventOutcome::Stop` or `EventOutcome::Return`. Returning `Stop` from a vetoable event (ex: `Closed`) cancels the system action.
+
         Commit may returns:  
           • `Error::EventNotSupported` if the event is not supported on the callback  
           • `Error::ControlRequired` if the id do not indentify a control  
@@ -737,12 +1047,14 @@ impl<ID:Hash+Clone> Ui<ID> {
           • `Error::ControlInUse` if NWG is currently executing the callback of the event  
         
     */
-    pub fn bind<T>(&self, id: &ID, cb_id: &ID, event: Event, cb: T) where
-      T: Fn(&Ui<ID>, &ID, &Event, &EventArgs) -> ()+'static {
+    pub fn bind<T, R>(&self, id: &ID, cb_id: &ID, event: Event, cb: T) where
+      T: Fn(&Ui<ID>, &ID, &Event, &EventArgs) -> R+'static,
+      R: Into<EventOutcome> {
         use low::defs::{NWG_BIND};
         
         let inner = unsafe{ &mut *self.inner };
         let (inner_id, cb_inner_id) = (UiInner::hash_id(id), UiInner::hash_id(cb_id));
+        let cb = move |ui: &Ui<ID>, id: &ID, event: &Event, args: &EventArgs| cb(ui, id, event, args).into();
         let data = BindArgs{ id: inner_id, cb_id: cb_inner_id, event: event, cb: Box::new(cb)};
         inner.messages.post(self.inner, NWG_BIND, Box::new(data) as Box<Any> );
     }
@@ -797,6 +1109,227 @@ impl<ID:Hash+Clone> Ui<ID> {
         inner.messages.post(self.inner, NWG_TRIGGER, Box::new(data) as Box<Any> );
     }
 
+    /**
+        Return a proxy to the ui. A proxy can be cloned and sent to other threads in order to execute code
+        or trigger events in the ui thread. See `UiProxy`.
+    */
+    pub fn proxy(&self) -> UiProxy<ID> {
+        let inner = unsafe{ &mut *self.inner };
+        UiProxy{ queue: inner.proxy_queue() }
+    }
+
+    /**
+        Run a future in the ui thread. The future do not need to be `Send` and can hold references to the ui elements.
+
+        The future is first polled by the events loop (`dispatch_events`) or by the next `ui.commit`. After that, 
+        the future is polled each time it is woken up. The waker can be sent to other threads.  
+        Pending futures are dropped when the ui is freed.
+
+        Params:  
+          • future: The future to run  
+    */
+    pub fn spawn_local<F>(&self, future: F) where F: Future<Output=()> + 'static {
+        let inner = unsafe{ &mut *self.inner };
+        let task = inner.next_local_task;
+        inner.next_local_task += 1;
+
+        inner.local_tasks.insert(task, Box::pin(future));
+        inner.wake_local_task(task);
+    }
+
+    /**
+        Bind a user value to a property of a control. Unlike most of the ui methods, the binding is installed immediately:
+        the control is updated with the current value (unless the binding mode is `OneWayToSource`) and updated again each time
+        the value changes.
+
+        If the binding is `TwoWay` or `OneWayToSource`, the value is also updated when the user modifies the control.
+        The control event is bound using the value id as the callback id. This part is delayed,
+        either call `ui.commit` to execute it now or wait for the command to be executed in the main event loop.
+
+        The binding is removed when the value or the control is unpacked. A control can only be bound once to a value.
+
+        Params:
+          • value_id: The id of a `binding::Observable<M>` packed in the ui
+          • control_id: The id of the control
+          • binding: The binding definition
+
+        Returns:
+          • `Error::KeyNotFound` if one of the id is not in the Ui
+          • `Error::BadType` if the value is not an `Observable<M>`
+          • `Error::ControlRequired` if the control id do not identify a control
+          • `Error::KeyExists` if the control is already bound to the value
+          • `Error::BadBinding` if the user cannot modify the property and the binding is not `OneWay`
+          • Any error returned by the property when the control is updated
+    */
+    pub fn bind_value<M, V>(&self, value_id: &ID, control_id: &ID, binding: Binding<ID, M, V>) -> Result<(), Error> where
+      M: Clone+'static,
+      V: 'static
+    {
+        let observable = match self.get::<Observable<M>>(value_id) {
+            Ok(v) => (**v).clone(),
+            Err(e) => { return Err(e); }
+        };
+
+        let inner = unsafe{ &mut *self.inner };
+        let (value, control) = (UiInner::hash_id(value_id), UiInner::hash_id(control_id));
+        if !inner.inner_public_map.contains_key(&control) { return Err(Error::KeyNotFound); }
+        if !inner.controls.contains_key(&control) { return Err(Error::ControlRequired); }
+        if inner.bindings.iter().any(|b| b.value == value && b.control == control) { return Err(Error::KeyExists); }
+
+        let mode = binding.mode_for(self, control_id);
+        let event = match (mode, binding.property().changed_by(self, control_id)) {
+            (BindingMode::OneWay, _) => None,
+            (_, Some(e)) => Some(e),
+            (_, None) => { return Err(Error::BadBinding("The user cannot modify the property, use BindingMode::OneWay".to_string())); }
+        };
+
+        // Set while the binding writes the control or the value, so that the change is not copied back
+        let updating = Rc::new(Cell::new(false));
+        let binding = Rc::new(binding);
+
+        let release: Box<Fn()> = if mode != BindingMode::OneWayToSource {
+            updating.set(true);
+            let result = binding.write_control(self, control_id, &observable.get());
+            updating.set(false);
+            if let Err(e) = result { return Err(e); }
+
+            let (ui_ptr, control_id, b, u) = (self.inner, control_id.clone(), binding.clone(), updating.clone());
+            let key = observable.subscribe(move |v: &M| {
+                if u.get() { return; }
+                let tmp_ui: Ui<ID> = Ui{inner: ui_ptr};
+                u.set(true);
+                b.write_control(&tmp_ui, &control_id, v).ok();
+                u.set(false);
+                ::std::mem::forget(tmp_ui);
+            });
+
+            let o = observable.clone();
+            Box::new(move || { o.unsubscribe(key); })
+        } else {
+            Box::new(|| {})
+        };
+
+        if let Some(event) = event {
+            let (b, u) = (binding.clone(), updating.clone());
+            self.bind(control_id, value_id, event, move |ui, id, _, _| {
+                if u.get() { return; }
+                if let Some(v) = b.read_control(ui, id) {
+                    u.set(true);
+                    observable.set(v);
+                    u.set(false);
+                }
+            });
+        }
+
+        inner.bindings.push(ValueBinding{ value: value, control: control, event: event, release: release });
+
+        Ok(())
+    }
+
+    /**
+        Remove a binding installed by `ui.bind_value`. The control keeps its current state.
+        Returns `Error::KeyNotFound` if the control is not bound to the value.
+
+        Params:
+          • value_id: The id of the bound value
+          • control_id: The id of the bound control
+    */
+    pub fn unbind_value(&self, value_id: &ID, control_id: &ID) -> Result<(), Error> {
+        let inner = unsafe{ &mut *self.inner };
+        let (value, control) = (UiInner::hash_id(value_id), UiInner::hash_id(control_id));
+        match inner.remove_bindings(|b| b.value == value && b.control == control) {
+            0 => Err(Error::KeyNotFound),
+            _ => Ok(())
+        }
+    }
+
+    /**
+        Validate the `TextInput` and `TextBox` controls in a parent window (see `TextInput.validate`). 
+        The controls that are rejected by their validators display an error icon and a balloon tip.
+
+        Params:
+          • parent: The id of the parent window in the ui
+
+        Returns the ids of the rejected controls in the order of the parent children or:
+          • `Error::KeyNotFound` if the id is not in the Ui
+          • `Error::ControlRequired` if the id do not identify a window-like control
+    */
+    #[cfg(windows)]
+    pub fn validate_all(&self, parent: &ID) -> Result<Vec<ID>, Error> {
+        use controls::{TextInput, TextBox};
+        use low::window_helper::list_window_children;
+
+        let handle = match self.handle_of(parent) {
+            Ok(AnyHandle::HWND(h)) => h,
+            Ok(_) => { return Err(Error::ControlRequired); },
+            Err(e) => { return Err(e); }
+        };
+
+        let mut rejected = Vec::new();
+        for child in unsafe{ list_window_children(handle) }.iter() {
+            let id = match self.id_from_handle(child) {
+                Ok(id) => id,
+                Err(_) => { continue; }  // Not a NWG control
+            };
+
+            let valid = match self.type_of_control(&id) {
+                Ok(ControlType::TextInput) => self.get::<TextInput>(&id).map(|c| c.validate().is_ok()).unwrap_or(true),
+                Ok(ControlType::TextBox) => self.get::<TextBox>(&id).map(|c| c.validate().is_ok()).unwrap_or(true),
+                _ => true
+            };
+
+            if !valid {
+                rejected.push(id);
+            }
+        }
+
+        Ok(rejected)
+    }
+
+    /**
+        Let a layout manage the geometry of the controls in a parent window.
+        The layout is computed over the client area of the parent and is recomputed each time the parent is resized.
+        The toolbars and the status bars of the parent are not managed, the layout uses the area between them.
+        Delayed, this only registers the command in the ui message queue.
+        Either call `ui.commit` to execute it now or wait for the command to be executed in the main event loop.
+
+        The layout is applied when the command is executed and from a callback bound to the `Resized` event of the parent.
+        The callback uses a reserved callback id, it does not replace or remove the user callbacks.
+        Setting a new layout replaces the previous one. Use `ui.remove_layout` to remove it.
+
+        Params:
+          • parent: The id that identify the parent window in the ui
+          • layout: The layout to apply
+
+        Commit may returns:
+          • `Error::ControlRequired` if the id do not indentify a control
+          • `Error::KeyNotFound` if the id is not in the Ui.
+    */
+    pub fn set_layout<L: Into<Layout<ID>>>(&self, parent: &ID, layout: L) {
+        self.post_layout(parent, Some(layout.into()));
+    }
+
+    /**
+        Remove the layout of a parent window. The controls keep their current geometry.
+        Delayed, this only registers the command in the ui message queue.
+        Either call `ui.commit` to execute it now or wait for the command to be executed in the main event loop.
+
+        Commit may returns:
+          • `Error::ControlRequired` if the id do not indentify a control
+          • `Error::KeyNotFound` if the id is not in the Ui or if the parent has no layout
+    */
+    pub fn remove_layout(&self, parent: &ID) {
+        self.post_layout(parent, None);
+    }
+
+    fn post_layout(&self, parent: &ID, layout: Option<Layout<ID>>) {
+        use low::defs::NWG_LAYOUT;
+
+        let inner = unsafe{ &mut *self.inner };
+        let data = LayoutArgs{ id: UiInner::hash_id(parent), layout: layout };
+        inner.messages.post(self.inner, NWG_LAYOUT, Box::new(data) as Box<Any> );
+    }
+
     /**
         Return the underlying handle of a control or a resource.
         While this method is safe, anything done with the returned handle definitely won't be.
@@ -861,7 +1394,34 @@ impl<ID:Hash+Clone> Ui<ID> {
     */
     pub unsafe fn message_handle(&self) -> ::winapi::HWND {
         let inner = &mut (&*self.inner);
-        inner.messages.hwnd
+        inner.messages.message_handle()
+    }
+
+    /**
+        Send a system message to the events callbacks of the ui as if the system had sent it to the window identified by `id`.
+        The message do not reach the underlying window. Mostly useful to test the events of a Ui.
+
+        Params:  
+          • id: The id that identify the window in the ui  
+          • msg, w, l: The message parameters  
+
+        Returns:  
+          • `Ok(None)` if the message was dispatched and the system would process the message normally  
+          • `Ok(Some(result))` if a callback vetoed the event or returned a value with `EventOutcome::Return`  
+          • `Error::KeyNotFound` if the id is not in the Ui.  
+          • `Error::ControlRequired` if the id do not identify a window-like control  
+    */
+    pub fn simulate_message(&self, id: &ID, msg: ::winapi::UINT, w: ::winapi::WPARAM, l: ::winapi::LPARAM) -> Result<Option<::winapi::LRESULT>, Error> {
+        use low::events::dispatch_message;
+
+        match self.handle_of(id) {
+            Ok(AnyHandle::HWND(hwnd)) => {
+                let inner = unsafe{ &mut *self.inner };
+                Ok(dispatch_message(inner, hwnd, msg, w, l))
+            },
+            Ok(_) | Err(Error::ControlOrResourceRequired) => Err(Error::ControlRequired),
+            Err(e) => Err(e)
+        }
     }
 
 }
@@ -874,11 +1434,111 @@ impl<ID: Hash+Clone> Drop for Ui<ID> {
 }
 
 
+/**
+    A handle to a Ui that can be sent to other threads. Created with `Ui.proxy`.
+
+    The tasks sent with a proxy are executed in the ui thread, in the order they were sent, by the 
+    events loop (`dispatch_events`) or by the next `ui.commit`.
+*/
+pub struct UiProxy<ID: Hash+Clone+'static> {
+    queue: Arc<ProxyQueue<ID>>
+}
+
+impl<ID: Hash+Clone> UiProxy<ID> {
+
+    /**
+        Send a closure to the ui thread. The closure receives a reference to the ui.
+
+        Params:  
+          • task: The closure to execute in the ui thread  
+
+        Returns:  
+          • `Ok(())` if the task was queued  
+          • `Error::BadUi` if the ui was freed  
+    */
+    pub fn post<F>(&self, task: F) -> Result<(), Error> where F: FnOnce(&Ui<ID>) -> () + Send + 'static {
+        self.queue.push(Box::new(task))
+    }
+
+    /**
+        Trigger an event in the ui thread. See `Ui.trigger`.
+
+        Params:  
+          • id: The id that identify the element in the ui  
+          • event: The type of the event to trigger  
+          • event_arg: The arguments to send to the callbacks  
+
+        Returns:  
+          • `Ok(())` if the event was queued  
+          • `Error::BadUi` if the ui was freed  
+          • `Error::UserError` if the arguments hold a system handle (see `EventArgs.has_handles`)  
+    */
+    pub fn trigger(&self, id: &ID, event: Event, event_arg: EventArgs) -> Result<(), Error> where ID: Send {
+        if event_arg.has_handles() {
+            return Err(Error::UserError("Events arguments that hold a system handle cannot be sent by a proxy".to_string()));
+        }
+
+        let id = id.clone();
+        let args = ProxyArgs(event_arg);
+        self.post(move |ui| { let args = args; ui.trigger(&id, event, args.0) })
+    }
+
+}
+
+/// Events arguments sent by a proxy. `UiProxy.trigger` only builds it with arguments that do not hold a system handle.
+struct ProxyArgs(EventArgs);
+
+unsafe impl Send for ProxyArgs {}
+
+impl<ID: Hash+Clone> Clone for UiProxy<ID> {
+    fn clone(&self) -> UiProxy<ID> {
+        UiProxy{ queue: self.queue.clone() }
+    }
+}
+
+
+/**
+    Compute the layout of `parent` over its client area and move the controls accordingly. The toolbars at the top
+    and the status bars at the bottom of the parent are docked by the system, their height is left out of the layout area.
+    Controls that are not window-like or that are not in the ui are ignored.
+*/
+fn apply_layout<ID: Hash+Clone>(ui: &Ui<ID>, parent: &ID) {
+    let inner = unsafe{ &*ui.inner };
+
+    let layout = match inner.layouts.get(&UiInner::<ID>::hash_id(parent)) {
+        Some(l) => l.clone(),
+        None => { return; }
+    };
+
+    let parent_handle = match ui.handle_of(parent) {
+        Ok(AnyHandle::HWND(h)) => h,
+        _ => { return; }
+    };
+
+    let (width, height) = inner.messages.window_size(parent_handle);
+    let (mut top, mut bottom) = (0, 0);
+    for child in inner.messages.visible_children(parent_handle) {
+        match ui.id_from_handle(&AnyHandle::HWND(child)).and_then(|id| ui.type_of_control(&id)) {
+            Ok(ControlType::Toolbar) => { top += inner.messages.window_size(child).1; },
+            Ok(ControlType::StatusBar) => { bottom += inner.messages.window_size(child).1; },
+            _ => {}
+        }
+    }
+
+    let area = (width, height.saturating_sub(top + bottom));
+    for (id, position, size) in layout.solve((0, top as i32), area) {
+        if let Ok(AnyHandle::HWND(h)) = ui.handle_of(&id) {
+            inner.messages.set_window_geometry(h, position, size);
+        }
+    }
+}
+
 /**
     Dispatch the messages waiting the the system message queue to the associated Uis. This includes NWG custom messages.
 
     Return once a quit event was received.
 */
+#[cfg(windows)]
 pub fn dispatch_events() {
     // Actual code is located under the low module because that's where most of the unsafe code should be
     unsafe{ ::low::events::dispatch_events(); }
@@ -887,6 +1547,7 @@ pub fn dispatch_events() {
 /**
     Send a WM_QUIT to the system queue. Breaks the `dispatch_events` loop.
 */
+#[cfg(windows)]
 pub fn exit() {
     // Actual code is located under the low module because that's where most of the unsafe code should be
     unsafe{ ::low::events::exit(); }
@@ -895,6 +1556,7 @@ pub fn exit() {
 /**
     Toggle the console visibility
 */
+#[cfg(windows)]
 pub fn toggle_console(visible: bool) {
     unsafe{ ::low::window_helper::toggle_console(visible) }
 }
\ No newline at end of file
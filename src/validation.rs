@@ -0,0 +1,276 @@
+/*!
+    Validation rules for the text controls (`TextInput` and `TextBox`).
+
+    The validators in this module are pure rust. Displaying the validation errors
+    next to a control (balloon tip and error icon) is done by `ErrorFeedback` and requires the system controls.
+*/
+
+use std::fmt;
+use std::rc::Rc;
+
+use regex::Regex;
+
+use error::Error;
+
+/**
+    A validation rule for the text of a control. The rules are checked by `Validator::check_text`.
+
+    Members:
+    • `Required`: The text must not be empty or contain only whitespaces
+    • `Regex`: The whole text must match a pattern. The string is the message returned when the text do not match
+    • `Integer`: The text must be an integer between `min` and `max` (inclusive)
+    • `Float`: The text must be a number between `min` and `max` (inclusive)
+    • `Custom`: A function that returns an error message if the text is rejected
+*/
+#[derive(Clone)]
+pub enum Validator {
+    Required,
+    Regex(Pattern, String),
+    Integer{min: i64, max: i64},
+    Float{min: f64, max: f64},
+    Custom(Rc<Fn(&str) -> Result<(), String>>)
+}
+
+impl Validator {
+
+    /// Create a `Regex` validator. Returns `Err(Error::BadPattern)` if the pattern is not valid.
+    pub fn regex<S: Into<String>>(pattern: &str, message: S) -> Result<Validator, Error> {
+        match Pattern::new(pattern) {
+            Ok(p) => Ok(Validator::Regex(p, message.into())),
+            Err(e) => Err(e)
+        }
+    }
+
+    /// Create a `Custom` validator
+    pub fn custom<F: Fn(&str) -> Result<(), String>+'static>(f: F) -> Validator {
+        Validator::Custom(Rc::new(f))
+    }
+
+    /**
+        Check a text with a single rule. The text is trimmed before being parsed by the `Integer` and `Float` rules.
+        Returns the error message if the text is rejected.
+    */
+    pub fn check(&self, text: &str) -> Result<(), String> {
+        match self {
+            &Validator::Required => {
+                if text.trim().is_empty() { Err("This field is required".to_string()) }
+                else { Ok(()) }
+            },
+            &Validator::Regex(ref pattern, ref message) => {
+                if pattern.is_match(text) { Ok(()) }
+                else { Err(message.clone()) }
+            },
+            &Validator::Integer{min, max} => match text.trim().parse::<i64>() {
+                Ok(v) if v >= min && v <= max => Ok(()),
+                _ => Err(format!("The value must be an integer between {} and {}", min, max))
+            },
+            &Validator::Float{min, max} => match text.trim().parse::<f64>() {
+                Ok(v) if v >= min && v <= max => Ok(()),
+                _ => Err(format!("The value must be a number between {} and {}", min, max))
+            },
+            &Validator::Custom(ref f) => f(text)
+        }
+    }
+
+    /**
+        Check a text with a list of rules and return the message of the first rule that rejects the text.
+
+        An empty text (or a text with only whitespaces) is only checked by the `Required` rule. This means that
+        the other rules only apply to optional fields when the user fills them.
+    */
+    pub fn check_text(validators: &[Validator], text: &str) -> Result<(), String> {
+        if text.trim().is_empty() {
+            return match validators.iter().find(|v| match v { &&Validator::Required => true, _ => false }) {
+                Some(v) => v.check(text),
+                None => Ok(())
+            };
+        }
+
+        for v in validators.iter() {
+            if let Err(e) = v.check(text) {
+                return Err(e);
+            }
+        }
+
+        Ok(())
+    }
+
+}
+
+impl fmt::Debug for Validator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &Validator::Required => write!(f, "Required"),
+            &Validator::Regex(ref p, ref m) => write!(f, "Regex({:?}, {:?})", p.as_str(), m),
+            &Validator::Integer{min, max} => write!(f, "Integer{{min: {}, max: {}}}", min, max),
+            &Validator::Float{min, max} => write!(f, "Float{{min: {}, max: {}}}", min, max),
+            &Validator::Custom(_) => write!(f, "Custom")
+        }
+    }
+}
+
+/**
+    A regular expression used by the `Regex` validator. The whole text must match the pattern.
+
+    The pattern is compiled by the `regex` crate (see its documentation for the supported syntax). Matching runs in
+    linear time with the length of the text, so a pattern cannot hang or crash the application on a long input.
+    Anchors (`^` and `$`) are optional because the pattern is always matched against the whole text.
+*/
+#[derive(Clone)]
+pub struct Pattern {
+    source: String,
+    regex: Regex
+}
+
+impl Pattern {
+
+    /// Compile a pattern. Returns `Err(Error::BadPattern)` if the pattern is not valid.
+    pub fn new(pattern: &str) -> Result<Pattern, Error> {
+        // The pattern is checked alone first, so that an unbalanced group cannot escape the anchors added below
+        if let Err(e) = Regex::new(pattern) {
+            return Err(Error::BadPattern(format!("{} in \"{}\"", e, pattern)));
+        }
+
+        match Regex::new(&format!("^(?:{})$", pattern)) {
+            Ok(r) => Ok(Pattern{ source: pattern.to_string(), regex: r }),
+            Err(e) => Err(Error::BadPattern(format!("{} in \"{}\"", e, pattern)))
+        }
+    }
+
+    /// Return true if the whole text matches the pattern
+    pub fn is_match(&self, text: &str) -> bool {
+        self.regex.is_match(text)
+    }
+
+    /// Return the source of the pattern
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+}
+
+impl fmt::Debug for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Pattern({:?})", self.source)
+    }
+}
+
+/**
+    Display a validation error next to an edit control: a balloon tip under the control and an error icon on its right.
+    The icon is a static control created in the parent of the edit control the first time an error is shown.
+*/
+#[cfg(windows)]
+pub struct ErrorFeedback {
+    icon: ::std::cell::Cell<::winapi::HWND>
+}
+
+#[cfg(windows)]
+impl ErrorFeedback {
+
+    pub fn new() -> ErrorFeedback {
+        ErrorFeedback{ icon: ::std::cell::Cell::new(::std::ptr::null_mut()) }
+    }
+
+    /// Show the error icon and a balloon tip with the message
+    pub unsafe fn show(&self, edit: ::winapi::HWND, title: &str, message: &str) {
+        use std::mem::size_of;
+        use user32::SendMessageW;
+        use winapi::{EDITBALLOONTIP, EM_SHOWBALLOONTIP, TTI_ERROR, DWORD, INT, LPARAM};
+        use low::other_helper::to_utf16;
+        use low::window_helper::set_window_visibility;
+
+        if self.icon.get().is_null() {
+            self.icon.set(build_error_icon(edit));
+        }
+
+        if !self.icon.get().is_null() {
+            self.place(edit);
+            set_window_visibility(self.icon.get(), true);
+        }
+
+        let title = to_utf16(title);
+        let message = to_utf16(message);
+        let tip = EDITBALLOONTIP{
+            cbStruct: size_of::<EDITBALLOONTIP>() as DWORD,
+            pszTitle: title.as_ptr(),
+            pszText: message.as_ptr(),
+            ttiIcon: TTI_ERROR as INT
+        };
+
+        SendMessageW(edit, EM_SHOWBALLOONTIP, 0, &tip as *const EDITBALLOONTIP as LPARAM);
+    }
+
+    /// Hide the error icon and the balloon tip
+    pub unsafe fn hide(&self, edit: ::winapi::HWND) {
+        use user32::SendMessageW;
+        use winapi::EM_HIDEBALLOONTIP;
+        use low::window_helper::set_window_visibility;
+
+        if !self.icon.get().is_null() {
+            set_window_visibility(self.icon.get(), false);
+        }
+
+        SendMessageW(edit, EM_HIDEBALLOONTIP, 0, 0);
+    }
+
+    /// Return true if the error icon is displayed
+    pub fn visible(&self) -> bool {
+        use low::window_helper::get_window_visibility;
+        !self.icon.get().is_null() && unsafe{ get_window_visibility(self.icon.get()) }
+    }
+
+    /// Move the error icon next to the edit control. Must be called when the edit control is moved or resized.
+    pub unsafe fn place(&self, edit: ::winapi::HWND) {
+        use low::window_helper::{get_window_position, get_window_size, set_window_position};
+
+        if self.icon.get().is_null() { return; }
+
+        let (x, y) = get_window_position(edit);
+        let (w, h) = get_window_size(edit);
+        set_window_position(self.icon.get(), x + (w as i32) + ERROR_ICON_MARGIN, y + ((h as i32) - ERROR_ICON_SIZE) / 2);
+    }
+
+    /// Destroy the error icon
+    pub unsafe fn free(&self) {
+        use user32::DestroyWindow;
+
+        if !self.icon.get().is_null() {
+            DestroyWindow(self.icon.get());
+            self.icon.set(::std::ptr::null_mut());
+        }
+    }
+
+}
+
+#[cfg(windows)] const ERROR_ICON_SIZE: i32 = 16;
+#[cfg(windows)] const ERROR_ICON_MARGIN: i32 = 4;
+
+/// Create the hidden static control that displays the system error icon. Returns a null handle if the control could not be created.
+#[cfg(windows)]
+unsafe fn build_error_icon(edit: ::winapi::HWND) -> ::winapi::HWND {
+    use std::ptr;
+    use user32::{GetParent, LoadImageW, SendMessageW};
+    use winapi::{LPCWSTR, LR_SHARED, IMAGE_ICON, WS_CHILD, WPARAM, LPARAM};
+    use low::defs::{SS_ICON, SS_CENTERIMAGE, STM_SETIMAGE, OIC_HAND};
+    use low::window_helper::{WindowParams, build_window};
+
+    let params = WindowParams {
+        title: "",
+        class_name: "STATIC",
+        position: (0, 0),
+        size: (ERROR_ICON_SIZE as u32, ERROR_ICON_SIZE as u32),
+        flags: WS_CHILD | SS_ICON | SS_CENTERIMAGE,
+        ex_flags: Some(0),
+        parent: GetParent(edit)
+    };
+
+    let handle = match build_window(params) {
+        Ok(h) => h,
+        Err(_) => { return ptr::null_mut(); }
+    };
+
+    let icon = LoadImageW(ptr::null_mut(), (OIC_HAND as usize) as LPCWSTR, IMAGE_ICON, ERROR_ICON_SIZE, ERROR_ICON_SIZE, LR_SHARED);
+    SendMessageW(handle, STM_SETIMAGE, IMAGE_ICON as WPARAM, icon as LPARAM);
+
+    handle
+}
@@ -1,7 +1,10 @@
+#![cfg(windows)]
 #![allow(unused_must_use)]
 #![allow(unused_variables)]
 
 extern crate winapi;
+extern crate user32;
+extern crate gdi32;
 
 extern crate native_windows_gui as nwg;
 
@@ -13,7 +16,7 @@ use nwg::events::*;
 use nwg::events as nwge;
 
 fn setup_ui() -> Ui<u64> { Ui::new().unwrap() }
-fn window<T: Hash+Clone>() -> WindowT<T, &'static str> {  WindowT{title: "", position:(-600,-600), size:(100, 100), resizable:true, visible:true, disabled:false, exit_on_close:true, icon: None} }
+fn window<T: Hash+Clone>() -> WindowT<T, &'static str> {  WindowT{title: "", position:(-600,-600), size:(100, 100), resizable:true, visible:true, disabled:false, exit_on_close:true, icon: None, owner: None, tool_window: false, text_color: None, background_color: None} }
 fn default_font() -> FontT<&'static str> { FontT{ family: "Arial", size: 10, weight: FONT_WEIGHT_BOLD, decoration: FONT_DECO_ITALIC|FONT_DECO_STRIKEOUT } }
 
 macro_rules! test_visibility {
@@ -151,7 +154,7 @@ fn test_ui_pack_control() {
     let ui = setup_ui();
 
     ui.pack_control(&1000, window());
-    ui.pack_control(&1001, MenuItemT{text: "", parent: 1000, disabled: false});
+    ui.pack_control(&1001, MenuItemT{text: "", parent: 1000, disabled: false, checked: false, radio: false, default: false, image: None, index: None});
 
     assert!(!ui.has_id(&1000), "ID 1000 was found in ui before commit");
     ui.commit().expect("Commit was not successful");
@@ -161,7 +164,7 @@ fn test_ui_pack_control() {
     { let w = ui.get::<Window>(&1000); w.expect("Failed to get control"); }
 
     // Id already exists
-    ui.pack_control(&1000, MenuItemT{text: "", parent: 1000, disabled: false});
+    ui.pack_control(&1000, MenuItemT{text: "", parent: 1000, disabled: false, checked: false, radio: false, default: false, image: None, index: None});
     let r = ui.commit();
     assert!(r.is_err() && r.err().unwrap() == Error::KeyExists, "Commit was successful");
 
@@ -195,7 +198,7 @@ fn test_ui_unpack() {
     ui.pack_value(&1002, true);
     ui.pack_resource(&1003, default_font());
     ui.pack_resource(&1004, default_font());
-    ui.pack_control(&1005, ButtonT{text: "TEST", position:(10, 10), size: (100, 30), visible: true, disabled: false, parent: 1001, font: None});
+    ui.pack_control(&1005, ButtonT{text: "TEST", position:(10, 10), size: (100, 30), visible: true, disabled: false, parent: 1001, font: None, tooltip: None, style: ButtonStyle::Push, image: None, text_color: None, background_color: None});
     
     ui.bind(&1001, &5000, Destroyed, move |_, _, _, _|{ unsafe{ *(&mut *x) += 1; } } );
     ui.bind(&1005, &5000, Destroyed, move |_, _, _, _|{ unsafe{ *(&mut *x) += 1; } } );
@@ -241,7 +244,7 @@ fn test_ui_bind() {
     ui.pack_value(&1000, 5u32);
     ui.pack_control(&1001, window());
     ui.pack_control(&1002, window());
-    ui.pack_control(&1003, MenuItemT{text: "", parent: 1001, disabled: false});
+    ui.pack_control(&1003, MenuItemT{text: "", parent: 1001, disabled: false, checked: false, radio: false, default: false, image: None, index: None});
 
     // Binding successful
     ui.bind(&1001, &5000, Destroyed, |ui, id, _, _|{
@@ -289,7 +292,7 @@ fn test_ui_unbind() {
     let ui = setup_ui();
     
     ui.pack_control(&1000, window());
-    ui.pack_control(&1002, MenuItemT{text: "", parent: 1000, disabled: false});
+    ui.pack_control(&1002, MenuItemT{text: "", parent: 1000, disabled: false, checked: false, radio: false, default: false, image: None, index: None});
     ui.pack_value(&1001, 5u32);
 
     ui.bind(&1000, &5000, Destroyed, |_, _, _, _|{});
@@ -430,33 +433,33 @@ fn test_menus() {
     ui.pack_control(&1000, window());
     ui.bind(&1000, &10_000, Destroyed, move |_,_,_,_|{ unsafe{  *(&mut *x) += 1 } });
     
-    ui.pack_control(&1001, MenuT{ text: "Test1", parent: 1000, disabled: false  });
+    ui.pack_control(&1001, MenuT{ text: "Test1", parent: 1000, disabled: false, index: None  });
     ui.bind(&1001, &10_000, Destroyed, move |_,_,_,_|{ unsafe{  *(&mut *x) += 1 } });
 
-    ui.pack_control(&2003, MenuItemT{ text: "TestItem4", parent: 1000, disabled: false  });
+    ui.pack_control(&2003, MenuItemT{ text: "TestItem4", parent: 1000, disabled: false, checked: false, radio: false, default: false, image: None, index: None  });
     ui.bind(&2003, &10_000, Destroyed, move |_,_,_,_|{ unsafe{  *(&mut *x) += 1 } });
     
-    ui.pack_control(&1002, MenuT{ text: "Test2", parent: 1000, disabled: false  });
-    ui.pack_control(&1003, MenuT{ text: "Test3", parent: 1002, disabled: false  });
-    ui.pack_control(&1004, MenuT{ text: "Test4", parent: 1002, disabled: false  });
-    ui.pack_control(&2000, MenuItemT{ text: "TestItem1", parent: 1002, disabled: false  });
+    ui.pack_control(&1002, MenuT{ text: "Test2", parent: 1000, disabled: false, index: None  });
+    ui.pack_control(&1003, MenuT{ text: "Test3", parent: 1002, disabled: false, index: None  });
+    ui.pack_control(&1004, MenuT{ text: "Test4", parent: 1002, disabled: false, index: None  });
+    ui.pack_control(&2000, MenuItemT{ text: "TestItem1", parent: 1002, disabled: false, checked: false, radio: false, default: false, image: None, index: None  });
     ui.bind(&1002, &10_000, Destroyed, move |_,_,_,_|{ unsafe{  *(&mut *x) += 1 } });
     ui.bind(&1003, &10_000, Destroyed, move |_,_,_,_|{ unsafe{  *(&mut *x) += 1 } });
     ui.bind(&1004, &10_000, Destroyed, move |_,_,_,_|{ unsafe{  *(&mut *x) += 1 } });
     ui.bind(&2000, &10_000, Destroyed, move |_,_,_,_|{ unsafe{  *(&mut *x) += 1 } });
 
-    ui.pack_control(&1005, MenuT{ text: "Test5", parent: 1000, disabled: false });
-    ui.pack_control(&1006, MenuT{ text: "Test6", parent: 1005, disabled: false });
-    ui.pack_control(&1007, MenuT{ text: "Test7", parent: 1006, disabled: false });
-    ui.pack_control(&2001, MenuItemT{ text: "TestItem2", parent: 1007, disabled: false  });
+    ui.pack_control(&1005, MenuT{ text: "Test5", parent: 1000, disabled: false, index: None });
+    ui.pack_control(&1006, MenuT{ text: "Test6", parent: 1005, disabled: false, index: None });
+    ui.pack_control(&1007, MenuT{ text: "Test7", parent: 1006, disabled: false, index: None });
+    ui.pack_control(&2001, MenuItemT{ text: "TestItem2", parent: 1007, disabled: false, checked: false, radio: false, default: false, image: None, index: None  });
     ui.bind(&1005, &10_000, Destroyed, move |_,_,_,_|{ unsafe{  *(&mut *x) += 1 } });
     ui.bind(&1006, &10_000, Destroyed, move |_,_,_,_|{ unsafe{  *(&mut *x) += 1 } });
     ui.bind(&1007, &10_000, Destroyed, move |_,_,_,_|{ unsafe{  *(&mut *x) += 1 } });
     ui.bind(&2001, &10_000, Destroyed, move |_,_,_,_|{ unsafe{  *(&mut *x) += 1 } });
 
-    ui.pack_control(&1008, MenuT{ text: "Test8", parent: 1000, disabled: false });
-    ui.pack_control(&1009, MenuT{ text: "Test9", parent: 1008, disabled: false });
-    ui.pack_control(&2002, MenuItemT{ text: "TestItem3", parent: 1000, disabled: false  });
+    ui.pack_control(&1008, MenuT{ text: "Test8", parent: 1000, disabled: false, index: None });
+    ui.pack_control(&1009, MenuT{ text: "Test9", parent: 1008, disabled: false, index: None });
+    ui.pack_control(&2002, MenuItemT{ text: "TestItem3", parent: 1000, disabled: false, checked: false, radio: false, default: false, image: None, index: None  });
     ui.bind(&1008, &10_000, Destroyed, move |_,_,_,_|{ unsafe{  *(&mut *x) += 1 } });
     ui.bind(&1009, &10_000, Destroyed, move |_,_,_,_|{ unsafe{  *(&mut *x) += 1 } });
     ui.bind(&2002, &10_000, Destroyed, move |_,_,_,_|{ unsafe{  *(&mut *x) += 1 } });
@@ -516,6 +519,138 @@ fn test_menus() {
     assert!(free_count == 14, "Freecount was not increased by 3!");
 }
 
+#[test]
+fn test_menu_items() {
+    use winapi::{HMENU, UINT, c_int};
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetMenuItemID(menu: HMENU, pos: c_int) -> UINT;
+        fn GetSubMenu(menu: HMENU, pos: c_int) -> HMENU;
+    }
+
+    let ui = setup_ui();
+    let item = |text: &'static str, checked: bool, radio: bool, default: bool, index: Option<u32>| {
+        MenuItemT{ text: text, parent: 1001, disabled: false, checked: checked, radio: radio, default: default, image: None, index: index }
+    };
+
+    ui.pack_control(&1000, window());
+    ui.pack_control(&1001, MenuT{ text: "View", parent: 1000, disabled: false, index: None });
+    ui.pack_control(&1002, item("Small", true, true, false, None));
+    ui.pack_control(&1003, item("Large", false, true, false, None));
+    ui.pack_control(&1004, item("Refresh", false, false, true, None));
+    ui.pack_control(&1005, item("First", false, false, false, Some(0)));
+    ui.pack_control(&1006, MenuT{ text: "Sort", parent: 1001, disabled: false, index: Some(1) });
+    ui.pack_control(&1007, item("Last", false, false, false, Some(100)));
+    ui.commit().expect("Commit was not successful");
+
+    let menu = match ui.handle_of(&1001) { Ok(nwg::custom::AnyHandle::HMENU(h)) => h, _ => unreachable!() };
+    let uid = |id: u64| match ui.handle_of(&id) { Ok(nwg::custom::AnyHandle::HMENU_ITEM(_, uid)) => uid, _ => unreachable!() };
+    let submenu = match ui.handle_of(&1006) { Ok(nwg::custom::AnyHandle::HMENU(h)) => h, _ => unreachable!() };
+
+    // Items are inserted at their index or at the end if the index is out of bounds
+    unsafe {
+        assert_eq!(GetMenuItemID(menu, 0), uid(1005));
+        assert_eq!(GetSubMenu(menu, 1), submenu);
+        assert_eq!(GetMenuItemID(menu, 2), uid(1002));
+        assert_eq!(GetMenuItemID(menu, 4), uid(1004));
+        assert_eq!(GetMenuItemID(menu, 5), uid(1007));
+    }
+
+    let (small, large, refresh) = (ui.get::<MenuItem>(&1002).unwrap(), ui.get::<MenuItem>(&1003).unwrap(), ui.get::<MenuItem>(&1004).unwrap());
+
+    // Checking a radio item unchecks the other items of its group
+    assert!(small.get_checked() && small.get_radio());
+    assert!(!large.get_checked() && large.get_radio());
+    assert!(!refresh.get_checked() && !refresh.get_radio());
+    large.set_checked(true);
+    assert!(large.get_checked());
+    assert!(!small.get_checked());
+    large.set_checked(false);
+    assert!(!large.get_checked());
+
+    large.set_radio(false);
+    assert!(!large.get_radio());
+    large.set_checked(true);
+    assert!(large.get_checked());
+
+    // A menu has only one default item
+    assert!(refresh.get_default());
+    assert!(!small.get_default());
+    small.set_default(true);
+    assert!(small.get_default());
+    assert!(!refresh.get_default());
+    refresh.set_default(false);
+    assert!(small.get_default());
+    small.set_default(false);
+    assert!(!small.get_default());
+
+    assert_eq!(small.get_text(), "Small");
+    small.set_text("Small icons");
+    assert_eq!(small.get_text(), "Small icons");
+}
+
+#[test]
+fn test_accelerator() {
+    use std::rc::Rc;
+    use std::cell::RefCell;
+    use winapi::{HMENU, UINT, LPWSTR, c_int};
+
+    #[link(name = "user32")]
+    extern "system" { fn GetMenuStringW(menu: HMENU, item: UINT, text: LPWSTR, max: c_int, flags: UINT) -> c_int; }
+
+    // The text displayed by the menu item, including the shortcut
+    let displayed_text = |ui: &Ui<u64>| {
+        let mut buffer = [0u16; 64];
+        let (menu, uid) = match ui.handle_of(&1002) { Ok(nwg::custom::AnyHandle::HMENU_ITEM(m, uid)) => (m, uid), _ => unreachable!() };
+        let len = unsafe{ GetMenuStringW(menu, uid, buffer.as_mut_ptr(), 64, 0) } as usize;  // 0 is MF_BYCOMMAND
+        String::from_utf16_lossy(&buffer[0..len])
+    };
+
+    let ui = setup_ui();
+    let triggered = Rc::new(RefCell::new(Vec::new()));
+    let (t1, t2) = (triggered.clone(), triggered.clone());
+
+    ui.pack_control(&1000, window());
+    ui.pack_control(&1001, MenuT{ text: "File", parent: 1000, disabled: false, index: None });
+    ui.pack_control(&1002, MenuItemT{ text: "Save", parent: 1001, disabled: false, checked: false, radio: false, default: false, image: None, index: None });
+    ui.pack_control(&1003, nwg_accelerator!(window=1000; items=vec![("Ctrl+S", 1002)]; shortcuts=vec!["F5", "Ctrl+0xC0"]));
+    ui.bind(&1002, &5000, menu::Triggered, move |_, _, _, _| { t1.borrow_mut().push("Save".to_string()); });
+    ui.bind(&1003, &5000, accelerator::Triggered, move |_, _, _, args| { t2.borrow_mut().push(args.shortcut().unwrap().to_string()); });
+    ui.commit().expect("Commit was not successful");
+
+    // The shortcut is displayed after the menu item text, but it is not part of the item text
+    assert_eq!(displayed_text(&ui), "Save\tCtrl+S");
+    {
+        let item = ui.get::<MenuItem>(&1002).unwrap();
+        assert_eq!(item.get_text(), "Save");
+        item.set_text("Save all");
+        assert_eq!(item.get_text(), "Save all");
+    }
+    assert_eq!(displayed_text(&ui), "Save all\tCtrl+S");
+
+    // An accelerator table sends a WM_COMMAND with 1 in the high word of wparam and the command id in the low word
+    let uid = match ui.handle_of(&1002) { Ok(nwg::custom::AnyHandle::HMENU_ITEM(_, uid)) => uid, _ => unreachable!() };
+    let accel = |cmd: winapi::WPARAM| ui.simulate_message(&1000, winapi::WM_COMMAND, (1 << 16) | cmd, 0).unwrap();
+    accel(uid as winapi::WPARAM);
+    accel(0x8074);  // F5
+    accel(0x84C0);  // Ctrl+0xC0
+    assert_eq!(*triggered.borrow(), vec!["Save".to_string(), "F5".to_string(), "Ctrl+0xC0".to_string()]);
+
+    // A window can only have one accelerator table
+    ui.pack_control(&1004, nwg_accelerator!(window=1000; shortcuts=vec!["F6"]));
+    match ui.commit() { Err(Error::BadParent(_)) => {}, r => panic!("Should have returned Error::BadParent, got {:?}", r) }
+
+    // Shortcuts are validated
+    ui.unpack(&1003);
+    ui.pack_control(&1004, nwg_accelerator!(window=1000; shortcuts=vec!["Ctrl+Foo"]));
+    match ui.commit() { Err(Error::BadShortcut(_)) => {}, r => panic!("Should have returned Error::BadShortcut, got {:?}", r) }
+
+    // Freeing the accelerator removes the shortcut from the menu item
+    assert_eq!(displayed_text(&ui), "Save all");
+    assert!(!ui.has_id(&1003));
+}
+
 #[test]
 fn test_window() {
     let ui = setup_ui();
@@ -531,16 +666,85 @@ fn test_window() {
     test_enabled!(ui, &1000, Window);
 }
 
+#[test]
+fn test_modal_window() {
+    let ui = setup_ui();
+
+    ui.pack_control(&1000, window());
+    ui.pack_control(&1001, nwg_window!(owner=Some(1000); tool_window=true; visible=false; exit_on_close=false));
+    ui.pack_control(&1002, nwg_button!(parent=1001; text="OK"));
+    ui.pack_control(&1003, nwg_button!(parent=1001; text="Cancel"));
+    ui.pack_control(&1004, nwg_button!(parent=1000; text="Other"));
+    ui.pack_control(&1005, nwg_timer!(interval=10));
+    ui.commit().expect("Commit was not successful");
+
+    ui.bind(&1005, &5000, nwge::timer::Tick, |ui, _, _, _| {
+        let dialog = ui.get::<Window>(&1001).unwrap();
+        assert!(dialog.is_modal());
+        dialog.end_modal(42u32);
+    });
+
+    {
+        let dialog = ui.get::<Window>(&1001).unwrap();
+        assert_eq!(dialog.get_owner(&ui), Some(1000));
+        assert_eq!(ui.get::<Window>(&1000).unwrap().get_owner(&ui), None);
+
+        assert!(dialog.set_default_button(&ui, Some(&1002)).is_ok());
+        assert!(dialog.set_cancel_button(&ui, Some(&1003)).is_ok());
+        assert_eq!(dialog.get_default_button(&ui), Some(1002));
+        assert_eq!(dialog.get_cancel_button(&ui), Some(1003));
+
+        match dialog.set_default_button(&ui, Some(&1004)) { Err(Error::UserError(_)) => {}, r => panic!("Should have returned Error::UserError, got {:?}", r) }
+        match dialog.set_cancel_button(&ui, Some(&1000)) { Err(Error::UserError(_)) => {}, r => panic!("Should have returned Error::UserError, got {:?}", r) }
+        assert_eq!(dialog.get_default_button(&ui), Some(1002));
+
+        assert!(dialog.set_cancel_button(&ui, None).is_ok());
+        assert_eq!(dialog.get_cancel_button(&ui), None);
+
+        // Not running
+        assert!(!dialog.is_modal());
+        dialog.end_modal(0u32);
+        assert!(!dialog.get_visibility());
+    }
+
+    ui.get_mut::<Timer>(&1005).unwrap().start();
+    {
+        let dialog = ui.get::<Window>(&1001).unwrap();
+        assert_eq!(dialog.run_modal::<u32>().unwrap(), Some(42));
+        assert!(!dialog.is_modal());
+        assert!(!dialog.get_visibility());
+        assert!(ui.get::<Window>(&1000).unwrap().get_enabled());
+
+        match dialog.run_modal::<String>() { Err(Error::BadType) => {}, r => panic!("Should have returned Error::BadType, got {:?}", r) }
+    }
+    ui.get_mut::<Timer>(&1005).unwrap().stop();
+
+    // Tool windows do not use WS_EX_COMPOSITED
+    let dialog = match ui.handle_of(&1001) { Ok(nwg::custom::AnyHandle::HWND(h)) => h, _ => unreachable!() };
+    let ex_style = unsafe{ user32::GetWindowLongW(dialog, winapi::GWL_EXSTYLE) } as u32;
+    assert_eq!(ex_style & winapi::WS_EX_TOOLWINDOW, winapi::WS_EX_TOOLWINDOW);
+    assert_eq!(ex_style & winapi::WS_EX_COMPOSITED, 0);
+
+    // Removing the default button also removes it from the window
+    ui.unpack(&1002);
+    ui.commit().expect("Commit was not successful");
+    let dialog = ui.get::<Window>(&1001).unwrap();
+    assert_eq!(dialog.get_default_button(&ui), None);
+    assert!(dialog.set_cancel_button(&ui, Some(&1003)).is_ok());
+    assert_eq!(dialog.get_default_button(&ui), None);
+    assert_eq!(dialog.get_cancel_button(&ui), Some(1003));
+}
+
 #[test]
 fn test_buttons() {
     let ui = setup_ui();
 
-    let mut btn_t = ButtonT{text: "TEST", position:(10, 10), size: (100, 30), visible: true, disabled: false, parent: 1000, font: None};
-    let btn_t2 = CheckBoxT{text: "TEST", position:(10, 10), size: (100, 30), visible: true, disabled: false, checkstate: CheckState::Checked, tristate: false, parent: 1000, font: None};
+    let mut btn_t = ButtonT{text: "TEST", position:(10, 10), size: (100, 30), visible: true, disabled: false, parent: 1000, font: None, tooltip: None, style: ButtonStyle::Push, image: None, text_color: None, background_color: None};
+    let btn_t2 = CheckBoxT{text: "TEST", position:(10, 10), size: (100, 30), visible: true, disabled: false, checkstate: CheckState::Checked, tristate: false, parent: 1000, font: None, tooltip: None, text_color: None, background_color: None};
 
     ui.pack_resource(&10_000, default_font());
     ui.pack_control(&1000, window());
-    ui.pack_control(&1001, MenuItemT{text: "", parent: 1000, disabled: false});
+    ui.pack_control(&1001, MenuItemT{text: "", parent: 1000, disabled: false, checked: false, radio: false, default: false, image: None, index: None});
 
     // pack test
     ui.pack_control(&1002, btn_t.clone());
@@ -582,6 +786,88 @@ fn test_buttons() {
     }
 }
 
+#[test]
+fn test_button_styles() {
+    let ui = setup_ui();
+
+    ui.pack_resource(&1001, OemImageT{ source: OemImage::Icon(OemIcon::Information), size: (16, 16) });
+    ui.pack_resource(&1002, default_font());
+    ui.pack_control(&1000, window());
+    ui.pack_control(&1003, nwg_button!(parent=1000; text="Split"; style=ButtonStyle::Split; image=Some(1001)));
+    ui.pack_control(&1004, nwg_button!(parent=1000; text="Link"; style=ButtonStyle::CommandLink; size=(200, 60)));
+    ui.pack_control(&1005, nwg_button!(parent=1000; text="Flat"; style=ButtonStyle::Flat));
+    ui.pack_control(&1006, nwg_button!(parent=1000; text="Drawn"; style=ButtonStyle::OwnerDraw));
+    ui.pack_control(&1007, nwg_button!(parent=1000; text="Push"));
+    ui.commit().expect("Commit was not successful");
+
+    ui.pack_control(&1008, nwg_button!(parent=1000; image=Some(1002)));
+    match ui.commit() { Err(Error::BadResource(_)) => {}, r => panic!("Should have returned Error::BadResource, got {:?}", r) }
+
+    let styles = [(1003, ButtonStyle::Split), (1004, ButtonStyle::CommandLink), (1005, ButtonStyle::Flat), (1006, ButtonStyle::OwnerDraw), (1007, ButtonStyle::Push)];
+    for &(id, style) in styles.iter() {
+        assert_eq!(ui.get::<Button>(&id).unwrap().get_style(), style);
+    }
+
+    {
+        let split = ui.get::<Button>(&1003).unwrap();
+        assert_eq!(split.get_image(&ui), Some(1001));
+        assert!(split.set_image(&ui, None).is_ok());
+        assert_eq!(split.get_image(&ui), None);
+        match split.set_image(&ui, Some(&1002)) { Err(Error::BadResource(_)) => {}, r => panic!("Should have returned Error::BadResource, got {:?}", r) }
+
+        let link = ui.get::<Button>(&1004).unwrap();
+        assert!(link.set_image(&ui, Some(&1001)).is_ok());
+        assert_eq!(link.get_image(&ui), Some(1001));
+        link.set_note("A note");
+        assert_eq!(link.get_note(), "A note");
+        assert_eq!(ui.get::<Button>(&1007).unwrap().get_note(), "");
+    }
+
+    // The default button keeps its style
+    {
+        let window = ui.get::<Window>(&1000).unwrap();
+        for &(id, style) in styles.iter() {
+            assert!(window.set_default_button(&ui, Some(&id)).is_ok());
+            assert_eq!(ui.get::<Button>(&id).unwrap().get_style(), style);
+        }
+    }
+
+    // Owner drawn buttons are painted by the Draw callback. The parent receives the WM_DRAWITEM message
+    let painted = Box::into_raw(Box::new(None::<ButtonState>)) as usize;
+    ui.bind(&1006, &5000, nwge::button::Draw, move |_, _, _, args| {
+        unsafe{ *(painted as *mut Option<ButtonState>) = args.button_state(); }
+    });
+
+    let button = match ui.handle_of(&1006) { Ok(nwg::custom::AnyHandle::HWND(h)) => h, _ => panic!("Button is not a window") };
+    let rect = winapi::RECT{ left: 0, top: 0, right: 100, bottom: 30 };
+    let draw = winapi::DRAWITEMSTRUCT{ CtlType: 4, CtlID: 0, itemID: 0, itemAction: 1, itemState: 0x0001 | 0x0010, hwndItem: button,
+      hDC: ::std::ptr::null_mut(), rcItem: rect, itemData: 0 };
+    ui.simulate_message(&1000, winapi::WM_DRAWITEM, 0, &draw as *const winapi::DRAWITEMSTRUCT as winapi::LPARAM).unwrap();
+
+    let state = unsafe{ *Box::from_raw(painted as *mut Option<ButtonState>) };
+    assert_eq!(state, Some(ButtonState{ hot: false, pressed: true, focused: true, disabled: false }));
+
+    // Owner drawn labels are painted by the label Draw callback
+    ui.pack_control(&1009, nwg_label!(parent=1000; text="Drawn"; owner_draw=true));
+    ui.commit().expect("Commit was not successful");
+
+    let painted = Box::into_raw(Box::new(None::<ButtonState>)) as usize;
+    ui.bind(&1009, &5001, nwge::label::Draw, move |_, _, _, args| {
+        unsafe{ *(painted as *mut Option<ButtonState>) = args.button_state(); }
+    });
+
+    let label = match ui.handle_of(&1009) { Ok(nwg::custom::AnyHandle::HWND(h)) => h, _ => panic!("Label is not a window") };
+    let style = unsafe{ user32::GetWindowLongW(label, winapi::GWL_STYLE) } as u32;
+    assert_eq!(style & 0x1F, 13);
+
+    let draw = winapi::DRAWITEMSTRUCT{ CtlType: 5, CtlID: 0, itemID: 0, itemAction: 1, itemState: 0x0004, hwndItem: label,
+      hDC: ::std::ptr::null_mut(), rcItem: rect, itemData: 0 };
+    ui.simulate_message(&1000, winapi::WM_DRAWITEM, 0, &draw as *const winapi::DRAWITEMSTRUCT as winapi::LPARAM).unwrap();
+
+    let state = unsafe{ *Box::from_raw(painted as *mut Option<ButtonState>) };
+    assert_eq!(state, Some(ButtonState{ hot: false, pressed: false, focused: false, disabled: true }));
+}
+
 #[test]
 fn test_listbox() {
     let ui = setup_ui();
@@ -708,7 +994,8 @@ fn test_combobox() {
         visible: true, disabled: false,
         placeholder: Some("TEST"),
         parent: 1000,
-        font: None 
+        font: None,
+        tooltip: None
     };
 
     ui.pack_control(&1000, window());
@@ -774,14 +1061,20 @@ fn test_textinput() {
         visible: true, disabled: false, readonly: false, password: false,
         limit: 10,
         placeholder: None,
+        validators: vec![],
         parent: 1000,
-        font: None
+        font: None,
+        tooltip: None,
+        text_color: None,
+        background_color: None
     };
 
     ui.pack_control(&1000, window());
 
     // pack test
     ui.pack_control(&1001, ti_t);
+    ui.pack_control(&1002, nwg_textinput!(parent=1000; text="12"; validators=vec![Validator::Required, Validator::Integer{min: 1, max: 20}]));
+    ui.pack_control(&1003, nwg_textbox!(parent=1000; validators=vec![Validator::Required]));
     ui.commit().expect("Commit was not successful");
 
     // methods test
@@ -809,6 +1102,888 @@ fn test_textinput() {
         tinput.set_limit(10_000);
         assert!(tinput.get_limit() == 10_000);
     }
+
+    // validation test
+    assert_eq!(ui.validate_all(&1000), Ok(vec![1003]));
+    {
+        let tinput = ui.get::<TextInput>(&1002).expect("Control not found");
+        assert!(tinput.is_valid() && !tinput.has_error());
+
+        tinput.set_text("25");
+        assert!(tinput.validate().is_err());
+        assert!(tinput.has_error());
+
+        tinput.set_text("5");
+        assert!(tinput.validate().is_ok());
+        assert!(!tinput.has_error());
+
+        let tbox = ui.get::<TextBox>(&1003).expect("Control not found");
+        assert!(tbox.has_error());
+        tbox.set_text("Hello");
+    }
+    assert_eq!(ui.validate_all(&1000), Ok(vec![]));
+}
+
+#[test]
+fn test_rich_textbox() {
+    let ui = setup_ui();
+
+    ui.pack_control(&1000, window());
+    ui.pack_resource(&1001, FontT{ family: "Consolas", size: 20, weight: 700, decoration: 0 });
+    ui.pack_control(&1002, nwg_rich_textbox!(parent=1000; text="Hello world, hello rust"));
+    ui.commit().expect("Commit was not successful");
+
+    test_visibility!(ui, &1002, RichTextBox);
+    test_position!(ui, &1002, RichTextBox);
+    test_size!(ui, &1002, RichTextBox);
+    test_enabled!(ui, &1002, RichTextBox);
+
+    let rich = ui.get::<RichTextBox>(&1002).expect("Control not found");
+    assert_eq!(rich.get_text(), "Hello world, hello rust");
+
+    // Selection
+    rich.set_selection((6, 11));
+    assert_eq!(rich.get_selection(), (6, 11));
+    assert_eq!(rich.get_selected_text(), "world");
+
+    // Formatting
+    let format = CharFormat{ bold: true, color: Some([255, 0, 0]), font_family: Some("Arial".to_string()), font_size: Some(14), ..Default::default() };
+    rich.set_char_format((0, 5), &format);
+    assert_eq!(rich.get_char_format((0, 5)), format);
+    assert!(!rich.get_char_format((0, 11)).bold);
+    assert_eq!(rich.get_selection(), (6, 11));
+
+    rich.set_char_font(&ui, (6, 11), &1001).expect("Failed to apply the font");
+    let font_format = rich.get_char_format((6, 11));
+    assert!(font_format.bold);
+    assert_eq!(font_format.font_family, Some("Consolas".to_string()));
+
+    rich.set_paragraph_align((0, 0), ParagraphAlign::Center);
+    assert_eq!(rich.get_paragraph_align((0, -1)), ParagraphAlign::Center);
+
+    // Streaming
+    let doc = rich.get_document().expect("Failed to read the document");
+    assert_eq!(doc.plain_text().trim_end(), "Hello world, hello rust");
+    assert!(doc.paragraphs[0].runs[0].format.bold);
+
+    let mut new_doc = RichDocument::from_text("First\nSecond");
+    new_doc.paragraphs[1].align = ParagraphAlign::Right;
+    rich.set_document(&new_doc).expect("Failed to write the document");
+    assert_eq!(rich.stream_out(StreamFormat::PlainText, false).unwrap().replace("\r\n", "\n").trim_end(), "First\nSecond");
+    assert_eq!(rich.get_paragraph_align((7, 7)), ParagraphAlign::Right);
+
+    rich.stream_in("Plain é text", StreamFormat::PlainText, false).expect("Failed to write the text");
+    assert_eq!(rich.get_text(), "Plain é text");
+
+    // Find & replace
+    rich.set_text("one two one two");
+    let options = FindOptions{ match_case: true, ..Default::default() };
+    assert_eq!(rich.find("two", 0, options), Some((4, 7)));
+    assert_eq!(rich.find("TWO", 0, options), None);
+    assert_eq!(rich.find("one", 15, FindOptions{ backward: true, ..options }), Some((8, 11)));
+
+    rich.set_selection((0, 0));
+    assert!(rich.replace("one", "1", options));
+    assert_eq!(rich.get_text(), "1 two one two");
+    assert_eq!(rich.replace_all("two", "2", options), 2);
+    assert_eq!(rich.get_text(), "1 2 one 2");
+    assert!(!rich.replace("", "x", options));
+    assert_eq!(rich.replace_all("", "", options), 0);
+    assert_eq!(rich.replace_all("", "x", options), 0);
+    assert_eq!(rich.replace_all("2", "", options), 2);
+    assert_eq!(rich.get_text(), "1  one ");
+    assert_eq!(rich.replace_all("one", "one one", options), 1);
+    assert_eq!(rich.get_text(), "1  one one ");
+    assert_eq!(rich.replace_all(" ", "  ", options), 4);
+    rich.set_text("1 2 one 2");
+
+    // Undo
+    rich.clear_undo();
+    assert!(!rich.can_undo());
+    rich.set_selection((0, 1));
+    rich.replace_selection("un", true);
+    assert!(rich.can_undo());
+    assert!(rich.undo());
+    assert_eq!(rich.get_text(), "1 2 one 2");
+    assert!(rich.can_redo());
+    assert!(rich.redo());
+    assert_eq!(rich.get_text(), "un 2 one 2");
+
+    assert!(!rich.get_auto_url());
+    rich.set_auto_url(true);
+    assert!(rich.get_auto_url());
+
+    assert!(!rich.get_readonly());
+    rich.set_readonly(true);
+    assert!(rich.get_readonly());
+}
+
+#[test]
+fn test_numeric_input() {
+    use std::rc::Rc;
+    use std::cell::Cell;
+
+    let ui = setup_ui();
+    let changed = Rc::new(Cell::new(0.0f64));
+    let changed2 = changed.clone();
+
+    ui.pack_control(&1000, window());
+    ui.pack_control(&1001, nwg_numeric_input!(parent=1000; value=5.0; range=(0.0, 10.0); step=2.5; wrap=true));
+    ui.pack_control(&1002, nwg_numeric_input!(parent=1000; value=1234567.891; range=(-1e9, 1e9); decimals=2; thousands_separator=true));
+    ui.bind(&1001, &5000, numeric_input::ValueChanged, move |_, _, _, args|{ changed2.set(args.number().unwrap()); } );
+    ui.commit().expect("Commit was not successful");
+
+    test_visibility!(ui, &1001, NumericInput);
+    test_position!(ui, &1001, NumericInput);
+    test_size!(ui, &1001, NumericInput);
+    test_enabled!(ui, &1001, NumericInput);
+
+    {
+        // Moving the control does not change its size
+        let num = ui.get::<NumericInput>(&1001).expect("Control not found");
+        let size = num.get_size();
+        num.set_position(10, 10);
+        num.set_position(20, 30);
+        assert_eq!(num.get_position(), (20, 30));
+        assert_eq!(num.get_size(), size);
+        num.set_size(120, 25);
+        num.set_size(120, 25);
+        assert_eq!(num.get_size(), (120, 25));
+    }
+
+    {
+        let num = ui.get::<NumericInput>(&1001).expect("Control not found");
+        assert_eq!(num.get_value(), 5.0);
+        assert_eq!(num.get_text(), "5");
+
+        num.set_value(12.0);
+        assert_eq!(num.get_value(), 10.0);
+        assert_eq!(changed.get(), 10.0);
+
+        num.set_value(3.4);
+        assert_eq!(num.get_value(), 3.0);
+
+        assert!(num.set_range(5.0, 1.0).is_err());
+        assert!(num.set_step(0.0).is_err());
+
+        num.set_decimals(1);
+        num.set_value(3.45);
+        assert_eq!(num.get_text(), "3.5");
+        assert_eq!(changed.get(), 3.5);
+
+        let num = ui.get::<NumericInput>(&1002).expect("Control not found");
+        assert_eq!(num.get_text(), "1,234,567.89");
+        assert_eq!(num.get_value(), 1234567.89);
+        num.set_value(-1000.0);
+        assert_eq!(num.get_text(), "-1,000.00");
+        num.set_thousands_separator(false);
+        assert_eq!(num.get_text(), "-1000.00");
+    }
+
+    // Click the arrows of the first input. The value wraps around the range.
+    unsafe {
+        let parent = match ui.handle_of(&1000) { Ok(nwg::custom::AnyHandle::HWND(h)) => h, _ => unreachable!() };
+        let edit = match ui.handle_of(&1001) { Ok(nwg::custom::AnyHandle::HWND(h)) => h, _ => unreachable!() };
+        let class = "msctls_updown32\0".encode_utf16().collect::<Vec<u16>>();
+        let mut updown = user32::FindWindowExW(parent, ::std::ptr::null_mut(), class.as_ptr(), ::std::ptr::null());
+        while user32::SendMessageW(updown, winapi::UDM_GETBUDDY, 0, 0) as winapi::HWND != edit {
+            updown = user32::FindWindowExW(parent, updown, class.as_ptr(), ::std::ptr::null());
+            assert!(!updown.is_null(), "The arrows of the numeric input were not found");
+        }
+
+        let click = |delta: i32| {
+            let mut info = winapi::NMUPDOWN{ hdr: winapi::NMHDR{ hwndFrom: updown, idFrom: 0, code: winapi::UDN_DELTAPOS }, iPos: 0, iDelta: delta };
+            user32::SendMessageW(parent, winapi::WM_NOTIFY, 0, &mut info as *mut winapi::NMUPDOWN as winapi::LPARAM);
+        };
+
+        let num = ui.get::<NumericInput>(&1001).expect("Control not found");
+        num.set_value(10.0);
+        click(1);
+        assert_eq!(num.get_value(), 0.0);
+        assert_eq!(changed.get(), 0.0);
+        click(-1);
+        assert_eq!(num.get_value(), 10.0);
+        click(-1);
+        assert_eq!(num.get_value(), 7.5);
+
+        // Without thousands separators, a ',' is not a valid character
+        let text = "1,5\0".encode_utf16().collect::<Vec<u16>>();
+        user32::SetWindowTextW(edit, text.as_ptr());
+        user32::SendMessageW(parent, winapi::WM_COMMAND, (0x0200 << 16) as winapi::WPARAM, edit as winapi::LPARAM);
+        assert_eq!(num.get_value(), 7.5);
+        assert_eq!(num.get_text(), "7.5");
+    }
+}
+
+#[test]
+fn test_slider() {
+    use std::rc::Rc;
+    use std::cell::RefCell;
+
+    let ui = setup_ui();
+    let moves = Rc::new(RefCell::new(Vec::new()));
+    let moves2 = moves.clone();
+
+    ui.pack_control(&1000, window());
+    ui.pack_control(&1001, nwg_label!(parent=1000; text="Min"));
+    ui.pack_control(&1002, nwg_label!(parent=1000; text="Max"));
+    ui.pack_control(&1003, nwg_slider!(parent=1000; range=(-10, 10); value=4; tick_frequency=5; buddies=(Some(1001), Some(1002))));
+    ui.bind(&1003, &5000, slider::ValueChanged, move |_, _, _, args|{ moves2.borrow_mut().push(args.slider_position().unwrap()); } );
+    ui.commit().expect("Commit was not successful");
+
+    test_visibility!(ui, &1003, Slider);
+    test_position!(ui, &1003, Slider);
+    test_size!(ui, &1003, Slider);
+    test_enabled!(ui, &1003, Slider);
+
+    let slider = ui.get::<Slider>(&1003).expect("Control not found");
+    assert_eq!(slider.get_range(), (-10, 10));
+    assert_eq!(slider.get_value(), 4);
+    slider.set_value(50);
+    assert_eq!(slider.get_value(), 10);
+
+    assert!(slider.set_range(5, 1).is_err());
+    slider.set_range(0, 1000).unwrap();
+    assert_eq!(slider.get_range(), (0, 1000));
+
+    slider.set_page_size(100);
+    slider.set_line_size(5);
+    assert_eq!(slider.get_page_size(), 100);
+    assert_eq!(slider.get_line_size(), 5);
+
+    assert_eq!(slider.get_selection(), None);
+    slider.set_selection(Some((100, 200)));
+    assert_eq!(slider.get_selection(), Some((100, 200)));
+    slider.set_selection(None);
+    assert_eq!(slider.get_selection(), None);
+
+    assert!(!slider.get_vertical());
+    assert_eq!(slider.get_buddy(&ui, true), Some(1001));
+    assert_eq!(slider.get_buddy(&ui, false), Some(1002));
+    slider.set_buddy(&ui, false, None).unwrap();
+    assert_eq!(slider.get_buddy(&ui, false), None);
+
+    // ValueChanged is only raised when a scroll notification moved the thumb
+    unsafe {
+        let parent = match ui.handle_of(&1000) { Ok(nwg::custom::AnyHandle::HWND(h)) => h, _ => unreachable!() };
+        let handle = match ui.handle_of(&1003) { Ok(nwg::custom::AnyHandle::HWND(h)) => h, _ => unreachable!() };
+        let scroll = |code: winapi::WPARAM| { user32::SendMessageW(parent, winapi::WM_HSCROLL, code, handle as winapi::LPARAM); };
+
+        slider.set_value(300);
+        scroll(winapi::TB_ENDTRACK);
+        assert!(moves.borrow().is_empty());
+
+        user32::SendMessageW(handle, winapi::TBM_SETPOS, 1, 400);
+        scroll(winapi::TB_THUMBPOSITION);
+        scroll(winapi::TB_ENDTRACK);
+        scroll(winapi::TB_LINEDOWN);
+    }
+
+    assert_eq!(&*moves.borrow(), &[400]);
+}
+
+#[test]
+fn test_status_bar() {
+    let ui = setup_ui();
+
+    ui.pack_control(&1000, nwg_window!(size=(400, 300); resizable=true; visible=false));
+    ui.pack_resource(&1001, OemImageT{ source: OemImage::Icon(OemIcon::Information), size: (16, 16) });
+    ui.pack_control(&1002, nwg_status_bar!(parent=1000; text="Ready"; parts=vec![StatusPartWidth::Stretch(1), StatusPartWidth::Fixed(100), StatusPartWidth::Fixed(50)]));
+    ui.commit().expect("Commit was not successful");
+
+    {
+        let window = ui.get::<Window>(&1000).expect("Control not found");
+        let status = ui.get::<StatusBar>(&1002).expect("Control not found");
+
+        assert_eq!(status.get_part_count(), 3);
+        assert_eq!(status.get_text(0), "Ready");
+        assert_eq!(status.get_part_size(1).unwrap().0, 100);
+        assert_eq!(status.get_part_size(0).unwrap().0, 250);
+        assert!(status.get_part_size(3).is_none());
+        assert!(status.get_size_grip());
+
+        // The status bar follows the parent
+        window.set_size(500, 300);
+        let (_, window_height) = window.get_size();
+        let (width, height) = status.get_size();
+        assert_eq!(width, 500);
+        assert_eq!(status.get_position().1 as u32, window_height - height);
+        assert_eq!(status.get_part_size(0).unwrap().0, 350);
+
+        status.set_text(1, "Line 1");
+        assert_eq!(status.get_text(1), "Line 1");
+        assert_eq!(status.get_text(5), "");
+
+        assert!(status.set_parts(&[]).is_err());
+        status.set_parts(&[StatusPartWidth::Stretch(1), StatusPartWidth::Stretch(3)]).expect("Failed to set the parts");
+        assert_eq!(status.get_part_size(0).unwrap().0, 125);
+        assert_eq!(status.get_text(1), "Line 1");
+
+        assert_eq!(status.get_icon(&ui, 0), None);
+        status.set_icon(&ui, 0, Some(&1001)).expect("Failed to set the icon");
+        assert_eq!(status.get_icon(&ui, 0), Some(1001));
+        assert!(status.set_icon(&ui, 5, Some(&1001)).is_err());
+
+        assert!(!status.get_simple());
+        status.show_menu_help(Some("Open a file"));
+        assert!(status.get_simple());
+        assert_eq!(status.get_simple_text(), "Open a file");
+        status.show_menu_help(None);
+        assert!(!status.get_simple());
+    }
+}
+
+#[test]
+fn test_toolbar() {
+    use std::rc::Rc;
+    use std::cell::Cell;
+
+    let ui = setup_ui();
+    let clicked = Rc::new(Cell::new(0u16));
+    let clicked2 = clicked.clone();
+
+    let buttons = vec![
+        ToolbarButton{ image: Some(1001), tooltip: Some("Open a file".to_string()), ..ToolbarButton::new(10, "Open") },
+        ToolbarButton::separator(),
+        ToolbarButton{ style: ToolbarButtonStyle::Check, checked: true, ..ToolbarButton::new(11, "Bold") },
+        ToolbarButton{ style: ToolbarButtonStyle::Dropdown, menu: Some(1002), ..ToolbarButton::new(12, "More") },
+    ];
+
+    ui.pack_control(&1000, nwg_window!(size=(400, 300); visible=false));
+    ui.pack_resource(&1001, OemImageT{ source: OemImage::Icon(OemIcon::Information), size: (16, 16) });
+    ui.pack_control(&1002, ContextMenuT);
+    ui.pack_control(&1003, nwg_toolbar!(parent=1000; buttons=buttons));
+    ui.pack_resource(&1004, OemImageT{ source: OemImage::Bitmap(OemBitmap::CheckBoxes), size: (64, 16) });
+    ui.bind(&1003, &5000, toolbar::Click, move |_, _, _, args|{ clicked2.set(args.toolbar_button().unwrap()); } );
+    ui.commit().expect("Commit was not successful");
+
+    {
+        let window = ui.get::<Window>(&1000).expect("Control not found");
+        let tb = ui.get::<Toolbar>(&1003).expect("Control not found");
+
+        assert_eq!(tb.len(), 4);
+        assert_eq!(tb.index_of(12), Some(3));
+        assert_eq!(tb.get_position(), (0, 0));
+        assert_eq!(tb.get_size().0, 400);
+
+        window.set_size(500, 300);
+        assert_eq!(tb.get_size().0, 500);
+
+        assert!(tb.get_checked(11));
+        tb.set_checked(11, false);
+        assert!(!tb.get_checked(11));
+
+        assert!(tb.get_button_enabled(10));
+        tb.set_button_enabled(10, false);
+        assert!(!tb.get_button_enabled(10));
+
+        assert_eq!(tb.get_tooltip(10), Some("Open a file".to_string()));
+        tb.set_tooltip(10, None);
+        assert_eq!(tb.get_tooltip(10), None);
+
+        tb.add_button(&ui, &ToolbarButton::new(13, "Save")).expect("Failed to add the button");
+        assert_eq!(tb.index_of(13), Some(4));
+        assert!(tb.remove_button(13));
+        assert!(!tb.remove_button(13));
+        assert_eq!(tb.len(), 4);
+
+        // A bitmap wider than the image size takes one image. Removing a button removes its image.
+        let toolbar_handle = match ui.handle_of(&1003) { Ok(nwg::custom::AnyHandle::HWND(h)) => h, _ => unreachable!() };
+        let image_of = |id: u16| unsafe{ user32::SendMessageW(toolbar_handle, winapi::TB_GETBITMAP, id as winapi::WPARAM, 0) };
+        tb.add_button(&ui, &ToolbarButton{ image: Some(1004), ..ToolbarButton::new(13, "Wide") }).expect("Failed to add the button");
+        tb.add_button(&ui, &ToolbarButton{ image: Some(1001), ..ToolbarButton::new(14, "Info") }).expect("Failed to add the button");
+        assert_eq!(image_of(10), 0);
+        assert_eq!(image_of(13), 1);
+        assert_eq!(image_of(14), 2);
+
+        assert!(tb.remove_button(13));
+        assert_eq!(image_of(14), 1);
+        assert!(tb.remove_button(14));
+        assert_eq!(image_of(10), 0);
+
+        // A dropdown menu must be a context menu
+        let bad = ToolbarButton{ menu: Some(1001), ..ToolbarButton::new(14, "") };
+        assert!(tb.add_button(&ui, &bad).is_err());
+        assert_eq!(tb.len(), 4);
+
+        // Buttons notify the toolbar parent with their id
+        ui.simulate_message(&1000, winapi::WM_COMMAND, 11, toolbar_handle as winapi::LPARAM).unwrap();
+    }
+
+    assert_eq!(clicked.get(), 11);
+}
+
+#[test]
+fn test_tooltip() {
+    use std::rc::Rc;
+    use std::cell::Cell;
+
+    let ui = setup_ui();
+    let calls = Rc::new(Cell::new(0u32));
+    let calls2 = calls.clone();
+
+    ui.pack_control(&1000, window());
+    ui.pack_control(&1001, nwg_button!(parent=1000; tooltip=Some("Template tip".to_string())));
+    ui.pack_control(&1002, nwg_label!(parent=1000; text="Name"));
+    ui.pack_control(&1003, nwg_textinput!(parent=1000;));
+    ui.pack_control(&1004, nwg_tooltip!(balloon=true; title="Help"; icon=TooltipIcon::Info; initial_delay=Some(100)));
+    ui.pack_resource(&1005, default_font());
+    ui.commit().expect("Commit was not successful");
+
+    {
+        let tip = ui.get::<Tooltip>(&1004).expect("Control not found");
+
+        assert!(tip.get_balloon());
+        assert_eq!(tip.get_delay(TooltipDelay::Initial), 100);
+        assert_eq!(tip.len(), 0);
+
+        tip.register(&ui, &1002, "Your name").expect("Failed to register the tip");
+        assert_eq!(tip.len(), 1);
+        assert_eq!(tip.get_text(&ui, &1002), Some("Your name".to_string()));
+
+        tip.register(&ui, &1002, "Your full name").expect("Failed to register the tip");
+        assert_eq!(tip.len(), 1);
+        assert_eq!(tip.get_text(&ui, &1002), Some("Your full name".to_string()));
+
+        tip.register_callback(&ui, &1003, move || { calls2.set(calls2.get() + 1); format!("Call {}", calls2.get()) }).expect("Failed to register the tip");
+        assert_eq!(tip.len(), 2);
+        assert_eq!(tip.get_text(&ui, &1003), Some("Call 1".to_string()));
+        assert_eq!(calls.get(), 1);
+
+        assert!(tip.register(&ui, &1005, "Not a control").is_err());
+        assert_eq!(tip.get_text(&ui, &1001), None);
+
+        tip.unregister(&ui, &1002).expect("Failed to unregister the tip");
+        tip.unregister(&ui, &1003).expect("Failed to unregister the tip");
+        assert_eq!(tip.len(), 0);
+        assert_eq!(tip.get_text(&ui, &1002), None);
+
+        tip.set_delay(TooltipDelay::AutoPop, Some(5000));
+        assert_eq!(tip.get_delay(TooltipDelay::AutoPop), 5000);
+
+        tip.set_enabled(false);
+        assert!(!tip.get_enabled());
+        tip.set_title("", TooltipIcon::None);
+    }
+
+    // The tip of a control is removed when the control is unpacked
+    {
+        let tip = ui.get::<Tooltip>(&1004).expect("Control not found");
+        tip.register(&ui, &1002, "Your name").expect("Failed to register the tip");
+        tip.register_callback(&ui, &1003, || "Callback".to_string()).expect("Failed to register the tip");
+        assert_eq!(tip.len(), 2);
+    }
+
+    ui.unpack(&1002);
+    ui.unpack(&1003);
+    ui.commit().expect("Commit was not successful");
+    assert_eq!(ui.get::<Tooltip>(&1004).expect("Control not found").len(), 0);
+
+    ui.unpack(&1001);
+    ui.unpack(&1004);
+}
+
+#[test]
+fn test_template_tooltip() {
+    use std::ptr;
+    use winapi::{HWND, LPARAM, BOOL};
+
+    unsafe extern "system" fn count_tooltips(hwnd: HWND, count: LPARAM) -> BOOL {
+        let mut class_name = [0u16; 32];
+        let len = user32::GetClassNameW(hwnd, class_name.as_mut_ptr(), 32) as usize;
+        if String::from_utf16_lossy(&class_name[0..len]) == "tooltips_class32" {
+            *(count as *mut usize) += 1;
+        }
+        1
+    }
+
+    let thread_tooltips = |thread| {
+        let mut count = 0usize;
+        unsafe{ user32::EnumThreadWindows(thread, Some(count_tooltips), &mut count as *mut usize as LPARAM); }
+        count
+    };
+
+    let thread = {
+        let ui = setup_ui();
+        ui.pack_control(&1000, window());
+        ui.pack_control(&1001, nwg_button!(parent=1000; tooltip=Some("First".to_string())));
+        ui.pack_control(&1002, nwg_label!(parent=1000; text="Name"; tooltip=Some("Second".to_string())));
+        ui.commit().expect("Commit was not successful");
+
+        let other_ui = setup_ui();
+        other_ui.pack_control(&1000, window());
+        other_ui.pack_control(&1001, nwg_button!(parent=1000; tooltip=Some("Other".to_string())));
+        other_ui.commit().expect("Commit was not successful");
+
+        let handle = match ui.handle_of(&1001) { Ok(nwg::custom::AnyHandle::HWND(h)) => h, _ => panic!("Button is not a window") };
+        let thread = unsafe{ user32::GetWindowThreadProcessId(handle, ptr::null_mut()) };
+
+        // One tooltip per Ui, shared by its templates
+        assert_eq!(thread_tooltips(thread), 2);
+
+        drop(other_ui);
+        assert_eq!(thread_tooltips(thread), 1);
+
+        ui.unpack(&1001);
+        ui.commit().expect("Commit was not successful");
+        assert_eq!(thread_tooltips(thread), 1);
+
+        thread
+    };
+
+    assert_eq!(thread_tooltips(thread), 0);
+}
+
+#[test]
+fn test_control_colors() {
+    let ui = setup_ui();
+    let mut window_t = window();
+    window_t.text_color = Some([200, 200, 200]);
+    window_t.background_color = Some([30, 30, 30]);
+
+    ui.pack_control(&1000, window_t);
+    ui.pack_control(&1001, nwg_label!(parent=1000; text="Name"; text_color=Some([255, 0, 0])));
+    ui.pack_control(&1002, nwg_textinput!(parent=1000; background_color=Some([255, 255, 255])));
+    ui.pack_control(&1003, nwg_textbox!(parent=1000;));
+    ui.pack_control(&1004, nwg_checkbox!(parent=1000;));
+    ui.pack_control(&1005, nwg_radiobutton!(parent=1000;));
+    ui.pack_control(&1006, nwg_groupbox!(parent=1000; background_color=Some([0, 0, 80])));
+    ui.pack_control(&1007, nwg_button!(parent=1000; text="Colored"; text_color=Some([255, 255, 0]); background_color=Some([0, 0, 255])));
+    ui.pack_control(&1008, nwg_button!(parent=1000; text="Split"; style=ButtonStyle::Split; text_color=Some([255, 255, 0])));
+    ui.pack_control(&1009, nwg_frame!(parent=1000;));
+    ui.pack_control(&1010, nwg_label!(parent=1009; text="Nested"));
+    ui.commit().expect("Commit was not successful");
+
+    {
+        let window = ui.get::<Window>(&1000).expect("Control not found");
+        let label = ui.get::<Label>(&1001).expect("Control not found");
+        let input = ui.get::<TextInput>(&1002).expect("Control not found");
+        let text = ui.get::<TextBox>(&1003).expect("Control not found");
+        let check = ui.get::<CheckBox>(&1004).expect("Control not found");
+        let radio = ui.get::<RadioButton>(&1005).expect("Control not found");
+        let group = ui.get::<GroupBox>(&1006).expect("Control not found");
+
+        assert_eq!(window.get_text_color(), Some([200, 200, 200]));
+        assert_eq!(window.get_background_color(), Some([30, 30, 30]));
+        assert_eq!(label.get_text_color(), Some([255, 0, 0]));
+        assert_eq!(label.get_background_color(), None);
+        assert_eq!(input.get_background_color(), Some([255, 255, 255]));
+        assert_eq!(group.get_background_color(), Some([0, 0, 80]));
+
+        // The inherited colors are not returned by the children
+        assert_eq!(text.get_text_color(), None);
+        assert_eq!(check.get_background_color(), None);
+
+        text.set_text_color(Some([0, 128, 0]));
+        check.set_background_color(Some([10, 20, 30]));
+        radio.set_text_color(Some([1, 2, 3]));
+        assert_eq!(text.get_text_color(), Some([0, 128, 0]));
+        assert_eq!(check.get_background_color(), Some([10, 20, 30]));
+        assert_eq!(radio.get_text_color(), Some([1, 2, 3]));
+
+        label.set_text_color(None);
+        group.set_background_color(None);
+        assert_eq!(label.get_text_color(), None);
+        assert_eq!(group.get_background_color(), None);
+
+        let button = ui.get::<Button>(&1007).expect("Control not found");
+        assert_eq!(button.get_text_color(), Some([255, 255, 0]));
+        assert_eq!(button.get_background_color(), Some([0, 0, 255]));
+        button.set_background_color(Some([0, 128, 0]));
+        assert_eq!(button.get_background_color(), Some([0, 128, 0]));
+    }
+
+    // The parent answers the color messages of its children
+    let handle = |id: u64| match ui.handle_of(&id) { Ok(nwg::custom::AnyHandle::HWND(h)) => h, _ => unreachable!() };
+    unsafe {
+        let parent = handle(1000);
+        let hdc = user32::GetDC(parent);
+
+        ui.get::<Label>(&1001).unwrap().set_text_color(Some([255, 0, 0]));
+        let brush = user32::SendMessageW(parent, winapi::WM_CTLCOLORSTATIC, hdc as winapi::WPARAM, handle(1001) as winapi::LPARAM);
+        assert!(brush != 0);
+        assert_eq!(gdi32::GetTextColor(hdc), 0x0000FF);
+        assert_eq!(gdi32::GetBkColor(hdc), 0x1E1E1E);
+
+        // Edits only inherit the text color
+        user32::SendMessageW(parent, winapi::WM_CTLCOLOREDIT, hdc as winapi::WPARAM, handle(1003) as winapi::LPARAM);
+        assert_eq!(gdi32::GetTextColor(hdc), 0x008000);
+        user32::SendMessageW(parent, winapi::WM_CTLCOLOREDIT, hdc as winapi::WPARAM, handle(1002) as winapi::LPARAM);
+        assert_eq!(gdi32::GetTextColor(hdc), 0xC8C8C8);
+        assert_eq!(gdi32::GetBkColor(hdc), 0xFFFFFF);
+
+        // Check boxes, radio buttons and group boxes
+        user32::SendMessageW(parent, winapi::WM_CTLCOLORSTATIC, hdc as winapi::WPARAM, handle(1004) as winapi::LPARAM);
+        assert_eq!(gdi32::GetTextColor(hdc), 0xC8C8C8);
+        assert_eq!(gdi32::GetBkColor(hdc), 0x1E140A);
+        user32::SendMessageW(parent, winapi::WM_CTLCOLORSTATIC, hdc as winapi::WPARAM, handle(1005) as winapi::LPARAM);
+        assert_eq!(gdi32::GetTextColor(hdc), 0x030201);
+        assert_eq!(gdi32::GetBkColor(hdc), 0x1E1E1E);
+        user32::SendMessageW(parent, winapi::WM_CTLCOLORSTATIC, hdc as winapi::WPARAM, handle(1006) as winapi::LPARAM);
+        assert_eq!(gdi32::GetTextColor(hdc), 0xC8C8C8);
+        assert_eq!(gdi32::GetBkColor(hdc), 0x1E1E1E);
+
+        // A frame without colors uses the colors of its parent, and so do its children
+        let frame = handle(1009);
+        let frame_hdc = user32::GetDC(frame);
+        let brush = user32::SendMessageW(frame, winapi::WM_CTLCOLORSTATIC, frame_hdc as winapi::WPARAM, handle(1010) as winapi::LPARAM);
+        assert!(brush != 0);
+        assert_eq!(gdi32::GetTextColor(frame_hdc), 0xC8C8C8);
+        assert_eq!(gdi32::GetBkColor(frame_hdc), 0x1E1E1E);
+        assert_eq!(user32::SendMessageW(frame, winapi::WM_ERASEBKGND, frame_hdc as winapi::WPARAM, 0), 1);
+        user32::ReleaseDC(frame, frame_hdc);
+
+        // Push buttons with colors are painted by NWG in their custom draw notification. The other styles keep the system painting.
+        let custom_draw = |button: winapi::HWND| {
+            let nmcd = winapi::NMCUSTOMDRAW{ hdr: winapi::NMHDR{ hwndFrom: button, idFrom: 0, code: winapi::NM_CUSTOMDRAW },
+              dwDrawStage: winapi::CDDS_PREPAINT, hdc: hdc, rc: winapi::RECT{ left: 0, top: 0, right: 100, bottom: 30 },
+              dwItemSpec: 0, uItemState: 0, lItemlParam: 0 };
+            user32::SendMessageW(parent, winapi::WM_NOTIFY, 0, &nmcd as *const winapi::NMCUSTOMDRAW as winapi::LPARAM)
+        };
+        assert_eq!(custom_draw(handle(1007)), winapi::CDRF_SKIPDEFAULT);
+        assert!(custom_draw(handle(1008)) != winapi::CDRF_SKIPDEFAULT);
+        ui.get::<Button>(&1007).unwrap().set_text_color(None);
+        ui.get::<Button>(&1007).unwrap().set_background_color(None);
+        assert!(custom_draw(handle(1007)) != winapi::CDRF_SKIPDEFAULT);
+
+        user32::ReleaseDC(parent, hdc);
+    }
+
+    ui.unpack(&1001);
+    ui.unpack(&1006);
+    ui.unpack(&1007);
+}
+
+#[test]
+fn test_tray_notification() {
+    use std::rc::Rc;
+    use std::cell::Cell;
+
+    let ui = setup_ui();
+    let clicks = Rc::new(Cell::new(0u32));
+    let (clicks2, clicks3) = (clicks.clone(), clicks.clone());
+
+    ui.pack_control(&1000, window());
+    ui.pack_resource(&1001, OemImageT{ source: OemImage::Icon(OemIcon::Information), size: (16, 16) });
+    ui.pack_resource(&1002, OemImageT{ source: OemImage::Icon(OemIcon::Warning), size: (16, 16) });
+    ui.pack_control(&1003, ContextMenuT);
+    ui.pack_control(&1004, nwg_tray_notification!(icon=1001; tooltip=Some("Sync"); menu=Some(1003)));
+    ui.bind(&1004, &5000, tray::Click, move |_, _, _, _|{ clicks2.set(clicks2.get() + 1); } );
+    ui.bind(&1004, &5001, tray::DoubleClick, move |_, _, _, _|{ clicks3.set(clicks3.get() + 10); } );
+    ui.commit().expect("Commit was not successful");
+
+    {
+        let tray = ui.get::<TrayNotification>(&1004).expect("Control not found");
+
+        assert!(tray.get_visibility());
+        assert_eq!(tray.get_menu(&ui), Some(1003));
+
+        assert!(tray.set_icon(&ui, &1002).is_ok());
+        assert!(tray.set_icon(&ui, &1003).is_err());
+        assert!(tray.set_menu(&ui, Some(&1001)).is_err());
+        assert!(tray.set_menu(&ui, None).is_ok());
+        assert_eq!(tray.get_menu(&ui), None);
+
+        tray.set_tooltip(Some("Sync in progress"));
+        tray.show_balloon("Sync", "The files are up to date", TrayBalloonIcon::Info, true);
+        tray.hide_balloon();
+
+        tray.set_visibility(false);
+        assert!(!tray.get_visibility());
+    }
+
+    let tray_id = match ui.handle_of(&1004) { Ok(nwg::custom::AnyHandle::Custom(_, id)) => id as winapi::WPARAM, _ => unreachable!() };
+    ui.simulate_message(&1000, 0x422, tray_id, winapi::WM_LBUTTONUP as winapi::LPARAM).unwrap();
+    ui.simulate_message(&1000, 0x422, tray_id, winapi::WM_LBUTTONDBLCLK as winapi::LPARAM).unwrap();
+    ui.simulate_message(&1000, 0x422, tray_id + 1, winapi::WM_LBUTTONUP as winapi::LPARAM).unwrap();
+    assert_eq!(clicks.get(), 11);
+
+    ui.unpack(&1004);
+}
+
+
+#[test]
+fn test_file_dialog() {
+    let ui = setup_ui();
+
+    ui.pack_control(&1000, window());
+    ui.pack_control(&1001, nwg_filedialog!(parent=Some(1000); filters=Some("Text(*.txt;*.md)|Any(*.*)"); default_name=Some("notes"); default_extension=Some("txt")));
+    ui.pack_control(&1002, nwg_filedialog!(action=FileDialogAction::Save; overwrite_prompt=false));
+    ui.pack_control(&1003, nwg_folderdialog!(multiselect=true));
+    ui.commit().expect("Commit was not successful");
+
+    ui.pack_control(&1004, nwg_filedialog!(filters=Some("Text*.txt")));
+    match ui.commit() { Err(Error::BadFilter(_)) => {}, r => panic!("Should have returned Error::BadFilter, got {:?}", r) }
+
+    {
+        let dialog = ui.get::<FileDialog>(&1001).unwrap();
+        assert!(dialog.set_file_filters(&FileFilters::new().add("Rust", &["*.rs"])).is_ok());
+        assert!(dialog.set_file_filters(&FileFilters::new()).is_err());
+        assert!(dialog.set_default_name("readme").is_ok());
+        assert!(dialog.set_default_extension(".md").is_ok());
+        match dialog.set_overwrite_prompt(true) { Err(Error::UserError(_)) => {}, r => panic!("Should have returned Error::UserError, got {:?}", r) }
+        assert!(dialog.get_selected_path().is_err());
+
+        let save = ui.get::<FileDialog>(&1002).unwrap();
+        assert!(!save.get_overwrite_prompt());
+        assert!(save.set_overwrite_prompt(true).is_ok());
+        assert!(save.get_overwrite_prompt());
+
+        let folders = ui.get::<FileDialog>(&1003).unwrap();
+        assert!(folders.get_multiselect());
+        assert_eq!(folders.action(), FileDialogAction::OpenDirectory);
+    }
+
+    // Folder and selection changes are sent to the ui hidden window
+    let x: *mut u32 = Box::into_raw(Box::new(0));
+    ui.bind(&1001, &5000, nwge::file_dialog::FolderChanged, move |_, _, _, _| { unsafe{ *x += 1; } });
+    ui.bind(&1001, &5001, nwge::file_dialog::SelectionChanged, move |_, _, _, _| { unsafe{ *x += 10; } });
+
+    let handle = match ui.handle_of(&1001) { Ok(nwg::custom::AnyHandle::Custom(_, h)) => h as winapi::LPARAM, _ => unreachable!() };
+    ui.simulate_message(&1000, 0x423, 0, handle).unwrap();
+    ui.simulate_message(&1000, 0x423, 1, handle).unwrap();
+    ui.simulate_message(&1000, 0x423, 1, handle + 1).unwrap();
+    unsafe{ assert_eq!(*x, 11); drop(Box::from_raw(x)); }
+}
+
+#[test]
+fn test_color_dialog() {
+    let ui = setup_ui();
+
+    ui.pack_control(&1000, window());
+    ui.pack_control(&1001, nwg_colordialog!(parent=Some(1000); color=[255, 0, 0]; custom_colors=vec![[0, 128, 255]]));
+    ui.commit().expect("Commit was not successful");
+
+    ui.pack_control(&1002, nwg_colordialog!(custom_colors=vec![[0, 0, 0]; 17]));
+    match ui.commit() { Err(Error::UserError(_)) => {}, r => panic!("Should have returned Error::UserError, got {:?}", r) }
+
+    let dialog = ui.get::<ColorDialog>(&1001).unwrap();
+    assert_eq!(dialog.get_color(), [255, 0, 0]);
+    dialog.set_color([10, 20, 30]);
+    assert_eq!(dialog.get_color(), [10, 20, 30]);
+
+    let colors = dialog.get_custom_colors();
+    assert_eq!(colors.len(), 16);
+    assert_eq!(colors[0], [0, 128, 255]);
+    assert_eq!(colors[1], [255, 255, 255]);
+
+    assert!(dialog.set_custom_colors(&[[1, 2, 3], [4, 5, 6]]).is_ok());
+    assert_eq!(&dialog.get_custom_colors()[0..3], &[[1, 2, 3], [4, 5, 6], [255, 255, 255]]);
+    assert!(dialog.set_custom_colors(&[[0, 0, 0]; 17]).is_err());
+
+    assert!(!dialog.get_full_open());
+    dialog.set_full_open(true);
+    assert!(dialog.get_full_open());
+}
+
+#[test]
+fn test_font_dialog() {
+    let ui = setup_ui();
+
+    let font = FontT{family: "Arial", size: -16, weight: FONT_WEIGHT_BOLD, decoration: FONT_DECO_ITALIC | FONT_DECO_UNDERLINE};
+
+    ui.pack_control(&1000, window());
+    ui.pack_control(&1001, nwg_fontdialog!(parent=Some(1000); font=Some(font.clone()); color=[0, 0, 255]));
+    ui.pack_control(&1002, nwg_fontdialog!());
+    ui.commit().expect("Commit was not successful");
+
+    let dialog = ui.get::<FontDialog>(&1001).unwrap();
+    let selected = dialog.get_font().unwrap();
+    assert_eq!(&selected.family, "Arial");
+    assert_eq!(selected.size, -16);
+    assert_eq!(selected.weight, FONT_WEIGHT_BOLD);
+    assert_eq!(selected.decoration, FONT_DECO_ITALIC | FONT_DECO_UNDERLINE);
+    assert_eq!(dialog.get_color(), [0, 0, 255]);
+
+    // The selected font can be packed as a resource
+    ui.pack_resource(&10_000, selected);
+    ui.commit().expect("Commit was not successful");
+
+    dialog.set_font::<&str>(None);
+    assert!(dialog.get_font().is_none());
+    assert!(ui.get::<FontDialog>(&1002).unwrap().get_font().is_none());
+}
+
+#[test]
+fn test_print_dialog() {
+    let ui = setup_ui();
+
+    ui.pack_control(&1000, window());
+    ui.pack_control(&1001, nwg_printdialog!(parent=Some(1000); pages=Some((1, 10)); selection=true));
+    ui.pack_control(&1002, nwg_printdialog!());
+    ui.commit().expect("Commit was not successful");
+
+    ui.pack_control(&1003, nwg_printdialog!(pages=Some((10, 1))));
+    match ui.commit() { Err(Error::UserError(_)) => {}, r => panic!("Should have returned Error::UserError, got {:?}", r) }
+
+    let dialog = ui.get::<PrintDialog>(&1001).unwrap();
+    assert_eq!(dialog.get_settings(), None);
+    assert_eq!(dialog.get_range(), PrintRange::All);
+
+    assert!(dialog.set_range(PrintRange::Pages(2, 5)).is_ok());
+    assert_eq!(dialog.get_range(), PrintRange::Pages(2, 5));
+    assert!(dialog.set_range(PrintRange::Pages(5, 11)).is_err());
+    assert!(dialog.set_range(PrintRange::Selection).is_ok());
+
+    let dialog2 = ui.get::<PrintDialog>(&1002).unwrap();
+    assert!(dialog2.set_range(PrintRange::Selection).is_err());
+    assert!(dialog2.set_range(PrintRange::Pages(1, 1)).is_err());
+
+    // The test machine may not have any printer
+    if dialog2.load_default() {
+        let settings = dialog2.get_settings().unwrap();
+        assert!(settings.printer.len() > 0);
+        assert_eq!(settings.range, PrintRange::All);
+    }
+}
+
+#[test]
+fn test_task_dialog() {
+    let ui = setup_ui();
+
+    ui.pack_control(&1000, window());
+    ui.pack_control(&1001, nwg_button!(parent=1000; text="Button"));
+    ui.commit().expect("Commit was not successful");
+
+    let bad = [
+        TaskDialog::new("Test").common_buttons(&[TaskDialogButton::Custom(0)]),
+        TaskDialog::new("Test").default_button(TaskDialogButton::Custom(0)),
+        TaskDialog::new("Test").common_buttons(&[TaskDialogButton::Ok]).default_button(TaskDialogButton::Yes),
+        TaskDialog::new("Test").radio("One").default_radio(1),
+        TaskDialog::new("Test").progress(TaskDialogProgress::Range(10, 10)),
+    ];
+    for dialog in bad.iter() {
+        match dialog.run() { Err(Error::UserError(_)) => {}, r => panic!("Should have returned Error::UserError, got {:?}", r) }
+    }
+
+    match TaskDialog::new("Test").run_with_parent(&ui, &1001) { Err(Error::BadParent(_)) => {}, r => panic!("Should have returned Error::BadParent, got {:?}", r) }
+
+    let dialog = TaskDialog::new("Working")
+        .title("Test")
+        .content("A <a href=\"link\">link</a>")
+        .icon(MessageIcons::Question)
+        .button("First\nA note")
+        .button("Second")
+        .command_links(true)
+        .common_buttons(&[TaskDialogButton::Cancel])
+        .radio("One")
+        .radio("Two")
+        .default_radio(0)
+        .verification("Don't show again", false)
+        .expanded_information("Details", true)
+        .footer("Footer", MessageIcons::Info)
+        .hyperlinks(true)
+        .progress(TaskDialogProgress::Range(0, 10))
+        .on_timer(|dialog, _| {
+            dialog.set_progress(10);
+            dialog.set_content("Done");
+            dialog.click_radio(1);
+            dialog.set_verification_checked(true);
+            dialog.click_button(TaskDialogButton::Custom(1));
+        });
+
+    let result = dialog.run_with_parent(&ui, &1000).expect("The task dialog failed to run");
+    assert_eq!(result, TaskDialogResult{ button: TaskDialogButton::Custom(1), radio: Some(1), verification_checked: true });
 }
 
 #[test]
@@ -835,7 +2010,7 @@ fn test_treeview() {
     ui.pack_control(&1004, tree_item_t3.clone());
     ui.pack_control(&1005, tree_item_t4.clone());
     ui.pack_control(&1006, tree_item_t5.clone());
-    ui.pack_control(&1100, MenuItemT{text: "", parent: 1000, disabled: false});
+    ui.pack_control(&1100, MenuItemT{text: "", parent: 1000, disabled: false, checked: false, radio: false, default: false, image: None, index: None});
     ui.commit().expect("Commit was not successful");
 
     let bad_tree_item_t1 = TreeViewItemT{ text: "test", parent: 1000u64, disabled: false };
@@ -870,6 +2045,125 @@ fn test_treeview() {
     }
 }
 
+#[test]
+fn test_list_view() {
+    let ui = setup_ui();
+
+    let mut right = ListViewColumn::new("Price", 60);
+    right.align = HTextAlign::Right;
+
+    ui.pack_control(&1000, window());
+    ui.pack_control(&1001, nwg_list_view!(parent=1000; multi_select=true; columns=vec![ListViewColumn::new("Name", 100), right.clone()]));
+    ui.commit().expect("Commit was not successful");
+
+    test_visibility!(ui, &1001, ListView);
+    test_position!(ui, &1001, ListView);
+    test_size!(ui, &1001, ListView);
+    test_enabled!(ui, &1001, ListView);
+
+    let list = ui.get::<ListView>(&1001).expect("Failed to get the control");
+    assert_eq!(list.col_count(), 2);
+    assert_eq!(list.get_col(1), Some(right));
+    assert_eq!(list.get_col(5), None);
+
+    // Column headers longer than the first buffer are read completely
+    let long = ListViewColumn::new("x".repeat(700), 80);
+    assert_eq!(list.add_col(&long), Some(2));
+    assert_eq!(list.get_col(2), Some(long));
+
+    assert_eq!(list.add(&["Apple", "1.50"]), Some(0));
+    assert_eq!(list.add(&["Banana", "0.25"]), Some(1));
+    assert_eq!(list.get_count(), 2);
+    assert_eq!(&list.get_text(1, 1), "0.25");
+    list.set_text(0, 0, &"y".repeat(600));
+    assert_eq!(list.get_text(0, 0), "y".repeat(600));
+}
+
+#[test]
+fn test_virtual_list_view() {
+    use std::rc::Rc;
+    use std::cell::RefCell;
+
+    struct Rows(Vec<String>);
+    impl ListViewDataSource for Rows {
+        fn row_count(&self) -> usize { self.0.len() }
+        fn cell(&self, row: usize, col: usize) -> String { format!("{} {}", self.0[row], col) }
+    }
+
+    let ui = setup_ui();
+    let rows = Rc::new(RefCell::new(Rows(vec!["Apple".to_string(), "Banana".to_string(), "Blueberry".to_string()])));
+
+    ui.pack_control(&1000, window());
+    ui.pack_control(&1001, nwg_virtual_list_view!(parent=1000; data=rows.clone(); multi_select=true; columns=vec![ListViewColumn::new("Name", 100), ListViewColumn::new("Col", 50)]));
+    ui.commit().expect("Commit was not successful");
+
+    test_visibility!(ui, &1001, VirtualListView);
+    test_position!(ui, &1001, VirtualListView);
+    test_size!(ui, &1001, VirtualListView);
+    test_enabled!(ui, &1001, VirtualListView);
+
+    let list = ui.get::<VirtualListView>(&1001).expect("Failed to get the control");
+    assert!(list.get_count() == 3);
+    assert!(list.col_count() == 2);
+    assert!(&list.get_text(1, 1) == "Banana 1");
+    assert!(&list.get_text(10, 0) == "");
+    assert!(list.find("blue", 0) == Some(2));
+    assert!(list.find("b", 2) == Some(2));
+    assert!(list.find("cherry", 0) == None);
+
+    list.set_index_selected(usize::max_value(), true);
+    assert!(list.len_selected() == 3);
+
+    rows.borrow_mut().0.push("Cherry".to_string());
+    list.notify_changed();
+    assert!(list.get_count() == 4);
+    assert!(list.find("cherry", 0) == Some(3));
+
+    drop(list);
+    ui.unpack(&1001);
+    ui.commit().expect("Commit was not successful");
+    assert!(Rc::strong_count(&rows) == 1, "The data source was not released");
+}
+
+#[test]
+fn test_layout() {
+    let ui = setup_ui();
+
+    ui.pack_control(&1000, window());
+    ui.pack_control(&1001, ButtonT{text: "", position:(0, 0), size:(10, 10), visible:true, disabled:false, parent: 1000, font: None, tooltip: None, style: ButtonStyle::Push, image: None, text_color: None, background_color: None});
+    ui.pack_control(&1002, ButtonT{text: "", position:(0, 0), size:(10, 10), visible:true, disabled:false, parent: 1000, font: None, tooltip: None, style: ButtonStyle::Push, image: None, text_color: None, background_color: None});
+    ui.bind(&1000, &1000, nwge::Resized, |_, _, _, args| { args.size().unwrap(); });
+    ui.set_layout(&1000, BoxLayout::vertical().add(LayoutItem::control(1001)).add(LayoutItem::control(1002)));
+    ui.commit().expect("Commit was not successful");
+
+    {
+        let window = ui.get::<Window>(&1000).unwrap();
+        let (w, h) = window.get_size();
+        let btn = ui.get::<Button>(&1002).unwrap();
+        assert!(btn.get_position() == (0, (h/2) as i32), "Button position do not match the layout");
+        assert!(btn.get_size() == (w, h/2), "Button size do not match the layout");
+    }
+
+    // A new layout replaces the previous one
+    ui.set_layout(&1000, BoxLayout::horizontal().add(LayoutItem::control(1001)).add(LayoutItem::control(1002)));
+    ui.commit().expect("Commit was not successful");
+
+    {
+        let window = ui.get::<Window>(&1000).unwrap();
+        let (w, h) = window.get_size();
+        let btn = ui.get::<Button>(&1002).unwrap();
+        assert!(btn.get_position() == ((w/2) as i32, 0), "Button position do not match the new layout");
+        assert!(btn.get_size() == (w/2, h), "Button size do not match the new layout");
+    }
+
+    ui.remove_layout(&1000);
+    ui.commit().expect("Commit was not successful");
+
+    // The layouts do not replace or remove the user callbacks
+    ui.unbind(&1000, &1000, nwge::Resized);
+    ui.commit().expect("Commit was not successful");
+}
+
 #[test]
 fn sizeof_events_unpack_function() {
     use std::mem::{size_of_val, size_of};
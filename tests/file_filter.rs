@@ -0,0 +1,43 @@
+/*!
+    Tests for the file filters used by the file dialogs. Those do not require a system window and can run on any platform.
+*/
+
+extern crate native_windows_gui as nwg;
+
+use nwg::{FileFilter, FileFilters, Error};
+
+#[test]
+fn test_file_filters_parse() {
+    let filters = FileFilters::parse("Text files(*.txt;*.md)|Any(*.*)").unwrap();
+    assert_eq!(filters.len(), 2);
+    assert_eq!(filters.filters()[0], FileFilter::new("Text files", &["*.txt", "*.md"]));
+    assert_eq!(filters.filters()[1].spec(), "*.*");
+
+    // Spaces are ignored and the last parenthesis starts the patterns
+    let filters: FileFilters = " Rust (crate) ( *.rs ; Cargo.toml ) ".parse().unwrap();
+    assert_eq!(filters.filters()[0], FileFilter::new("Rust (crate)", &["*.rs", "Cargo.toml"]));
+
+    // The builder and the parser give the same filters
+    let built = FileFilters::new().add("Text files", &["*.txt", "*.md"]).add("Any", &["*.*"]);
+    assert!(built.validate().is_ok());
+    assert_eq!(built.to_string(), "Text files(*.txt;*.md)|Any(*.*)");
+    assert_eq!(FileFilters::parse(&built.to_string()), Ok(built));
+}
+
+#[test]
+fn test_file_filters_errors() {
+    let bad = ["", "Text", "Text(*.txt", "(*.txt)", "Text()", "Text(*.txt;)", "Text(*.txt)|", "Text(*.txt)|Any"];
+    for text in bad.iter() {
+        match FileFilters::parse(text) {
+            Err(Error::BadFilter(_)) => {},
+            r => panic!("Parsing {:?} should fail, got {:?}", text, r)
+        }
+    }
+
+    assert!(FileFilters::new().validate().is_err());
+    assert!(FileFilters::new().add("Text", &[]).validate().is_err());
+    assert!(FileFilters::new().add("", &["*.txt"]).validate().is_err());
+    assert!(FileFilters::new().add("A|B", &["*.txt"]).validate().is_err());
+    assert!(FileFilters::new().add("Text", &["*.txt;*.md"]).validate().is_err());
+    assert!(FileFilter::new("Text", &["*.txt", "*.md"]).validate().is_ok());
+}
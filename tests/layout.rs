@@ -0,0 +1,65 @@
+/*!
+    Tests for the layout solver. Those do not require a system window and can run on any platform.
+*/
+
+extern crate native_windows_gui as nwg;
+
+use nwg::{Layout, LayoutItem, BoxLayout, GridLayout, DockLayout, DockSide, Margin};
+
+#[test]
+fn test_layout_solver() {
+    // Box layout: fixed items keep their minimum size, the free space goes to the stretched items
+    let hbox: Layout<u64> = BoxLayout::horizontal()
+        .margin(Margin::all(5))
+        .spacing(10)
+        .add(LayoutItem::control(1).fixed_size(50, 20))
+        .add(LayoutItem::control(2).stretch(1))
+        .add(LayoutItem::control(3).stretch(3))
+        .into();
+
+    let r = hbox.solve((0, 0), (320, 100));
+    assert!(r == vec![(1, (5, 5), (50, 20)), (2, (65, 5), (60, 90)), (3, (135, 5), (180, 90))], "Box layout result do not match: {:?}", r);
+    assert!(hbox.min_size() == (80, 30), "Box layout minimum size do not match");
+
+    // Box layout: a stretched item never grows past its maximum size
+    let vbox: Layout<u64> = BoxLayout::vertical()
+        .add(LayoutItem::control(1).max_size(100, 30))
+        .add(LayoutItem::spacer((0, 10)))
+        .add(LayoutItem::control(2))
+        .into();
+
+    let r = vbox.solve((10, 10), (100, 200));
+    assert!(r == vec![(1, (10, 10), (100, 30)), (2, (10, 50), (100, 160))], "Box layout result do not match: {:?}", r);
+
+    // Grid layout: columns use their stretch factor, spanning cells cover the spacing between the tracks
+    let grid: Layout<u64> = GridLayout::new(2, 2)
+        .spacing(10, 10)
+        .column_stretch(0, 0)
+        .add(LayoutItem::control(1).min_size(80, 0), 0, 0)
+        .add(LayoutItem::control(2), 1, 0)
+        .add_span(LayoutItem::control(3), 0, 1, 2, 1)
+        .into();
+
+    let r = grid.solve((0, 0), (300, 210));
+    assert!(r == vec![(1, (0, 0), (80, 100)), (2, (90, 0), (210, 100)), (3, (0, 110), (300, 100))], "Grid layout result do not match: {:?}", r);
+
+    // Dock layout
+    let dock: Layout<u64> = DockLayout::new()
+        .add(DockSide::Top, LayoutItem::control(1).min_size(0, 30).stretch(0))
+        .add(DockSide::Bottom, LayoutItem::control(2).min_size(0, 20).stretch(0))
+        .add(DockSide::Left, LayoutItem::control(3).min_size(50, 0).stretch(0))
+        .add(DockSide::Fill, LayoutItem::control(4))
+        .into();
+
+    let r = dock.solve((0, 0), (200, 200));
+    assert!(r == vec![(1, (0, 0), (200, 30)), (2, (0, 180), (200, 20)), (3, (0, 30), (50, 150)), (4, (50, 30), (150, 150))], "Dock layout result do not match: {:?}", r);
+
+    // Nested layouts
+    let nested: Layout<u64> = BoxLayout::vertical()
+        .add(LayoutItem::layout(BoxLayout::horizontal().add(LayoutItem::control(1)).add(LayoutItem::control(2))))
+        .add(LayoutItem::control(3))
+        .into();
+
+    let r = nested.solve((0, 0), (100, 100));
+    assert!(r == vec![(1, (0, 0), (50, 50)), (2, (50, 0), (50, 50)), (3, (0, 50), (100, 50))], "Nested layout result do not match: {:?}", r);
+}
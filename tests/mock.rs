@@ -0,0 +1,825 @@
+/*!
+    Tests that run on the headless mock backend. Those do not require a system window and can run on any platform.
+*/
+
+extern crate native_windows_gui as nwg;
+
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use nwg::{Ui, Error, EventArgs, EventOutcome};
+use nwg::mock::{MockBackend, MockControlT, MockControl};
+use nwg::custom::AnyHandle;
+use nwg::constants::ControlType;
+use nwg::events as nwge;
+
+const WM_SIZE: u32 = 0x0005;
+const WM_CLOSE: u32 = 0x0010;
+const WM_KEYDOWN: u32 = 0x0100;
+const WM_COMMAND: u32 = 0x0111;
+const WM_NOTIFY: u32 = 0x004E;
+const WM_DRAWITEM: u32 = 0x002B;
+const WM_MOUSEWHEEL: u32 = 0x020A;
+const WM_HSCROLL: u32 = 0x0114;
+const NWG_NUMERIC_CHANGED: u32 = 0x0421;
+const NWG_SLIDER_CHANGED: u32 = 0x0424;
+const TB_THUMBTRACK: usize = 5;
+const BN_CLICKED: usize = 0;
+const TVN_ITEMEXPANDINGW: u32 = -454i32 as u32;
+const TVN_ITEMEXPANDEDW: u32 = -455i32 as u32;
+const TVN_DELETEITEMW: u32 = -458i32 as u32;
+const TVE_EXPAND: u32 = 0x0002;
+const LVN_ITEMCHANGED: u32 = -101i32 as u32;
+const LVN_COLUMNCLICK: u32 = -108i32 as u32;
+const LVN_ITEMACTIVATE: u32 = -114i32 as u32;
+const LVN_ODSTATECHANGED: u32 = -115i32 as u32;
+const LVIF_STATE: u32 = 0x0008;
+const LVIS_SELECTED: u32 = 0x0002;
+const LVIS_FOCUSED: u32 = 0x0001;
+const BCN_DROPDOWN: u32 = -1248i32 as u32;
+const ODT_BUTTON: u32 = 4;
+const ODT_STATIC: u32 = 5;
+const ODT_LISTBOX: u32 = 2;
+const ODS_SELECTED: u32 = 0x0001;
+const ODS_DISABLED: u32 = 0x0004;
+const ODS_HOTLIGHT: u32 = 0x0040;
+
+// Layout of the system `NMHDR`, `TVITEMW` and `NMTREEVIEWW` structures
+#[repr(C)]
+struct Nmhdr { hwnd_from: usize, id_from: usize, code: u32 }
+
+#[repr(C)]
+struct TvItem { mask: u32, item: usize, state: u32, state_mask: u32, text: usize, text_max: i32, image: i32, selected_image: i32, children: i32, param: isize }
+
+#[repr(C)]
+struct NmTreeView { hdr: Nmhdr, action: u32, item_old: TvItem, item_new: TvItem, drag: (i32, i32) }
+
+// Layout of the system `NMLISTVIEW` and `NMITEMACTIVATE` structures
+#[repr(C)]
+struct NmListView { hdr: Nmhdr, item: i32, sub_item: i32, new_state: u32, old_state: u32, changed: u32, action: (i32, i32), param: isize, key_flags: u32 }
+
+// Layout of the system `NMLVODSTATECHANGE` structure
+#[repr(C)]
+struct NmOdStateChange { hdr: Nmhdr, from: i32, to: i32, new_state: u32, old_state: u32 }
+
+// Layout of the system `DRAWITEMSTRUCT` structure
+#[repr(C)]
+struct DrawItem { ctl_type: u32, ctl_id: u32, item_id: u32, item_action: u32, item_state: u32, item: usize, dc: usize, rect: (i32, i32, i32, i32), data: usize }
+
+fn list_notify(list: usize, code: u32, item: i32, sub_item: i32, old_state: u32, new_state: u32) -> NmListView {
+    NmListView{ hdr: Nmhdr{ hwnd_from: list, id_from: 0, code: code }, item: item, sub_item: sub_item, new_state: new_state, 
+      old_state: old_state, changed: LVIF_STATE, action: (0, 0), param: 0, key_flags: 0 }
+}
+
+fn tv_item(item: usize) -> TvItem {
+    TvItem{ mask: 0, item: item, state: 0, state_mask: 0, text: 0, text_max: 0, image: 0, selected_image: 0, children: 0, param: 0 }
+}
+
+fn mock_ui() -> Ui<&'static str> {
+    Ui::with_backend(Box::new(MockBackend::new()))
+}
+
+fn mock_control(control_type: ControlType, parent: Option<&'static str>) -> MockControlT<&'static str, &'static str> {
+    MockControlT{ control_type: control_type, text: "Mock", position: (0, 0), size: (100, 50), visible: true, disabled: false, parent: parent }
+}
+
+fn raw_handle(ui: &Ui<&'static str>, id: &'static str) -> usize {
+    match ui.handle_of(&id) {
+        Ok(AnyHandle::HWND(h)) => h as usize,
+        _ => panic!("Id is not a window")
+    }
+}
+
+#[test]
+fn test_mock_user_values() {
+    let ui = mock_ui();
+
+    ui.pack_value(&"MyValue", 5u32);
+    ui.pack_value(&"MyString", "Hello".to_string());
+    ui.commit().expect("Commit failed");
+
+    assert!(ui.has_id(&"MyValue"));
+    assert_eq!(**ui.get::<u32>(&"MyValue").unwrap(), 5);
+    assert!(ui.get::<String>(&"MyValue").is_err());
+
+    {
+        let mut v = ui.get_mut::<String>(&"MyString").unwrap();
+        v.push_str(" World");
+    }
+    assert_eq!(&**ui.get::<String>(&"MyString").unwrap(), "Hello World");
+
+    ui.pack_value(&"MyValue", 10u32);
+    match ui.commit() {
+        Err(Error::KeyExists) => {},
+        _ => panic!("Packing a value with an existing id should fail")
+    }
+}
+
+#[test]
+fn test_mock_controls() {
+    let ui = mock_ui();
+
+    ui.pack_control(&"Window", mock_control(ControlType::Window, None));
+    ui.pack_control(&"Button", mock_control(ControlType::Button, Some("Window")));
+    ui.commit().expect("Commit failed");
+
+    assert_eq!(ui.type_of_control(&"Button").unwrap(), ControlType::Button);
+
+    {
+        let btn = ui.get::<MockControl>(&"Button").unwrap();
+        assert_eq!(&btn.get_text(), "Mock");
+        btn.set_text("Click me");
+        btn.set_size(10, 20);
+        assert_eq!(&btn.get_text(), "Click me");
+        assert_eq!(btn.get_size(), (10, 20));
+    }
+
+    ui.pack_value(&"Value", 0u8);
+    ui.pack_control(&"BadChild", mock_control(ControlType::Button, Some("Value")));
+    match ui.commit() {
+        Err(Error::ControlOrResourceRequired) => {},
+        _ => panic!("Packing a control with a user value as parent should fail")
+    }
+    assert!(!ui.has_id(&"BadChild"));
+
+    ui.pack_control(&"BadChild", mock_control(ControlType::Button, Some("Nope")));
+    match ui.commit() {
+        Err(Error::KeyNotFound) => {},
+        _ => panic!("Packing a control with an unknown parent should fail")
+    }
+}
+
+#[test]
+fn test_mock_bind_trigger() {
+    let ui = mock_ui();
+    let calls: Rc<RefCell<Vec<&'static str>>> = Rc::new(RefCell::new(Vec::new()));
+
+    ui.pack_control(&"Window", mock_control(ControlType::Window, None));
+    ui.commit().expect("Commit failed");
+
+    let c = calls.clone();
+    ui.bind(&"Window", &"Cb1", nwge::Moved, move |_, _, _, args| {
+        if let &EventArgs::Position(10, 20) = args { c.borrow_mut().push("Cb1"); }
+    });
+    let c = calls.clone();
+    ui.bind(&"Window", &"Cb2", nwge::Moved, move |_, _, _, _| { c.borrow_mut().push("Cb2"); });
+    ui.commit().expect("Commit failed");
+
+    ui.trigger(&"Window", nwge::Moved, EventArgs::Position(10, 20));
+    ui.commit().expect("Commit failed");
+    assert_eq!(&*calls.borrow(), &["Cb1", "Cb2"]);
+
+    ui.unbind(&"Window", &"Cb1", nwge::Moved);
+    ui.trigger(&"Window", nwge::Moved, EventArgs::Position(0, 0));
+    ui.commit().expect("Commit failed");
+    assert_eq!(&*calls.borrow(), &["Cb1", "Cb2", "Cb2"]);
+
+    ui.unbind(&"Window", &"Cb1", nwge::Moved);
+    match ui.commit() {
+        Err(Error::KeyNotFound) => {},
+        _ => panic!("Unbinding an unknown callback should fail")
+    }
+}
+
+#[test]
+fn test_mock_unpack() {
+    let ui = mock_ui();
+    let destroyed: Rc<RefCell<Vec<&'static str>>> = Rc::new(RefCell::new(Vec::new()));
+
+    ui.pack_control(&"Window", mock_control(ControlType::Window, None));
+    ui.pack_control(&"Frame", mock_control(ControlType::Frame, Some("Window")));
+    ui.pack_control(&"Button", mock_control(ControlType::Button, Some("Frame")));
+    ui.pack_control(&"Other", mock_control(ControlType::Window, None));
+    ui.commit().expect("Commit failed");
+
+    for id in ["Window", "Frame", "Button"].iter() {
+        let d = destroyed.clone();
+        ui.bind(id, &"Destroy", nwge::Destroyed, move |_, id, _, _| { d.borrow_mut().push(*id); });
+    }
+
+    ui.unpack(&"Window");
+    ui.commit().expect("Commit failed");
+
+    assert!(!ui.has_id(&"Window"));
+    assert!(!ui.has_id(&"Frame"));
+    assert!(!ui.has_id(&"Button"));
+    assert!(ui.has_id(&"Other"));
+
+    let mut destroyed = destroyed.borrow().clone();
+    destroyed.sort();
+    assert_eq!(&destroyed, &["Button", "Frame", "Window"]);
+
+    ui.unpack(&"Window");
+    match ui.commit() {
+        Err(Error::KeyNotFound) => {},
+        _ => panic!("Unpacking an unknown id should fail")
+    }
+}
+
+#[test]
+fn test_mock_simulate_message() {
+    let ui = mock_ui();
+    let calls: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+
+    ui.pack_control(&"Window", mock_control(ControlType::Window, None));
+    ui.pack_control(&"Button", mock_control(ControlType::Button, Some("Window")));
+    ui.pack_value(&"Value", 0u8);
+    ui.commit().expect("Commit failed");
+
+    let c = calls.clone();
+    ui.bind(&"Window", &"Key", nwge::KeyDown, move |_, _, _, args| {
+        if let Some(k) = args.key() { c.borrow_mut().push(format!("Key {}", k)); }
+    });
+    let c = calls.clone();
+    ui.bind(&"Window", &"Resize", nwge::Resized, move |_, _, _, args| {
+        if let &EventArgs::Size(w, h) = args { c.borrow_mut().push(format!("Size {} {}", w, h)); }
+    });
+    let c = calls.clone();
+    ui.bind(&"Button", &"Click", nwge::button::Click, move |_, id, _, _| {
+        c.borrow_mut().push(format!("Click {}", id));
+    });
+    let c = calls.clone();
+    ui.bind(&"Button", &"Any", nwge::Any, move |_, _, _, args| {
+        if let &EventArgs::Raw(msg, _, _) = args { c.borrow_mut().push(format!("Any {}", msg)); }
+    });
+    ui.commit().expect("Commit failed");
+
+    ui.simulate_message(&"Window", WM_KEYDOWN, 65, 0).unwrap();
+    {
+        ui.get::<MockControl>(&"Window").unwrap().set_size(300, 200);
+    }
+    ui.simulate_message(&"Window", WM_SIZE, 0, 0).unwrap();
+
+    let btn = raw_handle(&ui, "Button");
+    ui.simulate_message(&"Window", WM_COMMAND, (BN_CLICKED << 16) as _, btn as _).unwrap();
+    ui.simulate_message(&"Button", WM_KEYDOWN, 13, 0).unwrap();
+
+    assert_eq!(&*calls.borrow(), &["Key 65", "Size 300 200", "Click Button", "Any 256"]);
+
+    match ui.simulate_message(&"Value", WM_KEYDOWN, 65, 0) {
+        Err(Error::ControlRequired) => {},
+        _ => panic!("Simulating a message on a user value should fail")
+    }
+    match ui.simulate_message(&"Nope", WM_KEYDOWN, 65, 0) {
+        Err(Error::KeyNotFound) => {},
+        _ => panic!("Simulating a message on an unknown id should fail")
+    }
+}
+
+#[test]
+fn test_mock_layout() {
+    use nwg::{BoxLayout, LayoutItem};
+
+    let ui = mock_ui();
+    let sizes: Rc<RefCell<Vec<(u32, u32)>>> = Rc::new(RefCell::new(Vec::new()));
+
+    let mut window = mock_control(ControlType::Window, None);
+    window.size = (300, 200);
+    let mut toolbar = mock_control(ControlType::Toolbar, Some("Window"));
+    toolbar.size = (300, 30);
+    let mut status = mock_control(ControlType::StatusBar, Some("Window"));
+    status.size = (300, 20);
+
+    ui.pack_control(&"Window", window);
+    ui.pack_control(&"Toolbar", toolbar);
+    ui.pack_control(&"Status", status);
+    ui.pack_control(&"Button1", mock_control(ControlType::Button, Some("Window")));
+    ui.pack_control(&"Button2", mock_control(ControlType::Button, Some("Window")));
+
+    // A user callback using the parent id as callback id is kept by the layout
+    let s = sizes.clone();
+    ui.bind(&"Window", &"Window", nwge::Resized, move |_, _, _, args| {
+        s.borrow_mut().push(args.size().unwrap());
+    });
+
+    ui.set_layout(&"Window", BoxLayout::vertical().add(LayoutItem::control("Button1")).add(LayoutItem::control("Button2")));
+    ui.commit().expect("Commit failed");
+
+    // The layout is applied without triggering the user callbacks, the docked bars are left out of the layout area
+    assert!(sizes.borrow().is_empty(), "The layout should not trigger the user callbacks");
+    {
+        let btn2 = ui.get::<MockControl>(&"Button2").unwrap();
+        assert_eq!(btn2.get_position(), (0, 105));
+        assert_eq!(btn2.get_size(), (300, 75));
+    }
+
+    // A new layout replaces the previous one and is recomputed when the parent is resized
+    ui.set_layout(&"Window", BoxLayout::horizontal().add(LayoutItem::control("Button1")).add(LayoutItem::control("Button2")));
+    ui.commit().expect("Commit failed");
+    {
+        ui.get::<MockControl>(&"Window").unwrap().set_size(400, 250);
+    }
+    ui.simulate_message(&"Window", WM_SIZE, 0, 0).unwrap();
+
+    assert_eq!(&*sizes.borrow(), &[(400, 250)]);
+    {
+        let btn2 = ui.get::<MockControl>(&"Button2").unwrap();
+        assert_eq!(btn2.get_position(), (200, 30));
+        assert_eq!(btn2.get_size(), (200, 200));
+    }
+
+    // Removing the layout keeps the user callbacks
+    ui.remove_layout(&"Window");
+    ui.commit().expect("Commit failed");
+    {
+        ui.get::<MockControl>(&"Window").unwrap().set_size(500, 250);
+    }
+    ui.simulate_message(&"Window", WM_SIZE, 0, 0).unwrap();
+
+    assert_eq!(&*sizes.borrow(), &[(400, 250), (500, 250)]);
+    {
+        let btn2 = ui.get::<MockControl>(&"Button2").unwrap();
+        assert_eq!(btn2.get_position(), (200, 30));
+    }
+
+    ui.remove_layout(&"Window");
+    match ui.commit() {
+        Err(Error::KeyNotFound) => {},
+        _ => panic!("Removing a missing layout should fail")
+    }
+}
+
+#[test]
+fn test_mock_event_payloads() {
+    let ui = mock_ui();
+    let calls: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+
+    ui.pack_control(&"Window", mock_control(ControlType::Window, None));
+    ui.pack_control(&"Tree", mock_control(ControlType::TreeView, Some("Window")));
+    ui.pack_control(&"Number", mock_control(ControlType::NumericInput, Some("Window")));
+    ui.pack_control(&"Slider", mock_control(ControlType::Slider, Some("Window")));
+    ui.pack_control(&"Toolbar", mock_control(ControlType::Toolbar, Some("Window")));
+    ui.commit().expect("Commit failed");
+
+    let c = calls.clone();
+    ui.bind(&"Window", &"Wheel", nwge::MouseWheel, move |_, _, _, args| {
+        match args {
+            &EventArgs::MouseWheel{delta, horizontal, pos} => c.borrow_mut().push(format!("Wheel {} {} {:?}", delta, horizontal, pos)),
+            _ => c.borrow_mut().push("Bad wheel args".to_string())
+        }
+    });
+    let c = calls.clone();
+    ui.bind(&"Tree", &"Expanded", nwge::treeview::ItemExpanded, move |_, _, _, args| {
+        match args {
+            &EventArgs::TreeItemExpand{item: AnyHandle::HTREE_ITEM(item, _), expanded} => c.borrow_mut().push(format!("Expanded {} {}", item as usize, expanded)),
+            _ => c.borrow_mut().push("Bad tree args".to_string())
+        }
+    });
+    let c = calls.clone();
+    ui.bind(&"Tree", &"Deleted", nwge::treeview::DeleteItem, move |_, _, _, args| {
+        match args {
+            &EventArgs::TreeItem(AnyHandle::HTREE_ITEM(item, _)) => c.borrow_mut().push(format!("Deleted {}", item as usize)),
+            &EventArgs::None => c.borrow_mut().push("Deleted none".to_string()),
+            _ => c.borrow_mut().push("Bad tree args".to_string())
+        }
+    });
+    let c = calls.clone();
+    ui.bind(&"Number", &"Changed", nwge::numeric_input::ValueChanged, move |_, _, _, args| {
+        c.borrow_mut().push(format!("Number {:?}", args.number()));
+    });
+    let c = calls.clone();
+    ui.bind(&"Slider", &"Moved", nwge::slider::ValueChanged, move |_, _, _, args| {
+        c.borrow_mut().push(format!("Slider {:?}", args.slider_position()));
+    });
+    let c = calls.clone();
+    ui.bind(&"Toolbar", &"Click", nwge::toolbar::Click, move |_, _, _, args| {
+        c.borrow_mut().push(format!("Toolbar {:?}", args.toolbar_button()));
+    });
+    ui.commit().expect("Commit failed");
+
+    // Wheel delta is a signed value in the high word of wparam
+    let delta = (-120i16 as u16 as usize) << 16;
+    ui.simulate_message(&"Window", WM_MOUSEWHEEL, delta as _, ((20 << 16) | 10) as _).unwrap();
+
+    let tree = raw_handle(&ui, "Tree");
+    let data = NmTreeView{ hdr: Nmhdr{ hwnd_from: tree, id_from: 0, code: TVN_ITEMEXPANDEDW }, action: TVE_EXPAND, item_old: tv_item(0), item_new: tv_item(42), drag: (0, 0) };
+    ui.simulate_message(&"Window", WM_NOTIFY, 0, &data as *const NmTreeView as _).unwrap();
+
+    // A deleted item is always reported, even if the system does not give its handle
+    for item in [9, 0].iter() {
+        let data = NmTreeView{ hdr: Nmhdr{ hwnd_from: tree, id_from: 0, code: TVN_DELETEITEMW }, action: 0, item_old: tv_item(*item), item_new: tv_item(0), drag: (0, 0) };
+        ui.simulate_message(&"Window", WM_NOTIFY, 0, &data as *const NmTreeView as _).unwrap();
+    }
+
+    // The numeric input sends a pointer to the new value
+    let value = 2.5f64;
+    ui.simulate_message(&"Number", NWG_NUMERIC_CHANGED, 0, &value as *const f64 as _).unwrap();
+
+    // The slider sends its new position to itself. The raw trackbar notifications sent to the parent are not events
+    let slider = raw_handle(&ui, "Slider");
+    ui.simulate_message(&"Window", WM_HSCROLL, TB_THUMBTRACK as _, slider as _).unwrap();
+    ui.simulate_message(&"Slider", NWG_SLIDER_CHANGED, -3i32 as usize as _, 0).unwrap();
+
+    // Toolbars send the id of the clicked button to their parent
+    let toolbar = raw_handle(&ui, "Toolbar");
+    ui.simulate_message(&"Window", WM_COMMAND, (BN_CLICKED << 16 | 7) as _, toolbar as _).unwrap();
+
+    assert_eq!(&*calls.borrow(), &["Wheel -120 false (10, 20)", "Expanded 42 true", "Deleted 9", "Deleted none", "Number Some(2.5)", "Slider Some(-3)", "Toolbar Some(7)"]);
+
+    // Accessors
+    let args = EventArgs::MouseWheel{delta: 240, horizontal: true, pos: (1, 2)};
+    assert_eq!(args.wheel_delta(), Some(240));
+    assert_eq!(args.position(), Some((1, 2)));
+    assert_eq!(args.key(), None);
+    #[allow(deprecated)]
+    let key = EventArgs::Key(13);
+    assert_eq!(key.key(), Some(13));
+    assert_eq!(EventArgs::SelectedIndex(Some(3)).selected_index(), Some(3));
+    assert_eq!(EventArgs::SelectedIndex(None).selected_index(), None);
+    assert_eq!(EventArgs::Char('a').char(), Some('a'));
+    assert_eq!(EventArgs::Raw(1, 2, 3).raw(), Some((1, 2, 3)));
+}
+
+#[test]
+fn test_mock_button_events() {
+    let ui = mock_ui();
+    let calls: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+
+    ui.pack_control(&"Window", mock_control(ControlType::Window, None));
+    ui.pack_control(&"Button", mock_control(ControlType::Button, Some("Window")));
+    ui.commit().expect("Commit failed");
+
+    let c = calls.clone();
+    ui.bind(&"Button", &"Draw", nwge::button::Draw, move |_, _, _, args| {
+        match args {
+            &EventArgs::ButtonDraw{state, dc, rect} => c.borrow_mut().push(format!("Draw {:?} {} {:?}", state, dc as usize, rect)),
+            _ => c.borrow_mut().push("Bad draw args".to_string())
+        }
+    });
+    let c = calls.clone();
+    ui.bind(&"Button", &"DropDown", nwge::button::DropDown, move |_, _, _, _| { c.borrow_mut().push("DropDown".to_string()); });
+    ui.commit().expect("Commit failed");
+
+    // The parent receives the draw requests and the notifications of the button
+    let button = raw_handle(&ui, "Button");
+    let draw = |ctl_type: u32, state: u32| DrawItem{ ctl_type: ctl_type, ctl_id: 0, item_id: 0, item_action: 1, item_state: state, item: button,
+      dc: 7, rect: (0, 0, 100, 30), data: 0 };
+    ui.simulate_message(&"Window", WM_DRAWITEM, 0, &draw(ODT_BUTTON, ODS_SELECTED | ODS_HOTLIGHT) as *const DrawItem as _).unwrap();
+    ui.simulate_message(&"Window", WM_DRAWITEM, 0, &draw(ODT_BUTTON, ODS_DISABLED) as *const DrawItem as _).unwrap();
+    ui.simulate_message(&"Window", WM_DRAWITEM, 0, &draw(ODT_LISTBOX, 0) as *const DrawItem as _).unwrap();
+
+    let notify = Nmhdr{ hwnd_from: button, id_from: 0, code: BCN_DROPDOWN };
+    ui.simulate_message(&"Window", WM_NOTIFY, 0, &notify as *const Nmhdr as _).unwrap();
+
+    assert_eq!(&*calls.borrow(), &[
+        "Draw ButtonState { hot: true, pressed: true, focused: false, disabled: false } 7 (0, 0, 100, 30)",
+        "Draw ButtonState { hot: false, pressed: false, focused: false, disabled: true } 7 (0, 0, 100, 30)",
+        "DropDown"
+    ]);
+
+    let args = EventArgs::ButtonDraw{state: Default::default(), dc: ::std::ptr::null_mut(), rect: (0, 0, 0, 0)};
+    assert_eq!(args.button_state(), Some(Default::default()));
+    assert_eq!(EventArgs::None.button_state(), None);
+}
+
+#[test]
+fn test_mock_label_draw() {
+    let ui = mock_ui();
+    let calls: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+
+    ui.pack_control(&"Window", mock_control(ControlType::Window, None));
+    ui.pack_control(&"Label", mock_control(ControlType::Label, Some("Window")));
+    ui.commit().expect("Commit failed");
+
+    let c = calls.clone();
+    ui.bind(&"Label", &"Draw", nwge::label::Draw, move |_, _, _, args| {
+        c.borrow_mut().push(format!("Draw {:?}", args.button_state()));
+    });
+    ui.commit().expect("Commit failed");
+
+    // Owner drawn labels send WM_DRAWITEM with ODT_STATIC. The button draw requests are not sent to the label
+    let label = raw_handle(&ui, "Label");
+    let draw = |ctl_type: u32, state: u32| DrawItem{ ctl_type: ctl_type, ctl_id: 0, item_id: 0, item_action: 1, item_state: state, item: label,
+      dc: 7, rect: (0, 0, 100, 30), data: 0 };
+    ui.simulate_message(&"Window", WM_DRAWITEM, 0, &draw(ODT_STATIC, ODS_DISABLED) as *const DrawItem as _).unwrap();
+    ui.simulate_message(&"Window", WM_DRAWITEM, 0, &draw(ODT_BUTTON, 0) as *const DrawItem as _).unwrap();
+
+    assert_eq!(&*calls.borrow(), &["Draw Some(ButtonState { hot: false, pressed: false, focused: false, disabled: true })"]);
+}
+
+#[test]
+fn test_mock_list_view_events() {
+    let ui = mock_ui();
+    let calls: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+
+    ui.pack_control(&"Window", mock_control(ControlType::Window, None));
+    ui.pack_control(&"List", mock_control(ControlType::ListView, Some("Window")));
+    ui.commit().expect("Commit failed");
+
+    let c = calls.clone();
+    ui.bind(&"List", &"Activated", nwge::list_view::ItemActivated, move |_, _, _, args| {
+        match args {
+            &EventArgs::ListItem{item, sub_item} => c.borrow_mut().push(format!("Activated {} {}", item, sub_item)),
+            _ => c.borrow_mut().push("Bad activate args".to_string())
+        }
+    });
+    let c = calls.clone();
+    ui.bind(&"List", &"Column", nwge::list_view::ColumnClick, move |_, _, _, args| {
+        c.borrow_mut().push(format!("Column {:?}", args.column()));
+    });
+    let c = calls.clone();
+    ui.bind(&"List", &"Selection", nwge::list_view::SelectionChanged, move |_, _, _, args| {
+        match args {
+            &EventArgs::ListSelection{item, selected} => c.borrow_mut().push(format!("Selection {:?} {}", item, selected)),
+            _ => c.borrow_mut().push("Bad selection args".to_string())
+        }
+    });
+    ui.commit().expect("Commit failed");
+
+    let list = raw_handle(&ui, "List");
+    let send = |data: NmListView| { ui.simulate_message(&"Window", WM_NOTIFY, 0, &data as *const NmListView as _).unwrap(); };
+
+    send(list_notify(list, LVN_ITEMACTIVATE, 3, 1, 0, 0));
+    send(list_notify(list, LVN_COLUMNCLICK, -1, 2, 0, 0));
+    send(list_notify(list, LVN_ITEMCHANGED, 5, 0, 0, LVIS_SELECTED|LVIS_FOCUSED));
+    send(list_notify(list, LVN_ITEMCHANGED, -1, 0, LVIS_SELECTED, 0));
+
+    // Focus changes are not selection changes
+    send(list_notify(list, LVN_ITEMCHANGED, 5, 0, 0, LVIS_FOCUSED));
+
+    // Virtual list views send a single notification for a range of items
+    let range = NmOdStateChange{ hdr: Nmhdr{ hwnd_from: list, id_from: 0, code: LVN_ODSTATECHANGED }, from: 2, to: 8, new_state: LVIS_SELECTED, old_state: 0 };
+    ui.simulate_message(&"Window", WM_NOTIFY, 0, &range as *const NmOdStateChange as _).unwrap();
+
+    assert_eq!(&*calls.borrow(), &["Activated 3 1", "Column Some(2)", "Selection Some(5) true", "Selection None false", "Selection None true"]);
+
+    assert_eq!(EventArgs::ListItem{item: 4, sub_item: 0}.list_item(), Some(4));
+    assert_eq!(EventArgs::ListSelection{item: None, selected: true}.list_item(), None);
+    assert_eq!(EventArgs::Column(1).column(), Some(1));
+}
+
+#[test]
+fn test_mock_event_outcome() {
+    let ui = mock_ui();
+    let calls: Rc<RefCell<Vec<&'static str>>> = Rc::new(RefCell::new(Vec::new()));
+
+    ui.pack_control(&"Window", mock_control(ControlType::Window, None));
+    ui.pack_control(&"Tree", mock_control(ControlType::TreeView, Some("Window")));
+    ui.commit().expect("Commit failed");
+
+    assert!(nwge::Closed.vetoable());
+    assert!(nwge::treeview::ItemExpanding.vetoable());
+    assert!(!nwge::treeview::ItemExpanded.vetoable());
+
+    // Callbacks returning `()` continue
+    let c = calls.clone();
+    ui.bind(&"Window", &"Close1", nwge::Closed, move |_, _, _, _| { c.borrow_mut().push("Close1"); });
+    ui.commit().expect("Commit failed");
+    assert_eq!(ui.simulate_message(&"Window", WM_CLOSE, 0, 0), Ok(None));
+
+    // Stop skips the next callbacks and vetoes the event
+    let c = calls.clone();
+    ui.bind(&"Window", &"Close2", nwge::Closed, move |_, _, _, _| { c.borrow_mut().push("Close2"); EventOutcome::Stop });
+    let c = calls.clone();
+    ui.bind(&"Window", &"Close3", nwge::Closed, move |_, _, _, _| { c.borrow_mut().push("Close3"); });
+    ui.commit().expect("Commit failed");
+    assert_eq!(ui.simulate_message(&"Window", WM_CLOSE, 0, 0), Ok(Some(0)));
+    assert_eq!(&*calls.borrow(), &["Close1", "Close1", "Close2"]);
+
+    // Stop on a non vetoable event only stops the callbacks
+    ui.bind(&"Window", &"Resize", nwge::Resized, |_, _, _, _| EventOutcome::Stop);
+    ui.commit().expect("Commit failed");
+    assert_eq!(ui.simulate_message(&"Window", WM_SIZE, 0, 0), Ok(None));
+
+    // Vetoing a tree item expansion returns TRUE to the system
+    ui.bind(&"Tree", &"Expanding", nwge::treeview::ItemExpanding, |_, _, _, args| {
+        match args.tree_item() {
+            Some(_) => EventOutcome::Stop,
+            None => EventOutcome::Continue
+        }
+    });
+    ui.commit().expect("Commit failed");
+    let tree = raw_handle(&ui, "Tree");
+    let data = NmTreeView{ hdr: Nmhdr{ hwnd_from: tree, id_from: 0, code: TVN_ITEMEXPANDINGW }, action: TVE_EXPAND, item_old: tv_item(0), item_new: tv_item(7), drag: (0, 0) };
+    assert_eq!(ui.simulate_message(&"Window", WM_NOTIFY, 0, &data as *const NmTreeView as _), Ok(Some(1)));
+
+    // Return sends a custom value to the system
+    ui.bind(&"Window", &"Any", nwge::Any, |_, _, _, args| {
+        match args.raw() {
+            Some((WM_KEYDOWN, _, _)) => EventOutcome::Return(42),
+            _ => EventOutcome::Continue
+        }
+    });
+    ui.commit().expect("Commit failed");
+    assert_eq!(ui.simulate_message(&"Window", WM_KEYDOWN, 65, 0), Ok(Some(42)));
+}
+
+#[test]
+fn test_mock_proxy() {
+    use std::thread;
+    use nwg::UiProxy;
+
+    let ui = mock_ui();
+    let calls: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+
+    ui.pack_control(&"Window", mock_control(ControlType::Window, None));
+    ui.pack_value(&"Counter", 0u32);
+    ui.commit().expect("Commit failed");
+
+    let c = calls.clone();
+    ui.bind(&"Window", &"Moved", nwge::Moved, move |_, _, _, args| {
+        if let &EventArgs::Position(x, y) = args { c.borrow_mut().push(format!("Moved {} {}", x, y)); }
+    });
+    ui.commit().expect("Commit failed");
+
+    let proxy: UiProxy<&'static str> = ui.proxy();
+    let workers: Vec<thread::JoinHandle<()>> = (0..4).map(|_| {
+        let proxy = proxy.clone();
+        thread::spawn(move || {
+            for _ in 0..10 {
+                proxy.post(|ui| { **ui.get_mut::<u32>(&"Counter").unwrap() += 1; }).unwrap();
+            }
+        })
+    }).collect();
+
+    for w in workers { w.join().unwrap(); }
+
+    let p = proxy.clone();
+    thread::spawn(move || { p.trigger(&"Window", nwge::Moved, EventArgs::Position(5, 6)).unwrap(); }).join().unwrap();
+
+    // Arguments tied to the ui thread cannot be sent
+    match proxy.trigger(&"Window", nwge::Moved, EventArgs::Raw(0, 0, 0)) {
+        Err(Error::UserError(_)) => {},
+        r => panic!("Expected a UserError, got {:?}", r)
+    }
+
+    // Tasks are only executed in the ui thread
+    assert_eq!(**ui.get::<u32>(&"Counter").unwrap(), 0);
+    assert!(calls.borrow().is_empty());
+
+    ui.commit().expect("Commit failed");
+    assert_eq!(**ui.get::<u32>(&"Counter").unwrap(), 40);
+    assert_eq!(&*calls.borrow(), &["Moved 5 6"]);
+
+    drop(ui);
+    match proxy.post(|_| {}) {
+        Err(Error::BadUi(_)) => {},
+        _ => panic!("Posting a task to a freed ui should fail")
+    }
+}
+
+#[test]
+fn test_mock_spawn_local() {
+    use std::thread;
+    use std::cell::Cell;
+    use std::sync::{Arc, Mutex};
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll, Waker};
+
+    type Signal = Arc<Mutex<(bool, Option<Waker>)>>;
+
+    /// A future that completes once the signal is set. Counts the number of times it was polled.
+    struct WaitSignal {
+        signal: Signal,
+        polls: Rc<Cell<u32>>
+    }
+
+    impl Future for WaitSignal {
+        type Output = ();
+        fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+            self.polls.set(self.polls.get() + 1);
+            let mut signal = self.signal.lock().unwrap();
+            if signal.0 {
+                Poll::Ready(())
+            } else {
+                signal.1 = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+
+    let ui = mock_ui();
+    let signal: Signal = Arc::new(Mutex::new((false, None)));
+    let polls = Rc::new(Cell::new(0));
+
+    ui.spawn_local(WaitSignal{ signal: signal.clone(), polls: polls.clone() });
+    assert_eq!(polls.get(), 0);
+
+    ui.commit().expect("Commit failed");
+    assert_eq!(polls.get(), 1);
+
+    // Wake the task from another thread
+    let s = signal.clone();
+    thread::spawn(move || {
+        let mut signal = s.lock().unwrap();
+        signal.0 = true;
+        signal.1.take().unwrap().wake();
+    }).join().unwrap();
+
+    assert_eq!(polls.get(), 1);
+    ui.commit().expect("Commit failed");
+    assert_eq!(polls.get(), 2);
+
+    // Waking a completed task does nothing
+    let waker = {
+        let s2: Signal = Arc::new(Mutex::new((false, None)));
+        ui.spawn_local(WaitSignal{ signal: s2.clone(), polls: polls.clone() });
+        ui.commit().expect("Commit failed");
+        s2.lock().unwrap().0 = true;
+        let waker = s2.lock().unwrap().1.take().unwrap();
+        waker.wake_by_ref();
+        ui.commit().expect("Commit failed");
+        waker
+    };
+    assert_eq!(polls.get(), 4);
+    waker.wake();
+    ui.commit().expect("Commit failed");
+    assert_eq!(polls.get(), 4);
+
+    // Pending tasks are dropped with the ui
+    let pending_polls = Rc::new(Cell::new(0));
+    ui.spawn_local(WaitSignal{ signal: Arc::new(Mutex::new((false, None))), polls: pending_polls.clone() });
+    ui.commit().expect("Commit failed");
+    assert_eq!(Rc::strong_count(&pending_polls), 2);
+    drop(ui);
+    assert_eq!(Rc::strong_count(&pending_polls), 1);
+}
+
+#[test]
+fn test_mock_value_binding() {
+    use nwg::{Observable, Binding, BindingMode, Property};
+
+    const EN_UPDATE: usize = 0x0400;
+
+    // Like `Property::text`, the text of a label is read only
+    fn mock_text() -> Property<&'static str, String> {
+        Property::new(
+            |ui, id| ui.get::<MockControl>(id).map(|c| c.get_text()),
+            |ui, id, text: &String| ui.get::<MockControl>(id).map(|c| c.set_text(text)),
+            Some(nwge::textinput::ValueChanged)
+        ).read_only(|ui, id| ui.type_of_control(id) == Ok(ControlType::Label))
+    }
+
+    let ui = mock_ui();
+    let errors: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+
+    ui.pack_control(&"Window", mock_control(ControlType::Window, None));
+    ui.pack_control(&"Input", mock_control(ControlType::TextInput, Some("Window")));
+    ui.pack_control(&"Label", mock_control(ControlType::Label, Some("Window")));
+    ui.pack_value(&"Count", Observable::new(5u32));
+    ui.commit().expect("Commit failed");
+
+    let e = errors.clone();
+    let binding = Binding::parse(mock_text())
+      .validate(|v: &u32| if *v <= 100 { Ok(()) } else { Err("Too big".to_string()) })
+      .on_error(move |_, _, err| e.borrow_mut().push(err.unwrap_or("Ok").to_string()));
+
+    ui.bind_value(&"Count", &"Input", binding).expect("Binding failed");
+    let label_binding = Binding::with_converter(mock_text(), |v: &u32| format!("{} items", v), |_| Err("".to_string()));
+    assert_eq!(label_binding.get_mode(), None);
+    assert_eq!(label_binding.mode_for(&ui, &"Label"), BindingMode::OneWay);
+    assert_eq!(label_binding.mode_for(&ui, &"Input"), BindingMode::TwoWay);
+    ui.bind_value(&"Count", &"Label", label_binding).expect("Binding failed");
+    ui.commit().expect("Commit failed");
+
+    let count = (**ui.get::<Observable<u32>>(&"Count").unwrap()).clone();
+    let text = |id| ui.get::<MockControl>(&id).unwrap().get_text();
+    assert_eq!(count.listeners_count(), 2);
+    assert_eq!((text("Input"), text("Label")), ("5".to_string(), "5 items".to_string()));
+
+    // Value to controls
+    count.set(42);
+    assert_eq!((text("Input"), text("Label")), ("42".to_string(), "42 items".to_string()));
+
+    // Control to value, with conversion and validation
+    let input = raw_handle(&ui, "Input");
+    let edit = |t: &str| {
+        ui.get::<MockControl>(&"Input").unwrap().set_text(t);
+        ui.simulate_message(&"Window", WM_COMMAND, (EN_UPDATE << 16) as _, input as _).unwrap();
+    };
+
+    edit(" 17");
+    assert_eq!(count.get(), 17);
+    assert_eq!(text("Input"), " 17");
+    assert_eq!(text("Label"), "17 items");
+
+    edit("abc");
+    edit("500");
+    assert_eq!(count.get(), 17);
+    assert_eq!(&*errors.borrow(), &["Ok", "\"abc\" is not a valid value", "Too big"]);
+
+    // Errors
+    assert_eq!(ui.bind_value(&"Count", &"Input", Binding::<_, u32, _>::parse(mock_text())), Err(Error::KeyExists));
+    assert_eq!(ui.bind_value(&"Nope", &"Input", Binding::<_, u32, _>::parse(mock_text())), Err(Error::KeyNotFound));
+    assert_eq!(ui.bind_value(&"Count", &"Input", Binding::new(mock_text())), Err(Error::BadType));
+    let read_only: Property<&'static str, String> = Property::new(|_, _| Ok(String::new()), |_, _, _| Ok(()), None);
+    match ui.bind_value(&"Count", &"Window", Binding::<_, u32, _>::parse(read_only).mode(BindingMode::TwoWay)) {
+        Err(Error::BadBinding(_)) => {},
+        r => panic!("Should have returned Error::BadBinding, got {:?}", r)
+    }
+    match ui.bind_value(&"Count", &"Window", Binding::<_, u32, _>::parse(mock_text().read_only(|_, _| true)).mode(BindingMode::TwoWay)) {
+        Err(Error::BadBinding(_)) => {},
+        r => panic!("Should have returned Error::BadBinding, got {:?}", r)
+    }
+
+    // Unbinding
+    ui.unbind_value(&"Count", &"Label").expect("Unbinding failed");
+    assert_eq!(ui.unbind_value(&"Count", &"Label"), Err(Error::KeyNotFound));
+    count.set(3);
+    assert_eq!((text("Input"), text("Label")), ("3".to_string(), "17 items".to_string()));
+
+    ui.unpack(&"Input");
+    ui.commit().expect("Commit failed");
+    assert_eq!(count.listeners_count(), 0);
+}
@@ -0,0 +1,93 @@
+/*!
+    Tests for the document model of the rich text box. Those do not require a system window and can run on any platform.
+*/
+
+extern crate native_windows_gui as nwg;
+
+use nwg::{RichDocument, CharFormat, ParagraphAlign, Error};
+
+#[test]
+fn test_rich_document_rtf_round_trip() {
+    let bold = CharFormat{ bold: true, ..Default::default() };
+    let red = CharFormat{ color: Some([255, 0, 0]), font_family: Some("Consolas".to_string()), font_size: Some(12), ..Default::default() };
+    let mixed = CharFormat{ italic: true, underline: true, strikeout: true, color: Some([0, 128, 255]), ..Default::default() };
+
+    let mut doc = RichDocument::new();
+    doc.push_paragraph(ParagraphAlign::Center);
+    doc.push_text("Title {with} \\braces\\", &bold);
+    doc.push_paragraph(ParagraphAlign::Justify);
+    doc.push_text("Plain, ", &CharFormat::default());
+    doc.push_text("red\tcode", &red);
+    doc.push_text(" and ", &CharFormat::default());
+    doc.push_text("unicode: é€😀", &mixed);
+    doc.push_text("\nSecond line\n", &CharFormat::default());
+
+    assert_eq!(doc.paragraphs.len(), 4);
+    assert_eq!(doc.plain_text(), "Title {with} \\braces\\\nPlain, red\tcode and unicode: é€😀\nSecond line\n");
+
+    let rtf = doc.to_rtf();
+    assert!(rtf.starts_with("{\\rtf1"));
+    assert!(rtf.is_ascii());
+
+    let read = RichDocument::from_rtf(&rtf).expect("Failed to read the RTF document");
+    assert_eq!(read, doc);
+
+    assert_eq!(RichDocument::from_rtf(&RichDocument::new().to_rtf()), Ok(RichDocument::new()));
+
+    // Font sizes are clamped to the range of a RTF parameter
+    let mut doc = RichDocument::new();
+    doc.push_text("Huge", &CharFormat{ font_size: Some(u32::max_value()), ..Default::default() });
+    let read = RichDocument::from_rtf(&doc.to_rtf()).expect("Failed to read the RTF document");
+    assert_eq!(read.paragraphs[0].runs[0].format.font_size, Some(16383));
+}
+
+#[test]
+fn test_rich_document_read_rtf() {
+    // Similar to the output of a rich edit control
+    let rtf = "{\\rtf1\\ansi\\ansicpg1252\\deff0\\nouicompat\\deflang1033{\\fonttbl{\\f0\\fnil\\fcharset0 Calibri;}{\\f1\\fswiss Arial}}\r\n\
+{\\colortbl ;\\red0\\green0\\blue0;\\red255\\green0\\blue0;}\r\n\
+{\\*\\generator Riched20 10.0.19041}\\viewkind4\\uc1 \r\n\
+\\pard\\sa200\\sl276\\slmult1\\qr\\f0\\fs22\\lang9 Hello \\b bold\\b0  \\cf2 red\\cf0  caf\\'e9 \\u8364?\\par\r\n\
+{\\pard\\f1\\i Italic\\line next}\\par\r\n\
+}";
+
+    let doc = RichDocument::from_rtf(rtf).expect("Failed to read the RTF document");
+    assert_eq!(doc.plain_text(), "Hello bold red café €\nItalic\nnext");
+    assert_eq!(doc.paragraphs[0].align, ParagraphAlign::Right);
+    assert_eq!(doc.paragraphs[1].align, ParagraphAlign::Left);
+
+    let runs = &doc.paragraphs[0].runs;
+    let calibri = CharFormat{ font_family: Some("Calibri".to_string()), font_size: Some(11), ..Default::default() };
+    assert_eq!(runs[0].text, "Hello ");
+    assert_eq!(runs[0].format, calibri);
+    assert_eq!(runs[1].text, "bold");
+    assert!(runs[1].format.bold);
+    assert_eq!(runs[3].text, "red");
+    assert_eq!(runs[3].format.color, Some([255, 0, 0]));
+    assert_eq!(runs[4].format, calibri);
+
+    let italic = &doc.paragraphs[1].runs[0].format;
+    assert!(italic.italic);
+    assert_eq!(italic.font_family, Some("Arial".to_string()));
+
+    for bad in ["", "hello", "{\\rtf1 {abc}", "{\\rtf"].iter() {
+        match RichDocument::from_rtf(bad) {
+            Err(Error::BadRtf(_)) => {},
+            r => panic!("{:?} should have returned Error::BadRtf, got {:?}", bad, r)
+        }
+    }
+}
+
+#[test]
+fn test_rich_document_read_rtf_unicode_font() {
+    // The characters that replace a unicode character are skipped in every destination
+    let rtf = "{\\rtf1{\\fonttbl{\\f0\\fnil \\u26032?\\u31169?Font;}}\\f0 Hello\\par}";
+    let doc = RichDocument::from_rtf(rtf).expect("Failed to read the RTF document");
+    assert_eq!(doc.plain_text(), "Hello");
+    assert_eq!(doc.paragraphs[0].runs[0].format.font_family, Some("\u{65B0}\u{79C1}Font".to_string()));
+
+    // The skip count follows `\uc` and ends with the group
+    let rtf = "{\\rtf1\\uc2 a\\u233\\'65\\'65b{\\uc1 \\u233?}c{\\u8364}d\\par}";
+    let doc = RichDocument::from_rtf(rtf).expect("Failed to read the RTF document");
+    assert_eq!(doc.plain_text(), "a\u{E9}b\u{E9}c\u{20AC}d");
+}
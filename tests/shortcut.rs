@@ -0,0 +1,68 @@
+/*!
+    Tests for the shortcut parser used by the accelerator tables. Those do not require a system window and can run on any platform.
+*/
+
+extern crate native_windows_gui as nwg;
+
+use nwg::{Shortcut, Error};
+use nwg::constants::KeyModifiers;
+
+fn shortcut(key: u32, ctrl: bool, shift: bool, alt: bool) -> Shortcut {
+    Shortcut{ key: key, modifiers: KeyModifiers{ ctrl: ctrl, shift: shift, alt: alt } }
+}
+
+#[test]
+fn test_shortcut_parse() {
+    assert_eq!(Shortcut::parse("Ctrl+S"), Ok(shortcut(0x53, true, false, false)));
+    assert_eq!(Shortcut::parse("Ctrl+Shift+N"), Ok(shortcut(0x4E, true, true, false)));
+    assert_eq!(Shortcut::parse("Alt+F4"), Ok(shortcut(0x73, false, false, true)));
+    assert_eq!(Shortcut::parse("F5"), Ok(shortcut(0x74, false, false, false)));
+    assert_eq!(Shortcut::parse("F24"), Ok(shortcut(0x87, false, false, false)));
+    assert_eq!(Shortcut::parse("Ctrl+1"), Ok(shortcut(0x31, true, false, false)));
+    assert_eq!(Shortcut::parse("Ctrl+Plus"), Ok(shortcut(0xBB, true, false, false)));
+
+    // Case, aliases and spaces are ignored
+    assert_eq!(Shortcut::parse("control + shift + del"), Ok(shortcut(0x2E, true, true, false)));
+    assert_eq!(Shortcut::parse("ESCAPE"), Shortcut::parse("Esc"));
+    assert_eq!("shift+pgdn".parse::<Shortcut>(), Ok(shortcut(0x22, false, true, false)));
+
+    // Keys without a name use their virtual key code
+    assert_eq!(Shortcut::parse("Ctrl+0xC0"), Ok(shortcut(0xC0, true, false, false)));
+    assert_eq!(Shortcut::parse("0x2c"), Ok(shortcut(0x2C, false, false, false)));
+}
+
+#[test]
+fn test_shortcut_parse_errors() {
+    let bad = ["", "Ctrl+", "+S", "Ctrl++S", "Ctrl+Ctrl+S", "Super+S", "Ctrl+Foo", "F0", "F25", "Ctrl+S+Shift", "0x", "0x00", "0xFF", "0x1G"];
+    for text in bad.iter() {
+        match Shortcut::parse(text) {
+            Err(Error::BadShortcut(_)) => {},
+            r => panic!("Parsing {:?} should fail, got {:?}", text, r)
+        }
+    }
+}
+
+#[test]
+fn test_shortcut_display() {
+    assert_eq!(format!("{}", Shortcut::parse("shift+ctrl+n").unwrap()), "Ctrl+Shift+N");
+    assert_eq!(format!("{}", Shortcut::parse("alt+f4").unwrap()), "Alt+F4");
+    assert_eq!(format!("{}", Shortcut::parse("Return").unwrap()), "Enter");
+    assert_eq!(Shortcut::parse("Ctrl+Alt+Delete").unwrap().to_string(), "Ctrl+Alt+Delete");
+
+    // Displaying then parsing a shortcut returns the same shortcut
+    for text in ["Ctrl+Shift+Alt+Z", "Space", "Shift+Tab", "Ctrl+Period", "F12"].iter() {
+        let s = Shortcut::parse(text).unwrap();
+        assert_eq!(Shortcut::parse(&s.to_string()), Ok(s));
+    }
+}
+
+#[test]
+fn test_shortcut_round_trip() {
+    // Every key code, named or not, is parsed back from its name
+    for key in 0x01..0xFF {
+        let s = shortcut(key, key % 2 == 0, key % 3 == 0, key % 5 == 0);
+        assert_eq!(Shortcut::parse(&s.to_string()), Ok(s), "Key 0x{:02X} is not parsed back from {:?}", key, s.to_string());
+    }
+
+    assert_eq!(shortcut(0xC0, true, false, false).to_string(), "Ctrl+0xC0");
+}
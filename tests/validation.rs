@@ -0,0 +1,92 @@
+/*!
+    Tests for the validators of the text controls. Those do not require a system window and can run on any platform.
+*/
+
+extern crate native_windows_gui as nwg;
+
+use nwg::{Validator, Pattern, Error};
+
+fn matches(pattern: &str, text: &str) -> bool {
+    Pattern::new(pattern).expect("Failed to parse the pattern").is_match(text)
+}
+
+#[test]
+fn test_pattern_match() {
+    assert!(matches("abc", "abc"));
+    assert!(!matches("abc", "abcd"));
+    assert!(!matches("abc", "ab"));
+    assert!(matches("a.c", "a-c"));
+    assert!(matches("^\\d{3}-\\d{4}$", "555-1234"));
+    assert!(!matches("\\d{3}-\\d{4}", "55-1234"));
+    assert!(matches("[A-Z][a-z]*", "Hello"));
+    assert!(!matches("[A-Z][a-z]*", "hello"));
+    assert!(matches("[^0-9]+", "abc"));
+    assert!(!matches("[^0-9]+", "ab1"));
+    assert!(matches("(cat|dog)s?", "dogs"));
+    assert!(matches("(?:cat|dog)s?", "cat"));
+    assert!(!matches("(cat|dog)s?", "cow"));
+    assert!(matches("[\\w.+-]+@\\w+(\\.\\w+)+", "first.last+tag@example.co.uk"));
+    assert!(!matches("[\\w.+-]+@\\w+(\\.\\w+)+", "first.last@example"));
+    assert!(matches("a{2,}", "aaaa"));
+    assert!(!matches("a{2,3}", "aaaa"));
+    assert!(matches("a\\{,", "a{,"));
+    assert!(matches("(a*)*b", "aaab"));
+    assert!(matches("a+?b", "aaab"));
+    assert!(matches("\\s*\\S+\\s*", "  word "));
+    assert!(matches("", ""));
+}
+
+#[test]
+fn test_pattern_performance() {
+    // Nested quantifiers that do not match must not backtrack exponentially
+    let a22: String = ::std::iter::repeat('a').take(22).collect();
+    assert!(!matches("(a*)*b", &a22));
+    assert!(!matches("(a|aa)+c", &a22));
+
+    // Long inputs must not overflow the stack
+    let long: String = ::std::iter::repeat('x').take(200_000).collect();
+    assert!(matches("[a-z]*", &long));
+    assert!(!matches("[a-z]*y", &long));
+}
+
+#[test]
+fn test_pattern_errors() {
+    for p in ["(abc", "abc)", "*a", "a|+", "[abc", "[z-a]", "\\", "a{,", "a)|(b"].iter() {
+        match Pattern::new(p) {
+            Err(Error::BadPattern(_)) => {},
+            r => panic!("Pattern {:?} should have returned Error::BadPattern, got {:?}", p, r)
+        }
+    }
+}
+
+#[test]
+fn test_validators() {
+    let int = Validator::Integer{min: -5, max: 10};
+    assert_eq!(int.check(" 10 "), Ok(()));
+    assert!(int.check("11").is_err());
+    assert!(int.check("1.5").is_err());
+
+    let float = Validator::Float{min: 0.0, max: 1.0};
+    assert_eq!(float.check("0.25"), Ok(()));
+    assert!(float.check("1.01").is_err());
+    assert!(float.check("abc").is_err());
+
+    let zip = Validator::regex("\\d{5}", "Expected a zip code").unwrap();
+    assert_eq!(zip.check("12345"), Ok(()));
+    assert_eq!(zip.check("1234"), Err("Expected a zip code".to_string()));
+    assert!(Validator::regex("(", "").is_err());
+
+    let even = Validator::custom(|t| if t.len() % 2 == 0 { Ok(()) } else { Err("Odd".to_string()) });
+    assert_eq!(even.check("ab"), Ok(()));
+    assert_eq!(even.check("abc"), Err("Odd".to_string()));
+
+    // The first failing rule gives the message
+    let rules = vec![Validator::Required, Validator::Integer{min: 0, max: 100}, even];
+    assert_eq!(Validator::check_text(&rules, "42"), Ok(()));
+    assert_eq!(Validator::check_text(&rules, "420"), Err("The value must be an integer between 0 and 100".to_string()));
+    assert_eq!(Validator::check_text(&rules, "5"), Err("Odd".to_string()));
+    assert_eq!(Validator::check_text(&rules, "  "), Err("This field is required".to_string()));
+
+    // Empty optional fields are only checked by `Required`
+    assert_eq!(Validator::check_text(&rules[1..], ""), Ok(()));
+}